@@ -0,0 +1,82 @@
+//! Criterion benchmarks for each processing stage over the bundled fixtures.
+//!
+//! Run with `cargo bench`. The fixtures in `benches/fixtures/` are synthetic and
+//! cover the shapes of input the pipeline sees: a dense-numeric filing excerpt,
+//! long narrative prose and a short news brief. Inputs are cleaned once up front
+//! so each benchmark measures a single stage.
+
+use std::hint::black_box;
+use std::time::Duration;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use rag_rust_core::{
+    chunk_text_impl, clean_text_with_options, extract_metadata_impl,
+    split_sentences_preserve_punct, CleanOptions,
+};
+
+const FIXTURES: [(&str, &str); 3] = [
+    (
+        "filing_excerpt",
+        include_str!("fixtures/filing_excerpt.txt"),
+    ),
+    ("long_prose", include_str!("fixtures/long_prose.txt")),
+    ("news_brief", include_str!("fixtures/news_brief.txt")),
+];
+
+fn bench_stages(c: &mut Criterion) {
+    let options = CleanOptions::default();
+
+    let mut group = c.benchmark_group("clean_text");
+    for (name, text) in FIXTURES {
+        group.throughput(Throughput::Bytes(text.len() as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(name), text, |b, text| {
+            b.iter(|| clean_text_with_options(black_box(text), &options))
+        });
+    }
+    group.finish();
+
+    let cleaned: Vec<(&str, String)> = FIXTURES
+        .iter()
+        .map(|(name, text)| (*name, clean_text_with_options(text, &options)))
+        .collect();
+
+    let mut group = c.benchmark_group("split_sentences_preserve_punct");
+    for (name, text) in &cleaned {
+        group.throughput(Throughput::Bytes(text.len() as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(name), text, |b, text| {
+            b.iter(|| split_sentences_preserve_punct(black_box(text)))
+        });
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("chunk_text_impl");
+    for (name, text) in &cleaned {
+        group.throughput(Throughput::Bytes(text.len() as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(name), text, |b, text| {
+            b.iter(|| chunk_text_impl(black_box(text), 500, 100))
+        });
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("extract_metadata_impl");
+    for (name, text) in &cleaned {
+        group.throughput(Throughput::Bytes(text.len() as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(name), text, |b, text| {
+            b.iter(|| extract_metadata_impl(black_box(text), false))
+        });
+    }
+    group.finish();
+}
+
+criterion_group! {
+    name = benches;
+    // Longer measurement and a wider noise threshold keep results comparable
+    // across commits on ordinary developer machines
+    config = Criterion::default()
+        .warm_up_time(Duration::from_secs(2))
+        .measurement_time(Duration::from_secs(5))
+        .sample_size(100)
+        .noise_threshold(0.03);
+    targets = bench_stages
+}
+criterion_main!(benches);
@@ -0,0 +1,532 @@
+//! Arrow export of processed chunks, backing `process_document_arrow` and
+//! `process_documents_arrow` (cargo feature `arrow`).
+//!
+//! Chunks are laid out as a record batch and handed over through the Arrow C
+//! data interface, which any Arrow implementation can import without copying:
+//! pyarrow, polars and duckdb all accept the PyCapsule protocol built on it.
+//! Only the three layouts needed here are produced: int32, utf8 (int32
+//! offsets) and list<utf8>. No column has nulls.
+
+use crate::ProcessedChunk;
+use std::ffi::{c_char, c_void, CString};
+use std::ptr;
+
+/// Every field is declared nullable, as pyarrow does by default, even though
+/// no value is ever null.
+const ARROW_FLAG_NULLABLE: i64 = 2;
+
+/// `struct ArrowSchema` of the C data interface.
+#[repr(C)]
+#[derive(Debug)]
+pub(crate) struct ArrowSchema {
+    format: *const c_char,
+    name: *const c_char,
+    metadata: *const c_char,
+    flags: i64,
+    n_children: i64,
+    children: *mut *mut ArrowSchema,
+    dictionary: *mut ArrowSchema,
+    release: Option<unsafe extern "C" fn(*mut ArrowSchema)>,
+    private_data: *mut c_void,
+}
+
+/// `struct ArrowArray` of the C data interface.
+#[repr(C)]
+#[derive(Debug)]
+pub(crate) struct ArrowArray {
+    length: i64,
+    null_count: i64,
+    offset: i64,
+    n_buffers: i64,
+    n_children: i64,
+    buffers: *mut *const c_void,
+    children: *mut *mut ArrowArray,
+    dictionary: *mut ArrowArray,
+    release: Option<unsafe extern "C" fn(*mut ArrowArray)>,
+    private_data: *mut c_void,
+}
+
+// SAFETY: both structs exclusively own everything they point to, through
+// `private_data`, so they can be released from any thread.
+unsafe impl Send for ArrowSchema {}
+unsafe impl Send for ArrowArray {}
+
+/// What an `ArrowSchema` points to, freed by its release callback. Fields
+/// only the C structs read through raw pointers are just kept alive here.
+#[allow(dead_code)]
+struct SchemaData {
+    format: CString,
+    name: CString,
+    children: Vec<ArrowSchema>,
+    child_pointers: Vec<*mut ArrowSchema>,
+}
+
+/// What an `ArrowArray` points to, freed by its release callback. Buffers are
+/// stored as u64 words so every buffer is 8-byte aligned.
+#[allow(dead_code)]
+struct ArrayData {
+    buffers: Vec<Option<Vec<u64>>>,
+    buffer_pointers: Vec<*const c_void>,
+    children: Vec<ArrowArray>,
+    child_pointers: Vec<*mut ArrowArray>,
+}
+
+unsafe extern "C" fn release_schema(schema: *mut ArrowSchema) {
+    // SAFETY: only ever installed by `ArrowSchema::new`, whose private data is
+    // a leaked `SchemaData`; the callback is cleared so it runs once.
+    let schema = unsafe { &mut *schema };
+    drop(unsafe { Box::from_raw(schema.private_data.cast::<SchemaData>()) });
+    schema.release = None;
+}
+
+unsafe extern "C" fn release_array(array: *mut ArrowArray) {
+    // SAFETY: as for `release_schema`, with `ArrayData`.
+    let array = unsafe { &mut *array };
+    drop(unsafe { Box::from_raw(array.private_data.cast::<ArrayData>()) });
+    array.release = None;
+}
+
+impl ArrowSchema {
+    fn new(format: &str, name: &str, mut children: Vec<ArrowSchema>) -> Self {
+        let mut child_pointers: Vec<*mut ArrowSchema> =
+            children.iter_mut().map(|child| child as *mut _).collect();
+        let children_ptr = if child_pointers.is_empty() {
+            ptr::null_mut()
+        } else {
+            child_pointers.as_mut_ptr()
+        };
+        let data = Box::new(SchemaData {
+            format: CString::new(format).unwrap(),
+            name: CString::new(name).unwrap(),
+            children,
+            child_pointers,
+        });
+        ArrowSchema {
+            format: data.format.as_ptr(),
+            name: data.name.as_ptr(),
+            metadata: ptr::null(),
+            flags: ARROW_FLAG_NULLABLE,
+            n_children: data.child_pointers.len() as i64,
+            children: children_ptr,
+            dictionary: ptr::null_mut(),
+            release: Some(release_schema),
+            private_data: Box::into_raw(data).cast(),
+        }
+    }
+}
+
+impl ArrowArray {
+    /// An array of `length` values with no nulls; `buffers` start with the
+    /// (absent) validity bitmap.
+    fn new(length: usize, buffers: Vec<Option<Vec<u64>>>, mut children: Vec<ArrowArray>) -> Self {
+        let mut child_pointers: Vec<*mut ArrowArray> =
+            children.iter_mut().map(|child| child as *mut _).collect();
+        let mut buffer_pointers: Vec<*const c_void> = buffers
+            .iter()
+            .map(|buffer| buffer.as_ref().map_or(ptr::null(), |b| b.as_ptr().cast()))
+            .collect();
+        let children_ptr = if child_pointers.is_empty() {
+            ptr::null_mut()
+        } else {
+            child_pointers.as_mut_ptr()
+        };
+        let buffers_ptr = buffer_pointers.as_mut_ptr();
+        let data = Box::new(ArrayData {
+            buffers,
+            buffer_pointers,
+            children,
+            child_pointers,
+        });
+        ArrowArray {
+            length: length as i64,
+            null_count: 0,
+            offset: 0,
+            n_buffers: data.buffers.len() as i64,
+            n_children: data.child_pointers.len() as i64,
+            buffers: buffers_ptr,
+            children: children_ptr,
+            dictionary: ptr::null_mut(),
+            release: Some(release_array),
+            private_data: Box::into_raw(data).cast(),
+        }
+    }
+}
+
+// A consumer that imports a struct moves it out and clears `release`; one that
+// was never handed over is released when dropped. Children are owned by their
+// parent's private data and dropped with it.
+impl Drop for ArrowSchema {
+    fn drop(&mut self) {
+        if let Some(release) = self.release {
+            // SAFETY: `release` is still set, so the data is still ours.
+            unsafe { release(self) };
+        }
+    }
+}
+
+impl Drop for ArrowArray {
+    fn drop(&mut self) {
+        if let Some(release) = self.release {
+            // SAFETY: as above.
+            unsafe { release(self) };
+        }
+    }
+}
+
+/// Copy `values` into 8-byte aligned words.
+fn aligned<T: Copy>(values: &[T]) -> Vec<u64> {
+    let bytes = std::mem::size_of_val(values);
+    let mut words = vec![0u64; bytes.div_ceil(8)];
+    // SAFETY: `words` holds at least `bytes` bytes and does not overlap `values`.
+    unsafe {
+        ptr::copy_nonoverlapping(
+            values.as_ptr().cast::<u8>(),
+            words.as_mut_ptr().cast(),
+            bytes,
+        )
+    };
+    words
+}
+
+/// Offsets of `lengths` laid end to end, or None past i32::MAX.
+fn offsets(lengths: impl Iterator<Item = usize>) -> Option<Vec<i32>> {
+    let mut offsets = vec![0i32];
+    let mut end = 0i32;
+    for length in lengths {
+        end = end.checked_add(i32::try_from(length).ok()?)?;
+        offsets.push(end);
+    }
+    Some(offsets)
+}
+
+/// One column of a `ChunkTable`.
+#[derive(Debug, Clone, PartialEq)]
+enum Column {
+    Int32(Vec<i32>),
+    Utf8(Vec<String>),
+    Utf8List(Vec<Vec<String>>),
+}
+
+impl Column {
+    fn int32(values: impl Iterator<Item = usize>) -> Result<Self, String> {
+        values
+            .map(i32::try_from)
+            .collect::<Result<_, _>>()
+            .map(Column::Int32)
+            .map_err(|_| "a value does not fit in an int32 column".to_string())
+    }
+
+    /// Check that every offset fits in 32 bits.
+    fn validate(&self) -> Result<(), String> {
+        let fits = match self {
+            Column::Int32(_) => true,
+            Column::Utf8(values) => offsets(values.iter().map(String::len)).is_some(),
+            Column::Utf8List(lists) => {
+                offsets(lists.iter().map(Vec::len)).is_some()
+                    && offsets(lists.iter().flatten().map(String::len)).is_some()
+            }
+        };
+        if fits {
+            Ok(())
+        } else {
+            Err("column data exceeds the 2 GiB limit of 32-bit Arrow offsets".to_string())
+        }
+    }
+
+    fn schema(&self, name: &str) -> ArrowSchema {
+        match self {
+            Column::Int32(_) => ArrowSchema::new("i", name, Vec::new()),
+            Column::Utf8(_) => ArrowSchema::new("u", name, Vec::new()),
+            Column::Utf8List(_) => {
+                ArrowSchema::new("+l", name, vec![ArrowSchema::new("u", "item", Vec::new())])
+            }
+        }
+    }
+
+    fn utf8_array(values: &[&String]) -> ArrowArray {
+        let offsets = offsets(values.iter().map(|v| v.len())).expect("validated");
+        let data: Vec<u8> = values.iter().flat_map(|v| v.bytes()).collect();
+        ArrowArray::new(
+            values.len(),
+            vec![None, Some(aligned(&offsets)), Some(aligned(&data))],
+            Vec::new(),
+        )
+    }
+
+    fn array(&self) -> ArrowArray {
+        match self {
+            Column::Int32(values) => {
+                ArrowArray::new(values.len(), vec![None, Some(aligned(values))], Vec::new())
+            }
+            Column::Utf8(values) => Column::utf8_array(&values.iter().collect::<Vec<_>>()),
+            Column::Utf8List(lists) => {
+                let offsets = offsets(lists.iter().map(Vec::len)).expect("validated");
+                let items: Vec<&String> = lists.iter().flatten().collect();
+                ArrowArray::new(
+                    lists.len(),
+                    vec![None, Some(aligned(&offsets))],
+                    vec![Column::utf8_array(&items)],
+                )
+            }
+        }
+    }
+}
+
+/// Metadata categories exported as list<utf8> columns.
+const METADATA_COLUMNS: [&str; 4] = [
+    "monetary_amounts",
+    "percentages",
+    "dates",
+    "potential_tickers",
+];
+
+/// Processed chunks as named columns, ready to export as a record batch:
+/// document_id (batches only), chunk_index, text, char_count, then the
+/// `METADATA_COLUMNS` when metadata was extracted.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct ChunkTable {
+    columns: Vec<(&'static str, Column)>,
+    num_rows: usize,
+}
+
+impl ChunkTable {
+    /// Lay out chunks, each tagged with the index of its document when
+    /// `document_ids` is set. Fails if a value overflows its 32-bit column.
+    fn new(
+        rows: Vec<(usize, ProcessedChunk)>,
+        document_ids: bool,
+        with_metadata: bool,
+    ) -> Result<Self, String> {
+        let num_rows = rows.len();
+        let document_id = Column::int32(rows.iter().map(|(id, _)| *id))?;
+        let chunk_index = Column::int32(rows.iter().map(|(_, c)| c.chunk_index))?;
+        let char_count = Column::int32(rows.iter().map(|(_, c)| c.char_count))?;
+        let mut text = Vec::with_capacity(num_rows);
+        let mut metadata: [Vec<Vec<String>>; 4] = Default::default();
+        for (_, chunk) in rows {
+            text.push(chunk.text);
+            if with_metadata {
+                let m = chunk.metadata.unwrap_or_default();
+                let values = [
+                    m.monetary_amounts.into_iter().map(|a| a.raw).collect(),
+                    m.percentages.into_iter().map(|p| p.raw).collect(),
+                    m.dates.into_iter().map(|d| d.raw).collect(),
+                    m.potential_tickers,
+                ];
+                for (column, values) in metadata.iter_mut().zip(values) {
+                    column.push(values);
+                }
+            }
+        }
+
+        let mut columns = Vec::new();
+        if document_ids {
+            columns.push(("document_id", document_id));
+        }
+        columns.push(("chunk_index", chunk_index));
+        columns.push(("text", Column::Utf8(text)));
+        columns.push(("char_count", char_count));
+        if with_metadata {
+            for (name, values) in METADATA_COLUMNS.into_iter().zip(metadata) {
+                columns.push((name, Column::Utf8List(values)));
+            }
+        }
+        for (_, column) in &columns {
+            column.validate()?;
+        }
+        Ok(ChunkTable { columns, num_rows })
+    }
+
+    /// The chunks of one document.
+    pub fn from_document(chunks: Vec<ProcessedChunk>, with_metadata: bool) -> Result<Self, String> {
+        let rows = chunks.into_iter().map(|chunk| (0, chunk)).collect();
+        ChunkTable::new(rows, false, with_metadata)
+    }
+
+    /// The chunks of a batch, with the input index of each document as
+    /// `document_id`; documents that failed contribute no rows.
+    pub fn from_documents(
+        documents: Vec<Option<Vec<ProcessedChunk>>>,
+        with_metadata: bool,
+    ) -> Result<Self, String> {
+        let rows = documents
+            .into_iter()
+            .enumerate()
+            .flat_map(|(id, chunks)| chunks.into_iter().flatten().map(move |c| (id, c)))
+            .collect();
+        ChunkTable::new(rows, true, with_metadata)
+    }
+
+    pub fn num_rows(&self) -> usize {
+        self.num_rows
+    }
+
+    pub fn column_names(&self) -> Vec<&'static str> {
+        self.columns.iter().map(|(name, _)| *name).collect()
+    }
+
+    /// The schema of the record batch: a struct with one field per column.
+    pub fn schema(&self) -> ArrowSchema {
+        let fields = self
+            .columns
+            .iter()
+            .map(|(name, c)| c.schema(name))
+            .collect();
+        let mut schema = ArrowSchema::new("+s", "", fields);
+        // The top-level struct of a record batch is not itself nullable
+        schema.flags = 0;
+        schema
+    }
+
+    /// The record batch as a struct array, with its schema. Each call exports
+    /// a fresh copy, so the table can be exported any number of times.
+    pub fn export(&self) -> (ArrowSchema, ArrowArray) {
+        let children = self.columns.iter().map(|(_, c)| c.array()).collect();
+        (
+            self.schema(),
+            ArrowArray::new(self.num_rows, vec![None], children),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{process_document_impl, ProcessConfig};
+    use std::ffi::CStr;
+
+    fn chunks(text: &str) -> Vec<ProcessedChunk> {
+        let config = ProcessConfig {
+            chunk_size: 20,
+            chunk_overlap: 0,
+            ..ProcessConfig::default()
+        };
+        process_document_impl(text, &config).unwrap()
+    }
+
+    unsafe fn format(schema: &ArrowSchema) -> &str {
+        unsafe { CStr::from_ptr(schema.format).to_str().unwrap() }
+    }
+
+    unsafe fn child_schema(schema: &ArrowSchema, i: usize) -> &ArrowSchema {
+        unsafe { &**schema.children.add(i) }
+    }
+
+    unsafe fn child_array(array: &ArrowArray, i: usize) -> &ArrowArray {
+        unsafe { &**array.children.add(i) }
+    }
+
+    /// Read back the strings of a utf8 array.
+    unsafe fn read_utf8(array: &ArrowArray) -> Vec<String> {
+        unsafe {
+            assert_eq!(array.n_buffers, 3);
+            assert!((*array.buffers).is_null());
+            let offsets = *array.buffers.add(1) as *const i32;
+            let data = *array.buffers.add(2) as *const u8;
+            (0..array.length as usize)
+                .map(|i| {
+                    let start = *offsets.add(i) as usize;
+                    let end = *offsets.add(i + 1) as usize;
+                    let bytes = std::slice::from_raw_parts(data.add(start), end - start);
+                    String::from_utf8(bytes.to_vec()).unwrap()
+                })
+                .collect()
+        }
+    }
+
+    unsafe fn read_int32(array: &ArrowArray) -> Vec<i32> {
+        unsafe {
+            let values = *array.buffers.add(1) as *const i32;
+            std::slice::from_raw_parts(values, array.length as usize).to_vec()
+        }
+    }
+
+    #[test]
+    fn test_export_document() {
+        let chunks = chunks("Revenue rose 12%. Costs fell to $4M. AAPL rallied.");
+        let texts: Vec<String> = chunks.iter().map(|c| c.text.clone()).collect();
+        let table = ChunkTable::from_document(chunks, true).unwrap();
+        assert_eq!(
+            table.column_names(),
+            [
+                "chunk_index",
+                "text",
+                "char_count",
+                "monetary_amounts",
+                "percentages",
+                "dates",
+                "potential_tickers"
+            ]
+        );
+        let (schema, array) = table.export();
+        unsafe {
+            assert_eq!(format(&schema), "+s");
+            assert_eq!(schema.n_children, 7);
+            let formats: Vec<&str> = (0..7).map(|i| format(child_schema(&schema, i))).collect();
+            assert_eq!(formats, ["i", "u", "i", "+l", "+l", "+l", "+l"]);
+            let list = child_schema(&schema, 3);
+            assert_eq!(format(child_schema(list, 0)), "u");
+            assert_eq!(
+                CStr::from_ptr(list.name).to_str().unwrap(),
+                "monetary_amounts"
+            );
+
+            assert_eq!(array.length, 3);
+            assert_eq!(read_int32(child_array(&array, 0)), [0, 1, 2]);
+            assert_eq!(read_utf8(child_array(&array, 1)), texts);
+            assert_eq!(read_int32(child_array(&array, 2)), [17, 18, 13]);
+
+            let money = child_array(&array, 3);
+            let list_offsets = *money.buffers.add(1) as *const i32;
+            assert_eq!(std::slice::from_raw_parts(list_offsets, 4), [0, 0, 1, 1]);
+            assert_eq!(read_utf8(child_array(money, 0)), ["$4M"]);
+            let tickers = child_array(&array, 6);
+            assert_eq!(read_utf8(child_array(tickers, 0)), ["AAPL"]);
+        }
+    }
+
+    #[test]
+    fn test_export_batch_without_metadata() {
+        let documents = vec![Some(chunks("One. Two.")), None, Some(chunks("Three."))];
+        let table = ChunkTable::from_documents(documents, false).unwrap();
+        assert_eq!(
+            table.column_names(),
+            ["document_id", "chunk_index", "text", "char_count"]
+        );
+        assert_eq!(table.num_rows(), 2);
+        let (_, array) = table.export();
+        unsafe {
+            assert_eq!(read_int32(child_array(&array, 0)), [0, 2]);
+            assert_eq!(read_utf8(child_array(&array, 2)), ["One. Two.", "Three."]);
+        }
+    }
+
+    #[test]
+    fn test_release_by_consumer() {
+        let table = ChunkTable::from_document(chunks("Revenue rose."), true).unwrap();
+        let (mut schema, mut array) = table.export();
+        // A consumer releases what it imported; dropping afterwards is a no-op
+        unsafe {
+            (schema.release.unwrap())(&mut schema);
+            (array.release.unwrap())(&mut array);
+        }
+        assert!(schema.release.is_none() && array.release.is_none());
+    }
+
+    #[test]
+    fn test_empty_table() {
+        let table = ChunkTable::from_document(Vec::new(), true).unwrap();
+        let (_, array) = table.export();
+        assert_eq!(array.length, 0);
+        unsafe {
+            assert!(read_utf8(child_array(&array, 1)).is_empty());
+        }
+    }
+
+    #[test]
+    fn test_offsets_overflow() {
+        assert_eq!(offsets([1, 2, 3].into_iter()), Some(vec![0, 1, 3, 6]));
+        assert_eq!(offsets([i32::MAX as usize, 1].into_iter()), None);
+        assert!(Column::int32([usize::MAX].into_iter()).is_err());
+    }
+}
@@ -0,0 +1,5 @@
+//! `rag-core`: the pipeline from the shell. See `rag_rust_core::cli`.
+
+fn main() -> std::process::ExitCode {
+    rag_rust_core::cli::main()
+}
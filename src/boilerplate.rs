@@ -0,0 +1,231 @@
+//! Removal of running headers, footers and page numbers from page-extracted
+//! text, backing `remove_boilerplate`.
+//!
+//! Works on whole lines. A line is boilerplate when it is a bare page number
+//! ("12", "- 12 -", "Page 12 of 40") or when, with its digits ignored, it
+//! occurs at least `min_occurrences` times: "Confidential — Page 12" and
+//! "Confidential — Page 13" are the same footer. Only one number may vary
+//! among the repeats, the page number: lines of a template filled in with
+//! figures ("Revenue in region 3 rose 5%") are body text. Only short lines with a
+//! letter can repeat as boilerplate, so rules and table separators ("---",
+//! "|---|") stay, and so does a paragraph that a document repeats on purpose.
+
+use regex::Regex;
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+// A line holding only a page number, optionally as "Page N", "N of M" or
+// between dashes ("- 12 -")
+static PAGE_NUMBER_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^[\s\-–—]*(?i:page\s+)?\d+(?:\s*(?:of|/)\s*\d+)?[\s\-–—]*$").unwrap()
+});
+
+// A run of digits, one per '#' in a line key
+static DIGITS_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"[0-9]+").unwrap());
+
+/// Lines sharing a key: how many there are, and which of their digit runs
+/// differ between them.
+struct Repeats<'a> {
+    count: usize,
+    numbers: Vec<&'a str>,
+    varying: Vec<bool>,
+}
+
+impl<'a> Repeats<'a> {
+    fn new(line: &'a str) -> Self {
+        let numbers: Vec<&str> = DIGITS_RE.find_iter(line).map(|m| m.as_str()).collect();
+        let varying = vec![false; numbers.len()];
+        Repeats {
+            count: 1,
+            numbers,
+            varying,
+        }
+    }
+
+    fn add(&mut self, line: &str) {
+        self.count += 1;
+        for (i, number) in DIGITS_RE.find_iter(line).enumerate() {
+            if let Some(first) = self.numbers.get(i) {
+                self.varying[i] |= *first != number.as_str();
+            }
+        }
+    }
+
+    /// Whether the lines are boilerplate: often enough repeated, with at most
+    /// a page number changing between them.
+    fn is_boilerplate(&self, min_occurrences: usize) -> bool {
+        self.count >= min_occurrences && self.varying.iter().filter(|&&v| v).count() <= 1
+    }
+}
+
+/// The line with surrounding whitespace trimmed, inner whitespace collapsed
+/// and each run of digits replaced by '#', so page-numbered repeats compare
+/// equal.
+fn line_key(line: &str) -> String {
+    let mut key = String::with_capacity(line.len());
+    let mut previous = ' ';
+    for c in line.trim().chars() {
+        let c = if c.is_ascii_digit() {
+            '#'
+        } else if c.is_whitespace() {
+            ' '
+        } else {
+            c
+        };
+        if !(c == previous && (c == '#' || c == ' ')) {
+            key.push(c);
+        }
+        previous = c;
+    }
+    key
+}
+
+/// Drop page numbers and lines of at most `max_length` characters repeating
+/// at least `min_occurrences` times. Lines differing only in one of their
+/// numbers count as repeats of each other.
+///
+/// Texts with fewer than `min_lines` non-blank lines are returned unchanged,
+/// so a short document's few lines are not mistaken for boilerplate. Blank
+/// lines are kept, and removed lines take their line break with them.
+pub fn remove_boilerplate(
+    text: &str,
+    min_occurrences: usize,
+    min_lines: usize,
+    max_length: usize,
+) -> String {
+    let keys: Vec<String> = text.lines().map(line_key).collect();
+    if keys.iter().filter(|k| !k.is_empty()).count() < min_lines {
+        return text.to_string();
+    }
+    let mut repeats: HashMap<&str, Repeats> = HashMap::new();
+    let candidates = text
+        .lines()
+        .zip(&keys)
+        .filter(|(_, k)| k.chars().any(char::is_alphabetic) && k.chars().count() <= max_length);
+    for (line, key) in candidates {
+        match repeats.get_mut(key.as_str()) {
+            Some(lines) => lines.add(line),
+            None => {
+                repeats.insert(key, Repeats::new(line));
+            }
+        }
+    }
+
+    let mut result = String::with_capacity(text.len());
+    for (line, key) in text.split_inclusive('\n').zip(&keys) {
+        let repeated = repeats
+            .get(key.as_str())
+            .is_some_and(|lines| lines.is_boilerplate(min_occurrences));
+        if !repeated && !PAGE_NUMBER_RE.is_match(line) {
+            result.push_str(line);
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Five pages of distinct body lines, each with a running header and a
+    /// numbered footer.
+    fn five_pages() -> String {
+        ["cloud", "devices", "services", "licensing", "consumer"]
+            .iter()
+            .zip(1..)
+            .map(|(segment, page)| {
+                format!(
+                    "ACME Corp Annual Report 2024\n\
+                     The {segment} segment grew revenue to ${page}0 million.\n\
+                     Margins in {segment} improved by {page}%.\n\
+                     Outlook for {segment} remains stable.\n\
+                     \n\
+                     Confidential — Page {page}\n\
+                     {page}\n"
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_strips_headers_footers_and_page_numbers() {
+        let cleaned = remove_boilerplate(&five_pages(), 3, 10, 100);
+        assert!(!cleaned.contains("ACME Corp Annual Report"));
+        assert!(!cleaned.contains("Confidential"));
+        assert!(!cleaned.lines().any(|l| l.trim().parse::<u32>().is_ok()));
+        assert!(cleaned.contains("The devices segment grew revenue to $20 million.\n"));
+        assert!(cleaned.contains("Outlook for consumer remains stable.\n"));
+        // Three body lines and the blank line of each page
+        assert_eq!(cleaned.lines().count(), 5 * 4);
+    }
+
+    #[test]
+    fn test_short_documents_are_left_alone() {
+        let text = five_pages();
+        assert_eq!(remove_boilerplate(&text, 3, 100, 100), text);
+        // Below the threshold the header is kept, page numbers are not
+        let kept = remove_boilerplate(&text, 6, 10, 100);
+        assert_eq!(kept.matches("ACME Corp Annual Report").count(), 5);
+        assert!(!kept.lines().any(|l| l.trim() == "3"));
+    }
+
+    #[test]
+    fn test_page_number_forms() {
+        for line in ["12", "  - 12 -", "Page 3", "page 3 of 40", "3/40", "— 7 —"] {
+            assert!(PAGE_NUMBER_RE.is_match(line), "{line:?}");
+        }
+        for line in ["1.", "Q3 2024", "12%", "Page three", "$12", "2024 results"] {
+            assert!(!PAGE_NUMBER_RE.is_match(line), "{line:?}");
+        }
+    }
+
+    #[test]
+    fn test_separators_are_not_boilerplate() {
+        let text: String = ["Revenue", "Costs", "Margins", "Outlook"]
+            .iter()
+            .map(|heading| format!("{heading} held steady.\n---\n|---|---|\n"))
+            .collect();
+        assert_eq!(remove_boilerplate(&text, 3, 1, 100), text);
+    }
+
+    #[test]
+    fn test_long_repeated_lines_are_kept() {
+        let notice = "All forward-looking statements are subject to the risks described in our most recent annual report.";
+        let text: String = (1..=4)
+            .map(|page| format!("Header line\n{notice}\nBody of page {page}.\n"))
+            .collect();
+        let cleaned = remove_boilerplate(&text, 3, 1, 80);
+        assert!(!cleaned.contains("Header line"));
+        assert_eq!(cleaned.matches(notice).count(), 4);
+        assert_eq!(
+            remove_boilerplate(&text, 3, 1, 200).matches(notice).count(),
+            0
+        );
+    }
+
+    #[test]
+    fn test_only_a_page_number_may_vary() {
+        let text: String = (1..=5)
+            .map(|page| {
+                format!(
+                    "Q3 2024 Review — Page {page} of 5\n\
+                     Region {page} revenue rose {}%.\n",
+                    page + 2
+                )
+            })
+            .collect();
+        let cleaned = remove_boilerplate(&text, 3, 1, 100);
+        assert!(!cleaned.contains("Q3 2024 Review"));
+        assert_eq!(cleaned.lines().count(), 5);
+        assert!(cleaned.contains("Region 4 revenue rose 6%."));
+    }
+
+    #[test]
+    fn test_line_key() {
+        assert_eq!(
+            line_key("  Confidential —  Page 12 "),
+            "Confidential — Page #"
+        );
+        assert_eq!(line_key("Page 1 of 100"), "Page # of #");
+    }
+}
@@ -0,0 +1,552 @@
+//! The `rag-core` command-line tool, behind the `cli` feature.
+//!
+//! Each subcommand runs the same code as the Python module, so results match
+//! it exactly: `clean` prints what `clean_text` returns, `chunk` the list
+//! `chunk_text` returns, `extract` the dict `extract_metadata` returns, and
+//! `process` writes one JSON line per chunk like `process_jsonl`, the chunk's
+//! `process_document` dict with its source path under "id".
+//!
+//! Input comes from stdin when no file (or "-") is given. Exit status is 0 on
+//! success, 1 when an input could not be read or the output could not be
+//! written, and 2 for usage errors. A failed input is reported on stderr and
+//! the remaining inputs are still processed.
+
+use crate::files::{decode, read_text_file, Encoding, ReadError};
+use crate::json::to_json;
+use crate::{
+    chunk_text, clean_text, extract_metadata, process_document_impl, ChunkConfig, ProcessConfig,
+    SizeUnit,
+};
+use clap::{value_parser, Arg, ArgAction, ArgMatches, Command};
+use serde_json::{json, Value};
+use std::ffi::OsString;
+use std::fs::File;
+use std::io::{self, BufWriter, Read, Write};
+use std::path::PathBuf;
+use std::process::ExitCode;
+use walkdir::WalkDir;
+
+/// Exit status when an input or the output failed.
+const EXIT_FAILURE: u8 = 1;
+
+/// Run `rag-core` with the process's arguments and standard streams.
+pub fn main() -> ExitCode {
+    let status = run(
+        std::env::args_os(),
+        &mut io::stdin().lock(),
+        &mut io::stdout().lock(),
+        &mut io::stderr(),
+    );
+    ExitCode::from(status)
+}
+
+fn input_arg() -> Arg {
+    Arg::new("file")
+        .value_name("FILE")
+        .value_parser(value_parser!(PathBuf))
+        .help("File to read; stdin when omitted or \"-\"")
+}
+
+fn size_args() -> [Arg; 3] {
+    [
+        Arg::new("size")
+            .long("size")
+            .value_parser(value_parser!(u64).range(1..))
+            .default_value("1500")
+            .help("Target chunk size in --size-unit"),
+        Arg::new("overlap")
+            .long("overlap")
+            .value_parser(value_parser!(u64))
+            .default_value("200")
+            .help("Overlap between chunks in --size-unit"),
+        Arg::new("size_unit")
+            .long("size-unit")
+            .value_parser(["chars", "words"])
+            .default_value("chars")
+            .help("What --size and --overlap count"),
+    ]
+}
+
+fn command() -> Command {
+    Command::new("rag-core")
+        .about("Clean, chunk and extract financial metadata from text")
+        .subcommand_required(true)
+        .arg(
+            Arg::new("out")
+                .long("out")
+                .short('o')
+                .global(true)
+                .value_name("PATH")
+                .value_parser(value_parser!(PathBuf))
+                .help("Write to PATH instead of stdout"),
+        )
+        .arg(
+            Arg::new("encoding")
+                .long("encoding")
+                .global(true)
+                .value_parser(["utf-8", "utf-8-lossy", "latin-1", "utf-8-or-latin-1"])
+                .default_value("utf-8")
+                .help("How input bytes are decoded"),
+        )
+        .subcommand(
+            Command::new("clean")
+                .about("Print the text as clean_text returns it")
+                .arg(input_arg()),
+        )
+        .subcommand(
+            Command::new("chunk")
+                .about("Print chunk_text's chunks as a JSON array of strings")
+                .arg(input_arg())
+                .args(size_args())
+                .arg(
+                    Arg::new("jsonl")
+                        .long("jsonl")
+                        .action(ArgAction::SetTrue)
+                        .help("Print one JSON string per line instead"),
+                ),
+        )
+        .subcommand(
+            Command::new("extract")
+                .about("Print extract_metadata's dict as JSON")
+                .arg(input_arg()),
+        )
+        .subcommand(
+            Command::new("process")
+                .about(
+                    "Write process_document's chunks as JSON lines, with the source path as \"id\"",
+                )
+                .arg(
+                    Arg::new("paths")
+                        .value_name("FILE_OR_DIR")
+                        .num_args(0..)
+                        .value_parser(value_parser!(PathBuf))
+                        .help("Files, and directories searched recursively; stdin when omitted"),
+                )
+                .args(size_args())
+                .arg(
+                    Arg::new("glob").long("glob").value_name("PATTERN").help(
+                        "Only process files in directories whose name matches, e.g. \"*.txt\"",
+                    ),
+                ),
+        )
+}
+
+/// Whether `name` matches `pattern`, where `*` stands for any run of
+/// characters and `?` for one character.
+fn glob_match(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    let (mut p, mut n) = (0, 0);
+    // Pattern and name positions just after the last `*`, to backtrack to
+    let mut star = None;
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                p += 1;
+                star = Some((p, n));
+            }
+            Some(&c) if c == '?' || c == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match star {
+                Some((star_p, star_n)) => {
+                    p = star_p;
+                    n = star_n + 1;
+                    star = Some((star_p, n));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// Where a document comes from: stdin or a file.
+enum Input {
+    Stdin,
+    File(PathBuf),
+}
+
+impl Input {
+    fn from_arg(path: Option<&PathBuf>) -> Self {
+        match path {
+            Some(path) if path.as_os_str() != "-" => Input::File(path.clone()),
+            _ => Input::Stdin,
+        }
+    }
+
+    /// The "id" written for this input's chunks.
+    fn name(&self) -> String {
+        match self {
+            Input::Stdin => "-".to_string(),
+            Input::File(path) => path.display().to_string(),
+        }
+    }
+
+    fn read(&self, stdin: &mut dyn Read, encoding: Encoding) -> Result<String, ReadError> {
+        match self {
+            Input::Stdin => {
+                let mut bytes = Vec::new();
+                stdin.read_to_end(&mut bytes).map_err(ReadError::Io)?;
+                decode(bytes, encoding)
+            }
+            Input::File(path) => read_text_file(path, encoding),
+        }
+    }
+}
+
+/// Describe a read failure for stderr.
+fn read_error_message(input: &Input, err: &ReadError) -> String {
+    let name = match input {
+        Input::Stdin => "<stdin>".to_string(),
+        Input::File(path) => path.display().to_string(),
+    };
+    match err {
+        ReadError::Io(err) => format!("rag-core: {name}: {err}"),
+        ReadError::Decode { start, .. } => format!(
+            "rag-core: {name}: invalid UTF-8 at byte {start} \
+             (try --encoding utf-8-lossy or latin-1)"
+        ),
+    }
+}
+
+/// The files under `paths` in the order they are processed: explicit files
+/// as given, directory contents sorted by path and filtered by `glob`. Walk
+/// errors (an unreadable directory) are returned alongside.
+fn collect_files(paths: &[PathBuf], glob: Option<&str>) -> Vec<Result<PathBuf, String>> {
+    let mut files = Vec::new();
+    for path in paths {
+        if !path.is_dir() {
+            files.push(Ok(path.clone()));
+            continue;
+        }
+        for entry in WalkDir::new(path).sort_by_file_name() {
+            match entry {
+                Ok(entry) if entry.file_type().is_file() => {
+                    let name = entry.file_name().to_string_lossy();
+                    if glob.is_none_or(|glob| glob_match(glob, &name)) {
+                        files.push(Ok(entry.into_path()));
+                    }
+                }
+                Ok(_) => {}
+                Err(err) => files.push(Err(format!("rag-core: {err}"))),
+            }
+        }
+    }
+    files
+}
+
+fn size_unit(matches: &ArgMatches) -> SizeUnit {
+    let name = matches
+        .get_one::<String>("size_unit")
+        .expect("has a default");
+    SizeUnit::parse(name).expect("checked by clap")
+}
+
+fn size(matches: &ArgMatches, name: &str) -> usize {
+    *matches.get_one::<u64>(name).expect("has a default") as usize
+}
+
+/// Write `value` as one line of JSON.
+fn write_json_line(out: &mut dyn Write, value: &Value) -> io::Result<()> {
+    serde_json::to_writer(&mut *out, value)?;
+    out.write_all(b"\n")
+}
+
+/// Run one subcommand, writing its output to `out`. Returns whether every
+/// input was read; read failures are reported on `stderr` as they happen.
+fn run_command(
+    matches: &ArgMatches,
+    encoding: Encoding,
+    stdin: &mut dyn Read,
+    out: &mut dyn Write,
+    stderr: &mut dyn Write,
+) -> io::Result<bool> {
+    let (name, matches) = matches.subcommand().expect("a subcommand is required");
+    if name == "process" {
+        let config = ProcessConfig {
+            chunk_size: size(matches, "size"),
+            chunk_overlap: size(matches, "overlap"),
+            size_unit: size_unit(matches),
+            ..ProcessConfig::default()
+        };
+        let paths: Vec<PathBuf> = matches
+            .get_many::<PathBuf>("paths")
+            .map(|paths| paths.cloned().collect())
+            .unwrap_or_default();
+        let inputs = if paths.is_empty() {
+            vec![Ok(Input::Stdin)]
+        } else {
+            let glob = matches.get_one::<String>("glob").map(String::as_str);
+            collect_files(&paths, glob)
+                .into_iter()
+                .map(|file| file.map(Input::File))
+                .collect()
+        };
+        let mut all_read = true;
+        for input in inputs {
+            let read = input.and_then(|input| match input.read(stdin, encoding) {
+                Ok(text) => Ok((input, text)),
+                Err(err) => Err(read_error_message(&input, &err)),
+            });
+            let (input, text) = match read {
+                Ok(read) => read,
+                Err(message) => {
+                    writeln!(stderr, "{message}")?;
+                    all_read = false;
+                    continue;
+                }
+            };
+            let id = Value::from(input.name());
+            // chunk_size is at least 1 and empty documents give no chunks,
+            // so processing cannot fail
+            let chunks = process_document_impl(&text, &config).unwrap_or_default();
+            for chunk in chunks {
+                let mut object = to_json(chunk);
+                object["id"] = id.clone();
+                write_json_line(out, &object)?;
+            }
+        }
+        return Ok(all_read);
+    }
+
+    let input = Input::from_arg(matches.get_one::<PathBuf>("file"));
+    let text = match input.read(stdin, encoding) {
+        Ok(text) => text,
+        Err(err) => {
+            writeln!(stderr, "{}", read_error_message(&input, &err))?;
+            return Ok(false);
+        }
+    };
+    match name {
+        "clean" => writeln!(out, "{}", clean_text(&text))?,
+        "chunk" => {
+            let config = ChunkConfig {
+                target_size: size(matches, "size"),
+                overlap: size(matches, "overlap"),
+                size_unit: size_unit(matches),
+            };
+            let chunks = chunk_text(&text, config).expect("target_size is at least 1");
+            if matches.get_flag("jsonl") {
+                for chunk in chunks {
+                    write_json_line(out, &Value::from(chunk.text))?;
+                }
+            } else {
+                let texts: Vec<String> = chunks.into_iter().map(|c| c.text).collect();
+                write_json_line(out, &json!(texts))?;
+            }
+        }
+        "extract" => write_json_line(out, &to_json(extract_metadata(&text)))?,
+        _ => unreachable!("unknown subcommand {name}"),
+    }
+    Ok(true)
+}
+
+/// Parse `args` and run the subcommand, returning the exit status.
+fn run(
+    args: impl IntoIterator<Item = impl Into<OsString> + Clone>,
+    stdin: &mut dyn Read,
+    stdout: &mut dyn Write,
+    stderr: &mut dyn Write,
+) -> u8 {
+    let matches = match command().try_get_matches_from(args) {
+        Ok(matches) => matches,
+        Err(err) => {
+            // --help and --version go to stdout with status 0
+            let rendered = err.render().to_string();
+            let _ = if err.use_stderr() {
+                stderr.write_all(rendered.as_bytes())
+            } else {
+                stdout.write_all(rendered.as_bytes())
+            };
+            return err.exit_code() as u8;
+        }
+    };
+    let encoding_name = matches
+        .get_one::<String>("encoding")
+        .expect("has a default");
+    let encoding = Encoding::parse(encoding_name).expect("checked by clap");
+
+    let out_path = matches.get_one::<PathBuf>("out");
+    let mut file;
+    let out: &mut dyn Write = match out_path {
+        Some(path) => match File::create(path) {
+            Ok(f) => {
+                file = BufWriter::new(f);
+                &mut file
+            }
+            Err(err) => {
+                let _ = writeln!(stderr, "rag-core: {}: {err}", path.display());
+                return EXIT_FAILURE;
+            }
+        },
+        None => stdout,
+    };
+    let result = run_command(&matches, encoding, stdin, out, stderr).and_then(|all_read| {
+        out.flush()?;
+        Ok(all_read)
+    });
+    match result {
+        Ok(true) => 0,
+        Ok(false) => EXIT_FAILURE,
+        // The reader went away (`rag-core process docs | head`): stop quietly
+        Err(err) if err.kind() == io::ErrorKind::BrokenPipe => 0,
+        Err(err) => {
+            let target = out_path.map_or("<stdout>".into(), |p| p.display().to_string());
+            let _ = writeln!(stderr, "rag-core: {target}: {err}");
+            EXIT_FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chunk_text_impl;
+
+    /// Run `rag-core` with `args` and `stdin`, returning the exit status,
+    /// stdout and stderr.
+    fn rag_core(args: &[&str], stdin: &str) -> (u8, String, String) {
+        let (mut stdout, mut stderr) = (Vec::new(), Vec::new());
+        let args = std::iter::once("rag-core").chain(args.iter().copied());
+        let status = run(args, &mut stdin.as_bytes(), &mut stdout, &mut stderr);
+        (
+            status,
+            String::from_utf8(stdout).unwrap(),
+            String::from_utf8(stderr).unwrap(),
+        )
+    }
+
+    /// A fresh directory under the system temp dir.
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("rag_core_cli_{name}_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    const TEXT: &str = "Revenue rose 12% to $5 million.   Costs fell.\n\n\nAAPL gained.";
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("*.txt", "q3.txt"));
+        assert!(glob_match("*.txt", ".txt"));
+        assert!(!glob_match("*.txt", "q3.txt.bak"));
+        assert!(glob_match("q?_*.md", "q3_call.md"));
+        assert!(!glob_match("q?_*.md", "q33_call.md"));
+        assert!(glob_match("*call*", "earnings_call_q3.txt"));
+        assert!(glob_match("*", ""));
+        assert!(!glob_match("a*b", "aXbY"));
+    }
+
+    #[test]
+    fn test_clean_chunk_and_extract_from_stdin() {
+        let (status, out, _) = rag_core(&["clean"], TEXT);
+        assert_eq!(status, 0);
+        assert_eq!(out, format!("{}\n", clean_text(TEXT)));
+
+        let (status, out, _) = rag_core(&["chunk", "--size", "20", "--overlap", "0"], TEXT);
+        assert_eq!(status, 0);
+        let chunks: Vec<String> = serde_json::from_str(&out).unwrap();
+        assert_eq!(chunks, chunk_text_impl(TEXT, 20, 0).unwrap());
+        assert!(chunks.len() > 1);
+        let (_, lines, _) = rag_core(
+            &["chunk", "--size", "20", "--overlap", "0", "--jsonl", "-"],
+            TEXT,
+        );
+        let from_lines: Vec<String> = lines
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(from_lines, chunks);
+
+        let (status, out, _) = rag_core(&["extract"], TEXT);
+        assert_eq!(status, 0);
+        let metadata: Value = serde_json::from_str(&out).unwrap();
+        assert_eq!(metadata, to_json(extract_metadata(TEXT)));
+        assert_eq!(metadata["monetary_amounts"], json!(["$5 million"]));
+    }
+
+    #[test]
+    fn test_process_directory_with_glob() {
+        let dir = temp_dir("process");
+        std::fs::create_dir(dir.join("sub")).unwrap();
+        std::fs::write(dir.join("b.txt"), "Costs fell to $4M.").unwrap();
+        std::fs::write(dir.join("sub/a.txt"), TEXT).unwrap();
+        std::fs::write(dir.join("notes.md"), "Skipped by the glob.").unwrap();
+        let out = dir.join("out.jsonl");
+        let args = [
+            "process",
+            dir.to_str().unwrap(),
+            "--glob",
+            "*.txt",
+            "--out",
+            out.to_str().unwrap(),
+        ];
+        let (status, stdout, stderr) = rag_core(&args, "");
+        assert_eq!((status, stdout.as_str(), stderr.as_str()), (0, "", ""));
+
+        let lines: Vec<Value> = std::fs::read_to_string(&out)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        let ids: Vec<&str> = lines.iter().map(|l| l["id"].as_str().unwrap()).collect();
+        let b = dir.join("b.txt").display().to_string();
+        let a = dir.join("sub/a.txt").display().to_string();
+        assert_eq!(ids, [b.as_str(), a.as_str()]);
+        let expected = to_json(process_document_impl(TEXT, &ProcessConfig::default()).unwrap());
+        let mut chunk = lines[1].clone();
+        chunk.as_object_mut().unwrap().remove("id");
+        assert_eq!(chunk, expected[0]);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_unreadable_inputs_are_reported_and_skipped() {
+        let dir = temp_dir("unreadable");
+        let good = dir.join("good.txt");
+        let missing = dir.join("missing.txt");
+        let latin1 = dir.join("latin1.txt");
+        std::fs::write(&good, "Revenue rose.").unwrap();
+        std::fs::write(&latin1, b"Caf\xe9 sales rose.").unwrap();
+        let args = [
+            "process",
+            missing.to_str().unwrap(),
+            latin1.to_str().unwrap(),
+            good.to_str().unwrap(),
+        ];
+        let (status, stdout, stderr) = rag_core(&args, "");
+        assert_eq!(status, EXIT_FAILURE);
+        assert_eq!(stdout.lines().count(), 1);
+        assert!(stdout.contains("\"Revenue rose.\""));
+        let errors: Vec<&str> = stderr.lines().collect();
+        assert_eq!(errors.len(), 2);
+        assert!(errors[0].starts_with(&format!("rag-core: {}: ", missing.display())));
+        assert!(errors[1].contains("invalid UTF-8 at byte 3"));
+
+        let (status, stdout, _) = rag_core(
+            &["clean", "--encoding", "latin-1", latin1.to_str().unwrap()],
+            "",
+        );
+        assert_eq!((status, stdout.as_str()), (0, "Café sales rose.\n"));
+        let (status, _, stderr) = rag_core(&["clean", missing.to_str().unwrap()], "");
+        assert_eq!(status, EXIT_FAILURE);
+        assert!(stderr.starts_with("rag-core: "));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_usage_errors() {
+        let (status, stdout, stderr) = rag_core(&["chunk", "--size", "0"], "");
+        assert_eq!(status, 2);
+        assert!(stdout.is_empty());
+        assert!(stderr.contains("--size"));
+        assert_eq!(rag_core(&[], "").0, 2);
+        assert_eq!(rag_core(&["summarize"], "").0, 2);
+        let (status, stdout, _) = rag_core(&["--help"], "");
+        assert_eq!(status, 0);
+        assert!(stdout.contains("process"));
+    }
+}
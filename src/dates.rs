@@ -0,0 +1,374 @@
+//! Normalization of date matches found by `DATE_RE` in `lib.rs`.
+//!
+//! Each surface form is parsed into a `DateValue` whose granularity follows
+//! what was written: a calendar day, a month, a fiscal quarter or a year. Parsing only
+//! reads the fields; `DateValue::is_valid` checks them, so "February 30, 2024"
+//! parses but is flagged instead of being rolled over or dropped.
+
+const MONTHS: [&str; 12] = [
+    "january",
+    "february",
+    "march",
+    "april",
+    "may",
+    "june",
+    "july",
+    "august",
+    "september",
+    "october",
+    "november",
+    "december",
+];
+
+/// How to read an ambiguous slash date such as "03/04/2024".
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) enum DateFormat {
+    /// Month first: March 4.
+    #[default]
+    Us,
+    /// Day first: 3 April. Only `date_format="eu"` asks for it.
+    #[cfg_attr(not(feature = "python"), allow(dead_code))]
+    Eu,
+}
+
+impl DateFormat {
+    #[cfg(any(feature = "python", test))]
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "us" => Some(DateFormat::Us),
+            "eu" => Some(DateFormat::Eu),
+            _ => None,
+        }
+    }
+}
+
+/// A date as written, at the granularity it was written in.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DateValue {
+    Day { year: u32, month: u32, day: u32 },
+    Month { year: u32, month: u32 },
+    Quarter { year: u32, quarter: u32 },
+    Year { year: u32 },
+}
+
+impl DateValue {
+    /// "day", "month", "quarter" or "year".
+    pub fn kind(&self) -> &'static str {
+        match self {
+            DateValue::Day { .. } => "day",
+            DateValue::Month { .. } => "month",
+            DateValue::Quarter { .. } => "quarter",
+            DateValue::Year { .. } => "year",
+        }
+    }
+
+    /// Whether the fields name a real date (no "February 30" or month 13).
+    pub fn is_valid(&self) -> bool {
+        match *self {
+            DateValue::Day { year, month, day } => {
+                (1..=12).contains(&month) && (1..=days_in_month(year, month)).contains(&day)
+            }
+            DateValue::Month { month, .. } => (1..=12).contains(&month),
+            DateValue::Quarter { quarter, .. } => (1..=4).contains(&quarter),
+            DateValue::Year { .. } => true,
+        }
+    }
+
+    /// ISO 8601 form of a day ("2024-01-15"), month ("2024-01") or year
+    /// ("2024"); quarters have no ISO calendar form and give None, as do
+    /// invalid dates.
+    pub fn iso(&self) -> Option<String> {
+        if !self.is_valid() {
+            return None;
+        }
+        match *self {
+            DateValue::Day { year, month, day } => Some(format!("{year:04}-{month:02}-{day:02}")),
+            DateValue::Month { year, month } => Some(format!("{year:04}-{month:02}")),
+            DateValue::Year { year } => Some(format!("{year:04}")),
+            DateValue::Quarter { .. } => None,
+        }
+    }
+
+    /// Canonical sortable form: "YYYY-MM-DD" for a valid day and "YYYY-Qn"
+    /// for a quarter. Months and years without a day and impossible dates
+    /// give None.
+    pub fn canonical(&self) -> Option<String> {
+        if !self.is_valid() {
+            return None;
+        }
+        match *self {
+            DateValue::Day { year, month, day } => Some(format!("{year:04}-{month:02}-{day:02}")),
+            DateValue::Quarter { year, quarter } => Some(format!("{year:04}-Q{quarter}")),
+            DateValue::Month { .. } | DateValue::Year { .. } => None,
+        }
+    }
+}
+
+fn is_leap_year(year: u32) -> bool {
+    (year.is_multiple_of(4) && !year.is_multiple_of(100)) || year.is_multiple_of(400)
+}
+
+fn days_in_month(year: u32, month: u32) -> u32 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Month number (1-12) of an English month name, full or abbreviated to at
+/// least three letters with an optional period ("Jan.", "Sept"), case-insensitively.
+fn month_number(name: &str) -> Option<u32> {
+    let name = name.trim_end_matches('.').to_lowercase();
+    if name.len() < 3 {
+        return None;
+    }
+    MONTHS
+        .iter()
+        .position(|m| m.starts_with(&name))
+        .map(|i| i as u32 + 1)
+}
+
+/// Parse the three numeric fields of "2024-01-15", "1/15/2024" or "15.1.2024".
+fn numeric_fields(raw: &str, separator: char) -> Option<[u32; 3]> {
+    let fields: Vec<u32> = raw
+        .split(separator)
+        .map(|f| f.parse().ok())
+        .collect::<Option<_>>()?;
+    fields.try_into().ok()
+}
+
+/// Read the fields of a slash date in the order `format` gives, unless only
+/// the other order makes a real date ("13/04/2024" is 13 April even for
+/// `Us`). The flag is set when the hint was overridden that way.
+fn slash_date(fields: [u32; 3], format: DateFormat) -> (DateValue, bool) {
+    let [first, second, year] = fields;
+    let (month, day) = match format {
+        DateFormat::Us => (first, second),
+        DateFormat::Eu => (second, first),
+    };
+    let hinted = DateValue::Day { year, month, day };
+    let swapped = DateValue::Day {
+        year,
+        month: day,
+        day: month,
+    };
+    if !hinted.is_valid() && swapped.is_valid() {
+        (swapped, true)
+    } else {
+        (hinted, false)
+    }
+}
+
+/// Whether `raw` is a slash date read against `format` because only the
+/// other order is a real date.
+pub(crate) fn overrides_format(raw: &str, format: DateFormat) -> bool {
+    numeric_fields(raw.trim(), '/').is_some_and(|fields| slash_date(fields, format).1)
+}
+
+/// Parse a date match: "Q1 2024", "2024-01-15", "January 15, 2024",
+/// "15 Jan. 2024", "January 2024", "03/04/2024" (ordered by `format`),
+/// "15.1.2024" (day first), or a year with a "fiscal", "calendar" or "FY" cue.
+pub(crate) fn parse_date(raw: &str, format: DateFormat) -> Option<DateValue> {
+    let raw = raw.trim();
+
+    if let Some(rest) = raw.strip_prefix('Q') {
+        let (quarter, year) = rest.split_once(char::is_whitespace)?;
+        return Some(DateValue::Quarter {
+            year: year.trim().parse().ok()?,
+            quarter: quarter.parse().ok()?,
+        });
+    }
+
+    if let Some([year, month, day]) = numeric_fields(raw, '-') {
+        return Some(DateValue::Day { year, month, day });
+    }
+    if let Some(fields) = numeric_fields(raw, '/') {
+        return Some(slash_date(fields, format).0);
+    }
+    if let Some([day, month, year]) = numeric_fields(raw, '.') {
+        return Some(DateValue::Day { year, month, day });
+    }
+
+    let words: Vec<&str> = raw
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|w| !w.is_empty())
+        .collect();
+    let (&last, rest) = words.split_last()?;
+    // "FY2024" carries its cue in the same word
+    let year_digits = last
+        .strip_prefix("FY")
+        .or_else(|| last.strip_prefix("fy"))
+        .unwrap_or(last);
+    let year: u32 = year_digits.parse().ok()?;
+    if year_digits.len() != last.len() {
+        return rest.is_empty().then_some(DateValue::Year { year });
+    }
+
+    match *rest {
+        [cue, "year" | "Year"] if is_year_cue(cue) => Some(DateValue::Year { year }),
+        [first, second] => match month_number(first) {
+            Some(month) => Some(DateValue::Day {
+                year,
+                month,
+                day: second.parse().ok()?,
+            }),
+            None => Some(DateValue::Day {
+                year,
+                month: month_number(second)?,
+                day: first.parse().ok()?,
+            }),
+        },
+        [month] => match month_number(month) {
+            Some(month) => Some(DateValue::Month { year, month }),
+            None => is_year_cue(month).then_some(DateValue::Year { year }),
+        },
+        _ => None,
+    }
+}
+
+/// Canonical sortable form of a date match (see `DateValue::canonical`);
+/// unreadable matches give None.
+#[cfg(any(feature = "python", test))]
+pub(crate) fn normalize_date(raw: &str, format: DateFormat) -> Option<String> {
+    parse_date(raw, format)?.canonical()
+}
+
+/// Words that make a following four-digit number a year.
+fn is_year_cue(word: &str) -> bool {
+    ["fiscal", "calendar", "fy"].contains(&word.to_lowercase().as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use DateFormat::{Eu, Us};
+
+    #[test]
+    fn test_parse_date_formats() {
+        assert_eq!(
+            parse_date("Q1 2024", Us),
+            Some(DateValue::Quarter {
+                year: 2024,
+                quarter: 1
+            })
+        );
+        assert_eq!(
+            parse_date("2024-01-15", Us).unwrap().iso().unwrap(),
+            "2024-01-15"
+        );
+        assert_eq!(
+            parse_date("January 15, 2024", Us).unwrap().iso().unwrap(),
+            "2024-01-15"
+        );
+        assert_eq!(
+            parse_date("March 5 2024", Us).unwrap().iso().unwrap(),
+            "2024-03-05"
+        );
+        assert_eq!(
+            parse_date("March 2024", Us).unwrap().iso().unwrap(),
+            "2024-03"
+        );
+        assert_eq!(parse_date("Q3 2024", Us).unwrap().iso(), None);
+        assert_eq!(parse_date("Smarch 5, 2024", Us), None);
+    }
+
+    #[test]
+    fn test_parse_date_extended_formats() {
+        let day = |year, month, day| Some(DateValue::Day { year, month, day });
+        assert_eq!(parse_date("Jan. 15, 2024", Us), day(2024, 1, 15));
+        assert_eq!(parse_date("Sept 3, 2024", Us), day(2024, 9, 3));
+        assert_eq!(parse_date("15 January 2024", Us), day(2024, 1, 15));
+        assert_eq!(parse_date("15 Jan. 2024", Us), day(2024, 1, 15));
+        assert_eq!(parse_date("1/15/2024", Us), day(2024, 1, 15));
+        assert_eq!(parse_date("15.1.2024", Us), day(2024, 1, 15));
+        assert_eq!(
+            parse_date("Mar. 2024", Us),
+            Some(DateValue::Month {
+                year: 2024,
+                month: 3
+            })
+        );
+        for raw in [
+            "fiscal 2023",
+            "Fiscal year 2023",
+            "calendar 2023",
+            "FY2023",
+            "FY 2023",
+        ] {
+            assert_eq!(
+                parse_date(raw, Us),
+                Some(DateValue::Year { year: 2023 }),
+                "{raw}"
+            );
+        }
+        assert_eq!(parse_date("page 2024", Us), None);
+        assert!(!parse_date("13/15/2024", Us).unwrap().is_valid());
+    }
+
+    #[test]
+    fn test_normalize_date() {
+        assert_eq!(
+            normalize_date("March 5, 2024", Us).as_deref(),
+            Some("2024-03-05")
+        );
+        assert_eq!(normalize_date("Q3 2023", Us).as_deref(), Some("2023-Q3"));
+        assert_eq!(
+            normalize_date("2024-01-15", Us).as_deref(),
+            Some("2024-01-15")
+        );
+        assert_eq!(
+            normalize_date("15.1.2024", Us).as_deref(),
+            Some("2024-01-15")
+        );
+        assert_eq!(normalize_date("January 2024", Us), None);
+        assert_eq!(normalize_date("fiscal 2024", Us), None);
+        assert_eq!(normalize_date("February 30, 2024", Us), None);
+        assert_eq!(normalize_date("Q5 2024", Us), None);
+    }
+
+    #[test]
+    fn test_slash_date_format_hint() {
+        let day = |year, month, day| Some(DateValue::Day { year, month, day });
+        assert_eq!(parse_date("03/04/2024", Us), day(2024, 3, 4));
+        assert_eq!(parse_date("03/04/2024", Eu), day(2024, 4, 3));
+        assert!(!overrides_format("03/04/2024", Us) && !overrides_format("03/04/2024", Eu));
+
+        // Only one reading is a real date, so the hint gives way
+        assert_eq!(parse_date("13/04/2024", Us), day(2024, 4, 13));
+        assert!(overrides_format("13/04/2024", Us));
+        assert_eq!(parse_date("04/13/2024", Eu), day(2024, 4, 13));
+        assert!(overrides_format("04/13/2024", Eu) && !overrides_format("04/13/2024", Us));
+
+        // Neither reading is real: kept as hinted and flagged invalid
+        assert!(!parse_date("13/13/2024", Eu).unwrap().is_valid());
+        assert!(!overrides_format("13/13/2024", Eu));
+        assert!(!overrides_format("13.04.2024", Us));
+
+        assert_eq!(
+            normalize_date("03/04/2024", Eu).as_deref(),
+            Some("2024-04-03")
+        );
+        assert_eq!(DateFormat::parse("eu"), Some(Eu));
+        assert_eq!(DateFormat::parse("EU"), None);
+    }
+
+    #[test]
+    fn test_invalid_dates_are_flagged() {
+        for raw in [
+            "February 30, 2024",
+            "2023-02-29",
+            "2024-13-01",
+            "April 31, 2024",
+        ] {
+            let date = parse_date(raw, Us).unwrap();
+            assert!(!date.is_valid(), "{raw}");
+            assert_eq!(date.iso(), None);
+            assert_eq!(date.kind(), "day");
+        }
+        assert!(parse_date("February 29, 2024", Us).unwrap().is_valid());
+        assert!(!parse_date("February 29, 1900", Us).unwrap().is_valid());
+        assert!(parse_date("February 29, 2000", Us).unwrap().is_valid());
+    }
+}
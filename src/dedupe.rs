@@ -0,0 +1,89 @@
+//! Duplicate chunk detection across a batch, backing `dedupe_chunks`.
+//!
+//! Exact mode compares chunk text byte for byte. Normalized mode compares an
+//! xxHash64 of the text lowercased with whitespace runs collapsed and trimmed,
+//! which catches copies differing only in case or spacing. Either way the
+//! first occurrence is kept, so the result depends only on input order.
+
+use std::collections::HashMap;
+use xxhash_rust::xxh64::xxh64;
+
+/// Hash of `text` lowercased, with whitespace runs collapsed to one space and
+/// leading and trailing whitespace dropped.
+fn normalized_hash(text: &str) -> u64 {
+    let mut normalized = String::with_capacity(text.len());
+    for word in text.split_whitespace() {
+        if !normalized.is_empty() {
+            normalized.push(' ');
+        }
+        normalized.extend(word.chars().flat_map(char::to_lowercase));
+    }
+    xxh64(normalized.as_bytes(), 0)
+}
+
+/// For each chunk, None if it is the first of its kind, or the index of the
+/// earlier chunk it duplicates.
+pub fn find_duplicates<S: AsRef<str>>(chunks: &[S], exact: bool) -> Vec<Option<usize>> {
+    if exact {
+        let mut first: HashMap<&str, usize> = HashMap::new();
+        chunks
+            .iter()
+            .enumerate()
+            .map(|(i, chunk)| {
+                first.get(chunk.as_ref()).copied().or_else(|| {
+                    first.insert(chunk.as_ref(), i);
+                    None
+                })
+            })
+            .collect()
+    } else {
+        let mut first: HashMap<u64, usize> = HashMap::new();
+        chunks
+            .iter()
+            .enumerate()
+            .map(|(i, chunk)| {
+                let hash = normalized_hash(chunk.as_ref());
+                first.get(&hash).copied().or_else(|| {
+                    first.insert(hash, i);
+                    None
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exact_duplicates_keep_first_occurrence() {
+        let chunks = ["a", "b", "a", "c", "b", "a", "A"];
+        assert_eq!(
+            find_duplicates(&chunks, true),
+            vec![None, None, Some(0), None, Some(1), Some(0), None]
+        );
+    }
+
+    #[test]
+    fn test_normalized_duplicates() {
+        let chunks = [
+            "Past performance is no guarantee.",
+            "PAST performance  is no\nguarantee.",
+            " past performance is no guarantee. ",
+            "Past performance is a guarantee.",
+        ];
+        assert_eq!(
+            find_duplicates(&chunks, false),
+            vec![None, Some(0), Some(0), None]
+        );
+        assert_eq!(find_duplicates(&chunks, true), vec![None; 4]);
+    }
+
+    #[test]
+    fn test_normalized_hash() {
+        assert_eq!(normalized_hash("Café  Q3"), normalized_hash("café q3"));
+        assert_ne!(normalized_hash("ab"), normalized_hash("a b"));
+        assert_eq!(normalized_hash(""), normalized_hash(" \n\t"));
+    }
+}
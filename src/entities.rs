@@ -0,0 +1,167 @@
+//! Extractors for link and contact entities (URLs, emails, phone numbers) found
+//! alongside the financial metadata in `lib.rs`.
+//!
+//! Each extractor returns matches deduplicated in first-occurrence order.
+
+use regex::Regex;
+use std::collections::HashSet;
+use std::sync::LazyLock;
+
+// http(s) URLs and bare www. domains; brackets and quotes never belong to a URL,
+// parentheses do only when balanced (see `trim_url`)
+static URL_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"(?i)\b(?:https?://|www\.)[^\s<>"\[\]{}]+"#).unwrap());
+
+// Local part allows plus-addressing; the domain may have any number of subdomains
+pub(crate) static EMAIL_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\b[A-Za-z0-9._%+-]+@(?:[A-Za-z0-9](?:[A-Za-z0-9-]*[A-Za-z0-9])?\.)+[A-Za-z]{2,}\b")
+        .unwrap()
+});
+// US numbers: optional +1, area code as "(212)" or "212", then 555-0123 with
+// space, dot or dash separators. Context is checked in `is_standalone_number`.
+pub(crate) static PHONE_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?:\+1[ .-]?)?(?:\(\d{3}\) ?|\d{3}[ .-])\d{3}[ .-]\d{4}").unwrap()
+});
+
+/// Deduplicate `items`, keeping the first occurrence of each in order.
+pub(crate) fn dedupe_in_order<I>(items: I) -> Vec<String>
+where
+    I: IntoIterator<Item = String>,
+{
+    let mut seen = HashSet::new();
+    items
+        .into_iter()
+        .filter(|item| seen.insert(item.clone()))
+        .collect()
+}
+
+/// Strip trailing punctuation that belongs to the surrounding sentence, plus a
+/// closing parenthesis with no matching opener inside the URL.
+fn trim_url(url: &str) -> &str {
+    let mut url = url;
+    loop {
+        let trimmed = url.trim_end_matches(['.', ',', ';', ':', '!', '?', '\'', '*']);
+        let trimmed = match trimmed.strip_suffix(')') {
+            Some(inner) if trimmed.matches('(').count() < trimmed.matches(')').count() => inner,
+            _ => trimmed,
+        };
+        if trimmed.len() == url.len() {
+            return url;
+        }
+        url = trimmed;
+    }
+}
+
+/// Extract http/https URLs and bare www. domains.
+pub(crate) fn extract_urls(text: &str) -> Vec<String> {
+    dedupe_in_order(
+        URL_RE
+            .find_iter(text)
+            .map(|m| trim_url(m.as_str()))
+            .filter(|url| url.contains('.'))
+            .map(str::to_string),
+    )
+}
+
+/// Extract email addresses.
+pub(crate) fn extract_emails(text: &str) -> Vec<String> {
+    dedupe_in_order(EMAIL_RE.find_iter(text).map(|m| m.as_str().to_string()))
+}
+
+/// Check that a phone-shaped match is not part of a longer number, amount or
+/// identifier: "$212 555 0123", "1-212-555-0123-45", "ID212-555-0123".
+pub(crate) fn is_standalone_number(text: &str, start: usize, end: usize) -> bool {
+    let before = text[..start].chars().next_back();
+    let after = text[end..].chars().next();
+    let glued_before = before
+        .is_some_and(|c| c.is_alphanumeric() || matches!(c, '$' | '-' | '+' | '.' | ',' | '/'));
+    let glued_after = after.is_some_and(|c| c.is_alphanumeric() || matches!(c, '-' | '/'));
+    !glued_before && !glued_after
+}
+
+/// Extract US phone numbers, as written.
+pub(crate) fn extract_phone_numbers(text: &str) -> Vec<String> {
+    dedupe_in_order(
+        PHONE_RE
+            .find_iter(text)
+            .filter(|m| is_standalone_number(text, m.start(), m.end()))
+            .map(|m| m.as_str().to_string()),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_urls_strips_trailing_punctuation() {
+        let text = "See https://example.com/ir. Also www.example.org/webcast, \
+                    and http://sec.gov/edgar?cik=1234; or https://example.com/ir!";
+        assert_eq!(
+            extract_urls(text),
+            vec![
+                "https://example.com/ir",
+                "www.example.org/webcast",
+                "http://sec.gov/edgar?cik=1234",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_extract_urls_parentheses_and_markdown() {
+        let text = "Slides (https://example.com/slides.pdf) and [the replay](https://example.com/replay). \
+                    Wiki: https://en.wikipedia.org/wiki/Acme_(company). \
+                    [https://example.com/a](https://example.com/a)";
+        assert_eq!(
+            extract_urls(text),
+            vec![
+                "https://example.com/slides.pdf",
+                "https://example.com/replay",
+                "https://en.wikipedia.org/wiki/Acme_(company)",
+                "https://example.com/a",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_extract_urls_ignores_bare_scheme() {
+        assert!(extract_urls("The https:// prefix alone is not a link.").is_empty());
+    }
+
+    #[test]
+    fn test_extract_emails() {
+        let text = "Contact ir+q3@acme.com or jane.doe@mail.investors.acme.co.uk. \
+                    Again: ir+q3@acme.com. Not an email: @acme or user@localhost.";
+        assert_eq!(
+            extract_emails(text),
+            vec!["ir+q3@acme.com", "jane.doe@mail.investors.acme.co.uk"]
+        );
+    }
+
+    #[test]
+    fn test_extract_phone_numbers_us_formats() {
+        let text = "Call (212) 555-0123, 212-555-0123 or +1 212 555 0123. \
+                    Fax 212.555.0199; again (212) 555-0123.";
+        assert_eq!(
+            extract_phone_numbers(text),
+            vec![
+                "(212) 555-0123",
+                "212-555-0123",
+                "+1 212 555 0123",
+                "212.555.0199"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_extract_phone_numbers_rejects_dates_and_amounts() {
+        let text = "Fiscal 2023-2024 guidance of $4,500-5,000 million ($212 555 0123 total). \
+                    Accession No. 0001193125-24-012345 filed 2024-03-15. \
+                    Revenue grew from 120 to 345 6789 units; ext 555-0123; ID 1212-555-01234.";
+        assert!(
+            extract_phone_numbers(text).is_empty(),
+            "{:?}",
+            extract_phone_numbers(text)
+        );
+    }
+}
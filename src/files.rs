@@ -0,0 +1,141 @@
+//! Reading documents from disk, backing `process_file` and `process_files`.
+//!
+//! Files are read and decoded in Rust so the GIL can stay released from the
+//! first byte to the last chunk. Only UTF-8 and Latin-1 are supported: Latin-1
+//! maps every byte to a character, which makes it the usual last resort for
+//! legacy exports whose real encoding is unknown.
+
+use std::io;
+use std::path::Path;
+
+/// How the bytes of a file are turned into text.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Encoding {
+    /// UTF-8; invalid bytes are an error.
+    #[default]
+    Utf8,
+    /// UTF-8, replacing invalid bytes with U+FFFD.
+    Utf8Lossy,
+    /// Latin-1 (ISO-8859-1): each byte is the code point of the same value.
+    Latin1,
+    /// UTF-8 if the whole file is valid UTF-8, Latin-1 otherwise.
+    Utf8OrLatin1,
+}
+
+impl Encoding {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "utf-8" => Some(Encoding::Utf8),
+            "utf-8-lossy" => Some(Encoding::Utf8Lossy),
+            "latin-1" => Some(Encoding::Latin1),
+            "utf-8-or-latin-1" => Some(Encoding::Utf8OrLatin1),
+            _ => None,
+        }
+    }
+}
+
+/// Why a file could not be turned into text.
+#[derive(Debug)]
+pub enum ReadError {
+    Io(io::Error),
+    /// The file is not valid UTF-8 and `Encoding::Utf8` was requested.
+    Decode {
+        bytes: Vec<u8>,
+        /// Byte range of the first invalid sequence.
+        start: usize,
+        end: usize,
+    },
+}
+
+/// Decode `bytes` with `encoding`. A UTF-8 byte order mark is dropped, as
+/// Python's "utf-8-sig" would.
+pub(crate) fn decode(bytes: Vec<u8>, encoding: Encoding) -> Result<String, ReadError> {
+    let latin1 = |bytes: &[u8]| bytes.iter().map(|&b| char::from(b)).collect::<String>();
+    let text = match encoding {
+        Encoding::Latin1 => return Ok(latin1(&bytes)),
+        Encoding::Utf8Lossy => match String::from_utf8(bytes) {
+            Ok(text) => text,
+            Err(e) => String::from_utf8_lossy(e.as_bytes()).into_owned(),
+        },
+        Encoding::Utf8OrLatin1 => match String::from_utf8(bytes) {
+            Ok(text) => text,
+            Err(e) => return Ok(latin1(e.as_bytes())),
+        },
+        Encoding::Utf8 => match String::from_utf8(bytes) {
+            Ok(text) => text,
+            Err(e) => {
+                let error = e.utf8_error();
+                let start = error.valid_up_to();
+                let bytes = e.into_bytes();
+                let end = error.error_len().map_or(bytes.len(), |len| start + len);
+                return Err(ReadError::Decode { bytes, start, end });
+            }
+        },
+    };
+    Ok(match text.strip_prefix('\u{feff}') {
+        Some(rest) => rest.to_string(),
+        None => text,
+    })
+}
+
+/// Read the file at `path` and decode it with `encoding`.
+pub fn read_text_file(path: &Path, encoding: Encoding) -> Result<String, ReadError> {
+    decode(std::fs::read(path).map_err(ReadError::Io)?, encoding)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_valid_utf8_and_bom() {
+        let text = "Revenue rose to €5M.";
+        for encoding in [Encoding::Utf8, Encoding::Utf8Lossy, Encoding::Utf8OrLatin1] {
+            assert_eq!(decode(text.into(), encoding).unwrap(), text);
+            let with_bom = format!("\u{feff}{text}");
+            assert_eq!(decode(with_bom.into(), encoding).unwrap(), text);
+        }
+    }
+
+    #[test]
+    fn test_decode_invalid_bytes() {
+        // "café" in Latin-1, then valid UTF-8
+        let bytes = b"caf\xe9 \xe2\x82\xac5M".to_vec();
+        match decode(bytes.clone(), Encoding::Utf8) {
+            Err(ReadError::Decode { start, end, .. }) => assert_eq!((start, end), (3, 4)),
+            other => panic!("expected a decode error, got {other:?}"),
+        }
+        assert_eq!(
+            decode(bytes.clone(), Encoding::Utf8Lossy).unwrap(),
+            "caf\u{fffd} €5M"
+        );
+        assert_eq!(
+            decode(bytes.clone(), Encoding::Utf8OrLatin1).unwrap(),
+            "café â\u{82}¬5M"
+        );
+        assert_eq!(decode(bytes, Encoding::Latin1).unwrap(), "café â\u{82}¬5M");
+    }
+
+    #[test]
+    fn test_decode_truncated_sequence_at_end() {
+        match decode(b"ok \xe2\x82".to_vec(), Encoding::Utf8) {
+            Err(ReadError::Decode { start, end, .. }) => assert_eq!((start, end), (3, 5)),
+            other => panic!("expected a decode error, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_read_text_file() {
+        let path = std::env::temp_dir().join(format!("rag_core_files_{}.txt", std::process::id()));
+        std::fs::write(&path, b"Q1 2024 revenue: \xa35M").unwrap();
+        assert_eq!(
+            read_text_file(&path, Encoding::Utf8OrLatin1).unwrap(),
+            "Q1 2024 revenue: £5M"
+        );
+        std::fs::remove_file(&path).unwrap();
+        match read_text_file(&path, Encoding::Utf8) {
+            Err(ReadError::Io(e)) => assert_eq!(e.kind(), io::ErrorKind::NotFound),
+            other => panic!("expected an I/O error, got {other:?}"),
+        }
+    }
+}
@@ -0,0 +1,168 @@
+//! Parsing of fiscal period matches found by `FISCAL_PERIOD_RE` in `lib.rs`.
+//!
+//! Every surface form ("FY23", "Q1 FY2025", "first half of 2024", "three
+//! months ended June 30, 2024") reduces to a period kind, the quarter or half
+//! it numbers, and the year it falls in, so metrics can be attached to
+//! periods whichever way a filing writes them.
+
+use crate::dates::{parse_date, DateFormat, DateValue};
+use serde::Serialize;
+
+/// A fiscal period match broken down into kind, number and year.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ParsedFiscalPeriod {
+    pub raw: String,
+    /// "FY", "H" (half year), "Q" (quarter), "9M" (nine months), or one of the
+    /// period-over-period tokens "QoQ", "YoY" and "YTD".
+    pub kind: &'static str,
+    /// Half (1-2) or quarter (1-4), when the period names one.
+    pub number: Option<u32>,
+    /// Year the period falls in, with two-digit years read as 20xx.
+    pub year: Option<u32>,
+}
+
+/// Number of an ordinal word ("first" to "fourth").
+fn ordinal_number(word: &str) -> Option<u32> {
+    ["first", "second", "third", "fourth"]
+        .iter()
+        .position(|&w| w == word)
+        .map(|i| i as u32 + 1)
+}
+
+/// Kind and number of a period code: "fy2024", "fiscal", "q1", "h2", "3q24", "1h24".
+fn code_period(code: &str) -> (&'static str, Option<u32>) {
+    if code.starts_with("fy") || code == "fiscal" {
+        return ("FY", None);
+    }
+    let bytes = code.as_bytes();
+    let (unit, digit) = match bytes {
+        [unit @ (b'q' | b'h'), digit, ..] | [digit, unit @ (b'q' | b'h'), ..] => (*unit, *digit),
+        _ => return ("FY", None),
+    };
+    let kind = if unit == b'q' { "Q" } else { "H" };
+    (kind, Some((digit - b'0') as u32))
+}
+
+/// Kind and number of "<length> months ended <date>": three months are the
+/// calendar quarter the end date falls in, six months the half year when they
+/// end in June or December, twelve months the fiscal year ending then.
+fn months_ended(length: &str, end_date: &str) -> (&'static str, Option<u32>) {
+    let month = match parse_date(end_date, DateFormat::Us) {
+        Some(DateValue::Day { month, .. }) => Some(month),
+        _ => None,
+    };
+    match length {
+        "three" => ("Q", month.map(|m| m.div_ceil(3))),
+        "six" => (
+            "H",
+            month
+                .and_then(|m| [6, 12].iter().position(|&e| e == m))
+                .map(|i| i as u32 + 1),
+        ),
+        "nine" => ("9M", None),
+        _ => ("FY", None),
+    }
+}
+
+/// The year a period ends with: its trailing digits, two-digit years read as 20xx.
+fn trailing_year(raw: &str) -> Option<u32> {
+    let digits = raw.len() - raw.trim_end_matches(|c: char| c.is_ascii_digit()).len();
+    let year: u32 = raw[raw.len() - digits..].parse().ok()?;
+    match digits {
+        2 => Some(2000 + year),
+        4 => Some(year),
+        _ => None,
+    }
+}
+
+/// Parse a fiscal period match.
+pub(crate) fn parse_fiscal_period(raw: &str) -> ParsedFiscalPeriod {
+    let lower = raw.to_lowercase();
+    let words: Vec<&str> = lower
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|w| !w.is_empty())
+        .collect();
+    let comparison = match words.as_slice() {
+        ["qoq"] => Some("QoQ"),
+        ["yoy"] => Some("YoY"),
+        ["ytd"] => Some("YTD"),
+        _ => None,
+    };
+    if let Some(kind) = comparison {
+        return ParsedFiscalPeriod {
+            raw: raw.to_string(),
+            kind,
+            number: None,
+            year: None,
+        };
+    }
+    let (kind, number) = match words.as_slice() {
+        [length, "months", _, end_date @ ..] => months_ended(length, &end_date.join(" ")),
+        [ordinal, "quarter", "of", ..] => ("Q", ordinal_number(ordinal)),
+        [ordinal, "half", "of", ..] => ("H", ordinal_number(ordinal)),
+        [code, ..] => code_period(code),
+        [] => ("FY", None),
+    };
+    ParsedFiscalPeriod {
+        raw: raw.to_string(),
+        kind,
+        number,
+        year: trailing_year(raw.trim()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parts(raw: &str) -> (&'static str, Option<u32>, Option<u32>) {
+        let parsed = parse_fiscal_period(raw);
+        (parsed.kind, parsed.number, parsed.year)
+    }
+
+    #[test]
+    fn test_parse_fiscal_period() {
+        let cases = [
+            ("FY2024", ("FY", None, Some(2024))),
+            ("FY 23", ("FY", None, Some(2023))),
+            ("fiscal year 2023", ("FY", None, Some(2023))),
+            ("Fiscal 2025", ("FY", None, Some(2025))),
+            ("Q1 FY2025", ("Q", Some(1), Some(2025))),
+            ("Q4 fiscal 2024", ("Q", Some(4), Some(2024))),
+            ("3Q24", ("Q", Some(3), Some(2024))),
+            ("H1 2023", ("H", Some(1), Some(2023))),
+            ("H2 FY24", ("H", Some(2), Some(2024))),
+            ("2H 2023", ("H", Some(2), Some(2023))),
+            ("first half of 2024", ("H", Some(1), Some(2024))),
+            ("Second Quarter of Fiscal 2025", ("Q", Some(2), Some(2025))),
+            ("fourth quarter of FY2023", ("Q", Some(4), Some(2023))),
+            (
+                "three months ended June 30, 2024",
+                ("Q", Some(2), Some(2024)),
+            ),
+            (
+                "three months ended Sept. 28, 2024",
+                ("Q", Some(3), Some(2024)),
+            ),
+            (
+                "six months ended December 31, 2023",
+                ("H", Some(2), Some(2023)),
+            ),
+            ("six months ended March 31, 2024", ("H", None, Some(2024))),
+            (
+                "nine months ended September 30, 2024",
+                ("9M", None, Some(2024)),
+            ),
+            (
+                "twelve months ended June 30, 2024",
+                ("FY", None, Some(2024)),
+            ),
+            ("YoY", ("YoY", None, None)),
+            ("qoq", ("QoQ", None, None)),
+            ("YTD", ("YTD", None, None)),
+        ];
+        for (raw, expected) in cases {
+            assert_eq!(parts(raw), expected, "{raw}");
+        }
+    }
+}
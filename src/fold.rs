@@ -0,0 +1,102 @@
+//! Accent folding for `CleanOptions(fold_to_ascii=True)`.
+//!
+//! Latin letters lose their diacritics ("café" -> "cafe") and a few letters
+//! without a decomposition get a conventional ASCII spelling ("ß" -> "ss").
+//! This is not transliteration: Greek, Cyrillic, CJK and other scripts, and
+//! symbols like "€", have no ASCII form here and are kept as they are.
+
+use unicode_normalization::char::{decompose_canonical, is_combining_mark};
+
+/// ASCII spellings of Latin letters that do not decompose into a base letter
+/// and combining marks.
+const ASCII_EQUIVALENTS: &[(char, &str)] = &[
+    ('ß', "ss"),
+    ('ẞ', "SS"),
+    ('æ', "ae"),
+    ('Æ', "AE"),
+    ('œ', "oe"),
+    ('Œ', "OE"),
+    ('ø', "o"),
+    ('Ø', "O"),
+    ('ð', "d"),
+    ('Ð', "D"),
+    ('đ', "d"),
+    ('Đ', "D"),
+    ('þ', "th"),
+    ('Þ', "Th"),
+    ('ł', "l"),
+    ('Ł', "L"),
+    ('ħ', "h"),
+    ('Ħ', "H"),
+    ('ı', "i"),
+    ('ŧ', "t"),
+    ('Ŧ', "T"),
+];
+
+/// Fold accented Latin letters in `text` to ASCII, keeping every character
+/// that has no ASCII form unchanged.
+///
+/// A letter whose canonical decomposition is an ASCII letter plus combining
+/// marks becomes that letter; combining marks written separately after an
+/// ASCII letter ("e\u{301}") are dropped too. Marks on other bases, such as
+/// Devanagari vowel signs, are kept.
+pub(crate) fn fold_to_ascii(text: &str) -> String {
+    if text.is_ascii() {
+        return text.to_string();
+    }
+    let mut out = String::with_capacity(text.len());
+    // Whether the last base character was written as ASCII, so marks after it are accents
+    let mut ascii_base = false;
+    for c in text.chars() {
+        if c.is_ascii() {
+            out.push(c);
+            ascii_base = true;
+        } else if is_combining_mark(c) {
+            if !ascii_base {
+                out.push(c);
+            }
+        } else if let Some(&(_, ascii)) = ASCII_EQUIVALENTS.iter().find(|&&(k, _)| k == c) {
+            out.push_str(ascii);
+            ascii_base = true;
+        } else {
+            let mut decomposed = Vec::new();
+            decompose_canonical(c, |d| decomposed.push(d));
+            match decomposed.split_first() {
+                Some((&base, marks))
+                    if base.is_ascii() && marks.iter().all(|&m| is_combining_mark(m)) =>
+                {
+                    out.push(base);
+                    ascii_base = true;
+                }
+                _ => {
+                    out.push(c);
+                    ascii_base = false;
+                }
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_folds_accented_latin() {
+        assert_eq!(fold_to_ascii("café naïve Ångström"), "cafe naive Angstrom");
+        assert_eq!(
+            fold_to_ascii("Ærøskøbing, Łódź, Straße, Việt Nam"),
+            "AEroskobing, Lodz, Strasse, Viet Nam"
+        );
+        // Decomposed input: the separate combining acute goes too
+        assert_eq!(fold_to_ascii("cafe\u{301}"), "cafe");
+    }
+
+    #[test]
+    fn test_keeps_characters_without_ascii_form() {
+        let text = "€5 Москва 東京 \u{0928}\u{092E}\u{0938}\u{094D}\u{0924}\u{0947} Ελλάδα";
+        assert_eq!(fold_to_ascii(text), text);
+        assert_eq!(fold_to_ascii("plain ASCII"), "plain ASCII");
+    }
+}
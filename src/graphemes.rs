@@ -0,0 +1,210 @@
+//! Grapheme clusters: the characters a reader sees, which chunk sizes and
+//! `char_count` are measured in.
+//!
+//! A cluster is a base character with everything attached to it, following the
+//! extended grapheme cluster rules of UAX #29 that matter for prose: combining
+//! marks, variation selectors and emoji modifiers (so a decomposed "é" is one
+//! cluster), emoji ZWJ sequences ("👨‍👩‍👧‍👦"), regional indicator pairs (flags),
+//! Hangul syllable sequences and CR LF. Prepend characters and Indic conjuncts
+//! are not joined.
+
+use unicode_normalization::char::is_combining_mark;
+
+const ZWJ: char = '\u{200D}';
+
+/// Attaches to the preceding character without a ZWJ.
+fn is_extend(c: char) -> bool {
+    is_combining_mark(c)
+        || matches!(c,
+            '\u{200C}' // ZWNJ
+            | '\u{FE00}'..='\u{FE0F}' // Variation selectors
+            | '\u{1F3FB}'..='\u{1F3FF}' // Skin tone modifiers
+            | '\u{E0020}'..='\u{E007F}' // Tags (subdivision flags)
+            | '\u{E0100}'..='\u{E01EF}')
+}
+
+/// Never part of a larger cluster, except CR LF.
+fn is_control(c: char) -> bool {
+    c.is_control() || matches!(c, '\u{2028}' | '\u{2029}')
+}
+
+fn is_regional_indicator(c: char) -> bool {
+    matches!(c, '\u{1F1E6}'..='\u{1F1FF}')
+}
+
+/// Emoji and symbols that a ZWJ joins into one cluster (a superset of
+/// Extended_Pictographic outside the emoji blocks, which only matters after a ZWJ).
+fn is_pictographic(c: char) -> bool {
+    matches!(c,
+        '\u{00A9}' | '\u{00AE}' | '\u{203C}' | '\u{2049}' | '\u{2122}' | '\u{2139}'
+        | '\u{2194}'..='\u{21AA}'
+        | '\u{2300}'..='\u{23FF}'
+        | '\u{25A0}'..='\u{27BF}'
+        | '\u{2B00}'..='\u{2BFF}'
+        | '\u{3030}' | '\u{303D}' | '\u{3297}' | '\u{3299}'
+        | '\u{1F000}'..='\u{1FAFF}')
+        && !is_regional_indicator(c)
+}
+
+/// Hangul jamo and syllable types, for rules GB6-GB8.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Hangul {
+    Leading,
+    Vowel,
+    Trailing,
+    Lv,
+    Lvt,
+}
+
+fn hangul(c: char) -> Option<Hangul> {
+    match c {
+        '\u{1100}'..='\u{115F}' | '\u{A960}'..='\u{A97C}' => Some(Hangul::Leading),
+        '\u{1160}'..='\u{11A7}' | '\u{D7B0}'..='\u{D7C6}' => Some(Hangul::Vowel),
+        '\u{11A8}'..='\u{11FF}' | '\u{D7CB}'..='\u{D7FB}' => Some(Hangul::Trailing),
+        '\u{AC00}'..='\u{D7A3}' if (c as u32 - 0xAC00).is_multiple_of(28) => Some(Hangul::Lv),
+        '\u{AC00}'..='\u{D7A3}' => Some(Hangul::Lvt),
+        _ => None,
+    }
+}
+
+/// The cluster being built, as much of it as the break rules look at.
+struct Cluster {
+    last: char,
+    /// Regional indicators at the end of the cluster.
+    regional_indicators: usize,
+    /// Whether the cluster holds a pictograph a ZWJ can extend.
+    pictographic: bool,
+}
+
+impl Cluster {
+    fn start(c: char) -> Self {
+        Cluster {
+            last: c,
+            regional_indicators: usize::from(is_regional_indicator(c)),
+            pictographic: is_pictographic(c),
+        }
+    }
+
+    fn continues_with(&self, c: char) -> bool {
+        use Hangul::*;
+        if self.last == '\r' && c == '\n' {
+            return true;
+        }
+        if is_control(self.last) || is_control(c) {
+            return false;
+        }
+        if is_extend(c) || c == ZWJ {
+            return true;
+        }
+        if self.last == ZWJ {
+            return self.pictographic && is_pictographic(c);
+        }
+        if is_regional_indicator(c) {
+            return self.regional_indicators % 2 == 1;
+        }
+        matches!(
+            (hangul(self.last), hangul(c)),
+            (Some(Leading), Some(Leading | Vowel | Lv | Lvt))
+                | (Some(Lv | Vowel), Some(Vowel | Trailing))
+                | (Some(Lvt | Trailing), Some(Trailing))
+        )
+    }
+
+    fn push(&mut self, c: char) {
+        self.last = c;
+        self.regional_indicators = if is_regional_indicator(c) {
+            self.regional_indicators + 1
+        } else {
+            0
+        };
+        self.pictographic |= is_pictographic(c);
+    }
+}
+
+/// Byte offsets just past each grapheme cluster of `s`, in order.
+fn cluster_ends(s: &str) -> impl Iterator<Item = usize> + '_ {
+    let mut chars = s.char_indices().peekable();
+    std::iter::from_fn(move || {
+        let (_, first) = chars.next()?;
+        let mut cluster = Cluster::start(first);
+        while let Some(&(i, c)) = chars.peek() {
+            if !cluster.continues_with(c) {
+                return Some(i);
+            }
+            cluster.push(c);
+            chars.next();
+        }
+        Some(s.len())
+    })
+}
+
+/// Number of grapheme clusters in `s`.
+pub(crate) fn count(s: &str) -> usize {
+    cluster_ends(s).count()
+}
+
+/// The grapheme clusters of `s`, in order.
+pub(crate) fn clusters(s: &str) -> impl Iterator<Item = &str> + '_ {
+    let mut start = 0;
+    cluster_ends(s).map(move |end| {
+        let cluster = &s[start..end];
+        start = end;
+        cluster
+    })
+}
+
+/// Byte offset just past the first `n` grapheme clusters of `s`, or `s.len()`
+/// if it has no more than `n`.
+pub(crate) fn byte_offset(s: &str, n: usize) -> usize {
+    match n {
+        0 => 0,
+        _ => cluster_ends(s).nth(n - 1).unwrap_or(s.len()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FLAG: &str = "\u{1F1E9}\u{1F1EA}";
+    const FAMILY: &str = "\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}\u{200D}\u{1F466}";
+    const DECOMPOSED: &str = "cafe\u{301}";
+
+    #[test]
+    fn test_count_clusters() {
+        assert_eq!(count(""), 0);
+        assert_eq!(count("abc"), 3);
+        assert_eq!(count(FLAG), 1);
+        assert_eq!(count(FAMILY), 1);
+        assert_eq!(count(DECOMPOSED), 4);
+        assert_eq!(count("\u{1F44D}\u{1F3FD}"), 1); // Thumbs up, medium skin tone
+        assert_eq!(count("\u{2764}\u{FE0F}"), 1);
+        assert_eq!(count("\r\n"), 1);
+        assert_eq!(count("\u{1100}\u{1161}\u{11A8}"), 1); // Decomposed Hangul syllable
+        assert_eq!(count(&format!("{FLAG}{FLAG}\u{1F1E9}")), 3);
+    }
+
+    #[test]
+    fn test_zwj_only_joins_pictographs() {
+        assert_eq!(count("a\u{200D}b"), 2);
+        assert_eq!(count("\u{1F469}\u{200D}\u{1F52C}"), 1); // Woman scientist
+    }
+
+    #[test]
+    fn test_byte_offset() {
+        let text = format!("{FAMILY}{DECOMPOSED}{FLAG}");
+        assert_eq!(byte_offset(&text, 0), 0);
+        assert_eq!(byte_offset(&text, 1), FAMILY.len());
+        assert_eq!(byte_offset(&text, 5), FAMILY.len() + DECOMPOSED.len());
+        assert_eq!(byte_offset(&text, 6), text.len());
+        assert_eq!(byte_offset(&text, 99), text.len());
+    }
+
+    #[test]
+    fn test_clusters() {
+        let text = format!("a{FLAG}{DECOMPOSED}");
+        let clusters: Vec<&str> = clusters(&text).collect();
+        assert_eq!(clusters, vec!["a", FLAG, "c", "a", "f", "e\u{301}"]);
+        assert_eq!(clusters.concat(), text);
+    }
+}
@@ -0,0 +1,282 @@
+//! Lightweight HTML-to-text conversion for scraped documents.
+//!
+//! This is a forgiving tag scanner, not an HTML5 parser: it drops tags and
+//! comments, skips the contents of `script`/`style`/`noscript`, turns block
+//! element boundaries into paragraph breaks and decodes character references.
+//! Malformed markup never fails; a `<` that does not start a tag is kept as text
+//! and a tag left open at the end of the input is dropped.
+
+/// Elements whose contents are never text.
+const RAW_TEXT_ELEMENTS: &[&str] = &["script", "style", "noscript"];
+
+/// Elements whose start and end tags become paragraph breaks.
+const BLOCK_ELEMENTS: &[&str] = &[
+    "address",
+    "article",
+    "aside",
+    "blockquote",
+    "dd",
+    "div",
+    "dl",
+    "dt",
+    "figcaption",
+    "figure",
+    "footer",
+    "form",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "header",
+    "hr",
+    "li",
+    "main",
+    "nav",
+    "ol",
+    "p",
+    "pre",
+    "section",
+    "table",
+    "title",
+    "tr",
+    "ul",
+];
+
+/// Named character references decoded by `decode_entities_into`.
+const NAMED_ENTITIES: &[(&str, char)] = &[
+    ("amp", '&'),
+    ("lt", '<'),
+    ("gt", '>'),
+    ("quot", '"'),
+    ("apos", '\''),
+    ("nbsp", '\u{A0}'),
+    ("ndash", '\u{2013}'),
+    ("mdash", '\u{2014}'),
+    ("lsquo", '\u{2018}'),
+    ("rsquo", '\u{2019}'),
+    ("ldquo", '\u{201C}'),
+    ("rdquo", '\u{201D}'),
+    ("hellip", '\u{2026}'),
+    ("bull", '\u{2022}'),
+    ("middot", '\u{B7}'),
+    ("copy", '\u{A9}'),
+    ("reg", '\u{AE}'),
+    ("trade", '\u{2122}'),
+    ("cent", '\u{A2}'),
+    ("pound", '\u{A3}'),
+    ("yen", '\u{A5}'),
+    ("euro", '\u{20AC}'),
+];
+
+/// Longest reference body (between `&` and `;`) worth trying to decode.
+const MAX_ENTITY_LEN: usize = 32;
+
+/// What a `<` at the current position turned out to start.
+enum Markup<'a> {
+    /// Not markup; emit the `<` as text.
+    Text,
+    /// A comment, doctype or processing instruction.
+    Skip,
+    Tag {
+        name: &'a str,
+        closing: bool,
+    },
+}
+
+/// Convert HTML to plain text, keeping paragraph structure as blank lines.
+pub(crate) fn strip_html(html: &str) -> String {
+    let mut out = String::with_capacity(html.len());
+    let mut rest = html;
+
+    while let Some(lt) = rest.find('<') {
+        decode_entities_into(&rest[..lt], &mut out);
+        rest = &rest[lt..];
+
+        let (markup, len) = scan_markup(rest);
+        match markup {
+            Markup::Text => out.push('<'),
+            Markup::Skip => {}
+            Markup::Tag { name, closing } => {
+                let name = name.to_ascii_lowercase();
+                if name == "br" {
+                    out.push('\n');
+                } else if name == "td" || name == "th" {
+                    out.push(' ');
+                } else if BLOCK_ELEMENTS.contains(&name.as_str()) {
+                    out.push_str("\n\n");
+                }
+                if !closing && RAW_TEXT_ELEMENTS.contains(&name.as_str()) {
+                    rest = &rest[len..];
+                    rest = &rest[raw_text_end(rest, &name)..];
+                    continue;
+                }
+            }
+        }
+        rest = &rest[len..];
+    }
+
+    decode_entities_into(rest, &mut out);
+    out
+}
+
+/// Classify the markup at the start of `s` (which begins with `<`) and return its length.
+fn scan_markup(s: &str) -> (Markup<'_>, usize) {
+    if let Some(body) = s.strip_prefix("<!--") {
+        let len = body.find("-->").map_or(s.len(), |i| 4 + i + 3);
+        return (Markup::Skip, len);
+    }
+
+    let after_lt = &s[1..];
+    let (closing, name_start) = match after_lt.chars().next() {
+        Some('!' | '?') => return (Markup::Skip, tag_end(s, 1)),
+        Some('/') => (true, 2),
+        Some(c) if c.is_ascii_alphabetic() => (false, 1),
+        _ => return (Markup::Text, 1),
+    };
+
+    let name_len = s[name_start..]
+        .find(|c: char| !c.is_ascii_alphanumeric())
+        .unwrap_or(s.len() - name_start);
+    if name_len == 0 || !s[name_start..].starts_with(|c: char| c.is_ascii_alphabetic()) {
+        return (Markup::Text, 1);
+    }
+
+    let name = &s[name_start..name_start + name_len];
+    (
+        Markup::Tag { name, closing },
+        tag_end(s, name_start + name_len),
+    )
+}
+
+/// Byte offset just past the `>` closing the tag that starts at the beginning of `s`.
+///
+/// Quoted attribute values may contain `>`; a quote only opens a value when it
+/// follows `=`, so apostrophes in unquoted values don't swallow the rest of the
+/// document. Returns `s.len()` for a tag that is never closed.
+fn tag_end(s: &str, from: usize) -> usize {
+    let mut quote = None;
+    let mut after_eq = false;
+    for (i, c) in s[from..].char_indices() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None => match c {
+                '>' => return from + i + 1,
+                '"' | '\'' if after_eq => quote = Some(c),
+                _ => {}
+            },
+        }
+        if !c.is_whitespace() {
+            after_eq = quote.is_none() && c == '=';
+        }
+    }
+    s.len()
+}
+
+/// Byte offset just past the end tag of raw text element `name`, or `s.len()`.
+fn raw_text_end(s: &str, name: &str) -> usize {
+    let mut offset = 0;
+    while let Some(i) = s[offset..].find("</") {
+        let start = offset + i;
+        let name_end = start + 2 + name.len();
+        let matches_name = s
+            .get(start + 2..name_end)
+            .is_some_and(|n| n.eq_ignore_ascii_case(name));
+        let terminated =
+            !s[name_end.min(s.len())..].starts_with(|c: char| c.is_ascii_alphanumeric());
+        if matches_name && terminated {
+            return tag_end(s, name_end);
+        }
+        offset = start + 2;
+    }
+    s.len()
+}
+
+/// Append `text` to `out`, decoding named and numeric character references.
+/// Unknown or malformed references are kept as written.
+fn decode_entities_into(text: &str, out: &mut String) {
+    let mut rest = text;
+    while let Some(amp) = rest.find('&') {
+        out.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+        match decode_entity(rest) {
+            Some((c, len)) => {
+                out.push(c);
+                rest = &rest[len..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+}
+
+/// Decode the reference at the start of `s` (which begins with `&`), returning the
+/// character and the reference's length including the trailing `;`.
+fn decode_entity(s: &str) -> Option<(char, usize)> {
+    let semi = s.bytes().take(MAX_ENTITY_LEN + 2).position(|b| b == b';')?;
+    let body = &s[1..semi];
+    let c = if let Some(num) = body.strip_prefix('#') {
+        let code = match num.strip_prefix(['x', 'X']) {
+            Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+            None => num.parse().ok()?,
+        };
+        char::from_u32(code).filter(|c| *c != '\0')?
+    } else {
+        NAMED_ENTITIES
+            .iter()
+            .find(|(name, _)| *name == body)
+            .map(|(_, c)| *c)?
+    };
+    Some((c, semi + 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strip_html_blocks_become_paragraphs() {
+        let html = "<html><body><h1>Results</h1><p>Revenue grew.</p><div>Costs fell.<br>Again.</div></body></html>";
+        let text = strip_html(html);
+        assert_eq!(
+            text.split("\n\n")
+                .map(str::trim)
+                .filter(|p| !p.is_empty())
+                .collect::<Vec<_>>(),
+            vec!["Results", "Revenue grew.", "Costs fell.\nAgain."]
+        );
+    }
+
+    #[test]
+    fn test_strip_html_drops_script_style_and_comments() {
+        let html =
+            "<p>Keep</p><script type=\"text/javascript\">if (a < b) { x = '</p>'; }</script>\
+                    <STYLE>p { color: red }</STYLE><noscript>Enable JS</noscript><!-- note -->tail";
+        let text = strip_html(html);
+        assert!(text.contains("Keep"));
+        assert!(text.contains("tail"));
+        for junk in ["if (a", "color", "Enable JS", "note"] {
+            assert!(!text.contains(junk), "{junk:?} leaked into {text:?}");
+        }
+    }
+
+    #[test]
+    fn test_strip_html_decodes_entities() {
+        let text = strip_html("AT&amp;T&#8217;s &quot;Q3&quot; &#x2014; &lt;b&gt; &bogus; & done");
+        assert_eq!(text, "AT&T\u{2019}s \"Q3\" \u{2014} <b> &bogus; & done");
+    }
+
+    #[test]
+    fn test_strip_html_tolerates_malformed_markup() {
+        assert_eq!(strip_html("<a title=\"x > y\">link</a>"), "link");
+        assert_eq!(strip_html("<a title=don't>link</a>"), "link");
+        assert_eq!(strip_html("a < b and 3 <4"), "a < b and 3 <4");
+        assert_eq!(strip_html("before <p class=\"unterminated"), "before \n\n");
+        assert_eq!(strip_html("<script>never closed"), "");
+    }
+}
@@ -0,0 +1,259 @@
+//! JSON counterparts of the dicts built for Python, for output written without
+//! holding the GIL.
+//!
+//! The `Serialize` derives on the metadata and chunk types mirror their
+//! `into_py_dict` key for key, so a chunk read back with `json.loads` equals
+//! the dict `process_document` returns for it. The shapes a derive cannot
+//! express (the keys derived from the parsed amounts, percentages and dates,
+//! `ticker_counts`, custom patterns, the document summary) go through the
+//! helpers here. `to_json` sorts object keys;
+//! non-finite floats become null.
+
+use crate::dates::DateValue;
+use crate::{
+    DocumentMetadata, MetadataSpans, ParsedDate, ParsedMoney, ParsedPercentage, Span, TickerCount,
+};
+use serde::ser::SerializeMap;
+use serde::{Serialize, Serializer};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// `value` as a JSON value, with object keys sorted.
+pub(crate) fn to_json(value: impl Serialize) -> Value {
+    // Every map these types serialize has string keys, so this cannot fail
+    serde_json::to_value(value).expect("string keys")
+}
+
+/// A `monetary_amounts_normalized` or `percentages_normalized` entry.
+#[derive(Serialize)]
+struct NormalizedJson<'a> {
+    raw: &'a str,
+    value: Option<f64>,
+    sign_inferred: bool,
+}
+
+/// A `monetary_amounts_detailed` entry, for an amount with a currency.
+#[derive(Serialize)]
+struct MoneyDetailJson<'a> {
+    raw: &'a str,
+    currency: &'a str,
+}
+
+/// The `monetary_amounts*` and `monetary_values` keys, read off the parsed amounts.
+pub(crate) fn monetary_amounts<S: Serializer>(
+    amounts: &[ParsedMoney],
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let raw: Vec<&str> = amounts.iter().map(|m| m.raw.as_str()).collect();
+    let detailed: Vec<MoneyDetailJson> = amounts
+        .iter()
+        .filter_map(|m| {
+            Some(MoneyDetailJson {
+                raw: &m.raw,
+                currency: m.currency.as_deref()?,
+            })
+        })
+        .collect();
+    let normalized: Vec<NormalizedJson> = amounts
+        .iter()
+        .map(|m| NormalizedJson {
+            raw: &m.raw,
+            value: m.value,
+            sign_inferred: m.sign_inferred,
+        })
+        .collect();
+    let values: Vec<Option<f64>> = amounts.iter().map(|m| m.value).collect();
+    let mut map = serializer.serialize_map(Some(5))?;
+    map.serialize_entry("monetary_amounts", &raw)?;
+    map.serialize_entry("monetary_amounts_detailed", &detailed)?;
+    map.serialize_entry("monetary_amounts_normalized", &normalized)?;
+    map.serialize_entry("monetary_values", &values)?;
+    map.serialize_entry("monetary_amounts_parsed", amounts)?;
+    map.end()
+}
+
+/// The `percentages*` and `percentage_values` keys, read off the parsed percentages.
+pub(crate) fn percentages<S: Serializer>(
+    percentages: &[ParsedPercentage],
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let raw: Vec<&str> = percentages.iter().map(|p| p.raw.as_str()).collect();
+    let normalized: Vec<NormalizedJson> = percentages
+        .iter()
+        .map(|p| NormalizedJson {
+            raw: &p.raw,
+            value: p.value,
+            sign_inferred: p.sign_inferred,
+        })
+        .collect();
+    let values: Vec<Option<f64>> = percentages.iter().map(|p| p.value).collect();
+    let mut map = serializer.serialize_map(Some(4))?;
+    map.serialize_entry("percentages", &raw)?;
+    map.serialize_entry("percentages_normalized", &normalized)?;
+    map.serialize_entry("percentage_values", &values)?;
+    map.serialize_entry("percentages_parsed", percentages)?;
+    map.end()
+}
+
+/// The `dates*` keys, read off the parsed dates.
+pub(crate) fn dates<S: Serializer>(dates: &[ParsedDate], serializer: S) -> Result<S::Ok, S::Error> {
+    let raw: Vec<&str> = dates.iter().map(|d| d.raw.as_str()).collect();
+    let normalized: Vec<Option<String>> = dates
+        .iter()
+        .map(|d| d.value.and_then(|v| v.canonical()))
+        .collect();
+    let overridden: Vec<&str> = dates
+        .iter()
+        .filter(|d| d.format_overridden)
+        .map(|d| d.raw.as_str())
+        .collect();
+    let mut map = serializer.serialize_map(Some(4))?;
+    map.serialize_entry("dates", &raw)?;
+    map.serialize_entry("dates_parsed", dates)?;
+    map.serialize_entry("dates_normalized", &normalized)?;
+    map.serialize_entry("dates_format_overridden", &overridden)?;
+    map.end()
+}
+
+/// `ticker_counts` as an object of symbol to count.
+pub(crate) fn ticker_counts<S: Serializer>(
+    counts: &[TickerCount],
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.collect_map(counts.iter().map(|t| (&t.symbol, t.count)))
+}
+
+/// Matches of user-registered patterns as extra keys of the metadata object.
+pub(crate) fn custom_patterns<S: Serializer>(
+    custom: &[(String, Vec<String>)],
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.collect_map(custom.iter().map(|(name, matches)| (name, matches)))
+}
+
+/// A `dates_parsed` entry: `normalized` is `{"year", "quarter"}` for a
+/// quarter and the ISO string otherwise, and None for an impossible date.
+#[derive(Serialize)]
+pub(crate) struct ParsedDateJson {
+    raw: String,
+    kind: Option<&'static str>,
+    normalized: Option<NormalizedDate>,
+    valid: bool,
+}
+
+#[derive(Serialize)]
+#[serde(untagged)]
+enum NormalizedDate {
+    Quarter { year: u32, quarter: u32 },
+    Iso(String),
+}
+
+impl From<ParsedDate> for ParsedDateJson {
+    fn from(date: ParsedDate) -> Self {
+        let valid = date.value.filter(DateValue::is_valid);
+        let normalized = match valid {
+            Some(DateValue::Quarter { year, quarter }) => {
+                Some(NormalizedDate::Quarter { year, quarter })
+            }
+            _ => valid.and_then(|v| v.iso()).map(NormalizedDate::Iso),
+        };
+        ParsedDateJson {
+            raw: date.raw,
+            kind: date.value.map(|v| v.kind()),
+            normalized,
+            valid: valid.is_some(),
+        }
+    }
+}
+
+impl From<MetadataSpans> for Vec<Span> {
+    fn from(spans: MetadataSpans) -> Self {
+        spans.into_sorted()
+    }
+}
+
+/// The document summary: each category's distinct values as a key of its
+/// own, with their occurrence totals under `counts`.
+#[derive(Serialize)]
+pub(crate) struct DocumentMetadataJson {
+    #[serde(flatten)]
+    categories: BTreeMap<&'static str, Vec<String>>,
+    counts: BTreeMap<&'static str, usize>,
+    #[serde(serialize_with = "ticker_counts")]
+    ticker_counts: Vec<TickerCount>,
+}
+
+impl From<DocumentMetadata> for DocumentMetadataJson {
+    fn from(document: DocumentMetadata) -> Self {
+        let mut categories = BTreeMap::new();
+        let mut counts = BTreeMap::new();
+        for (name, values, total) in document.categories {
+            categories.insert(name, values);
+            counts.insert(name, total);
+        }
+        DocumentMetadataJson {
+            categories,
+            counts,
+            ticker_counts: document.ticker_counts,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        extract_metadata_impl, process_document_impl, process_document_with_metadata_impl,
+        ProcessConfig,
+    };
+    use serde_json::json;
+
+    #[test]
+    fn test_metadata_json() {
+        let metadata = extract_metadata_impl("Revenue rose 12% to $5 million in Q3 2024.", false);
+        let json = to_json(metadata);
+        assert_eq!(json["monetary_amounts"][0], "$5 million");
+        assert_eq!(json["monetary_amounts_parsed"][0]["value"], 5e6);
+        assert_eq!(json["monetary_amounts_parsed"][0]["scale"], "million");
+        assert_eq!(json["percentage_values"][0], 12.0);
+        assert_eq!(json["dates_parsed"][0]["kind"], "quarter");
+        assert_eq!(
+            json["dates_parsed"][0]["normalized"],
+            serde_json::json!({"year": 2024, "quarter": 3})
+        );
+        assert!(json["ticker_counts"].as_object().unwrap().is_empty());
+        assert_eq!(json.as_object().unwrap().len(), crate::METADATA_KEYS.len());
+    }
+
+    #[test]
+    fn test_chunk_json() {
+        let config = ProcessConfig {
+            chunk_size: 20,
+            chunk_overlap: 0,
+            include_spans: true,
+            ..ProcessConfig::default()
+        };
+        let chunks =
+            process_document_impl("Revenue rose 12%. Costs fell to $4M.", &config).unwrap();
+        let json = to_json(chunks[1].clone());
+        assert_eq!(json["chunk_index"], 1);
+        assert_eq!(json["text"], "Costs fell to $4M.");
+        assert_eq!(json["char_count"], 18);
+        assert_eq!(json["metadata"]["monetary_amounts"][0], "$4M");
+        assert_eq!(json["spans"][0]["kind"], "money");
+        assert!(json.get("reason").is_none());
+    }
+
+    #[test]
+    fn test_document_metadata_json() {
+        let text = "AAPL rose 5%. AAPL and MSFT rose 5% in Q3 2024.";
+        let (chunks, document) =
+            process_document_with_metadata_impl(text, &ProcessConfig::default()).unwrap();
+        let json = to_json(document);
+        assert_eq!(json["percentages"], json!(["5%"]));
+        assert_eq!(json["counts"]["percentages"], 2);
+        assert_eq!(json["potential_tickers"], json!(["AAPL", "MSFT"]));
+        assert_eq!(json["ticker_counts"], json!({"AAPL": 2, "MSFT": 1}));
+        assert_eq!(to_json(chunks).as_array().unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,180 @@
+//! Streaming JSONL corpora through the pipeline, backing `process_jsonl`.
+//!
+//! Input is read one line at a time and each chunk is written as soon as its
+//! record is processed, so memory use is bounded by the largest record rather
+//! than the file. Lines that are not a JSON object with a string text field
+//! are skipped and reported by line number instead of failing the run.
+
+use crate::json::to_json;
+use crate::{process_document_impl, ProcessConfig};
+use serde_json::Value;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+/// Where records are found in each input line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsonlFields {
+    pub text_field: String,
+    pub id_field: String,
+}
+
+/// What `process_jsonl_impl` read and wrote.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct JsonlSummary {
+    /// Records processed, including those that produced no chunks.
+    pub records: usize,
+    pub chunks: usize,
+    /// 1-based numbers of the lines skipped as malformed.
+    pub skipped_lines: Vec<usize>,
+}
+
+/// An I/O error with the file it happened on.
+#[derive(Debug)]
+pub struct JsonlError {
+    pub path: PathBuf,
+    pub error: io::Error,
+}
+
+/// The id and text of a record, or None if the line is malformed. A missing
+/// id is null; the text must be a string.
+fn parse_record(line: &[u8], fields: &JsonlFields) -> Option<(Value, String)> {
+    let Value::Object(mut record) = serde_json::from_slice(line).ok()? else {
+        return None;
+    };
+    let Value::String(text) = record.remove(&fields.text_field)? else {
+        return None;
+    };
+    let id = record.remove(&fields.id_field).unwrap_or(Value::Null);
+    Some((id, text))
+}
+
+/// Process every record of the JSONL file at `input`, writing one JSON line
+/// per chunk to `output`: the chunk's `process_document` dict with the
+/// record's id added under "id". Blank lines are ignored.
+pub fn process_jsonl_impl(
+    input: &Path,
+    output: &Path,
+    fields: &JsonlFields,
+    config: &ProcessConfig,
+) -> Result<JsonlSummary, JsonlError> {
+    let on_input = |error| JsonlError {
+        path: input.to_path_buf(),
+        error,
+    };
+    let on_output = |error| JsonlError {
+        path: output.to_path_buf(),
+        error,
+    };
+    let mut reader = BufReader::new(File::open(input).map_err(on_input)?);
+    let mut writer = BufWriter::new(File::create(output).map_err(on_output)?);
+
+    let mut summary = JsonlSummary::default();
+    let mut line = Vec::new();
+    let mut line_number = 0;
+    loop {
+        line.clear();
+        if reader.read_until(b'\n', &mut line).map_err(on_input)? == 0 {
+            break;
+        }
+        line_number += 1;
+        if line.trim_ascii().is_empty() {
+            continue;
+        }
+        let Some((id, text)) = parse_record(&line, fields) else {
+            summary.skipped_lines.push(line_number);
+            continue;
+        };
+        // Only an empty document with `OnEmpty::Error` fails, and that is not
+        // a malformed line: the record simply has no chunks
+        let chunks = process_document_impl(&text, config).unwrap_or_default();
+        summary.records += 1;
+        for chunk in chunks {
+            let mut object = to_json(chunk);
+            object["id"] = id.clone();
+            serde_json::to_writer(&mut writer, &object).map_err(|e| on_output(e.into()))?;
+            writer.write_all(b"\n").map_err(on_output)?;
+            summary.chunks += 1;
+        }
+    }
+    writer.flush().map_err(on_output)?;
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fields() -> JsonlFields {
+        JsonlFields {
+            text_field: "text".to_string(),
+            id_field: "id".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_parse_record() {
+        let fields = fields();
+        assert_eq!(
+            parse_record(br#"{"id": 7, "text": "Revenue rose."}"#, &fields),
+            Some((Value::from(7), "Revenue rose.".to_string()))
+        );
+        assert_eq!(
+            parse_record(br#"{"text": "No id."}"#, &fields),
+            Some((Value::Null, "No id.".to_string()))
+        );
+        assert_eq!(parse_record(br#"{"id": 1, "text": 5}"#, &fields), None);
+        assert_eq!(parse_record(br#"{"id": 1}"#, &fields), None);
+        assert_eq!(parse_record(br#"["text"]"#, &fields), None);
+        assert_eq!(parse_record(br#"{"text": "cut"#, &fields), None);
+        assert_eq!(parse_record(b"\xff", &fields), None);
+    }
+
+    #[test]
+    fn test_process_jsonl() {
+        let dir = std::env::temp_dir();
+        let input = dir.join(format!("rag_core_jsonl_in_{}.jsonl", std::process::id()));
+        let output = dir.join(format!("rag_core_jsonl_out_{}.jsonl", std::process::id()));
+        std::fs::write(
+            &input,
+            "{\"id\": \"a\", \"text\": \"Revenue rose 12%. Costs fell to $4M.\"}\n\
+             not json\n\
+             \n\
+             {\"id\": \"b\", \"text\": \"   \"}\n\
+             {\"id\": \"c\", \"body\": \"wrong field\"}\n\
+             {\"id\": \"d\", \"text\": \"Margins held.\"}",
+        )
+        .unwrap();
+        let config = ProcessConfig {
+            chunk_size: 20,
+            chunk_overlap: 0,
+            ..ProcessConfig::default()
+        };
+        let summary = process_jsonl_impl(&input, &output, &fields(), &config).unwrap();
+        assert_eq!(
+            summary,
+            JsonlSummary {
+                records: 3,
+                chunks: 3,
+                skipped_lines: vec![2, 5],
+            }
+        );
+
+        let written = std::fs::read_to_string(&output).unwrap();
+        let lines: Vec<Value> = written
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        let ids: Vec<&Value> = lines.iter().map(|l| &l["id"]).collect();
+        assert_eq!(ids, ["a", "a", "d"]);
+        assert_eq!(lines[1]["chunk_index"], 1);
+        assert_eq!(lines[1]["text"], "Costs fell to $4M.");
+        assert_eq!(lines[1]["metadata"]["monetary_amounts"][0], "$4M");
+
+        std::fs::remove_file(&input).unwrap();
+        let missing = process_jsonl_impl(&input, &output, &fields(), &config).unwrap_err();
+        assert_eq!(missing.path, input);
+        assert_eq!(missing.error.kind(), io::ErrorKind::NotFound);
+        std::fs::remove_file(&output).unwrap();
+    }
+}
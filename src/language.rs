@@ -0,0 +1,256 @@
+//! A lightweight language guess, backing `detect_language`.
+//!
+//! Two steps, no models: the script with the most letters decides the
+//! language outright where one script means one language here (Greek,
+//! Hebrew, Thai, Hangul, Devanagari), or narrows it down by telltale letters
+//! (kana for Japanese among CJK text, "і"/"ї"/"є"/"ґ" for Ukrainian among
+//! Cyrillic, "پ"/"چ"/"ژ"/"گ" for Persian among Arabic script). Latin-script
+//! text is then told apart by counting common function words of English,
+//! German, French, Spanish, Italian, Portuguese and Dutch.
+//!
+//! Accuracy is that of a heuristic: the script step is reliable from a
+//! sentence on, while the Latin languages need a few sentences of running
+//! prose and are easily fooled by short, list-like or mixed-language text.
+//! Anything with too little evidence is "und" (undetermined).
+
+/// Writing systems told apart by their Unicode blocks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Script {
+    Latin,
+    Greek,
+    Cyrillic,
+    Hebrew,
+    Arabic,
+    Devanagari,
+    Thai,
+    Hangul,
+    Kana,
+    Han,
+}
+
+const SCRIPTS: [Script; 10] = [
+    Script::Latin,
+    Script::Greek,
+    Script::Cyrillic,
+    Script::Hebrew,
+    Script::Arabic,
+    Script::Devanagari,
+    Script::Thai,
+    Script::Hangul,
+    Script::Kana,
+    Script::Han,
+];
+
+fn script(c: char) -> Option<Script> {
+    let script = match c {
+        'a'..='z' | 'A'..='Z' | '\u{C0}'..='\u{24F}' | '\u{1E00}'..='\u{1EFF}' => Script::Latin,
+        '\u{370}'..='\u{3FF}' | '\u{1F00}'..='\u{1FFF}' => Script::Greek,
+        '\u{400}'..='\u{52F}' => Script::Cyrillic,
+        '\u{590}'..='\u{5FF}' => Script::Hebrew,
+        '\u{600}'..='\u{6FF}' | '\u{750}'..='\u{77F}' => Script::Arabic,
+        '\u{900}'..='\u{97F}' => Script::Devanagari,
+        '\u{E00}'..='\u{E7F}' => Script::Thai,
+        '\u{1100}'..='\u{11FF}' | '\u{3130}'..='\u{318F}' | '\u{AC00}'..='\u{D7AF}' => {
+            Script::Hangul
+        }
+        '\u{3040}'..='\u{30FF}' | '\u{31F0}'..='\u{31FF}' | '\u{FF66}'..='\u{FF9F}' => Script::Kana,
+        '\u{3400}'..='\u{4DBF}' | '\u{4E00}'..='\u{9FFF}' | '\u{F900}'..='\u{FAFF}' => Script::Han,
+        _ => return None,
+    };
+    // Marks, digits and punctuation inside the blocks do not count
+    c.is_alphabetic().then_some(script)
+}
+
+/// Fewer letters than this in the dominant script give "und".
+const MIN_LETTERS: usize = 10;
+
+/// Share of kana among CJK letters from which text is Japanese; Chinese
+/// text has none, while Japanese prose is mostly kana.
+const MIN_KANA_SHARE: f64 = 0.1;
+
+/// Fewer function-word hits than this in Latin-script text give "und".
+const MIN_WORD_HITS: usize = 2;
+
+/// Frequent function words of each Latin-script language. Words shared by
+/// several languages ("de", "la", "in") count for each of them; the words
+/// only one language uses decide.
+const FUNCTION_WORDS: &[(&str, &[&str])] = &[
+    (
+        "en",
+        &[
+            "the", "and", "of", "to", "is", "that", "for", "it", "with", "was", "on", "are", "as",
+            "this", "by", "be", "from", "have", "which", "its",
+        ],
+    ),
+    (
+        "de",
+        &[
+            "der", "die", "das", "und", "ist", "nicht", "mit", "den", "von", "zu", "ein", "eine",
+            "auf", "für", "sich", "dem", "des", "auch", "wird", "im",
+        ],
+    ),
+    (
+        "fr",
+        &[
+            "le", "la", "les", "et", "des", "est", "une", "du", "que", "pour", "dans", "qui",
+            "sur", "pas", "au", "avec", "ce", "sont", "ont", "aux",
+        ],
+    ),
+    (
+        "es",
+        &[
+            "el", "la", "los", "las", "y", "de", "que", "en", "es", "por", "con", "una", "para",
+            "del", "se", "su", "al", "como", "más", "fue",
+        ],
+    ),
+    (
+        "it",
+        &[
+            "il", "di", "che", "e", "la", "per", "un", "del", "della", "sono", "non", "con", "nel",
+            "si", "gli", "le", "da", "è", "anche", "alla",
+        ],
+    ),
+    (
+        "pt",
+        &[
+            "o", "a", "os", "as", "de", "que", "e", "do", "da", "em", "um", "uma", "para", "com",
+            "não", "no", "na", "dos", "se", "é",
+        ],
+    ),
+    (
+        "nl",
+        &[
+            "de", "het", "een", "en", "van", "is", "dat", "op", "te", "in", "voor", "met", "niet",
+            "zijn", "er", "ook", "aan", "bij", "wordt", "naar",
+        ],
+    ),
+];
+
+/// The Latin-script language whose function words occur most often. A tie
+/// goes to the language whose matched words cover more characters.
+fn latin_language(text: &str) -> &'static str {
+    let lowered = text.to_lowercase();
+    let words: Vec<&str> = lowered
+        .split(|c: char| !c.is_alphabetic())
+        .filter(|w| !w.is_empty())
+        .collect();
+    let mut best = ("und", 0, 0);
+    for (language, function_words) in FUNCTION_WORDS {
+        let matched = words.iter().filter(|w| function_words.contains(w));
+        let (hits, chars) = matched.fold((0, 0), |(hits, chars), w| (hits + 1, chars + w.len()));
+        if (hits, chars) > (best.1, best.2) {
+            best = (language, hits, chars);
+        }
+    }
+    if best.1 < MIN_WORD_HITS {
+        "und"
+    } else {
+        best.0
+    }
+}
+
+/// The ISO 639-1 code of the language `text` is most likely written in, or
+/// "und" when there is too little evidence to tell.
+pub fn detect_language(text: &str) -> &'static str {
+    let mut counts = [0usize; SCRIPTS.len()];
+    for c in text.chars() {
+        if let Some(script) = script(c) {
+            counts[SCRIPTS.iter().position(|&s| s == script).unwrap()] += 1;
+        }
+    }
+    let count = |script: Script| counts[SCRIPTS.iter().position(|&s| s == script).unwrap()];
+    // Kana and Han are one writing system for the dominance count
+    let cjk = count(Script::Kana) + count(Script::Han);
+    let (dominant, letters) = SCRIPTS
+        .iter()
+        .filter(|&&s| s != Script::Kana && s != Script::Han)
+        .map(|&s| (s, count(s)))
+        .chain([(Script::Han, cjk)])
+        .max_by_key(|&(_, n)| n)
+        .unwrap();
+    if letters < MIN_LETTERS {
+        return "und";
+    }
+    let has_any = |letters: &[char]| text.chars().any(|c| letters.contains(&c));
+    match dominant {
+        Script::Latin => latin_language(text),
+        Script::Greek => "el",
+        Script::Cyrillic if has_any(&['і', 'ї', 'є', 'ґ', 'І', 'Ї', 'Є', 'Ґ']) => "uk",
+        Script::Cyrillic => "ru",
+        Script::Hebrew => "he",
+        Script::Arabic if has_any(&['پ', 'چ', 'ژ', 'گ']) => "fa",
+        Script::Arabic => "ar",
+        Script::Devanagari => "hi",
+        Script::Thai => "th",
+        Script::Hangul => "ko",
+        Script::Kana | Script::Han if count(Script::Kana) as f64 >= cjk as f64 * MIN_KANA_SHARE => {
+            "ja"
+        }
+        Script::Kana | Script::Han => "zh",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scripts() {
+        let cases = [
+            ("Die Bilanzsumme stieg im dritten Quartal.", "de"),
+            ("Η εταιρεία ανακοίνωσε αύξηση των εσόδων.", "el"),
+            ("Выручка компании выросла на пять процентов.", "ru"),
+            ("Виручка компанії зросла на п'ять відсотків.", "uk"),
+            ("הכנסות החברה עלו בחמישה אחוזים ברבעון.", "he"),
+            ("ارتفعت إيرادات الشركة بنسبة خمسة في المائة.", "ar"),
+            (
+                "درآمد شرکت پنج درصد افزایش یافت و چشم انداز مثبت است.",
+                "fa",
+            ),
+            ("कंपनी का राजस्व पांच प्रतिशत बढ़ा।", "hi"),
+            ("รายได้ของบริษัทเพิ่มขึ้นห้าเปอร์เซ็นต์", "th"),
+            ("회사의 매출이 오 퍼센트 증가했습니다.", "ko"),
+            ("当社の売上高は前年同期比で五パーセント増加しました。", "ja"),
+            ("公司第三季度的收入同比增长了百分之五。", "zh"),
+        ];
+        for (text, expected) in cases {
+            assert_eq!(detect_language(text), expected, "{text}");
+        }
+    }
+
+    #[test]
+    fn test_latin_languages() {
+        let cases = [
+            ("The company said that revenue for the quarter was up, and it expects the same from the next one.", "en"),
+            ("Der Umsatz ist im Quartal gestiegen, und das Unternehmen erwartet auch für das nächste Jahr ein Wachstum.", "de"),
+            ("Le chiffre d'affaires est en hausse et la société prévoit une croissance pour les prochains mois.", "fr"),
+            ("Los ingresos de la empresa crecieron y el grupo espera que el próximo año sea mejor para las ventas.", "es"),
+            ("Il fatturato della società è cresciuto e gli analisti sono positivi per il prossimo anno.", "it"),
+            ("A receita da empresa cresceu e os analistas não esperam uma queda para o próximo ano.", "pt"),
+            ("De omzet van het bedrijf is gestegen en het management verwacht dat de groei ook volgend jaar doorzet.", "nl"),
+        ];
+        for (text, expected) in cases {
+            assert_eq!(detect_language(text), expected, "{text}");
+        }
+    }
+
+    #[test]
+    fn test_undetermined() {
+        for text in ["", "12,345.67 -- 2024", "AAPL MSFT NVDA", "Revenue", "Ok."] {
+            assert_eq!(detect_language(text), "und", "{text:?}");
+        }
+        // Enough letters, but none of the function words
+        assert_eq!(
+            detect_language("Quarterly revenue growth exceeded expectations"),
+            "und"
+        );
+    }
+
+    #[test]
+    fn test_tie_goes_to_longer_matches() {
+        // "de" and "la" count for French and Spanish alike; "los" is longer
+        // than "le"
+        assert_eq!(latin_language("de la los"), "es");
+        assert_eq!(latin_language("de la les"), "fr");
+    }
+}
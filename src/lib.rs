@@ -1,28 +1,161 @@
-use pyo3::exceptions::PyValueError;
-use pyo3::prelude::*;
-use pyo3::types::PyDict;
+//! Cleaning, chunking and financial metadata extraction for RAG pipelines.
+//!
+//! The crate root is plain Rust: `clean_text`, `chunk_text`,
+//! `extract_metadata`, the whole `process_document_impl` pipeline and its
+//! standalone steps can be called from a Rust service without linking
+//! Python. The `rag_rust_core` Python module is built from the bindings in
+//! `python`, behind the default `python` feature; build with
+//! `--no-default-features` to leave pyo3 out.
+
+#[cfg(feature = "arrow")]
+mod arrow;
+mod boilerplate;
+#[cfg(feature = "cli")]
+pub mod cli;
+mod dates;
+mod dedupe;
+mod entities;
+mod files;
+mod fiscal;
+mod fold;
+mod graphemes;
+mod html;
+mod json;
+mod jsonl;
+mod language;
+mod lists;
+mod markdown;
+mod mojibake;
+mod normalize;
+#[cfg(feature = "parallel")]
+mod parallel;
+#[cfg(any(feature = "python", test))]
+mod profile;
+#[cfg(feature = "python")]
+mod python;
+mod ranges;
+mod recursive;
+mod redact;
+mod sections;
+mod securities;
+mod shares;
+mod spelled;
+mod stats;
+#[cfg(any(feature = "python", test))]
+mod stream;
+mod tables;
+mod tickers;
+mod window;
+
+pub use boilerplate::remove_boilerplate;
+pub use dates::DateValue;
+use dates::{overrides_format, parse_date, DateFormat};
+pub use dedupe::find_duplicates;
+pub use files::{read_text_file, Encoding, ReadError};
+pub use fiscal::ParsedFiscalPeriod;
+pub use jsonl::{process_jsonl_impl, JsonlError, JsonlFields, JsonlSummary};
+pub use language::detect_language;
+pub use markdown::chunk_markdown_impl;
+pub use normalize::NormalizedValue;
+use normalize::{
+    money_scale, normalize_match, parse_money, parse_percentage, percentage_unit, split_currency,
+};
+pub use ranges::ParsedRange;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+pub use recursive::{chunk_text_recursive_impl, Separator};
+pub use redact::{check_mask, redact_pii, PiiCategories};
 use regex::Regex;
-use std::collections::{HashSet, VecDeque};
-use std::sync::LazyLock;
+pub use sections::detect_sections;
+pub use securities::UnvalidatedId;
+use serde::Serialize;
+pub use shares::{PerShareAmount, ShareCount};
+pub use stats::{text_stats_impl, TextStats};
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::panic;
+use std::path::PathBuf;
+use std::sync::{Arc, LazyLock};
+pub use tickers::ExplicitTicker;
+use tickers::{TickerOptions, TickerOrder};
 use unicode_normalization::UnicodeNormalization;
+pub use window::window_chunks;
 
 // Pre-compiled regex patterns for performance
 static WHITESPACE_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\s+").unwrap());
+// Whitespace runs without tabs, for collapsing around the tabs a `TabPolicy` keeps
+static NON_TAB_WHITESPACE_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"[^\S\t]+").unwrap());
+// One or more blank lines, including lines holding only spaces or tabs
+static BLANK_LINES_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\n(?:[^\S\n]*\n)+").unwrap());
+// Scale words after an amount: "5 million", "$5M", "£30m", "€2bn", "5,2 Mio.", "¥1,000億"
+const MONEY_SCALE: &str =
+    r"(?i:million|billion|thousand)|(?i:bn|mn)\b|(?i:mio|mrd)\b\.?|M|B|K|[km]\b|億|万";
+// Either a currency symbol or ISO code prefix ($5, $1.5 million, $.50, €1.2B,
+// USD 5 million), or a currency symbol, code or name after the amount (5,2 Mio. €,
+// 300 CHF, 5 million dollars). Negatives are written with a minus not preceded by
+// a word character (-$5M, −$5M, $-5, -5 million dollars) or accounting-style in
+// parentheses, around the number ($(1.2) million) or the whole amount ($1.2 million)
 static MONEY_RE: LazyLock<Regex> = LazyLock::new(|| {
-    Regex::new(r"\$[\d,]+(?:\.\d{2})?\s*(?:million|billion|thousand|M|B|K)?").unwrap()
+    let number = r"(?:\d[\d,]*(?:\.\d+)?|\.\d+)";
+    let codes = "USD|EUR|GBP|JPY|CNY|INR|CHF";
+    Regex::new(&format!(
+        r"\((?:[$€£¥₹]|\b(?:{codes})\s?){number}(?:\s*(?:{MONEY_SCALE}))?\)|(?:\B[-−])?(?:[$€£¥₹]|\b(?:{codes})\s?)(?:-?{number}|\({number}\))(?:\s*(?:{MONEY_SCALE}))?|(?:\B[-−])?\b\d(?:[\d.,]*\d)?(?:\s?(?:{MONEY_SCALE}))?\s?(?:[€£¥₹]|(?:{codes})\b|(?i:dollars?|euros?|pounds?(?:\s+sterling)?|yen|rupees?|francs?)\b)"
+    ))
+    .unwrap()
 });
+// Percentage units after a number: "12.5%" (no space before the sign), "12.5
+// percent", "3.5 pct", "40 basis points", "40bps"; "bp" only in lower case, so
+// "5 BP shares" is not read as basis points
+const PERCENT_UNIT: &str = r"%|\s?(?:(?i:percent|pct|basis\s+points?|bps)|bp)\b";
+// A number with a unit, optionally with a minus not preceded by a word character
+// ("-3%", "−3%"), or accounting-style "(3%)" / "(3.5)%"
 static PERCENTAGE_RE: LazyLock<Regex> = LazyLock::new(|| {
-    Regex::new(r"\d+(?:\.\d+)?%").unwrap()
+    Regex::new(&format!(
+        r"\(\d+(?:\.\d+)?\s?(?:{PERCENT_UNIT})\)|\(\d+(?:\.\d+)?\)%|(?:\B[-−])?\d+(?:\.\d+)?(?:{PERCENT_UNIT})"
+    ))
+    .unwrap()
 });
+// Month names, full or abbreviated with an optional period ("Jan.", "Sept")
+const MONTH_NAME: &str = r"(?:January|February|March|April|May|June|July|August|September|October|November|December|(?:Jan|Feb|Mar|Apr|Jun|Jul|Aug|Sept?|Oct|Nov|Dec)\b\.?)";
+// Quarters, ISO dates, "January 15, 2024", "15 January 2024", "March 2024",
+// "1/15/2024" (month first), "15.1.2024" (day first), and years only when a
+// "fiscal", "calendar" or "FY" cue makes them dates ("fiscal 2023", "FY2024")
 static DATE_RE: LazyLock<Regex> = LazyLock::new(|| {
-    Regex::new(r"(?:Q[1-4]\s+\d{4}|\d{4}-\d{2}-\d{2}|(?:January|February|March|April|May|June|July|August|September|October|November|December)\s+\d{1,2},?\s+\d{4})").unwrap()
+    Regex::new(&format!(
+        r"(?:Q[1-4]\s+\d{{4}}|\d{{4}}-\d{{2}}-\d{{2}}|\b{MONTH_NAME}\s+\d{{1,2}},?\s+\d{{4}}|\b\d{{1,2}}\s+{MONTH_NAME}\s+\d{{4}}\b|\b{MONTH_NAME}\s+\d{{4}}\b|\b\d{{1,2}}/\d{{1,2}}/\d{{4}}\b|\b\d{{1,2}}\.\d{{1,2}}\.\d{{4}}\b|\b(?:(?i:fiscal|calendar)(?:\s+(?i:year))?\s+|FY\s?)\d{{4}}\b)"
+    ))
+    .unwrap()
 });
+// Reporting periods ("three months ended June 30, 2024"), quarters and halves
+// of a fiscal year ("Q1 FY2025", "3Q24", "H1 2023", "second quarter of fiscal
+// 2025", "first half of 2024"), fiscal years ("FY2024", "FY 24", "fiscal 2023",
+// "fiscal year 2023") and period-over-period tokens ("QoQ", "YoY", "YTD").
+// A bare "Q1 2024" is left to DATE_RE.
+static FISCAL_PERIOD_RE: LazyLock<Regex> = LazyLock::new(|| {
+    let year = r"(?:(?i:fiscal)(?:\s+(?i:year))?\s+|FY\s?)?\d{4}\b";
+    let fiscal_year = r"(?:FY\s?\d{2}(?:\d{2})?|(?i:fiscal)\s+\d{4})\b";
+    Regex::new(&format!(
+        r"\b(?i:three|six|nine|twelve)\s+(?i:months)\s+(?i:ended|ending)\s+{MONTH_NAME}\s+\d{{1,2}},?\s+\d{{4}}\b|\b(?:(?i:first|second|third|fourth)\s+(?i:quarter)|(?i:first|second)\s+(?i:half))\s+(?i:of)\s+{year}|\bQ[1-4]\s?{fiscal_year}|\bH[12]\s?(?:{fiscal_year}|\d{{4}}\b)|\b[1-4]Q\s?\d{{2}}(?:\d{{2}})?\b|\b[12]H\s?\d{{2}}(?:\d{{2}})?\b|\bFY\s?\d{{2}}(?:\d{{2}})?\b|\b(?i:fiscal)(?:\s+(?i:year))?\s+\d{{4}}\b|\b(?i:QoQ|YoY|YTD)\b"
+    ))
+    .unwrap()
+});
+// A lowercase word fragment hyphenated at a line break ("inter-\nnational")
+static LINE_BREAK_HYPHEN_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\b(\p{Ll}+)-[ \t]*\r?\n[ \t]*(\p{Ll}+)\b").unwrap());
 static TICKER_RE: LazyLock<Regex> = LazyLock::new(|| {
     Regex::new(r"\b[A-Z]{2,5}\b").unwrap()
 });
-// Regex to find sentence-ending punctuation followed by whitespace
+// Regex to find sentence-ending punctuation followed by whitespace, or a
+// full-width CJK terminator ("。", "！", "？") with or without it. NFKC turns
+// "！" and "？" into ASCII, so those also end a sentence when a CJK character
+// directly follows; that character is matched too but is not part of the boundary.
+// Some matched periods and ellipses are not boundaries after all; see
+// `is_inner_period` and `is_pause`.
 static SENTENCE_BOUNDARY_RE: LazyLock<Regex> = LazyLock::new(|| {
-    Regex::new(r"[.!?]+\s+").unwrap()
+    Regex::new(
+        r"[.!?…]+\s+|[。！？]+[」』]*\s*|[!?]+[」』]*[\p{Han}\p{Hiragana}\p{Katakana}\p{Hangul}]",
+    )
+    .unwrap()
 });
 
 // Static set of common words to filter from ticker detection (avoids per-call allocation)
@@ -30,383 +163,5103 @@ static COMMON_TICKER_STOPWORDS: LazyLock<HashSet<&'static str>> = LazyLock::new(
     [
         "THE", "AND", "FOR", "ARE", "BUT", "NOT", "YOU", "ALL", "CAN", "HAD",
         "HER", "WAS", "ONE", "OUR", "OUT", "CEO", "CFO", "COO", "IPO", "USA",
+        // Currency codes recognized by MONEY_RE
+        "USD", "EUR", "GBP", "JPY", "CNY", "INR", "CHF",
+        // Labels of the security identifiers extracted separately
+        "ISIN", "CUSIP", "SEDOL",
     ]
     .iter()
     .cloned()
     .collect()
 });
 
-/// Helper to count characters (Unicode code points), not bytes.
+/// Helper to count characters as readers see them (grapheme clusters), not
+/// bytes or code points: a ZWJ emoji sequence or a decomposed "é" is one.
 #[inline]
 fn char_len(s: &str) -> usize {
-    s.chars().count()
+    graphemes::count(s)
 }
 
-/// Clean and normalize text for downstream processing.
-///
-/// Performs:
-/// - Unicode normalization (NFKC) - converts compatibility characters to canonical forms
-/// - Whitespace collapsing
-/// - Quote/dash standardization
-/// - Control character removal
-#[pyfunction]
-fn clean_text(text: &str) -> String {
-    // Apply NFKC normalization first (handles compatibility characters like ligatures, fullwidth forms)
-    let normalized: String = text.nfkc().collect();
+/// Helper to count words: whitespace-delimited tokens, so "state-of-the-art"
+/// and "1,234.5" are one word each.
+#[inline]
+fn word_count(s: &str) -> usize {
+    s.split_whitespace().count()
+}
 
-    // Standardize quotes (curly quotes to straight quotes)
-    let mut result = normalized
-        .replace('\u{201C}', "\"") // Left double quotation mark "
-        .replace('\u{201D}', "\"") // Right double quotation mark "
-        .replace('\u{2018}', "'") // Left single quotation mark '
-        .replace('\u{2019}', "'") // Right single quotation mark '
-        .replace('`', "'");
-
-    // Standardize dashes
-    result = result
-        .replace('–', "-")
-        .replace('—', "-")
-        .replace('−', "-");
+/// Hex digest identifying `text`: its xxHash64 (seed 0) over the UTF-8 bytes,
+/// as 16 lowercase hex digits. Part of the output format, so it never changes.
+pub fn hash_text_impl(text: &str) -> String {
+    format!("{:016x}", xxhash_rust::xxh64::xxh64(text.as_bytes(), 0))
+}
 
-    // Remove control characters (except newlines and tabs)
-    result = result
-        .chars()
-        .filter(|c| !c.is_control() || *c == '\n' || *c == '\t')
-        .collect();
+/// Stable ID of a chunk: `hash_text_impl` of "{chunk_index}:{text}", so equal
+/// text at another position gets another ID.
+fn chunk_id(chunk_index: usize, text: &str) -> String {
+    hash_text_impl(&format!("{chunk_index}:{text}"))
+}
 
-    // Collapse whitespace (preserve paragraph breaks)
-    let paragraphs: Vec<&str> = result.split("\n\n").collect();
-    let cleaned_paragraphs: Vec<String> = paragraphs
-        .iter()
-        .map(|p| WHITESPACE_RE.replace_all(p.trim(), " ").to_string())
-        .filter(|p| !p.is_empty())
-        .collect();
+/// Converts ascending byte offsets into character (code point) offsets in one pass.
+struct CharOffsets<'a> {
+    text: &'a str,
+    byte_pos: usize,
+    char_pos: usize,
+}
 
-    cleaned_paragraphs.join("\n\n")
+impl<'a> CharOffsets<'a> {
+    fn new(text: &'a str) -> Self {
+        CharOffsets {
+            text,
+            byte_pos: 0,
+            char_pos: 0,
+        }
+    }
+
+    /// Character offset of `byte`. Offsets must be requested in non-decreasing order.
+    fn char_offset(&mut self, byte: usize) -> usize {
+        self.char_pos += self.text[self.byte_pos..byte].chars().count();
+        self.byte_pos = byte;
+        self.char_pos
+    }
 }
 
-/// Split text into sentences while preserving original punctuation.
-/// Returns a vector of sentences including their terminating punctuation.
-fn split_sentences_preserve_punct(text: &str) -> Vec<String> {
-    let mut sentences = Vec::new();
-    let mut last_end = 0;
+/// Unicode normalization form applied by `clean_text`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnicodeForm {
+    Nfkc,
+    Nfc,
+    None,
+}
+
+impl UnicodeForm {
+    #[cfg(any(feature = "python", test))]
+    fn parse(name: &str) -> Option<Self> {
+        match name {
+            "nfkc" => Some(UnicodeForm::Nfkc),
+            "nfc" => Some(UnicodeForm::Nfc),
+            "none" => Some(UnicodeForm::None),
+            _ => None,
+        }
+    }
 
-    // Find each sentence boundary (punctuation + whitespace)
-    for m in SENTENCE_BOUNDARY_RE.find_iter(text) {
-        // Include everything up to and including the punctuation (but not trailing whitespace)
-        let boundary_start = m.start();
-        let boundary_text = m.as_str();
+    #[cfg(feature = "python")]
+    fn name(self) -> &'static str {
+        match self {
+            UnicodeForm::Nfkc => "nfkc",
+            UnicodeForm::Nfc => "nfc",
+            UnicodeForm::None => "none",
+        }
+    }
+}
 
-        // Find where the punctuation ends (before whitespace)
-        let punct_end = boundary_text
-            .char_indices()
-            .find(|(_, c)| c.is_whitespace())
-            .map(|(i, _)| boundary_start + i)
-            .unwrap_or(m.end());
+/// What separates paragraphs in the text `clean_text` collapses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParagraphSep {
+    /// Exactly "\n\n".
+    Double,
+    /// Every line break; each line is a paragraph.
+    Single,
+    /// One or more blank lines, which may hold spaces or tabs.
+    BlankLine,
+}
 
-        let sentence = text[last_end..punct_end].trim();
-        if !sentence.is_empty() {
-            sentences.push(sentence.to_string());
+impl ParagraphSep {
+    #[cfg(feature = "python")]
+    fn parse(name: &str) -> Option<Self> {
+        match name {
+            "double" => Some(ParagraphSep::Double),
+            "single" => Some(ParagraphSep::Single),
+            "blank-line" => Some(ParagraphSep::BlankLine),
+            _ => None,
+        }
+    }
+    #[cfg(feature = "python")]
+    #[cfg(any(feature = "python", test))]
+    fn name(self) -> &'static str {
+        match self {
+            ParagraphSep::Double => "double",
+            ParagraphSep::Single => "single",
+            ParagraphSep::BlankLine => "blank-line",
         }
-        last_end = m.end();
     }
 
-    // Don't forget the final segment (text after last sentence boundary)
-    let tail = text[last_end..].trim();
-    if !tail.is_empty() {
-        sentences.push(tail.to_string());
+    /// Split `text` into paragraphs, which may be empty or blank.
+    fn split(self, text: &str) -> Vec<&str> {
+        match self {
+            ParagraphSep::Double => text.split("\n\n").collect(),
+            ParagraphSep::Single => text.split('\n').collect(),
+            ParagraphSep::BlankLine => BLANK_LINES_RE.split(text).collect(),
+        }
     }
+}
 
-    sentences
+/// How `clean_text` treats tabs when it collapses whitespace.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TabPolicy {
+    /// Like any other whitespace: part of a run collapsed to one space.
+    Collapse,
+    /// Kept as they are; only the other whitespace around them is collapsed.
+    Preserve,
+    /// Each tab becomes this many spaces, which are not collapsed.
+    ToSpaces(usize),
 }
 
-/// Internal implementation of chunk_text (pure Rust, no PyO3 dependencies).
-/// Returns None if target_size is 0, otherwise returns the chunks.
-///
-/// Uses character counts (Unicode code points) for sizing, not bytes.
-fn chunk_text_impl(text: &str, target_size: usize, overlap: usize) -> Option<Vec<String>> {
-    // Validate parameters
-    if target_size == 0 {
-        return None;
+impl TabPolicy {
+    #[cfg(any(feature = "python", test))]
+    fn parse(name: &str) -> Option<Self> {
+        match name {
+            "collapse" => Some(TabPolicy::Collapse),
+            "preserve" => Some(TabPolicy::Preserve),
+            _ => name
+                .strip_prefix("to_spaces(")?
+                .strip_suffix(')')?
+                .parse()
+                .ok()
+                .filter(|&n| n > 0)
+                .map(TabPolicy::ToSpaces),
+        }
     }
 
-    // Clamp overlap to be less than target_size
-    let overlap = overlap.min(target_size.saturating_sub(1));
+    #[cfg(any(feature = "python", test))]
+    fn name(self) -> String {
+        match self {
+            TabPolicy::Collapse => "collapse".to_string(),
+            TabPolicy::Preserve => "preserve".to_string(),
+            TabPolicy::ToSpaces(n) => format!("to_spaces({n})"),
+        }
+    }
 
-    if text.is_empty() {
-        return Some(vec![]);
+    /// Collapse the whitespace runs of `text` to single spaces and trim it,
+    /// keeping or expanding its tabs unless they collapse too.
+    fn collapse(self, text: &str) -> String {
+        if self == TabPolicy::Collapse {
+            return WHITESPACE_RE.replace_all(text.trim(), " ").to_string();
+        }
+        let trimmed = text.trim_matches(|c: char| c.is_whitespace() && c != '\t');
+        let collapsed = NON_TAB_WHITESPACE_RE.replace_all(trimmed, " ");
+        match self {
+            TabPolicy::ToSpaces(n) => collapsed.replace('\t', &" ".repeat(n)),
+            _ => collapsed.to_string(),
+        }
     }
+}
 
-    // Split into sentences, preserving original punctuation
-    let sentences = split_sentences_preserve_punct(text);
+/// UTF-8 dashes misdecoded as Windows-1252: the non-breaking hyphen, en dash,
+/// em dash, horizontal bar and minus sign.
+const DASH_MOJIBAKE: [&str; 5] = [
+    "\u{E2}\u{20AC}\u{2018}",
+    "\u{E2}\u{20AC}\u{201C}",
+    "\u{E2}\u{20AC}\u{201D}",
+    "\u{E2}\u{20AC}\u{2022}",
+    "\u{E2}\u{2C6}\u{2019}",
+];
 
-    if sentences.is_empty() {
-        // No sentence boundaries found, return as single chunk or split by size
-        if char_len(text) <= target_size {
-            return Some(vec![text.to_string()]);
+/// Options controlling which steps `clean_text` performs.
+///
+/// Args:
+///     unicode_normalization: "nfkc" (default), "nfc" (keeps "²", "½" and other
+///         compatibility characters) or "none"
+///     fold_to_ascii: After normalization, strip diacritics from Latin letters
+///         ("café" becomes "cafe") and spell letters like "ß", "æ" and "ø" in
+///         ASCII. Lossy, so off by default. Not a transliteration: other
+///         scripts and symbols without an ASCII form are left unchanged
+///     fix_mojibake: Before normalization, repair UTF-8 text that was decoded
+///         as Windows-1252 or Latin-1 ("CafÃ©" becomes "Café", "â€œ" a curly
+///         quote), as `repair_mojibake` does. Off by default
+///     standardize_quotes: Replace curly quotes and backticks with straight quotes
+///     standardize_dashes: Replace hyphens (U+2010, U+2011), en/em dashes, the
+///         horizontal bar and minus signs with "-", including their mojibake
+///         forms ("â€“" for an en dash decoded as Windows-1252)
+///     collapse_whitespace: Collapse whitespace runs and trim paragraphs
+///     preserve_single_newlines: When collapsing, keep single newlines inside a
+///         paragraph instead of turning them into spaces, for addresses, lists
+///         and other line-oriented layouts; spaces are still collapsed on each
+///         line and trimmed around the newlines, and blank-line runs still
+///         become exactly "\n\n". `keep_single_newlines` is accepted as
+///         another name for it, and giving both raises `InvalidOption`
+///     preserve_lists: When collapsing, keep each list item (a line starting
+///         with "-", "*", "•", "1." or "1)") on a line of its own, joining only
+///         the indented lines that continue it, so a list separated by single
+///         newlines does not collapse into one run-on line
+///     tab_policy: How collapsing treats tabs: "collapse" (default) folds
+///         them into the surrounding whitespace like spaces, "preserve" keeps
+///         every tab and collapses only the other whitespace around it, and
+///         "to_spaces(n)" turns each tab into n spaces that are kept. Tabs at
+///         the start of a line are indentation only with
+///         preserve_single_newlines; otherwise lines are still joined
+///     paragraph_sep: How paragraphs are told apart before collapsing:
+///         "double" (default) splits at "\n\n", "single" at every line break,
+///         for PDF extractions with one line per paragraph, and "blank-line" at
+///         runs of lines that are empty or hold only spaces or tabs. Each
+///         paragraph becomes one line (soft-wrapped lines are joined) and
+///         paragraphs are separated by exactly "\n\n"
+///     strip_control_chars: Remove control characters other than newlines and tabs
+///     strip_invisible: Delete zero-width spaces (U+200B), word joiners
+///         (U+2060), byte order marks (U+FEFF), soft hyphens (U+00AD, so
+///         "fi\u00adnan\u00adcial" becomes "financial"), the zero-width
+///         joiner and non-joiner (U+200D, U+200C) and bidirectional controls
+///         (U+200E, U+200F, U+202A-U+202E, U+2066-U+2069), which can make text
+///         display differently from how it reads. Combining marks are kept
+///     keep_joiners: With strip_invisible, keep U+200C and U+200D, which
+///         Arabic and Indic scripts and emoji sequences depend on
+///     strip_html: Treat the input as HTML first: drop tags, comments and
+///         script/style/noscript contents, turn block element boundaries into
+///         paragraph breaks and decode character references like "&amp;"
+///     strip_markdown: Treat the input as Markdown: drop heading, emphasis,
+///         list, quote and inline code markers, keep only the text of links
+///         and images, and put each heading in its own paragraph. Fenced code
+///         blocks are left as they are, and "*" or "_" inside a word ("5*3*2",
+///         "snake_case") is kept
+///     preserve_code_blocks: Leave ```-fenced (or ~~~-fenced) code blocks
+///         byte-for-byte untouched, each as its own paragraph; the other steps
+///         only apply to the text around them
+///     preserve_tables: Keep plain-text tables (three or more lines of
+///         columns aligned with tabs or runs of spaces, as `extract_tables`
+///         finds them) out of whitespace collapsing, each as its own
+///         paragraph with its line breaks and column spacing; the other steps
+///         still apply to them
+///     dehyphenate: Rejoin words hyphenated across line breaks, as `dehyphenate`
+///         does, before whitespace is collapsed
+///
+/// The defaults reproduce `clean_text`'s behavior when no options are given.
+#[cfg_attr(
+    feature = "python",
+    pyo3::pyclass(module = "rag_rust_core", get_all, set_all)
+)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct CleanOptions {
+    pub unicode_normalization: UnicodeForm,
+    pub fold_to_ascii: bool,
+    pub fix_mojibake: bool,
+    pub standardize_quotes: bool,
+    pub standardize_dashes: bool,
+    pub collapse_whitespace: bool,
+    pub preserve_single_newlines: bool,
+    pub preserve_lists: bool,
+    pub tab_policy: TabPolicy,
+    pub paragraph_sep: ParagraphSep,
+    pub strip_control_chars: bool,
+    pub strip_invisible: bool,
+    pub keep_joiners: bool,
+    pub strip_html: bool,
+    pub strip_markdown: bool,
+    pub preserve_code_blocks: bool,
+    pub preserve_tables: bool,
+    pub dehyphenate: bool,
+}
+
+impl Default for CleanOptions {
+    fn default() -> Self {
+        CleanOptions {
+            unicode_normalization: UnicodeForm::Nfkc,
+            fold_to_ascii: false,
+            fix_mojibake: false,
+            standardize_quotes: true,
+            standardize_dashes: true,
+            collapse_whitespace: true,
+            preserve_single_newlines: false,
+            preserve_lists: false,
+            tab_policy: TabPolicy::Collapse,
+            paragraph_sep: ParagraphSep::Double,
+            strip_control_chars: true,
+            strip_invisible: true,
+            keep_joiners: false,
+            strip_html: false,
+            strip_markdown: false,
+            preserve_code_blocks: false,
+            preserve_tables: false,
+            dehyphenate: false,
         }
-        // Fall back to simple character-based splitting for very long text without periods
-        return Some(
-            text.chars()
-                .collect::<Vec<_>>()
-                .chunks(target_size)
-                .map(|c| c.iter().collect::<String>())
-                .collect(),
-        );
     }
+}
 
-    let mut chunks: Vec<String> = Vec::new();
-    let mut current_chunk = String::new();
-    let mut current_chunk_chars: usize = 0;
+/// Collapse whitespace within one paragraph, keeping list items on their own
+/// lines with `preserve_lists`.
+fn collapse_paragraph_keeping_lists(
+    paragraph: &str,
+    preserve_single_newlines: bool,
+    preserve_lists: bool,
+    tabs: TabPolicy,
+) -> String {
+    if !preserve_lists {
+        return collapse_paragraph(paragraph, preserve_single_newlines, tabs);
+    }
+    lists::split_list_items(paragraph)
+        .into_iter()
+        .map(|block| match block {
+            lists::Block::Item(item) => collapse_paragraph(item, false, tabs),
+            lists::Block::Prose(prose) => collapse_paragraph(prose, preserve_single_newlines, tabs),
+        })
+        .filter(|block| !block.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
 
-    // Use VecDeque for O(1) pop_front instead of Vec::remove(0) which is O(n)
-    let mut overlap_buffer: VecDeque<String> = VecDeque::new();
-    let mut overlap_len_chars: usize = 0;
+/// Collapse whitespace within one paragraph, optionally keeping its single newlines.
+fn collapse_paragraph(paragraph: &str, preserve_single_newlines: bool, tabs: TabPolicy) -> String {
+    if !preserve_single_newlines {
+        return tabs.collapse(paragraph);
+    }
+    paragraph
+        .lines()
+        .map(|line| tabs.collapse(line))
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
 
-    for sentence in sentences {
-        let sentence_chars = char_len(&sentence);
-        // Add a space separator if not the first sentence in the chunk
-        let separator = if current_chunk.is_empty() { "" } else { " " };
-        let separator_chars = separator.len(); // Always 0 or 1 for ASCII space
+/// Clean text with the default `CleanOptions`, as `clean_text` does in Python.
+pub fn clean_text(text: &str) -> String {
+    clean_text_with_options(text, &CleanOptions::default())
+}
 
-        // Check if adding this sentence would exceed target
-        if !current_chunk.is_empty()
-            && current_chunk_chars + separator_chars + sentence_chars > target_size
-        {
-            // Save current chunk
-            chunks.push(current_chunk.trim().to_string());
+/// Clean text with explicit options (pure Rust, no PyO3 dependencies).
+#[allow(clippy::collapsible_str_replace)]
+pub fn clean_text_with_options(text: &str, options: &CleanOptions) -> String {
+    // HTML is reduced to text first so decoded references get normalized below
+    let stripped;
+    let text = if options.strip_html {
+        stripped = html::strip_html(text);
+        stripped.as_str()
+    } else {
+        text
+    };
 
-            // Start new chunk with overlap from previous sentences
-            current_chunk = overlap_buffer
-                .iter()
-                .cloned()
-                .collect::<Vec<_>>()
-                .join(" ");
-            current_chunk_chars = char_len(&current_chunk);
+    // Markdown syntax goes next, while backticks still mark code spans
+    let unmarked;
+    let text = if options.strip_markdown {
+        unmarked = markdown::strip_markdown(text);
+        unmarked.as_str()
+    } else {
+        text
+    };
 
-            // Don't clear overlap_buffer - we keep it for continuity
-        }
+    // Code blocks pass through verbatim; only the prose around them is cleaned
+    if options.preserve_code_blocks {
+        let prose_options = CleanOptions {
+            strip_html: false,
+            strip_markdown: false,
+            preserve_code_blocks: false,
+            ..options.clone()
+        };
+        let pieces: Vec<String> = markdown::split_code_blocks(text)
+            .into_iter()
+            .map(|block| match block {
+                markdown::Block::Prose(prose) => clean_text_with_options(prose, &prose_options),
+                markdown::Block::Code(code) => code.to_string(),
+            })
+            .filter(|piece| !piece.is_empty())
+            .collect();
+        // Collapsing trims the prose, so code blocks need their own paragraphs
+        return pieces.join(if options.collapse_whitespace {
+            "\n\n"
+        } else {
+            ""
+        });
+    }
 
-        // Add sentence to current chunk
-        if !current_chunk.is_empty() {
-            current_chunk.push(' ');
-            current_chunk_chars += 1;
-        }
-        current_chunk.push_str(&sentence);
-        current_chunk_chars += sentence_chars;
+    // Tables keep their line breaks and column spacing; only collapsing is
+    // skipped for them
+    if options.preserve_tables {
+        let prose_options = CleanOptions {
+            strip_html: false,
+            strip_markdown: false,
+            preserve_tables: false,
+            ..options.clone()
+        };
+        let table_options = CleanOptions {
+            collapse_whitespace: false,
+            ..prose_options.clone()
+        };
+        let pieces: Vec<String> = tables::split_tables(text)
+            .into_iter()
+            .map(|block| match block {
+                tables::Block::Prose(prose) => clean_text_with_options(prose, &prose_options),
+                tables::Block::Table(table) => clean_text_with_options(table, &table_options),
+            })
+            .filter(|piece| !piece.is_empty())
+            .collect();
+        return pieces.join(if options.collapse_whitespace {
+            "\n\n"
+        } else {
+            ""
+        });
+    }
 
-        // Track recent sentences for overlap (using character count)
-        overlap_buffer.push_back(sentence.clone());
-        overlap_len_chars += sentence_chars + 1; // +1 for space separator
+    // Mojibake goes before normalization, which would rewrite some of its
+    // characters ("™" to "TM")
+    let repaired;
+    let text = if options.fix_mojibake {
+        repaired = mojibake::repair_mojibake(text);
+        repaired.as_str()
+    } else {
+        text
+    };
 
-        // Trim overlap buffer to stay within overlap limit (fix: recompute in loop!)
-        while overlap_len_chars > overlap && overlap_buffer.len() > 1 {
-            if let Some(removed) = overlap_buffer.pop_front() {
-                overlap_len_chars = overlap_len_chars.saturating_sub(char_len(&removed) + 1);
-            }
+    // NFKC (the default) handles compatibility characters like ligatures and fullwidth forms
+    let mut result: String = match options.unicode_normalization {
+        UnicodeForm::Nfkc => text.nfkc().collect(),
+        UnicodeForm::Nfc => text.nfc().collect(),
+        UnicodeForm::None => text.to_string(),
+    };
+
+    if options.fold_to_ascii {
+        result = fold::fold_to_ascii(&result);
+    }
+
+    // Standardize dashes (before quotes, whose characters the mojibake forms contain)
+    if options.standardize_dashes {
+        for garbled in DASH_MOJIBAKE {
+            result = result.replace(garbled, "-");
         }
+        result = result
+            .replace('\u{2010}', "-") // Hyphen
+            .replace('\u{2011}', "-") // Non-breaking hyphen
+            .replace('\u{2013}', "-") // En dash –
+            .replace('\u{2014}', "-") // Em dash —
+            .replace('\u{2015}', "-") // Horizontal bar ―
+            .replace('\u{2212}', "-"); // Minus sign −
     }
 
-    // Don't forget the last chunk
-    if !current_chunk.trim().is_empty() {
-        chunks.push(current_chunk.trim().to_string());
+    // Standardize quotes (curly quotes to straight quotes)
+    if options.standardize_quotes {
+        result = result
+            .replace('\u{201C}', "\"") // Left double quotation mark "
+            .replace('\u{201D}', "\"") // Right double quotation mark "
+            .replace('\u{2018}', "'") // Left single quotation mark '
+            .replace('\u{2019}', "'") // Right single quotation mark '
+            .replace('`', "'");
     }
 
-    Some(chunks)
+    // Remove invisible format characters, which are not control characters
+    // (bidi controls included, against spoofed display order)
+    if options.strip_invisible {
+        result.retain(|c| match c {
+            '\u{200B}' | '\u{2060}' | '\u{FEFF}' | '\u{00AD}' => false,
+            '\u{200E}' | '\u{200F}' | '\u{202A}'..='\u{202E}' | '\u{2066}'..='\u{2069}' => false,
+            '\u{200C}' | '\u{200D}' => options.keep_joiners,
+            _ => true,
+        });
+    }
+
+    // Remove control characters (except newlines and tabs)
+    if options.strip_control_chars {
+        result = result
+            .chars()
+            .filter(|c| !c.is_control() || *c == '\n' || *c == '\t')
+            .collect();
+    }
+
+    if options.dehyphenate {
+        result = dehyphenate_impl(&result);
+    }
+
+    if !options.collapse_whitespace {
+        return result;
+    }
+
+    // Collapse whitespace (preserve paragraph breaks)
+    let paragraphs = options.paragraph_sep.split(&result);
+    let cleaned_paragraphs: Vec<String> = paragraphs
+        .iter()
+        .map(|p| {
+            collapse_paragraph_keeping_lists(
+                p,
+                options.preserve_single_newlines,
+                options.preserve_lists,
+                options.tab_policy,
+            )
+        })
+        .filter(|p| !p.is_empty())
+        .collect();
+
+    cleaned_paragraphs.join("\n\n")
 }
 
-/// Split text into chunks suitable for embedding.
-///
-/// Args:
-///     text: The input text to chunk
-///     target_size: Target chunk size in characters (default: 1500, roughly ~375 tokens)
-///     overlap: Number of characters to overlap between chunks (default: 200)
+/// Rejoin words hyphenated across line breaks ("inter-\nnational" becomes
+/// "international"), dropping the hyphen and the line break.
 ///
-/// Returns:
-///     List of text chunks with sentence-boundary awareness
-///
-/// Raises:
-///     ValueError: If target_size is 0
-#[pyfunction]
-#[pyo3(signature = (text, target_size=1500, overlap=200))]
-fn chunk_text(text: &str, target_size: usize, overlap: usize) -> PyResult<Vec<String>> {
-    chunk_text_impl(text, target_size, overlap)
-        .ok_or_else(|| PyValueError::new_err("target_size must be greater than 0"))
+/// Only a line-final fragment of lowercase letters followed by a line-initial
+/// fragment of lowercase letters is joined. Capitalized fragments ("Coca-\nCola"),
+/// digits ("COVID-\n19") and compounds with a further hyphen on either side
+/// ("state-\nof-the-art") are left as they are. A genuine two-part compound
+/// broken at its own hyphen ("well-\nknown") cannot be told apart from
+/// typesetter hyphenation and is joined ("wellknown"); hyphenation is far more
+/// common in extracted text.
+pub fn dehyphenate_impl(text: &str) -> String {
+    let is_hyphen = |c: Option<char>| c == Some('-');
+    LINE_BREAK_HYPHEN_RE
+        .replace_all(text, |caps: &regex::Captures| {
+            let whole = caps.get(0).unwrap();
+            let before = text[..whole.start()].chars().next_back();
+            let after = text[whole.end()..].chars().next();
+            if is_hyphen(before) || is_hyphen(after) {
+                whole.as_str().to_string()
+            } else {
+                format!("{}{}", &caps[1], &caps[2])
+            }
+        })
+        .into_owned()
 }
 
-/// Extract financial metadata from text.
-///
-/// Identifies and extracts:
-/// - Monetary amounts ($X million, etc.)
-/// - Percentages
-/// - Dates (Q1 2024, January 15, 2024, etc.)
-/// - Potential ticker symbols (sorted alphabetically for deterministic output)
-///
-/// Returns a dict with lists of found entities.
-#[pyfunction]
-fn extract_metadata(py: Python<'_>, text: &str) -> PyResult<Py<PyDict>> {
-    let dict = PyDict::new(py);
+/// Split text into sentences while preserving original punctuation.
+/// Returns a vector of sentences including their terminating punctuation.
+pub fn split_sentences_preserve_punct(text: &str) -> Vec<String> {
+    split_sentence_units(text)
+        .into_iter()
+        .map(|unit| unit.text)
+        .collect()
+}
 
-    // Extract monetary amounts
-    let money: Vec<String> = MONEY_RE
-        .find_iter(text)
-        .map(|m| m.as_str().to_string())
-        .collect();
-    dict.set_item("monetary_amounts", money)?;
+/// A sentence, a window of an oversized one, or a code block, as packed into chunks.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Unit {
+    pub text: String,
+    /// Whether a single line break (not a paragraph break) separates it from
+    /// the previous unit in the source, so chunks keep it on its own line.
+    pub after_line_break: bool,
+    /// Whether it starts a paragraph: a blank line separates it from the
+    /// previous unit, or one of them is a preserved code block.
+    pub after_paragraph_break: bool,
+}
 
-    // Extract percentages
-    let percentages: Vec<String> = PERCENTAGE_RE
-        .find_iter(text)
-        .map(|m| m.as_str().to_string())
-        .collect();
-    dict.set_item("percentages", percentages)?;
+impl Unit {
+    fn new(text: String) -> Self {
+        Unit {
+            text,
+            after_line_break: false,
+            after_paragraph_break: false,
+        }
+    }
+}
 
-    // Extract dates
-    let dates: Vec<String> = DATE_RE
-        .find_iter(text)
-        .map(|m| m.as_str().to_string())
-        .collect();
-    dict.set_item("dates", dates)?;
+/// `split_sentences_preserve_punct`, recording which sentences start a new line.
+pub(crate) fn split_sentence_units(text: &str) -> Vec<Unit> {
+    split_sentence_units_at(text, &UnitOptions::default())
+}
 
-    // Extract potential ticker symbols (filter common words, dedupe, sort for determinism)
-    let mut tickers: Vec<String> = TICKER_RE
-        .find_iter(text)
-        .map(|m| m.as_str().to_string())
-        .filter(|t| !COMMON_TICKER_STOPWORDS.contains(t.as_str()))
-        .collect::<HashSet<_>>()
-        .into_iter()
-        .collect();
-    tickers.sort(); // Deterministic ordering for reproducible output
-    dict.set_item("potential_tickers", tickers)?;
+/// Compile a user-supplied sentence boundary pattern, refusing one that
+/// matches empty text (which would end a sentence at every character).
+#[cfg(any(feature = "python", test))]
+fn compile_boundary_pattern(pattern: &str) -> Result<Regex, String> {
+    let re = Regex::new(pattern).map_err(|e| format!("invalid boundary_pattern: {e}"))?;
+    if re.is_match("") {
+        return Err(format!(
+            "boundary_pattern {pattern:?} matches empty text; it must consume at least one character"
+        ));
+    }
+    Ok(re)
+}
 
-    Ok(dict.into())
+/// `split_sentence_units` at matches of `boundary` instead of
+/// `SENTENCE_BOUNDARY_RE`. A sentence keeps the part of a custom boundary match
+/// before its first whitespace ("; " leaves ";" on the sentence) and the part
+/// after that whitespace starts the next one (" (b) " starts it with "(b)").
+fn split_sentence_units_at(text: &str, units: &UnitOptions) -> Vec<Unit> {
+    let mut cursor = SentenceCursor::default();
+    std::iter::from_fn(|| cursor.next_unit(text, units)).collect()
 }
 
-/// Process a document through the full pipeline: clean, chunk, and extract metadata.
-///
-/// This is a convenience function that runs all three steps and returns
-/// a list of dicts, one per chunk, each containing the chunk text and its metadata.
-#[pyfunction]
-#[pyo3(signature = (text, chunk_size=1500, chunk_overlap=200))]
-fn process_document(
-    py: Python<'_>,
-    text: &str,
-    chunk_size: usize,
-    chunk_overlap: usize,
-) -> PyResult<Vec<Py<PyDict>>> {
-    let cleaned = clean_text(text);
-    let chunks = chunk_text(&cleaned, chunk_size, chunk_overlap)?;
+/// Abbreviations whose period never ends a sentence.
+const ABBREVIATIONS: [&str; 12] = [
+    "approx", "Approx", "vs", "Mr", "Mrs", "Ms", "Dr", "Prof", "Fig", "e.g", "i.e", "cf",
+];
+
+/// Abbreviations whose period does not end a sentence when a number follows
+/// ("No. 1 ranking"); "No." on its own is an answer.
+const NUMBER_ABBREVIATIONS: [&str; 4] = ["No", "Nos", "no", "nos"];
+
+/// Whether a `SENTENCE_BOUNDARY_RE` match is a single period that belongs to
+/// the sentence rather than ending it: after an initial ("U.S. Treasury",
+/// "J. Smith"), a list number at the start of a line ("1. Introduction") or an
+/// abbreviation ("approx. 4 million", "No. 1"). A line break after the period
+/// still ends the sentence.
+fn is_inner_period(text: &str, m: &regex::Match) -> bool {
+    let Some(whitespace) = m.as_str().strip_prefix('.') else {
+        return false;
+    };
+    if !whitespace.starts_with(char::is_whitespace) || whitespace.contains('\n') {
+        return false;
+    }
+    let before = &text[..m.start()];
+    let word = before.rsplit(char::is_whitespace).next().unwrap_or(before);
+    let mut last = word.chars().rev();
+    let initial = match (last.next(), last.next()) {
+        (Some(letter), previous) => {
+            letter.is_uppercase() && previous.is_none_or(|c| !c.is_alphanumeric())
+        }
+        (None, _) => false,
+    };
+    let line_prefix = before[..before.len() - word.len()]
+        .rsplit('\n')
+        .next()
+        .unwrap_or("");
+    let list_number = (1..=3).contains(&word.len())
+        && word.bytes().all(|b| b.is_ascii_digit())
+        && line_prefix.trim().is_empty();
+    let abbreviation = word.trim_start_matches(['(', '[', '"', '\'']);
+    let number_follows = text[m.end()..].starts_with(|c: char| c.is_ascii_digit());
+    initial
+        || list_number
+        || ABBREVIATIONS.contains(&abbreviation)
+        || (number_follows && NUMBER_ABBREVIATIONS.contains(&abbreviation))
+}
+
+/// Whether a `SENTENCE_BOUNDARY_RE` match is an ellipsis ("..." or "…")
+/// marking a pause mid-sentence: one followed by anything but a capitalized
+/// word or a line break. Clusters with "!" or "?" ("Really?!", "What?...")
+/// always end the sentence.
+fn is_pause(text: &str, m: &regex::Match) -> bool {
+    let cluster = m.as_str().trim_end();
+    let dots = cluster.matches('.').count();
+    let is_ellipsis =
+        cluster.chars().all(|c| c == '.' || c == '…') && (dots >= 2 || cluster.contains('…'));
+    let next = text[m.end()..]
+        .trim_start_matches(['"', '\'', '(', '['])
+        .chars()
+        .next();
+    is_ellipsis && !m.as_str().contains('\n') && next.is_some_and(|c| !c.is_uppercase())
+}
+
+/// Returned by `SentenceCursor::next_unit_of` when the next sentence may
+/// continue past the end of the text read so far.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Incomplete;
+
+/// Whether a boundary match ending at `end` is followed by enough of `text`
+/// to tell whether it ends a sentence: a character other than whitespace or
+/// an opening quote or bracket, which `is_inner_period` and `is_pause` look at.
+fn is_settled(text: &str, end: usize) -> bool {
+    text[end..].contains(|c: char| !c.is_whitespace() && !matches!(c, '"' | '\'' | '(' | '['))
+}
 
-    let mut results: Vec<Py<PyDict>> = Vec::with_capacity(chunks.len());
+/// Position of `split_sentence_units_at` in a text, so its sentences can be
+/// taken one at a time.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct SentenceCursor {
+    /// Where the search for the next boundary starts.
+    search_from: usize,
+    /// Start of the text not yet part of a sentence.
+    last_end: usize,
+    /// End of the previous sentence; the text between it and the next one is whitespace.
+    previous_end: Option<usize>,
+    done: bool,
+}
+
+impl SentenceCursor {
+    /// The next sentence of `text`, which must be the same text on every call.
+    /// Only `units.boundary`, `units.ellipsis_terminal` and
+    /// `units.newline_is_boundary` apply.
+    pub fn next_unit(&mut self, text: &str, units: &UnitOptions) -> Option<Unit> {
+        self.next_unit_of(text, units, true)
+            .expect("complete text always has its next sentence")
+    }
 
-    for (i, chunk) in chunks.iter().enumerate() {
-        let dict = PyDict::new(py);
-        dict.set_item("chunk_index", i)?;
-        dict.set_item("text", chunk)?;
-        dict.set_item("char_count", char_len(chunk))?; // True character count, not bytes
+    /// `next_unit` for text that is only the beginning of the whole text
+    /// unless `complete`: fails with `Incomplete` while more text could still
+    /// change where the next sentence ends. After more text is appended, call
+    /// again with the longer text.
+    pub fn next_unit_of(
+        &mut self,
+        text: &str,
+        units: &UnitOptions,
+        complete: bool,
+    ) -> Result<Option<Unit>, Incomplete> {
+        let boundary = units.boundary;
+        while !self.done {
+            // Find the next sentence boundary (punctuation + optional whitespace)
+            let found = boundary
+                .unwrap_or(&SENTENCE_BOUNDARY_RE)
+                .find_at(text, self.search_from);
+            // A line break before it ends a line without sentence punctuation,
+            // since a line that has it ends in a match spanning the break
+            let line_end = units
+                .newline_is_boundary
+                .then(|| text[self.search_from..].find('\n'))
+                .flatten()
+                .map(|i| self.search_from + i)
+                .filter(|&i| found.as_ref().is_none_or(|m| i < m.start()));
+            if let Some(line_end) = line_end {
+                let raw_start = self.last_end;
+                self.search_from = line_end + 1;
+                self.last_end = line_end + 1;
+                if let Some(unit) = self.sentence(text, raw_start, line_end) {
+                    return Ok(Some(unit));
+                }
+                continue;
+            }
+            if !complete && found.as_ref().is_none_or(|m| !is_settled(text, m.end())) {
+                return Err(Incomplete);
+            }
+            let (raw_start, raw_end) = match found {
+                // A period inside a sentence ("U.S. Treasury") or a pause
+                // ("He paused... then") is not a boundary
+                Some(m)
+                    if boundary.is_none()
+                        && (is_inner_period(text, &m)
+                            || (!units.ellipsis_terminal && is_pause(text, &m))) =>
+                {
+                    self.search_from = m.end();
+                    continue;
+                }
+                Some(m) => {
+                    // Include everything up to and including the punctuation and closing
+                    // quotes, but not trailing whitespace or a following CJK character
+                    let boundary_text = m.as_str();
+                    let punct_len = match boundary {
+                        None => boundary_text.find(|c: char| {
+                            !matches!(c, '.' | '!' | '?' | '…' | '。' | '！' | '？' | '」' | '』')
+                        }),
+                        Some(_) => boundary_text.find(char::is_whitespace),
+                    }
+                    .unwrap_or(boundary_text.len());
+                    let punct_end = m.start() + punct_len;
+                    let raw_start = self.last_end;
+                    self.search_from = m.end();
+                    self.last_end = punct_end
+                        + (text[punct_end..].len() - text[punct_end..].trim_start().len());
+                    (raw_start, punct_end)
+                }
+                // Don't forget the final segment (text after last sentence boundary)
+                None => {
+                    self.done = true;
+                    (self.last_end, text.len())
+                }
+            };
+            if let Some(unit) = self.sentence(text, raw_start, raw_end) {
+                return Ok(Some(unit));
+            }
+        }
+        Ok(None)
+    }
 
-        // Extract metadata for this chunk
-        let metadata = extract_metadata(py, chunk)?;
-        dict.set_item("metadata", metadata)?;
+    /// How many leading bytes of `text` the cursor no longer needs, none once
+    /// it is done. The last character of the previous sentence is kept, so a
+    /// "1." after it is not mistaken for a list number at the start of a line.
+    #[cfg(any(feature = "python", test))]
+    pub fn consumed(&self, text: &str) -> usize {
+        if self.done {
+            return 0;
+        }
+        self.previous_end.map_or(0, |end| {
+            text[..end].char_indices().next_back().map_or(0, |(i, _)| i)
+        })
+    }
 
-        results.push(dict.into());
+    /// Account for the first `n` bytes of the text being removed; `n` must be
+    /// at most `consumed`.
+    #[cfg(any(feature = "python", test))]
+    pub fn shift(&mut self, n: usize) {
+        self.search_from -= n;
+        self.last_end -= n;
+        self.previous_end = self.previous_end.map(|end| end - n);
     }
 
-    Ok(results)
+    /// The trimmed sentence in `text[raw_start..raw_end]`, if it is not blank.
+    fn sentence(&mut self, text: &str, raw_start: usize, raw_end: usize) -> Option<Unit> {
+        let raw = &text[raw_start..raw_end];
+        let sentence = raw.trim();
+        if sentence.is_empty() {
+            return None;
+        }
+        let start = raw_start + (raw.len() - raw.trim_start().len());
+        let line_breaks = self
+            .previous_end
+            .map_or(0, |end| text[end..start].matches('\n').count());
+        self.previous_end = Some(start + sentence.len());
+        Some(Unit {
+            text: sentence.to_string(),
+            after_line_break: line_breaks == 1,
+            after_paragraph_break: line_breaks >= 2,
+        })
+    }
 }
 
-/// The Python module definition.
-#[pymodule]
-fn rag_rust_core(m: &Bound<'_, PyModule>) -> PyResult<()> {
-    m.add_function(wrap_pyfunction!(clean_text, m)?)?;
-    m.add_function(wrap_pyfunction!(chunk_text, m)?)?;
-    m.add_function(wrap_pyfunction!(extract_metadata, m)?)?;
-    m.add_function(wrap_pyfunction!(process_document, m)?)?;
-    Ok(())
+/// Split text into pieces of at most `max_chars` characters, breaking at the last
+/// whitespace inside each window where there is one and mid-word otherwise,
+/// between grapheme clusters.
+fn split_by_chars(text: &str, max_chars: usize) -> Vec<String> {
+    let mut pieces = Vec::new();
+    let mut rest = text.trim();
+    let mut remaining = char_len(rest);
+
+    while remaining > max_chars {
+        // Byte offset just past the first max_chars characters
+        let window_end = graphemes::byte_offset(rest, max_chars);
+        let cut = if rest[window_end..].starts_with(char::is_whitespace) {
+            window_end
+        } else {
+            rest[..window_end]
+                .rfind(char::is_whitespace)
+                .filter(|&i| i > 0)
+                .unwrap_or(window_end)
+        };
+        pieces.push(rest[..cut].trim_end().to_string());
+        let next = rest[cut..].trim_start();
+        remaining -= char_len(&rest[..rest.len() - next.len()]);
+        rest = next;
+    }
+
+    if !rest.is_empty() {
+        pieces.push(rest.to_string());
+    }
+    pieces
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// Split text into pieces of at most `max_words` words, breaking only between
+/// words and keeping the text between them as written.
+fn split_by_words(text: &str, max_words: usize) -> Vec<String> {
+    // Byte offset of a word yielded by `split_whitespace` within `text`
+    let offset = |word: &str| word.as_ptr() as usize - text.as_ptr() as usize;
+    let words: Vec<&str> = text.split_whitespace().collect();
+    words
+        .chunks(max_words.max(1))
+        .map(|window| {
+            let first = window[0];
+            let last = window[window.len() - 1];
+            text[offset(first)..offset(last) + last.len()].to_string()
+        })
+        .collect()
+}
 
-    #[test]
-    fn test_clean_text() {
-        let input = "Hello   world.\n\n\nThis is   a test.";
-        let result = clean_text(input);
-        assert!(result.contains("Hello world."));
-        assert!(result.contains("This is a test."));
+/// Hard-split any sentence longer than `target_size` so no single sentence can
+/// produce an oversized chunk. The first window keeps the sentence's line break.
+pub(crate) fn split_oversized_sentences(
+    sentences: Vec<Unit>,
+    target_size: usize,
+    size_unit: SizeUnit,
+) -> Vec<Unit> {
+    if sentences
+        .iter()
+        .all(|s| size_unit.measure(&s.text) <= target_size)
+    {
+        return sentences;
     }
+    sentences
+        .into_iter()
+        .flat_map(|s| split_oversized_sentence(s, target_size, size_unit))
+        .collect()
+}
 
-    #[test]
-    fn test_clean_text_nfkc_normalization() {
-        // Test that NFKC normalization is actually applied
-        // The "ﬁ" ligature (U+FB01) should become "fi"
-        let input = "ﬁnance";
-        let result = clean_text(input);
-        assert_eq!(result, "finance");
+/// `split_oversized_sentences` for a single sentence.
+pub(crate) fn split_oversized_sentence(
+    sentence: Unit,
+    target_size: usize,
+    size_unit: SizeUnit,
+) -> Vec<Unit> {
+    if size_unit.measure(&sentence.text) <= target_size {
+        return vec![sentence];
     }
+    let mut windows: Vec<Unit> = size_unit
+        .split(&sentence.text, target_size)
+        .into_iter()
+        .map(Unit::new)
+        .collect();
+    windows[0].after_line_break = sentence.after_line_break;
+    windows[0].after_paragraph_break = sentence.after_paragraph_break;
+    windows
+}
 
-    #[test]
-    fn test_chunk_text() {
-        let text = "First sentence. Second sentence. Third sentence. Fourth sentence.";
-        let chunks = chunk_text_impl(text, 40, 10).unwrap();
-        assert!(!chunks.is_empty());
+/// Separator between two consecutive units of a chunk: a blank line around
+/// code blocks, a newline before a unit that started a line, a space otherwise.
+pub(crate) fn unit_separator(
+    previous_is_code: bool,
+    next: &Unit,
+    next_is_code: bool,
+) -> &'static str {
+    if previous_is_code || next_is_code {
+        "\n\n"
+    } else if next.after_line_break {
+        "\n"
+    } else {
+        " "
     }
+}
 
-    #[test]
-    fn test_chunk_text_zero_size_returns_none() {
-        let text = "Some text.";
-        let result = chunk_text_impl(text, 0, 10);
-        assert!(result.is_none());
+/// How much of the previous chunk is carried into the next one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Overlap {
+    /// Trailing sentences fitting in this size, in the chunks' `SizeUnit`
+    /// (always at least one).
+    Size(usize),
+    /// Exactly this many whole trailing sentences, from `overlap_sentences`.
+    #[cfg_attr(not(feature = "python"), allow(dead_code))]
+    Sentences(usize),
+}
+
+/// What chunk sizes, size-based overlaps and `min_chunk_size` count.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SizeUnit {
+    /// Grapheme clusters, as counted by `char_len`.
+    #[default]
+    Chars,
+    /// Whitespace-delimited tokens, as counted by `word_count`. Separators
+    /// between sentences are free.
+    Words,
+}
+
+impl SizeUnit {
+    #[cfg(any(feature = "python", feature = "cli"))]
+    fn parse(name: &str) -> Option<Self> {
+        match name {
+            "chars" => Some(SizeUnit::Chars),
+            "words" => Some(SizeUnit::Words),
+            _ => None,
+        }
     }
 
-    #[test]
-    fn test_chunk_text_preserves_punctuation() {
-        // Test that original punctuation is preserved
-        let text = "What is this? It is great! Really.";
-        let chunks = chunk_text_impl(text, 100, 10).unwrap();
-        assert_eq!(chunks.len(), 1);
-        // Should preserve the ? and ! instead of converting to .
-        assert!(chunks[0].contains("?"));
-        assert!(chunks[0].contains("!"));
+    /// Size of `text` in this unit.
+    fn measure(self, text: &str) -> usize {
+        match self {
+            SizeUnit::Chars => char_len(text),
+            SizeUnit::Words => word_count(text),
+        }
     }
 
-    #[test]
-    fn test_chunk_text_overlap_correctness() {
-        // Test that overlap actually works and is measured in characters
-        let text = "One. Two. Three. Four. Five.";
-        let chunks = chunk_text_impl(text, 15, 5).unwrap();
-        // With such small chunks, we should get multiple chunks with overlap
-        assert!(chunks.len() > 1);
+    /// Split `text` into pieces of at most `max` of this unit at word boundaries
+    /// (mid-word only for a single word longer than `max` characters, and never
+    /// inside a grapheme cluster).
+    fn split(self, text: &str, max: usize) -> Vec<String> {
+        match self {
+            SizeUnit::Chars => split_by_chars(text, max),
+            SizeUnit::Words => split_by_words(text, max),
+        }
     }
+}
 
-    #[test]
-    fn test_standardize_quotes() {
-        // Input with curly quotes (Unicode)
-        let input = "\u{201C}Hello\u{201D} and \u{2018}world\u{2019}";
-        let result = clean_text(input);
-        assert_eq!(result, "\"Hello\" and 'world'");
+/// How `chunk_text` cuts text into chunks.
+#[cfg(feature = "python")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+enum ChunkStrategy {
+    /// Pack whole sentences up to the target size.
+    #[default]
+    Sentence,
+    /// Fixed-size windows regardless of sentences, see `window::window_chunks`.
+    Window,
+}
+
+#[cfg(feature = "python")]
+impl ChunkStrategy {
+    fn parse(name: &str) -> Option<Self> {
+        match name {
+            "sentence" => Some(ChunkStrategy::Sentence),
+            "window" => Some(ChunkStrategy::Window),
+            _ => None,
+        }
     }
+}
 
-    #[test]
-    fn test_char_len_vs_byte_len() {
-        // Emoji is multiple bytes but one character (actually one grapheme cluster)
-        let emoji = "😀";
-        assert_eq!(char_len(emoji), 1);
-        assert_eq!(emoji.len(), 4); // UTF-8 bytes
+/// Internal implementation of chunk_text (pure Rust, no PyO3 dependencies).
+/// Returns None if target_size is 0, otherwise returns the chunks.
+///
+/// Uses character counts (grapheme clusters) for sizing, not bytes. Sentences
+/// longer than `target_size` are split into word-boundary windows first, and the
+/// overlap carried into a chunk is trimmed so the chunk's first new sentence
+/// still fits, so no chunk exceeds `target_size`.
+pub fn chunk_text_impl(text: &str, target_size: usize, overlap: usize) -> Option<Vec<String>> {
+    chunk_text_with_overlap(
+        text,
+        target_size,
+        Overlap::Size(overlap),
+        false,
+        SizeUnit::Chars,
+    )
+}
 
-        // Accented character
-        let accented = "é";
-        assert_eq!(char_len(accented), 1);
+/// Options for `chunk_text`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChunkConfig {
+    /// Target chunk size in `size_unit` (default: 1500).
+    pub target_size: usize,
+    /// Overlap between consecutive chunks in `size_unit` (default: 200).
+    pub overlap: usize,
+    pub size_unit: SizeUnit,
+}
+
+impl Default for ChunkConfig {
+    fn default() -> Self {
+        ChunkConfig {
+            target_size: 1500,
+            overlap: DEFAULT_OVERLAP,
+            size_unit: SizeUnit::Chars,
+        }
     }
+}
 
-    #[test]
-    fn test_split_sentences_preserve_punct() {
-        let text = "Hello! How are you? I am fine.";
-        let sentences = split_sentences_preserve_punct(text);
-        assert_eq!(sentences.len(), 3);
-        assert_eq!(sentences[0], "Hello!");
-        assert_eq!(sentences[1], "How are you?");
-        assert_eq!(sentences[2], "I am fine.");
+/// A chunk returned by `chunk_text`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Chunk {
+    pub index: usize,
+    /// `hash_text_impl` of `"{index}:{text}"`, as `process_document`'s `chunk_id`.
+    pub id: String,
+    pub text: String,
+    /// Grapheme clusters, not bytes.
+    pub char_count: usize,
+    pub word_count: usize,
+}
+
+/// Why `chunk_text` could not chunk the text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChunkError {
+    /// `ChunkConfig::target_size` was 0.
+    ZeroTargetSize,
+}
+
+impl std::fmt::Display for ChunkError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ChunkError::ZeroTargetSize => f.write_str("target_size must be greater than 0"),
+        }
+    }
+}
+
+impl std::error::Error for ChunkError {}
+
+/// Split text into chunks of whole sentences, as `chunk_text` does in Python
+/// with `target_size`, `overlap` and `size_unit` set from `config`.
+pub fn chunk_text(text: &str, config: ChunkConfig) -> Result<Vec<Chunk>, ChunkError> {
+    let chunks = chunk_text_with_overlap(
+        text,
+        config.target_size,
+        Overlap::Size(config.overlap),
+        false,
+        config.size_unit,
+    )
+    .ok_or(ChunkError::ZeroTargetSize)?;
+    Ok(chunks
+        .into_iter()
+        .enumerate()
+        .map(|(index, text)| Chunk {
+            index,
+            id: chunk_id(index, &text),
+            char_count: char_len(&text),
+            word_count: word_count(&text),
+            text,
+        })
+        .collect())
+}
+
+/// `chunk_text_impl` with the overlap measured either by size or in sentences,
+/// and sizes counted in `size_unit`.
+///
+/// With `preserve_code_blocks`, fenced code blocks are kept verbatim as single
+/// units that are never split (even when longer than `target_size`) and are
+/// separated from neighbouring sentences by a blank line instead of a space.
+fn chunk_text_with_overlap(
+    text: &str,
+    target_size: usize,
+    overlap: Overlap,
+    preserve_code_blocks: bool,
+    size_unit: SizeUnit,
+) -> Option<Vec<String>> {
+    let units = UnitOptions {
+        preserve_code_blocks,
+        ..UnitOptions::default()
+    };
+    let chunks = pack_chunks(text, target_size, overlap, &units, size_unit)?;
+    Some(chunks.into_iter().map(|c| c.text).collect())
+}
+
+/// How text is cut into the units chunks are packed from.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct UnitOptions<'a> {
+    /// Keep fenced code blocks verbatim as single units that are never split.
+    pub preserve_code_blocks: bool,
+    /// Make each list item one unit, on its own line, however many sentences
+    /// it holds.
+    pub preserve_lists: bool,
+    /// End sentences at matches of this pattern instead of the built-in rules.
+    pub boundary: Option<&'a Regex>,
+    /// End a sentence at every ellipsis followed by whitespace, not just at
+    /// those followed by a capitalized word or a line break.
+    pub ellipsis_terminal: bool,
+    /// End a sentence at every line break, so lines without sentence
+    /// punctuation (headings, bullets) are units of their own.
+    pub newline_is_boundary: bool,
+    /// Keep sentences longer than the target size whole, as chunks of their
+    /// own, instead of splitting them into windows.
+    pub keep_long_sentences: bool,
+    /// Carry no overlap back across a paragraph break, so a chunk never
+    /// starts with sentences of an earlier paragraph than its first new one.
+    pub respect_paragraphs: bool,
+}
+
+/// A chunk as packed, with what is needed to append it to the previous one.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct PackedChunk<'a> {
+    pub text: String,
+    /// Byte offset in `text` of the first sentence not carried over as overlap.
+    pub new_start: usize,
+    /// Separator between the previous chunk's last sentence and that sentence.
+    pub joiner: &'a str,
+}
+
+/// `chunk_text_with_overlap`, then chunks smaller than `min_chunk_size`
+/// (in `size_unit`) are appended to the previous chunk, which may then exceed
+/// `target_size`. Only the last chunk is merged unless `merge_all_small` is
+/// set; the first chunk never is. Overlap the merged chunk carried is dropped
+/// rather than repeated. Units are cut as `units` says.
+#[cfg(any(feature = "python", test))]
+fn chunk_text_with_min_size(
+    text: &str,
+    target_size: usize,
+    overlap: Overlap,
+    units: &UnitOptions,
+    size_unit: SizeUnit,
+    min_chunk_size: usize,
+    merge_all_small: bool,
+) -> Option<Vec<String>> {
+    let chunks = pack_chunks(text, target_size, overlap, units, size_unit)?;
+    Some(merge_small_chunks(
+        chunks,
+        size_unit,
+        min_chunk_size,
+        merge_all_small,
+    ))
+}
+
+/// Append chunks smaller than `min_chunk_size` to the previous chunk without
+/// the overlap they carried; see `chunk_text_with_min_size`.
+pub(crate) fn merge_small_chunks(
+    chunks: Vec<PackedChunk>,
+    size_unit: SizeUnit,
+    min_chunk_size: usize,
+    merge_all_small: bool,
+) -> Vec<String> {
+    let last = chunks.len().saturating_sub(1);
+    let mut merged: Vec<String> = Vec::with_capacity(chunks.len());
+    for (i, chunk) in chunks.into_iter().enumerate() {
+        let small =
+            size_unit.measure(&chunk.text) < min_chunk_size && (merge_all_small || i == last);
+        match merged.last_mut() {
+            Some(previous) if small => {
+                previous.push_str(chunk.joiner);
+                previous.push_str(&chunk.text[chunk.new_start..]);
+            }
+            _ => merged.push(chunk.text),
+        }
+    }
+    merged
+}
+
+/// Pack sentences into chunks; see `chunk_text_with_overlap`. Units are cut as
+/// `units` says.
+fn pack_chunks(
+    text: &str,
+    target_size: usize,
+    overlap: Overlap,
+    units: &UnitOptions,
+    size_unit: SizeUnit,
+) -> Option<Vec<PackedChunk<'static>>> {
+    // Validate parameters
+    if target_size == 0 {
+        return None;
+    }
+
+    if text.is_empty() {
+        return Some(vec![]);
+    }
+
+    // Split into sentences, preserving original punctuation
+    let sentences = if units.preserve_code_blocks {
+        split_units_preserving_code(text, target_size, size_unit, units)
+    } else {
+        split_sized_prose_units(text, target_size, size_unit, units)
+    };
+
+    if sentences.is_empty() {
+        let unpacked = |text| PackedChunk {
+            text,
+            new_start: 0,
+            joiner: " ",
+        };
+        // No sentence boundaries found, return as single chunk or split by size
+        if size_unit.measure(text) <= target_size {
+            return Some(vec![unpacked(text.to_string())]);
+        }
+        // Fall back to simple size-based splitting for very long text without periods
+        return Some(
+            size_unit
+                .split(text, target_size)
+                .into_iter()
+                .map(unpacked)
+                .collect(),
+        );
+    }
+
+    let mut packer = ChunkPacker::new(target_size, overlap, units.preserve_code_blocks, size_unit)
+        .respecting_paragraphs(units.respect_paragraphs);
+    let mut chunks: Vec<PackedChunk> = Vec::new();
+    for sentence in sentences {
+        chunks.extend(packer.push(sentence));
+    }
+    chunks.extend(packer.finish());
+    Some(chunks)
+}
+
+/// The packing loop of `pack_chunks`, fed one unit at a time so chunks can
+/// also be produced lazily, as `stream::ChunkStream` does.
+#[derive(Debug, Clone)]
+pub(crate) struct ChunkPacker {
+    target_size: usize,
+    overlap: Overlap,
+    preserve_code_blocks: bool,
+    size_unit: SizeUnit,
+    current_chunk: String,
+    current_chunk_size: usize,
+    // Where the current chunk's own sentences start, and their separator from
+    // the previous chunk
+    current_new_start: usize,
+    current_joiner: &'static str,
+    // Use VecDeque for O(1) pop_front instead of Vec::remove(0) which is O(n)
+    overlap_buffer: VecDeque<Unit>,
+    // Length of the buffered sentences joined with their separators
+    overlap_len: usize,
+    // Whether the previous sentence (which ends any non-empty chunk) is a code block
+    previous_is_code: bool,
+    // Whether a paragraph break empties the overlap buffer
+    respect_paragraphs: bool,
+}
+
+impl ChunkPacker {
+    /// A packer for chunks of `target_size`, which must be greater than 0.
+    pub fn new(
+        target_size: usize,
+        overlap: Overlap,
+        preserve_code_blocks: bool,
+        size_unit: SizeUnit,
+    ) -> Self {
+        // Clamp overlap to be less than target_size
+        let overlap = match overlap {
+            Overlap::Size(size) => Overlap::Size(size.min(target_size.saturating_sub(1))),
+            sentences => sentences,
+        };
+        ChunkPacker {
+            target_size,
+            overlap,
+            preserve_code_blocks,
+            size_unit,
+            current_chunk: String::new(),
+            current_chunk_size: 0,
+            current_new_start: 0,
+            current_joiner: " ",
+            overlap_buffer: VecDeque::new(),
+            overlap_len: 0,
+            previous_is_code: false,
+            respect_paragraphs: false,
+        }
+    }
+
+    /// Carry no sentences of an earlier paragraph into a chunk as overlap.
+    pub fn respecting_paragraphs(mut self, respect_paragraphs: bool) -> Self {
+        self.respect_paragraphs = respect_paragraphs;
+        self
+    }
+
+    /// Add the next unit, returning the chunk it closed if it did not fit.
+    pub fn push(&mut self, sentence: Unit) -> Option<PackedChunk<'static>> {
+        let size_unit = self.size_unit;
+        let target_size = self.target_size;
+        let preserve_code_blocks = self.preserve_code_blocks;
+        let is_code = |unit: &Unit| preserve_code_blocks && markdown::is_code_block(&unit.text);
+        let separator =
+            |prev: &Unit, next: &Unit| unit_separator(is_code(prev), next, is_code(next));
+
+        // Sentences before a paragraph break are no longer overlap candidates
+        if self.respect_paragraphs && sentence.after_paragraph_break {
+            self.overlap_buffer.clear();
+            self.overlap_len = 0;
+        }
+
+        let mut finished = None;
+        let sentence_size = size_unit.measure(&sentence.text);
+        let sentence_is_code = is_code(&sentence);
+        let separator_size = if self.current_chunk.is_empty() {
+            0
+        } else {
+            size_unit.measure(unit_separator(
+                self.previous_is_code,
+                &sentence,
+                sentence_is_code,
+            ))
+        };
+
+        // Check if adding this sentence would exceed target
+        if !self.current_chunk.is_empty()
+            && self.current_chunk_size + separator_size + sentence_size > target_size
+        {
+            // Save current chunk
+            finished = Some(finish_chunk(
+                &self.current_chunk,
+                self.current_new_start,
+                self.current_joiner,
+            ));
+
+            // Start new chunk with overlap from previous sentences, leaving out the
+            // oldest ones if this sentence would not fit alongside them
+            let overlap_buffer = &self.overlap_buffer;
+            let mut carried_size = self.overlap_len + separator_size;
+            let mut skip = 0;
+            while skip < overlap_buffer.len() && carried_size + sentence_size > target_size {
+                let next = overlap_buffer.get(skip + 1).unwrap_or(&sentence);
+                carried_size -= size_unit.measure(&overlap_buffer[skip].text)
+                    + size_unit.measure(separator(&overlap_buffer[skip], next));
+                skip += 1;
+            }
+            self.current_chunk.clear();
+            for (i, carried) in overlap_buffer.iter().enumerate().skip(skip) {
+                if i > skip {
+                    self.current_chunk
+                        .push_str(separator(&overlap_buffer[i - 1], carried));
+                }
+                self.current_chunk.push_str(&carried.text);
+            }
+            self.current_chunk_size = size_unit.measure(&self.current_chunk);
+            self.current_joiner =
+                unit_separator(self.previous_is_code, &sentence, sentence_is_code);
+            self.current_new_start = if self.current_chunk.is_empty() {
+                0
+            } else {
+                self.current_chunk.len() + self.current_joiner.len()
+            };
+
+            // Don't clear overlap_buffer - we keep it for continuity
+        }
+
+        // Add sentence to current chunk
+        if !self.current_chunk.is_empty() {
+            let sep = unit_separator(self.previous_is_code, &sentence, sentence_is_code);
+            self.current_chunk.push_str(sep);
+            self.current_chunk_size += size_unit.measure(sep);
+        }
+        self.current_chunk.push_str(&sentence.text);
+        self.current_chunk_size += sentence_size;
+        self.previous_is_code = sentence_is_code;
+
+        // Track recent sentences for overlap (in size_unit)
+        if let Some(last) = self.overlap_buffer.back() {
+            self.overlap_len += size_unit.measure(separator(last, &sentence));
+        }
+        self.overlap_len += sentence_size;
+        self.overlap_buffer.push_back(sentence);
+
+        // Trim overlap buffer to stay within overlap limit (fix: recompute in loop!).
+        // The budget also covers the separator before the next sentence.
+        let over_limit = |size: usize, len: usize| match self.overlap {
+            Overlap::Size(budget) => size + size_unit.measure(" ") > budget && len > 1,
+            Overlap::Sentences(n) => len > n,
+        };
+        while over_limit(self.overlap_len, self.overlap_buffer.len()) {
+            if let Some(removed) = self.overlap_buffer.pop_front() {
+                let sep = self
+                    .overlap_buffer
+                    .front()
+                    .map_or(0, |next| size_unit.measure(separator(&removed, next)));
+                self.overlap_len -= size_unit.measure(&removed.text) + sep;
+            }
+        }
+        finished
+    }
+
+    /// The last chunk, once every unit has been pushed.
+    pub fn finish(&mut self) -> Option<PackedChunk<'static>> {
+        // Don't forget the last chunk
+        (!self.current_chunk.trim().is_empty()).then(|| {
+            finish_chunk(
+                &self.current_chunk,
+                self.current_new_start,
+                self.current_joiner,
+            )
+        })
+    }
+}
+
+/// Trim a packed chunk, keeping `new_start` pointing at the same sentence.
+pub(crate) fn finish_chunk<'a>(chunk: &str, new_start: usize, joiner: &'a str) -> PackedChunk<'a> {
+    let leading = chunk.len() - chunk.trim_start().len();
+    let text = chunk.trim().to_string();
+    let new_start = new_start.saturating_sub(leading).min(text.len());
+    PackedChunk {
+        text,
+        new_start,
+        joiner,
+    }
+}
+
+/// Split text into packing units, keeping each fenced code block whole and
+/// verbatim and splitting the prose around it into sentences.
+fn split_units_preserving_code(
+    text: &str,
+    target_size: usize,
+    size_unit: SizeUnit,
+    units: &UnitOptions,
+) -> Vec<Unit> {
+    markdown::split_code_blocks(text)
+        .into_iter()
+        .enumerate()
+        .flat_map(|(i, block)| {
+            let mut block_units = match block {
+                markdown::Block::Prose(prose) => {
+                    split_sized_prose_units(prose, target_size, size_unit, units)
+                }
+                markdown::Block::Code(code) => vec![Unit::new(code.to_string())],
+            };
+            // A code block is a paragraph of its own
+            if let Some(first) = block_units.first_mut().filter(|_| i > 0) {
+                first.after_paragraph_break = true;
+            }
+            block_units
+        })
+        .collect()
+}
+
+/// `split_prose_units` with sentences longer than `target_size` split into
+/// windows, unless `units.keep_long_sentences` is set.
+fn split_sized_prose_units(
+    text: &str,
+    target_size: usize,
+    size_unit: SizeUnit,
+    units: &UnitOptions,
+) -> Vec<Unit> {
+    let prose_units = split_prose_units(text, units);
+    if units.keep_long_sentences {
+        prose_units
+    } else {
+        split_oversized_sentences(prose_units, target_size, size_unit)
+    }
+}
+
+/// Split prose into sentences, or with `preserve_lists` into list items and
+/// the sentences around them.
+fn split_prose_units(text: &str, units: &UnitOptions) -> Vec<Unit> {
+    if !units.preserve_lists {
+        return split_sentence_units_at(text, units);
+    }
+    let mut prose_units = Vec::new();
+    // End of the previous block's text, to tell whether a line break precedes the next
+    let mut previous_end: Option<usize> = None;
+    let mut offset = 0;
+    for block in lists::split_list_items(text) {
+        let (block_text, mut block_units) = match block {
+            lists::Block::Item(item) => (item, vec![Unit::new(item.trim().to_string())]),
+            lists::Block::Prose(prose) => (prose, split_sentence_units_at(prose, units)),
+        };
+        let start = offset + (block_text.len() - block_text.trim_start().len());
+        if let (Some(first), Some(end)) = (block_units.first_mut(), previous_end) {
+            let line_breaks = text[end..start].matches('\n').count();
+            first.after_line_break = line_breaks == 1;
+            first.after_paragraph_break = line_breaks >= 2;
+        }
+        if !block_text.trim().is_empty() {
+            previous_end = Some(offset + block_text.trim_end().len());
+        }
+        offset += block_text.len();
+        prose_units.extend(block_units);
+    }
+    prose_units
+}
+
+/// Default overlap in characters when neither `overlap` nor `overlap_ratio` is given.
+const DEFAULT_OVERLAP: usize = 200;
+
+/// An `overlap` or `chunk_overlap` argument: an int is a size, a float a
+/// fraction of the target size.
+#[cfg(any(feature = "python", test))]
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum OverlapArg {
+    Size(usize),
+    Fraction(f64),
+}
+
+#[cfg(any(feature = "python", test))]
+impl OverlapArg {
+    /// The overlap in characters (or words) for chunks of `target_size`.
+    fn resolve(self, target_size: usize) -> Result<usize, String> {
+        match self {
+            OverlapArg::Size(size) => Ok(size),
+            OverlapArg::Fraction(fraction) if (0.0..1.0).contains(&fraction) => {
+                Ok((target_size as f64 * fraction) as usize)
+            }
+            OverlapArg::Fraction(fraction) => Err(format!(
+                "a float overlap is a fraction of the target size and must be in [0, 1), \
+                 got {fraction}; pass an int for an absolute overlap"
+            )),
+        }
+    }
+}
+
+/// Resolve `chunk_text`'s overlap arguments, at most one of which may be given.
+///
+/// `overlap_ratio`, like a float `overlap`, is a fraction of `target_size` in
+/// `[0, 1)`; the resulting character count still goes through the clamping in
+/// `chunk_text_impl`.
+#[cfg(any(feature = "python", test))]
+fn resolve_overlap(
+    target_size: usize,
+    overlap: Option<OverlapArg>,
+    overlap_ratio: Option<f64>,
+    overlap_sentences: Option<usize>,
+) -> Result<Overlap, String> {
+    match (overlap, overlap_ratio, overlap_sentences) {
+        (None, None, None) => Ok(Overlap::Size(DEFAULT_OVERLAP)),
+        (Some(overlap), None, None) => overlap.resolve(target_size).map(Overlap::Size),
+        (None, Some(ratio), None) if (0.0..1.0).contains(&ratio) => {
+            Ok(Overlap::Size((target_size as f64 * ratio) as usize))
+        }
+        (None, Some(ratio), None) => Err(format!("overlap_ratio must be in [0, 1), got {ratio}")),
+        (None, None, Some(n)) => Ok(Overlap::Sentences(n)),
+        _ => Err(
+            "overlap, overlap_ratio and overlap_sentences are mutually exclusive; pass only one"
+                .to_string(),
+        ),
+    }
+}
+
+/// Reject an overlap that `ChunkPacker::new` would clamp to `target_size - 1`,
+/// for `chunk_text(strict_overlap=True)`.
+#[cfg(any(feature = "python", test))]
+fn check_strict_overlap(target_size: usize, overlap: Overlap) -> Result<(), String> {
+    match overlap {
+        Overlap::Size(size) if target_size > 0 && size >= target_size => Err(format!(
+            "overlap ({size}) must be smaller than target_size ({target_size}) \
+             with strict_overlap=True"
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// `chunk_text_by_words` (pure Rust, no PyO3 dependencies): sentences packed
+/// up to `max_words` whitespace-delimited words, with trailing sentences of up
+/// to `overlap_words` words carried into the next chunk (always at least one,
+/// as with `chunk_text_impl`). A sentence longer than `max_words` is kept whole
+/// as a chunk of its own. Returns None if `max_words` is 0.
+pub fn chunk_text_by_words_impl(
+    text: &str,
+    max_words: usize,
+    overlap_words: usize,
+) -> Option<Vec<String>> {
+    let units = UnitOptions {
+        keep_long_sentences: true,
+        ..UnitOptions::default()
+    };
+    let chunks = pack_chunks(
+        text,
+        max_words,
+        Overlap::Size(overlap_words),
+        &units,
+        SizeUnit::Words,
+    )?;
+    Some(chunks.into_iter().map(|c| c.text).collect())
+}
+
+/// Metadata extracted from a piece of text, kept as plain Rust data so it can be
+/// produced without holding the GIL and converted to a dict afterwards.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Metadata {
+    /// Monetary amounts in document order; the `monetary_amounts*` and
+    /// `monetary_values` keys of the dict are all read off these.
+    #[serde(flatten, serialize_with = "json::monetary_amounts")]
+    pub monetary_amounts: Vec<ParsedMoney>,
+    pub monetary_amounts_spelled: Vec<ParsedMoney>,
+    /// Percentages in document order, behind the `percentage*` keys.
+    #[serde(flatten, serialize_with = "json::percentages")]
+    pub percentages: Vec<ParsedPercentage>,
+    pub ranges: Vec<ParsedRange>,
+    pub per_share_amounts: Vec<PerShareAmount>,
+    pub share_counts: Vec<ShareCount>,
+    /// Dates in document order, behind the `dates*` keys.
+    #[serde(flatten, serialize_with = "json::dates")]
+    pub dates: Vec<ParsedDate>,
+    pub fiscal_periods: Vec<String>,
+    pub fiscal_periods_parsed: Vec<ParsedFiscalPeriod>,
+    pub potential_tickers: Vec<String>,
+    pub potential_tickers_detailed: Vec<ExplicitTicker>,
+    #[serde(serialize_with = "json::ticker_counts")]
+    pub ticker_counts: Vec<TickerCount>,
+    pub tickers_confident: Vec<ExplicitTicker>,
+    pub urls: Vec<String>,
+    pub emails: Vec<String>,
+    pub phone_numbers: Vec<String>,
+    pub isins: Vec<String>,
+    pub cusips: Vec<String>,
+    pub sedols: Vec<String>,
+    /// ISIN, CUSIP and SEDOL candidates failing their check digit, only in the
+    /// dict of `extract_metadata(include_unvalidated=True)`.
+    #[serde(skip)]
+    pub security_ids_unvalidated: Vec<UnvalidatedId>,
+    /// Matches of user-registered patterns, by pattern name in registration order.
+    #[serde(flatten, serialize_with = "json::custom_patterns")]
+    pub custom: Vec<(String, Vec<String>)>,
+}
+
+/// Keys of the dict built by `Metadata::into_py_dict`, which user-registered
+/// patterns may not reuse.
+const METADATA_KEYS: &[&str] = &[
+    "monetary_amounts",
+    "monetary_amounts_detailed",
+    "monetary_amounts_normalized",
+    "monetary_values",
+    "monetary_amounts_parsed",
+    "monetary_amounts_spelled",
+    "percentages",
+    "percentages_normalized",
+    "percentage_values",
+    "percentages_parsed",
+    "ranges",
+    "per_share_amounts",
+    "share_counts",
+    "dates",
+    "dates_parsed",
+    "dates_normalized",
+    "dates_format_overridden",
+    "fiscal_periods",
+    "fiscal_periods_parsed",
+    "potential_tickers",
+    "potential_tickers_detailed",
+    "ticker_counts",
+    "tickers_confident",
+    "urls",
+    "emails",
+    "phone_numbers",
+    "isins",
+    "cusips",
+    "sedols",
+];
+
+/// A monetary amount broken down into value, currency and scale.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ParsedMoney {
+    pub raw: String,
+    /// Value in base units, or None if the amount could not be parsed.
+    pub value: Option<f64>,
+    /// ISO code ("USD", "EUR", ...), or the bare symbol when it is ambiguous ("¥").
+    pub currency: Option<String>,
+    /// "thousand", "million" or "billion" when the amount carried a scale
+    /// suffix; for a spelled-out amount, its largest scale word, which may
+    /// also be "trillion".
+    pub scale: Option<&'static str>,
+    /// True when the sign of `value` was flipped by a direction cue; listed
+    /// under `monetary_amounts_normalized` only.
+    #[serde(skip)]
+    pub sign_inferred: bool,
+}
+
+/// A percentage with its value in percent units and the unit it was written in.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ParsedPercentage {
+    pub raw: String,
+    /// Value in percent units ("40 bps" -> 0.4), or None if it could not be parsed.
+    pub value: Option<f64>,
+    /// "percent" or "basis_points".
+    pub unit: Option<&'static str>,
+    /// True when the sign of `value` was flipped by a direction cue; listed
+    /// under `percentages_normalized` only.
+    #[serde(skip)]
+    pub sign_inferred: bool,
+}
+
+/// A date match with its parsed value.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(into = "json::ParsedDateJson")]
+pub struct ParsedDate {
+    pub raw: String,
+    /// None only if the match could not be read at all.
+    pub value: Option<DateValue>,
+    /// True for a slash date read against the requested `date_format`
+    /// because only the other order is a real date.
+    pub format_overridden: bool,
+}
+
+/// Extract financial metadata with the defaults of `extract_metadata` in Python.
+pub fn extract_metadata(text: &str) -> Metadata {
+    extract_metadata_impl(text, false)
+}
+
+/// Internal implementation of extract_metadata (pure Rust, no PyO3 dependencies).
+pub fn extract_metadata_impl(text: &str, infer_sign: bool) -> Metadata {
+    extract_metadata_with_options(
+        text,
+        infer_sign,
+        &TickerOptions::default(),
+        DateFormat::default(),
+    )
+}
+
+/// A potential ticker with how often it occurs.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TickerCount {
+    pub symbol: String,
+    /// Exchange of the first exchange-qualified mention, if any.
+    pub exchange: Option<String>,
+    pub count: usize,
+    /// Byte offset of the first mention.
+    first: usize,
+}
+
+/// Potential tickers, deduplicated with their occurrence counts, in the order
+/// `options.order` asks for.
+///
+/// Exchange-qualified symbols ("NYSE: MSFT") are explicit, so they skip the
+/// stopword check (though not an allowlist), and neither the exchange name nor
+/// the symbol is picked up again as a bare uppercase word.
+fn extract_potential_tickers(text: &str, options: &TickerOptions) -> Vec<TickerCount> {
+    let qualified = tickers::find_exchange_qualified(text);
+    let in_qualified = |start: usize| qualified.iter().any(|(range, _)| range.contains(&start));
+
+    let mut found: HashMap<String, TickerCount> = HashMap::new();
+    let mut record = |symbol: &str, exchange: &Option<String>, start: usize| {
+        let entry = found
+            .entry(symbol.to_string())
+            .or_insert_with(|| TickerCount {
+                symbol: symbol.to_string(),
+                exchange: None,
+                count: 0,
+                first: start,
+            });
+        entry.count += 1;
+        entry.first = entry.first.min(start);
+        if entry.exchange.is_none() {
+            entry.exchange.clone_from(exchange);
+        }
+    };
+    for (range, ticker) in &qualified {
+        if options.accepts_qualified(&ticker.symbol) {
+            record(&ticker.symbol, &ticker.exchange, range.start);
+        }
+    }
+    for m in TICKER_RE.find_iter(text) {
+        if !in_qualified(m.start()) && options.accepts(m.as_str()) {
+            record(m.as_str(), &None, m.start());
+        }
+    }
+
+    let mut found: Vec<TickerCount> = found.into_values().collect();
+    match options.order {
+        TickerOrder::Alphabetical => found.sort_by(|a, b| a.symbol.cmp(&b.symbol)),
+        TickerOrder::Document => found.sort_by_key(|t| t.first),
+    }
+    found
+}
+
+impl Metadata {
+    /// How often each amount, percentage, date and fiscal period occurs, by
+    /// the key `extract_metadata(frequencies=True)` adds, with the entities in
+    /// sorted order. Tickers are already counted in `ticker_counts`.
+    pub fn entity_counts(&self) -> [(&'static str, BTreeMap<String, usize>); 4] {
+        fn count<'a>(values: impl Iterator<Item = &'a String>) -> BTreeMap<String, usize> {
+            let mut counts = BTreeMap::new();
+            for value in values {
+                *counts.entry(value.clone()).or_default() += 1;
+            }
+            counts
+        }
+        [
+            (
+                "monetary_amounts_counts",
+                count(self.monetary_amounts.iter().map(|m| &m.raw)),
+            ),
+            (
+                "percentages_counts",
+                count(self.percentages.iter().map(|p| &p.raw)),
+            ),
+            ("dates_counts", count(self.dates.iter().map(|d| &d.raw))),
+            ("fiscal_periods_counts", count(self.fiscal_periods.iter())),
+        ]
+    }
+}
+
+/// `extract_metadata_impl` with custom options for potential tickers and the
+/// reading order of slash dates.
+fn extract_metadata_with_options(
+    text: &str,
+    infer_sign: bool,
+    ticker_options: &TickerOptions,
+    date_format: DateFormat,
+) -> Metadata {
+    // Extract monetary amounts
+    let monetary_amounts: Vec<ParsedMoney> = MONEY_RE
+        .find_iter(text)
+        .map(|m| {
+            let normalized = normalize_match(text, m.as_str(), m.start(), parse_money, infer_sign);
+            ParsedMoney {
+                currency: split_currency(m.as_str()).map(|(currency, _)| currency.to_string()),
+                scale: money_scale(m.as_str()),
+                raw: normalized.raw,
+                value: normalized.value,
+                sign_inferred: normalized.sign_inferred,
+            }
+        })
+        .collect();
+    // Amounts in words are kept apart, since their grammar is much looser
+    let monetary_amounts_spelled = spelled::extract_spelled_money(text);
+
+    // Extract percentages
+    let percentages: Vec<ParsedPercentage> = PERCENTAGE_RE
+        .find_iter(text)
+        .map(|m| {
+            let normalized =
+                normalize_match(text, m.as_str(), m.start(), parse_percentage, infer_sign);
+            ParsedPercentage {
+                unit: percentage_unit(m.as_str()),
+                raw: normalized.raw,
+                value: normalized.value,
+                sign_inferred: normalized.sign_inferred,
+            }
+        })
+        .collect();
+
+    // Ranges of amounts or percentages, whose bounds the lists above keep apart
+    let ranges = ranges::extract_ranges(text);
+
+    // Per-share figures and share counts, kept apart from aggregate amounts
+    let per_share_amounts = shares::extract_per_share_amounts(text);
+    let share_counts = shares::extract_share_counts(text);
+
+    // Extract dates
+    let dates: Vec<ParsedDate> = DATE_RE
+        .find_iter(text)
+        .map(|m| ParsedDate {
+            raw: m.as_str().to_string(),
+            value: parse_date(m.as_str(), date_format),
+            format_overridden: overrides_format(m.as_str(), date_format),
+        })
+        .collect();
+
+    // Extract fiscal periods (deduplicated, first occurrence first)
+    let fiscal_periods = entities::dedupe_in_order(
+        FISCAL_PERIOD_RE
+            .find_iter(text)
+            .map(|m| m.as_str().to_string()),
+    );
+    let fiscal_periods_parsed: Vec<ParsedFiscalPeriod> = fiscal_periods
+        .iter()
+        .map(|raw| fiscal::parse_fiscal_period(raw))
+        .collect();
+
+    // Extract potential ticker symbols (filter common words, dedupe, order for determinism)
+    let ticker_counts = extract_potential_tickers(text, ticker_options);
+    let potential_tickers: Vec<String> = ticker_counts.iter().map(|t| t.symbol.clone()).collect();
+    let potential_tickers_detailed: Vec<ExplicitTicker> = ticker_counts
+        .iter()
+        .map(|t| ExplicitTicker {
+            symbol: t.symbol.clone(),
+            exchange: t.exchange.clone(),
+        })
+        .collect();
+
+    // Cashtags and exchange-qualified symbols are explicit, so no stopword filter
+    let tickers_confident = tickers::extract_explicit_tickers(text);
+
+    // Extract links and contact details (deduplicated, first occurrence first)
+    let urls = entities::extract_urls(text);
+    let emails = entities::extract_emails(text);
+    let phone_numbers = entities::extract_phone_numbers(text);
+
+    // Extract security identifiers, checked against their check digits
+    let security_ids = securities::extract_security_ids(text);
+
+    Metadata {
+        monetary_amounts,
+        monetary_amounts_spelled,
+        percentages,
+        ranges,
+        per_share_amounts,
+        share_counts,
+        dates,
+        fiscal_periods,
+        fiscal_periods_parsed,
+        potential_tickers,
+        potential_tickers_detailed,
+        ticker_counts,
+        tickers_confident,
+        urls,
+        emails,
+        phone_numbers,
+        isins: security_ids.isins,
+        cusips: security_ids.cusips,
+        sedols: security_ids.sedols,
+        security_ids_unvalidated: security_ids.unvalidated,
+        custom: Vec::new(),
+    }
+}
+
+/// Configuration behind a `MetadataExtractor`: ticker options plus
+/// user-registered patterns, compiled when they were registered.
+#[derive(Debug, Clone, Default)]
+pub struct ExtractorConfig {
+    ticker_options: TickerOptions,
+    /// (name, pattern) in registration order; names are unique.
+    patterns: Vec<(String, Regex)>,
+}
+
+impl ExtractorConfig {
+    /// Register `pattern` under `name`, replacing a pattern registered earlier
+    /// under the same name. Fails for built-in metadata keys and invalid regexes.
+    pub fn add_pattern(&mut self, name: &str, pattern: &str) -> Result<(), String> {
+        if METADATA_KEYS.contains(&name) {
+            return Err(format!(
+                "{name:?} is a built-in metadata key; pick another pattern name"
+            ));
+        }
+        let re = Regex::new(pattern).map_err(|e| format!("invalid pattern for {name:?}: {e}"))?;
+        match self.patterns.iter_mut().find(|(n, _)| n == name) {
+            Some(existing) => existing.1 = re,
+            None => self.patterns.push((name.to_string(), re)),
+        }
+        Ok(())
+    }
+
+    /// Built-in metadata plus every occurrence of each registered pattern.
+    pub fn extract(&self, text: &str, infer_sign: bool) -> Metadata {
+        let mut metadata = extract_metadata_with_options(
+            text,
+            infer_sign,
+            &self.ticker_options,
+            DateFormat::default(),
+        );
+        metadata.custom = self
+            .patterns
+            .iter()
+            .map(|(name, re)| {
+                let matches = re.find_iter(text).map(|m| m.as_str().to_string()).collect();
+                (name.clone(), matches)
+            })
+            .collect();
+        metadata
+    }
+}
+
+/// Handles to the money, percentage, date and ticker patterns of
+/// `extract_metadata`, for running one category on its own.
+///
+/// Each `find_*` method returns the matched strings in the same order as the
+/// corresponding `extract_metadata` list (`monetary_amounts`, `percentages`,
+/// `dates` and `potential_tickers`), without building the other categories or
+/// any parsed values. The patterns are compiled on construction rather than on
+/// the first call. Methods release the GIL, and one instance can be shared
+/// across threads.
+#[cfg_attr(feature = "python", pyo3::pyclass(module = "rag_rust_core", frozen))]
+pub struct Matchers {
+    money: &'static Regex,
+    percentage: &'static Regex,
+    date: &'static Regex,
+    ticker_options: TickerOptions,
+}
+
+impl Matchers {
+    pub fn compiled() -> Self {
+        // Tickers go through `extract_potential_tickers`, which reads the
+        // static directly
+        LazyLock::force(&TICKER_RE);
+        Matchers {
+            money: LazyLock::force(&MONEY_RE),
+            percentage: LazyLock::force(&PERCENTAGE_RE),
+            date: LazyLock::force(&DATE_RE),
+            ticker_options: TickerOptions::default(),
+        }
+    }
+
+    pub fn money(&self, text: &str) -> Vec<String> {
+        matched_strings(self.money, text)
+    }
+
+    pub fn percentages(&self, text: &str) -> Vec<String> {
+        matched_strings(self.percentage, text)
+    }
+
+    pub fn dates(&self, text: &str) -> Vec<String> {
+        matched_strings(self.date, text)
+    }
+
+    pub fn tickers(&self, text: &str) -> Vec<String> {
+        extract_potential_tickers(text, &self.ticker_options)
+            .into_iter()
+            .map(|t| t.symbol)
+            .collect()
+    }
+}
+
+/// Every match of `re` in `text`, in document order.
+fn matched_strings(re: &Regex, text: &str) -> Vec<String> {
+    re.find_iter(text).map(|m| m.as_str().to_string()).collect()
+}
+
+/// An extracted entity and where it occurs, in character (code point) offsets.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Span {
+    pub text: String,
+    pub start: usize,
+    pub end: usize,
+    /// "money", "percentage", "date" or "ticker".
+    pub kind: &'static str,
+}
+
+/// Every occurrence of each metadata category, in document order.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(into = "Vec<Span>")]
+pub struct MetadataSpans {
+    pub monetary_amounts: Vec<Span>,
+    pub percentages: Vec<Span>,
+    pub dates: Vec<Span>,
+    pub potential_tickers: Vec<Span>,
+}
+
+impl MetadataSpans {
+    /// All spans in one list ordered by position; spans of different kinds
+    /// that overlap are all kept.
+    pub fn into_sorted(self) -> Vec<Span> {
+        let mut spans: Vec<Span> = [
+            self.monetary_amounts,
+            self.percentages,
+            self.dates,
+            self.potential_tickers,
+        ]
+        .concat();
+        spans.sort_by_key(|s| (s.start, s.end));
+        spans
+    }
+}
+
+/// Collect `kind` spans for every match of `re` accepted by `keep`.
+fn find_spans(
+    text: &str,
+    re: &Regex,
+    kind: &'static str,
+    keep: impl Fn(&str) -> bool,
+) -> Vec<Span> {
+    let mut offsets = CharOffsets::new(text);
+    re.find_iter(text)
+        .filter(|m| keep(m.as_str()))
+        .map(|m| Span {
+            text: m.as_str().to_string(),
+            start: offsets.char_offset(m.start()),
+            end: offsets.char_offset(m.end()),
+            kind,
+        })
+        .collect()
+}
+
+/// Internal implementation of extract_metadata_spans (pure Rust, no PyO3 dependencies).
+fn extract_metadata_spans_impl(text: &str, ticker_options: &TickerOptions) -> MetadataSpans {
+    MetadataSpans {
+        monetary_amounts: find_spans(text, &MONEY_RE, "money", |_| true),
+        percentages: find_spans(text, &PERCENTAGE_RE, "percentage", |_| true),
+        dates: find_spans(text, &DATE_RE, "date", |_| true),
+        // Unlike extract_metadata, tickers keep every occurrence in document order
+        potential_tickers: find_spans(text, &TICKER_RE, "ticker", |t| ticker_options.accepts(t)),
+    }
+}
+
+/// What `process_document` returns when a document cleans down to nothing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OnEmpty {
+    EmptyList,
+    Placeholder,
+    Error,
+}
+
+impl OnEmpty {
+    #[cfg(any(feature = "python", test))]
+    fn parse(name: &str) -> Option<Self> {
+        match name {
+            "empty_list" => Some(OnEmpty::EmptyList),
+            "placeholder" => Some(OnEmpty::Placeholder),
+            "error" => Some(OnEmpty::Error),
+            _ => None,
+        }
+    }
+}
+
+/// Reason attached to the placeholder chunk emitted for `on_empty="placeholder"`.
+const CLEANED_TO_EMPTY: &str = "cleaned_to_empty";
+
+/// Why `process_document_impl` produced no chunks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProcessError {
+    /// chunk_size was 0.
+    InvalidChunkSize,
+    /// Cleaning left no text and `on_empty` is `OnEmpty::Error`.
+    EmptyDocument,
+}
+
+impl std::fmt::Display for ProcessError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ProcessError::InvalidChunkSize => f.write_str("chunk_size must be greater than 0"),
+            ProcessError::EmptyDocument => f.write_str("document is empty after cleaning"),
+        }
+    }
+}
+
+impl std::error::Error for ProcessError {}
+
+/// A single processed chunk: the chunk text plus its extracted metadata.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ProcessedChunk {
+    pub chunk_index: usize,
+    /// `chunk_id(chunk_index, text)`.
+    pub chunk_id: String,
+    pub text: String,
+    pub char_count: usize,
+    pub word_count: usize,
+    /// None when extraction was turned off with `extract_meta`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<Metadata>,
+    /// Entity spans relative to `text`, when requested.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub spans: Option<MetadataSpans>,
+    /// When requested, the title of the last section heading at or before
+    /// the chunk's start, or None before the first heading.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub section: Option<Option<String>>,
+    /// With `CleanOptions::preserve_tables`, whether the chunk is a table
+    /// kept whole.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_table: Option<bool>,
+    /// Set only on placeholder chunks, explaining why the text is empty.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<&'static str>,
+}
+
+/// Chunks under this fraction of `chunk_size` are counted as small by
+/// `ChunkReport`.
+const SMALL_CHUNK_FRACTION: f64 = 0.1;
+
+/// How well the chunks of a document fit `chunk_size`, measured in
+/// `size_unit`; placeholder chunks are not counted.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChunkReport {
+    pub chunk_count: usize,
+    pub chunk_size: usize,
+    /// Indices of the chunks larger than `chunk_size`. Long sentences are
+    /// split, so these are code blocks and tables kept whole.
+    pub exceeded: Vec<usize>,
+    pub max_size: usize,
+    /// Chunks smaller than this are counted in `below_floor`.
+    pub floor: usize,
+    pub below_floor: usize,
+}
+
+impl ChunkReport {
+    pub fn new(chunks: &[ProcessedChunk], config: &ProcessConfig) -> Self {
+        let sizes: Vec<(usize, usize)> = chunks
+            .iter()
+            .filter(|c| c.reason.is_none())
+            .map(|c| {
+                let size = match config.size_unit {
+                    SizeUnit::Chars => c.char_count,
+                    SizeUnit::Words => c.word_count,
+                };
+                (c.chunk_index, size)
+            })
+            .collect();
+        let floor = (config.chunk_size as f64 * SMALL_CHUNK_FRACTION).ceil() as usize;
+        ChunkReport {
+            chunk_count: sizes.len(),
+            chunk_size: config.chunk_size,
+            exceeded: sizes
+                .iter()
+                .filter(|(_, size)| *size > config.chunk_size)
+                .map(|(index, _)| *index)
+                .collect(),
+            max_size: sizes.iter().map(|(_, size)| *size).max().unwrap_or(0),
+            floor,
+            below_floor: sizes.iter().filter(|(_, size)| *size < floor).count(),
+        }
+    }
+}
+
+/// Entities of a whole document: the financial categories deduplicated and
+/// sorted, with how many times each category occurred in total.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(into = "json::DocumentMetadataJson")]
+pub struct DocumentMetadata {
+    /// (category, sorted distinct values, total occurrences)
+    pub categories: Vec<(&'static str, Vec<String>, usize)>,
+    pub ticker_counts: Vec<TickerCount>,
+}
+
+/// Sort and deduplicate `values`, returning them with the count before deduplication.
+fn distinct_sorted(mut values: Vec<String>) -> (Vec<String>, usize) {
+    let total = values.len();
+    values.sort();
+    values.dedup();
+    (values, total)
+}
+
+impl DocumentMetadata {
+    /// Summarize `metadata`, which was extracted from all of `text`.
+    fn new(text: &str, metadata: Metadata) -> Self {
+        let mut ticker_counts = metadata.ticker_counts;
+        ticker_counts.sort_by(|a, b| a.symbol.cmp(&b.symbol));
+        let ticker_total = ticker_counts.iter().map(|t| t.count).sum();
+        let tickers = ticker_counts.iter().map(|t| t.symbol.clone()).collect();
+        let mut categories = Vec::new();
+        for (name, values) in [
+            (
+                "monetary_amounts",
+                metadata
+                    .monetary_amounts
+                    .into_iter()
+                    .map(|m| m.raw)
+                    .collect(),
+            ),
+            (
+                "percentages",
+                metadata.percentages.into_iter().map(|p| p.raw).collect(),
+            ),
+            ("dates", metadata.dates.into_iter().map(|d| d.raw).collect()),
+        ] {
+            let (values, total) = distinct_sorted(values);
+            categories.push((name, values, total));
+        }
+        // Fiscal periods are already deduplicated, so count them in the text
+        let (fiscal_periods, _) = distinct_sorted(metadata.fiscal_periods);
+        let fiscal_total = FISCAL_PERIOD_RE.find_iter(text).count();
+        categories.push(("fiscal_periods", fiscal_periods, fiscal_total));
+        categories.push(("potential_tickers", tickers, ticker_total));
+        DocumentMetadata {
+            categories,
+            ticker_counts,
+        }
+    }
+}
+
+/// Settings shared by `process_document` and `process_documents`.
+#[derive(Debug, Clone)]
+pub struct ProcessConfig {
+    pub chunk_size: usize,
+    pub chunk_overlap: usize,
+    /// What `chunk_size` and `chunk_overlap` count.
+    pub size_unit: SizeUnit,
+    pub infer_sign: bool,
+    /// Ticker options and registered patterns applied to each chunk.
+    pub extractor: Arc<ExtractorConfig>,
+    /// Whether chunks get metadata; false leaves just the cleaned chunks.
+    pub extract_meta: bool,
+    pub include_spans: bool,
+    /// Whether chunks get the `section` they fall in; see `sections`.
+    pub include_sections: bool,
+    /// Intra-document parallelism; None enables it for cleaned texts of at
+    /// least `parallel::PARALLEL_MIN_BYTES`. Output is identical either way.
+    /// Ignored without the `parallel` feature.
+    pub parallel: Option<bool>,
+    pub clean_options: CleanOptions,
+    pub on_empty: OnEmpty,
+}
+
+impl Default for ProcessConfig {
+    fn default() -> Self {
+        ProcessConfig {
+            chunk_size: 1500,
+            chunk_overlap: 200,
+            size_unit: SizeUnit::Chars,
+            infer_sign: false,
+            extractor: Arc::default(),
+            extract_meta: true,
+            include_spans: false,
+            include_sections: false,
+            parallel: None,
+            clean_options: CleanOptions::default(),
+            on_empty: OnEmpty::EmptyList,
+        }
+    }
+}
+
+/// Internal implementation of process_document (pure Rust, no PyO3 dependencies).
+/// Fails if chunk_size is 0, or if the document cleans to nothing and
+/// `config.on_empty` asks for an error.
+pub fn process_document_impl(
+    text: &str,
+    config: &ProcessConfig,
+) -> Result<Vec<ProcessedChunk>, ProcessError> {
+    let cleaned = clean_text_with_options(text, &config.clean_options);
+    process_cleaned(&cleaned, config)
+}
+
+/// `process_document_impl` that also returns metadata for the whole cleaned
+/// document, extracted before chunking.
+pub fn process_document_with_metadata_impl(
+    text: &str,
+    config: &ProcessConfig,
+) -> Result<(Vec<ProcessedChunk>, DocumentMetadata), ProcessError> {
+    let cleaned = clean_text_with_options(text, &config.clean_options);
+    let chunks = process_cleaned(&cleaned, config)?;
+    let ticker_options = &config.extractor.ticker_options;
+    let metadata = extract_metadata_with_options(
+        &cleaned,
+        config.infer_sign,
+        ticker_options,
+        DateFormat::default(),
+    );
+    Ok((chunks, DocumentMetadata::new(&cleaned, metadata)))
+}
+
+/// Chunk already cleaned text and extract metadata for each chunk.
+fn process_cleaned(
+    cleaned: &str,
+    config: &ProcessConfig,
+) -> Result<Vec<ProcessedChunk>, ProcessError> {
+    // Code blocks and list items kept by cleaning are kept whole by chunking too
+    let units = UnitOptions {
+        preserve_code_blocks: config.clean_options.preserve_code_blocks,
+        preserve_lists: config.clean_options.preserve_lists,
+        ..UnitOptions::default()
+    };
+    let pack = |text: &str| {
+        pack_chunks(
+            text,
+            config.chunk_size,
+            Overlap::Size(config.chunk_overlap),
+            &units,
+            config.size_unit,
+        )
+        .map(|chunks| {
+            chunks
+                .into_iter()
+                .map(|c| (c.text, false))
+                .collect::<Vec<_>>()
+        })
+    };
+    // Tables kept by cleaning are chunks of their own, never split or packed
+    // with prose, however large
+    let chunk_sequentially = || {
+        if !config.clean_options.preserve_tables {
+            return pack(cleaned);
+        }
+        if config.chunk_size == 0 {
+            return None;
+        }
+        let mut chunks = Vec::new();
+        for block in tables::split_tables(cleaned) {
+            match block {
+                tables::Block::Prose(prose) => chunks.extend(pack(prose.trim())?),
+                tables::Block::Table(table) => chunks.push((table.to_string(), true)),
+            }
+        }
+        Some(chunks)
+    };
+
+    // The segmented parallel chunker has no notion of code blocks or lists and
+    // counts characters only
+    #[cfg(feature = "parallel")]
+    let parallel = !units.preserve_code_blocks
+        && !units.preserve_lists
+        && !config.clean_options.preserve_tables
+        && config.size_unit == SizeUnit::Chars
+        && config
+            .parallel
+            .unwrap_or(cleaned.len() >= parallel::PARALLEL_MIN_BYTES);
+
+    #[cfg(feature = "parallel")]
+    let chunks = if parallel {
+        parallel::chunk_text_parallel(cleaned, config.chunk_size, config.chunk_overlap)
+            .map(|chunks| chunks.into_iter().map(|c| (c, false)).collect())
+    } else {
+        chunk_sequentially()
+    };
+    #[cfg(not(feature = "parallel"))]
+    let chunks = chunk_sequentially();
+    let chunks = chunks.ok_or(ProcessError::InvalidChunkSize)?;
+
+    if chunks.is_empty() {
+        return match config.on_empty {
+            OnEmpty::EmptyList => Ok(Vec::new()),
+            OnEmpty::Error => Err(ProcessError::EmptyDocument),
+            OnEmpty::Placeholder => Ok(vec![ProcessedChunk {
+                chunk_index: 0,
+                chunk_id: chunk_id(0, ""),
+                text: String::new(),
+                char_count: 0,
+                word_count: 0,
+                metadata: config
+                    .extract_meta
+                    .then(|| config.extractor.extract("", config.infer_sign)),
+                spans: config.include_spans.then(MetadataSpans::default),
+                section: config.include_sections.then_some(None),
+                is_table: config.clean_options.preserve_tables.then_some(false),
+                reason: Some(CLEANED_TO_EMPTY),
+            }]),
+        };
+    }
+
+    let sections = config
+        .include_sections
+        .then(|| sections::chunk_sections(cleaned, chunks.iter().map(|(c, _)| c.as_str())));
+    let build = |(i, (chunk, is_table)): (usize, (String, bool))| ProcessedChunk {
+        chunk_index: i,
+        chunk_id: chunk_id(i, &chunk),
+        char_count: char_len(&chunk),
+        word_count: word_count(&chunk),
+        metadata: config
+            .extract_meta
+            .then(|| config.extractor.extract(&chunk, config.infer_sign)),
+        spans: config
+            .include_spans
+            .then(|| extract_metadata_spans_impl(&chunk, &config.extractor.ticker_options)),
+        section: sections.as_ref().map(|sections| sections[i].clone()),
+        is_table: config.clean_options.preserve_tables.then_some(is_table),
+        text: chunk,
+        reason: None,
+    };
+
+    #[cfg(feature = "parallel")]
+    if parallel {
+        return Ok(chunks.into_par_iter().enumerate().map(build).collect());
+    }
+    Ok(chunks.into_iter().enumerate().map(build).collect())
+}
+
+/// Internal implementation of process_documents (pure Rust, no PyO3 dependencies).
+///
+/// Documents are processed in parallel (with the `parallel` feature) and returned
+/// in input order. A document whose processing panics yields None instead of
+/// aborting the whole batch.
+pub fn process_documents_impl(
+    texts: &[Option<String>],
+    config: &ProcessConfig,
+) -> Vec<Option<Vec<ProcessedChunk>>> {
+    let process = |text: &Option<String>| {
+        let text = text.as_deref()?;
+        panic::catch_unwind(|| process_document_impl(text, config))
+            .ok()?
+            .ok()
+    };
+    #[cfg(feature = "parallel")]
+    let results = texts.par_iter().map(process).collect();
+    #[cfg(not(feature = "parallel"))]
+    let results = texts.iter().map(process).collect();
+    results
+}
+
+/// Internal implementation of process_files (pure Rust, no PyO3 dependencies).
+///
+/// Each file is read and processed by the worker that picks it up, so reading
+/// overlaps with processing and only the files in flight are held in memory.
+/// A file that cannot be read, decoded or processed yields None.
+pub fn process_files_impl(
+    paths: &[Option<PathBuf>],
+    encoding: Encoding,
+    config: &ProcessConfig,
+) -> Vec<Option<Vec<ProcessedChunk>>> {
+    let process = |path: &Option<PathBuf>| {
+        let text = files::read_text_file(path.as_deref()?, encoding).ok()?;
+        panic::catch_unwind(|| process_document_impl(&text, config))
+            .ok()?
+            .ok()
+    };
+    #[cfg(feature = "parallel")]
+    let results = paths.par_iter().map(process).collect();
+    #[cfg(not(feature = "parallel"))]
+    let results = paths.iter().map(process).collect();
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The text of each amount, percentage or date record, in order.
+    fn raws<'a, T>(records: &'a [T], raw: impl Fn(&'a T) -> &'a str) -> Vec<&'a str> {
+        records.iter().map(raw).collect()
+    }
+
+    #[test]
+    fn test_clean_text() {
+        let input = "Hello   world.\n\n\nThis is   a test.";
+        let result = clean_text_with_options(input, &CleanOptions::default());
+        assert!(result.contains("Hello world."));
+        assert!(result.contains("This is a test."));
+    }
+
+    #[test]
+    fn test_core_api() {
+        let text = clean_text("Revenue rose   12% to $5 million.\n\n\nAAPL   gained.");
+        assert_eq!(text, "Revenue rose 12% to $5 million.\n\nAAPL gained.");
+
+        let config = ChunkConfig {
+            target_size: 40,
+            overlap: 0,
+            ..ChunkConfig::default()
+        };
+        let chunks = chunk_text(&text, config).unwrap();
+        let texts: Vec<&str> = chunks.iter().map(|c| c.text.as_str()).collect();
+        assert_eq!(texts, chunk_text_impl(&text, 40, 0).unwrap());
+        assert_eq!(chunks[1].index, 1);
+        assert_eq!(
+            chunks[1].id,
+            hash_text_impl(&format!("1:{}", chunks[1].text))
+        );
+        assert_eq!(chunks[1].char_count, chunks[1].text.chars().count());
+        assert_eq!(chunks[1].word_count, 2);
+        let zero = ChunkConfig {
+            target_size: 0,
+            ..config
+        };
+        let err = chunk_text(&text, zero).unwrap_err();
+        assert_eq!(err, ChunkError::ZeroTargetSize);
+        assert_eq!(err.to_string(), "target_size must be greater than 0");
+
+        let metadata = extract_metadata(&text);
+        assert_eq!(raws(&metadata.monetary_amounts, |m| &m.raw), ["$5 million"]);
+        assert_eq!(metadata.monetary_amounts[0].value, Some(5e6));
+        assert_eq!(metadata.percentages[0].value, Some(12.0));
+        assert_eq!(metadata.potential_tickers, ["AAPL"]);
+        assert_eq!(metadata.ticker_counts[0].count, 1);
+
+        let mut extractor = ExtractorConfig::default();
+        extractor.add_pattern("deal_id", r"D-\d+").unwrap();
+        let mut config = ProcessConfig {
+            chunk_size: 40,
+            chunk_overlap: 0,
+            extractor: Arc::new(extractor),
+            ..ProcessConfig::default()
+        };
+        let chunks = process_document_impl("Deal D-17 closed.   AAPL rose 5%.", &config).unwrap();
+        let metadata = chunks[0].metadata.as_ref().unwrap();
+        assert_eq!(metadata.custom[0].1, ["D-17"]);
+        config.on_empty = OnEmpty::Error;
+        let err = process_document_impl("  ", &config).unwrap_err();
+        assert_eq!(err.to_string(), "document is empty after cleaning");
+    }
+
+    #[test]
+    fn test_clean_text_nfkc_normalization() {
+        // Test that NFKC normalization is actually applied
+        // The "ﬁ" ligature (U+FB01) should become "fi"
+        let input = "ﬁnance";
+        let result = clean_text_with_options(input, &CleanOptions::default());
+        assert_eq!(result, "finance");
+    }
+
+    #[test]
+    fn test_chunk_text() {
+        let text = "First sentence. Second sentence. Third sentence. Fourth sentence.";
+        let chunks = chunk_text_impl(text, 40, 10).unwrap();
+        assert!(!chunks.is_empty());
+    }
+
+    #[test]
+    fn test_chunk_text_zero_size_returns_none() {
+        let text = "Some text.";
+        let result = chunk_text_impl(text, 0, 10);
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn test_chunk_text_preserves_punctuation() {
+        // Test that original punctuation is preserved
+        let text = "What is this? It is great! Really.";
+        let chunks = chunk_text_impl(text, 100, 10).unwrap();
+        assert_eq!(chunks.len(), 1);
+        // Should preserve the ? and ! instead of converting to .
+        assert!(chunks[0].contains("?"));
+        assert!(chunks[0].contains("!"));
+    }
+
+    #[test]
+    fn test_chunk_text_overlap_correctness() {
+        // Test that overlap actually works and is measured in characters
+        let text = "One. Two. Three. Four. Five.";
+        let chunks = chunk_text_impl(text, 15, 5).unwrap();
+        // With such small chunks, we should get multiple chunks with overlap
+        assert!(chunks.len() > 1);
+    }
+
+    #[test]
+    fn test_standardize_quotes() {
+        // Input with curly quotes (Unicode)
+        let input = "\u{201C}Hello\u{201D} and \u{2018}world\u{2019}";
+        let result = clean_text_with_options(input, &CleanOptions::default());
+        assert_eq!(result, "\"Hello\" and 'world'");
+    }
+
+    #[test]
+    fn test_char_len_vs_byte_len() {
+        // Emoji is multiple bytes but one character (actually one grapheme cluster)
+        let emoji = "😀";
+        assert_eq!(char_len(emoji), 1);
+        assert_eq!(emoji.len(), 4); // UTF-8 bytes
+
+        // Accented character
+        let accented = "é";
+        assert_eq!(char_len(accented), 1);
+
+        // Clusters of several code points count once
+        assert_eq!(char_len("\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}"), 1);
+        assert_eq!(char_len("e\u{301}"), 1);
+    }
+
+    #[test]
+    fn test_split_sentences_preserve_punct() {
+        let text = "Hello! How are you? I am fine.";
+        let sentences = split_sentences_preserve_punct(text);
+        assert_eq!(sentences.len(), 3);
+        assert_eq!(sentences[0], "Hello!");
+        assert_eq!(sentences[1], "How are you?");
+        assert_eq!(sentences[2], "I am fine.");
+    }
+
+    #[test]
+    fn test_split_sentences_keeps_decimals() {
+        assert_eq!(
+            split_sentences_preserve_punct("Revenue of $4.5 billion. Growth was 3.5 per cent."),
+            vec!["Revenue of $4.5 billion.", "Growth was 3.5 per cent."]
+        );
+    }
+
+    #[test]
+    fn test_split_sentences_keeps_initials() {
+        assert_eq!(
+            split_sentences_preserve_punct("The U.S. Treasury sold bonds. J. Smith agreed."),
+            vec!["The U.S. Treasury sold bonds.", "J. Smith agreed."]
+        );
+        // A line break after the period still ends the sentence
+        assert_eq!(
+            split_sentences_preserve_punct("Sold in the U.S.\nPrices rose."),
+            vec!["Sold in the U.S.", "Prices rose."]
+        );
+    }
+
+    #[test]
+    fn test_split_sentences_keeps_list_numbers() {
+        assert_eq!(
+            split_sentences_preserve_punct("1. Introduction to the plan\n2. Results were strong."),
+            vec!["1. Introduction to the plan\n2. Results were strong."]
+        );
+        // Only at the start of a line
+        assert_eq!(
+            split_sentences_preserve_punct("Headcount grew to 12. Costs fell."),
+            vec!["Headcount grew to 12.", "Costs fell."]
+        );
+    }
+
+    #[test]
+    fn test_split_sentences_keeps_abbreviations() {
+        assert_eq!(
+            split_sentences_preserve_punct(
+                "We hold the No. 1 ranking with approx. 4 million users."
+            ),
+            vec!["We hold the No. 1 ranking with approx. 4 million users."]
+        );
+        assert_eq!(
+            split_sentences_preserve_punct(
+                "Dr. Lee spoke (e.g. on pricing). Was it good? No. It was not."
+            ),
+            vec![
+                "Dr. Lee spoke (e.g. on pricing).",
+                "Was it good?",
+                "No.",
+                "It was not."
+            ]
+        );
+    }
+
+    #[test]
+    fn test_split_sentences_ellipsis_pauses() {
+        assert_eq!(
+            split_sentences_preserve_punct("He paused... then continued."),
+            vec!["He paused... then continued."]
+        );
+        assert_eq!(
+            split_sentences_preserve_punct("It ended\u{2026} The next day began."),
+            vec!["It ended\u{2026}", "The next day began."]
+        );
+        assert_eq!(
+            split_sentences_preserve_punct("Well\u{2026} maybe. Wait... \"Why\" Fine...\nnext"),
+            vec!["Well\u{2026} maybe.", "Wait...", "\"Why\" Fine...", "next"]
+        );
+    }
+
+    #[test]
+    fn test_split_sentences_keeps_punctuation_clusters() {
+        assert_eq!(
+            split_sentences_preserve_punct("Really?! Yes!?... it did. Done?.. ok"),
+            vec!["Really?!", "Yes!?...", "it did.", "Done?..", "ok"]
+        );
+    }
+
+    #[test]
+    fn test_ellipsis_terminal() {
+        let text = "He paused... then continued.";
+        let units = UnitOptions {
+            ellipsis_terminal: true,
+            ..UnitOptions::default()
+        };
+        let sentences: Vec<String> = split_sentence_units_at(text, &units)
+            .into_iter()
+            .map(|unit| unit.text)
+            .collect();
+        assert_eq!(sentences, vec!["He paused...", "then continued."]);
+    }
+
+    #[test]
+    fn test_chunk_text_by_words() {
+        // 6, 2, 8 and 5 words
+        let text = "Revenue grew 12% in the quarter. Costs fell. \
+                    Margins expanded to a record level this year. The board approved a dividend.";
+        assert_eq!(
+            chunk_text_by_words_impl(text, 10, 3).unwrap(),
+            vec![
+                "Revenue grew 12% in the quarter. Costs fell.",
+                "Costs fell. Margins expanded to a record level this year.",
+                "The board approved a dividend."
+            ]
+        );
+        assert_eq!(chunk_text_by_words_impl(text, 100, 50).unwrap(), vec![text]);
+        assert!(chunk_text_by_words_impl(text, 0, 0).is_none());
+        assert!(chunk_text_by_words_impl("", 10, 3).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_chunk_text_by_words_keeps_long_sentences_whole() {
+        let long = "This single sentence has far more than five words in it.";
+        let text = format!("Short one. {long} Another short one.");
+        assert_eq!(
+            chunk_text_by_words_impl(&text, 5, 0).unwrap(),
+            vec!["Short one.", long, "Another short one."]
+        );
+        // chunk_text(size_unit="words") splits it instead
+        let split =
+            chunk_text_with_overlap(&text, 5, Overlap::Sentences(0), false, SizeUnit::Words)
+                .unwrap();
+        assert!(split.iter().all(|chunk| word_count(chunk) <= 5));
+    }
+
+    #[test]
+    fn test_newline_is_boundary() {
+        let text = "Q3 Highlights\n- Revenue up 12%\n- Margin expansion\n\n\
+                    Outlook raised. Guidance follows\nnext quarter.\nThanks";
+        let units = UnitOptions {
+            newline_is_boundary: true,
+            ..UnitOptions::default()
+        };
+        let sentences = split_sentence_units_at(text, &units);
+        let texts: Vec<&str> = sentences.iter().map(|u| u.text.as_str()).collect();
+        assert_eq!(
+            texts,
+            vec![
+                "Q3 Highlights",
+                "- Revenue up 12%",
+                "- Margin expansion",
+                "Outlook raised.",
+                "Guidance follows",
+                "next quarter.",
+                "Thanks"
+            ]
+        );
+        let line_breaks: Vec<bool> = sentences.iter().map(|u| u.after_line_break).collect();
+        assert_eq!(
+            line_breaks,
+            vec![false, true, true, false, false, true, true]
+        );
+        // Without it, the unpunctuated lines fuse into one sentence
+        assert_eq!(
+            split_sentences_preserve_punct("Q3 Highlights\nRevenue up 12%\nMargin expansion"),
+            vec!["Q3 Highlights\nRevenue up 12%\nMargin expansion"]
+        );
+    }
+
+    #[test]
+    fn test_chunk_newline_is_boundary() {
+        let text = "Q3 Highlights\nRevenue up 12%\nMargin expansion\nNew markets opened";
+        let chunk = |units: &UnitOptions| {
+            chunk_text_with_min_size(
+                text,
+                35,
+                Overlap::Sentences(0),
+                units,
+                SizeUnit::Chars,
+                0,
+                false,
+            )
+            .unwrap()
+        };
+        let units = UnitOptions {
+            newline_is_boundary: true,
+            ..UnitOptions::default()
+        };
+        assert_eq!(
+            chunk(&units),
+            vec![
+                "Q3 Highlights\nRevenue up 12%",
+                "Margin expansion\nNew markets opened"
+            ]
+        );
+        // Composes with preserve_lists
+        let text_with_list = "Agenda\nItems\n- Budget review\n- Hiring plan";
+        let units = UnitOptions {
+            newline_is_boundary: true,
+            preserve_lists: true,
+            ..UnitOptions::default()
+        };
+        let texts: Vec<String> = split_prose_units(text_with_list, &units)
+            .into_iter()
+            .map(|u| u.text)
+            .collect();
+        assert_eq!(
+            texts,
+            vec!["Agenda", "Items", "- Budget review", "- Hiring plan"]
+        );
+    }
+
+    #[test]
+    fn test_split_sentences_japanese_paragraph() {
+        let text = "今日は晴れです。明日は雨が降るでしょう！傘を持っていますか？はい。";
+        assert_eq!(
+            split_sentences_preserve_punct(text),
+            vec![
+                "今日は晴れです。",
+                "明日は雨が降るでしょう！",
+                "傘を持っていますか？",
+                "はい。"
+            ]
+        );
+        // NFKC turns the full-width "！" and "？" into ASCII
+        let cleaned = clean_text_with_options(text, &CleanOptions::default());
+        assert_eq!(
+            split_sentences_preserve_punct(&cleaned),
+            vec![
+                "今日は晴れです。",
+                "明日は雨が降るでしょう!",
+                "傘を持っていますか?",
+                "はい。"
+            ]
+        );
+        assert_eq!(
+            split_sentences_preserve_punct("彼は「行きます。」と言った。次です。"),
+            vec!["彼は「行きます。」", "と言った。", "次です。"]
+        );
+    }
+
+    #[test]
+    fn test_split_sentences_mixed_english_and_cjk() {
+        let text = "Revenue grew 5%. 売上高は増加しました。Margins held! 利益率は横ばい? Yes.";
+        assert_eq!(
+            split_sentences_preserve_punct(text),
+            vec![
+                "Revenue grew 5%.",
+                "売上高は増加しました。",
+                "Margins held!",
+                "利益率は横ばい?",
+                "Yes."
+            ]
+        );
+        // ASCII terminators still need whitespace before Latin text or digits
+        assert_eq!(
+            split_sentences_preserve_punct("Version 2.0 shipped.日本語版も公開!次はv3?No."),
+            vec!["Version 2.0 shipped.日本語版も公開!", "次はv3?No."]
+        );
+    }
+
+    #[test]
+    fn test_hash_text_digests() {
+        // Reference xxHash64 (seed 0) vectors
+        assert_eq!(hash_text_impl(""), "ef46db3751d8e999");
+        assert_eq!(hash_text_impl("a"), "d24ec4f1a98c6e5b");
+        assert_eq!(hash_text_impl("abc"), "44bc2cf5ad770999");
+        assert_eq!(chunk_id(0, "abc"), hash_text_impl("0:abc"));
+        assert_ne!(chunk_id(0, "abc"), chunk_id(1, "abc"));
+    }
+
+    #[test]
+    fn test_process_document_without_metadata() {
+        let text = "Revenue hit $5 million. Margins rose 3%.";
+        let config = ProcessConfig {
+            extract_meta: false,
+            ..ProcessConfig::default()
+        };
+        let chunks = process_document_impl(text, &config).unwrap();
+        let with_metadata = process_document_impl(text, &ProcessConfig::default()).unwrap();
+        assert!(chunks.iter().all(|c| c.metadata.is_none()));
+        assert!(with_metadata.iter().all(|c| c.metadata.is_some()));
+        let texts = |chunks: &[ProcessedChunk]| -> Vec<String> {
+            chunks.iter().map(|c| c.text.clone()).collect()
+        };
+        assert_eq!(texts(&chunks), texts(&with_metadata));
+    }
+
+    #[test]
+    fn test_process_document_chunk_ids() {
+        let config = ProcessConfig {
+            chunk_size: 20,
+            chunk_overlap: 0,
+            ..ProcessConfig::default()
+        };
+        let text = "Same sentence here. Same sentence here.";
+        let chunks = process_document_impl(text, &config).unwrap();
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(chunks[0].text, chunks[1].text);
+        assert_ne!(chunks[0].chunk_id, chunks[1].chunk_id);
+        for chunk in &chunks {
+            assert_eq!(chunk.chunk_id, chunk_id(chunk.chunk_index, &chunk.text));
+        }
+        assert_eq!(process_document_impl(text, &config).unwrap(), chunks);
+    }
+
+    #[test]
+    fn test_process_document_word_size_unit() {
+        let config = ProcessConfig {
+            chunk_size: 7,
+            chunk_overlap: 0,
+            size_unit: SizeUnit::Words,
+            ..ProcessConfig::default()
+        };
+        let chunks = process_document_impl(WORD_SENTENCES, &config).unwrap();
+        let counts: Vec<(usize, usize)> = chunks
+            .iter()
+            .map(|c| (c.word_count, c.char_count))
+            .collect();
+        assert_eq!(counts, vec![(7, 41), (6, 35)]);
+    }
+
+    #[test]
+    fn test_process_document_impl_matches_pipeline() {
+        let text = "Revenue hit $5 million.   Margins rose 3%.";
+        let chunks = process_document_impl(text, &ProcessConfig::default()).unwrap();
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(chunks[0].chunk_index, 0);
+        assert_eq!(
+            chunks[0].text,
+            clean_text_with_options(text, &CleanOptions::default())
+        );
+        assert_eq!(chunks[0].char_count, char_len(&chunks[0].text));
+        assert_eq!(chunks[0].word_count, 7);
+        assert_eq!(
+            chunks[0].metadata,
+            Some(extract_metadata_impl(&chunks[0].text, false))
+        );
+        let zero_size = ProcessConfig {
+            chunk_size: 0,
+            ..ProcessConfig::default()
+        };
+        assert_eq!(
+            process_document_impl(text, &zero_size),
+            Err(ProcessError::InvalidChunkSize)
+        );
+    }
+
+    #[test]
+    fn test_chunk_report_counts_oversized_chunks() {
+        // process_document splits long sentences, so only a code block kept
+        // whole can exceed chunk_size
+        let text = format!("Revenue rose. Costs fell.\n{CODE_BLOCK}\nOk.");
+        let config = ProcessConfig {
+            chunk_size: 40,
+            chunk_overlap: 0,
+            clean_options: CleanOptions {
+                preserve_code_blocks: true,
+                ..CleanOptions::default()
+            },
+            ..ProcessConfig::default()
+        };
+        let chunks = process_document_impl(&text, &config).unwrap();
+        let report = ChunkReport::new(&chunks, &config);
+        assert_eq!(report.chunk_count, chunks.len());
+        let block = chunks.iter().position(|c| c.text == CODE_BLOCK).unwrap();
+        assert_eq!(report.exceeded, vec![block]);
+        assert_eq!(report.max_size, CODE_BLOCK.chars().count());
+        assert_eq!(report.floor, 4);
+        assert_eq!(report.below_floor, 1);
+
+        let words = ProcessConfig {
+            size_unit: SizeUnit::Words,
+            chunk_size: 40,
+            ..config
+        };
+        let chunks = process_document_impl(&text, &words).unwrap();
+        let report = ChunkReport::new(&chunks, &words);
+        assert!(report.exceeded.is_empty());
+        assert_eq!(report.below_floor, 0);
+    }
+
+    #[test]
+    fn test_process_documents_impl_preserves_order() {
+        let texts: Vec<Option<String>> = (0..50)
+            .map(|i| Some(format!("Document {i} reports ${i} million.")))
+            .collect();
+        let results = process_documents_impl(&texts, &ProcessConfig::default());
+        assert_eq!(results.len(), texts.len());
+        for (text, result) in texts.iter().zip(&results) {
+            let expected =
+                process_document_impl(text.as_deref().unwrap(), &ProcessConfig::default());
+            assert_eq!(result, &expected.ok());
+        }
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn test_process_documents_impl_in_local_pool() {
+        let texts: Vec<Option<String>> = (0..200)
+            .map(|i| Some(format!("Document {i} reports ${i} million. ").repeat(i % 7 + 1)))
+            .collect();
+        let config = ProcessConfig::default();
+        let expected = process_documents_impl(&texts, &config);
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(3)
+            .build()
+            .unwrap();
+        let results = pool.install(|| process_documents_impl(&texts, &config));
+        assert_eq!(results, expected);
+    }
+
+    #[test]
+    fn test_process_documents_impl_reports_bad_document_as_none() {
+        let texts = vec![
+            Some("First doc.".to_string()),
+            None,
+            Some("Third doc.".to_string()),
+        ];
+        let results = process_documents_impl(&texts, &ProcessConfig::default());
+        assert!(results[0].is_some());
+        assert!(results[1].is_none());
+        assert_eq!(results[2].as_ref().unwrap()[0].text, "Third doc.");
+    }
+
+    #[test]
+    fn test_entity_counts() {
+        let text = "Revenue of $5 million in Q3 2024 beat the $5 million guidance, \
+                    up 12% YoY; costs rose 3% while margins rose 12% in Q3 2024.";
+        let counts = extract_metadata(text).entity_counts();
+        let get = |key: &str| counts.iter().find(|(k, _)| *k == key).unwrap().1.clone();
+        let expected = |pairs: &[(&str, usize)]| -> BTreeMap<String, usize> {
+            pairs.iter().map(|(k, n)| (k.to_string(), *n)).collect()
+        };
+        assert_eq!(
+            get("monetary_amounts_counts"),
+            expected(&[("$5 million", 2)])
+        );
+        assert_eq!(
+            get("percentages_counts"),
+            expected(&[("12%", 2), ("3%", 1)])
+        );
+        assert_eq!(get("dates_counts"), expected(&[("Q3 2024", 2)]));
+        assert_eq!(get("fiscal_periods_counts"), expected(&[("YoY", 1)]));
+        assert!(Metadata::default()
+            .entity_counts()
+            .iter()
+            .all(|(_, c)| c.is_empty()));
+    }
+
+    #[test]
+    fn test_extract_metadata_multi_currency() {
+        let text = "Sales hit €4,200 and £30 million, costs were ¥1,000 and ₹20. \
+                    Guidance is USD 5 million, EUR 1.2B and GBP 500k versus $7.";
+        let metadata = extract_metadata_impl(text, false);
+        let currencies: Vec<(&str, &str)> = metadata
+            .monetary_amounts
+            .iter()
+            .map(|m| (m.raw.trim(), m.currency.as_deref().unwrap()))
+            .collect();
+        assert_eq!(
+            currencies,
+            vec![
+                ("€4,200", "EUR"),
+                ("£30 million", "GBP"),
+                ("¥1,000", "¥"),
+                ("₹20", "INR"),
+                ("USD 5 million", "USD"),
+                ("EUR 1.2B", "EUR"),
+                ("GBP 500k", "GBP"),
+                ("$7", "USD"),
+            ]
+        );
+        // Currency codes are not ticker symbols
+        assert!(metadata.potential_tickers.is_empty());
+    }
+
+    #[test]
+    fn test_process_document_impl_parallel_matches_sequential() {
+        let text = include_str!("../data/sample_transcripts/acme_q3_2025.txt").repeat(50);
+        let config = |parallel| ProcessConfig {
+            chunk_size: 500,
+            chunk_overlap: 100,
+            infer_sign: true,
+            parallel: Some(parallel),
+            ..ProcessConfig::default()
+        };
+        assert_eq!(
+            process_document_impl(&text, &config(true)),
+            process_document_impl(&text, &config(false))
+        );
+    }
+
+    #[test]
+    fn test_extract_metadata_spans_slice_to_match() {
+        let text = "Café ACME 😀 earned €5 million (up 3%) in Q1 2024; ACME and MSFT agree.";
+        let spans = extract_metadata_spans_impl(text, &TickerOptions::default());
+        let chars: Vec<char> = text.chars().collect();
+        let all = spans
+            .monetary_amounts
+            .iter()
+            .chain(&spans.percentages)
+            .chain(&spans.dates)
+            .chain(&spans.potential_tickers);
+        for span in all {
+            let sliced: String = chars[span.start..span.end].iter().collect();
+            assert_eq!(sliced, span.text);
+        }
+
+        let tickers: Vec<(&str, usize)> = spans
+            .potential_tickers
+            .iter()
+            .map(|s| (s.text.as_str(), s.start))
+            .collect();
+        assert_eq!(tickers, vec![("ACME", 5), ("ACME", 50), ("MSFT", 59)]);
+        assert_eq!(spans.dates[0].text, "Q1 2024");
+    }
+
+    #[test]
+    fn test_metadata_spans_sorted_with_kinds() {
+        let text = "ACME paid $5 million, 12% of Q1 2024 revenue, and ACME again.";
+        let spans = extract_metadata_spans_impl(text, &TickerOptions::default()).into_sorted();
+        let found: Vec<(&str, &str, usize)> = spans
+            .iter()
+            .map(|s| (s.kind, s.text.as_str(), s.start))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("ticker", "ACME", 0),
+                ("money", "$5 million", 10),
+                ("percentage", "12%", 22),
+                ("date", "Q1 2024", 29),
+                ("ticker", "ACME", 50),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_metadata_spans_report_overlaps() {
+        // "FY" is both a ticker-shaped word and part of the date
+        let text = "Guidance for FY 2024 was raised.";
+        let spans = extract_metadata_spans_impl(text, &TickerOptions::default()).into_sorted();
+        let found: Vec<(&str, &str, usize, usize)> = spans
+            .iter()
+            .map(|s| (s.kind, s.text.as_str(), s.start, s.end))
+            .collect();
+        assert_eq!(
+            found,
+            vec![("ticker", "FY", 13, 15), ("date", "FY 2024", 13, 20)]
+        );
+    }
+
+    #[test]
+    fn test_process_document_spans_are_chunk_relative() {
+        let config = ProcessConfig {
+            chunk_size: 40,
+            chunk_overlap: 0,
+            include_spans: true,
+            ..ProcessConfig::default()
+        };
+        let text = "Sales rose 5% in Q1 2024. ACME paid $3 million to MSFT.";
+        let chunks = process_document_impl(text, &config).unwrap();
+        assert_eq!(chunks.len(), 2);
+        for chunk in &chunks {
+            let chars: Vec<char> = chunk.text.chars().collect();
+            for span in chunk.spans.clone().unwrap().into_sorted() {
+                let sliced: String = chars[span.start..span.end].iter().collect();
+                assert_eq!(sliced, span.text);
+            }
+        }
+        let second = chunks[1].spans.clone().unwrap();
+        assert_eq!(second.monetary_amounts[0].start, 10);
+        assert!(
+            process_document_impl(text, &ProcessConfig::default()).unwrap()[0]
+                .spans
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_process_document_sections() {
+        let config = ProcessConfig {
+            chunk_size: 60,
+            chunk_overlap: 0,
+            include_sections: true,
+            ..ProcessConfig::default()
+        };
+        let text = "Cover page of the annual report.\n\n\
+                    ITEM 1A. RISK FACTORS\nOur business is subject to many risks.\n\n\
+                    Item 7. Management's Discussion and Analysis\nRevenue rose 12% to $5 million.";
+        let chunks = process_document_impl(text, &config).unwrap();
+        let sections: Vec<Option<&str>> = chunks
+            .iter()
+            .map(|c| c.section.as_ref().unwrap().as_deref())
+            .collect();
+        assert_eq!(
+            sections,
+            vec![
+                None,
+                Some("Item 1A. Risk Factors"),
+                Some("Item 1A. Risk Factors"),
+                Some("Item 7. Management's Discussion and Analysis"),
+                Some("Item 7. Management's Discussion and Analysis"),
+            ]
+        );
+        assert!(
+            process_document_impl(text, &ProcessConfig::default()).unwrap()[0]
+                .section
+                .is_none()
+        );
+    }
+
+    const BALANCE_SHEET: &str =
+        "The balance sheet is summarized below. Liquidity remains strong.\n\n\
+                                 (in millions)             2024        2023\n\
+                                 Cash and equivalents   $ 1,234     $ 1,100\n\
+                                 Receivables                512         480\n\
+                                 Total assets           $ 4,310     $ 3,950\n\n\
+                                 Total  assets grew   9%. We expect further growth.";
+
+    #[test]
+    fn test_clean_preserve_tables() {
+        let options = CleanOptions {
+            preserve_tables: true,
+            ..CleanOptions::default()
+        };
+        let cleaned = clean_text_with_options(BALANCE_SHEET, &options);
+        assert!(cleaned.contains("Cash and equivalents   $ 1,234     $ 1,100\nReceivables"));
+        assert!(cleaned.ends_with("\n\nTotal assets grew 9%. We expect further growth."));
+        assert!(!clean_text(BALANCE_SHEET).contains("   "));
+    }
+
+    #[test]
+    fn test_process_document_tables_are_whole_chunks() {
+        let config = ProcessConfig {
+            chunk_size: 60,
+            chunk_overlap: 20,
+            clean_options: CleanOptions {
+                preserve_tables: true,
+                ..CleanOptions::default()
+            },
+            ..ProcessConfig::default()
+        };
+        let chunks = process_document_impl(BALANCE_SHEET, &config).unwrap();
+        let flagged: Vec<(&str, Option<bool>)> = chunks
+            .iter()
+            .map(|c| (c.text.as_str(), c.is_table))
+            .collect();
+        let table = BALANCE_SHEET.split("\n\n").nth(1).unwrap();
+        assert_eq!(
+            flagged,
+            vec![
+                ("The balance sheet is summarized below.", Some(false)),
+                ("Liquidity remains strong.", Some(false)),
+                (table, Some(true)),
+                (
+                    "Total assets grew 9%. We expect further growth.",
+                    Some(false)
+                ),
+            ]
+        );
+        assert!(
+            process_document_impl(BALANCE_SHEET, &ProcessConfig::default()).unwrap()[0]
+                .is_table
+                .is_none()
+        );
+    }
+
+    fn options() -> CleanOptions {
+        CleanOptions::default()
+    }
+
+    #[test]
+    fn test_clean_options_default_matches_clean_text() {
+        let input = "\u{201C}ﬁnance\u{201D} – 2019—2023\u{0007}\n\n\n  spaced   out ";
+        assert_eq!(
+            clean_text_with_options(input, &options()),
+            clean_text_with_options(input, &CleanOptions::default())
+        );
+    }
+
+    #[test]
+    fn test_clean_options_unicode_normalization() {
+        let input = "x² + ½ ﬁ";
+        assert_eq!(clean_text_with_options(input, &options()), "x2 + 1⁄2 fi");
+        let nfc = CleanOptions {
+            unicode_normalization: UnicodeForm::Nfc,
+            ..options()
+        };
+        assert_eq!(clean_text_with_options(input, &nfc), "x² + ½ ﬁ");
+        // NFC composes "e" + combining acute; "none" leaves it decomposed
+        let decomposed = "cafe\u{0301}";
+        assert_eq!(clean_text_with_options(decomposed, &nfc), "café");
+        let none = CleanOptions {
+            unicode_normalization: UnicodeForm::None,
+            ..options()
+        };
+        assert_eq!(clean_text_with_options(decomposed, &none), decomposed);
+        assert_eq!(UnicodeForm::parse("nfd"), None);
+    }
+
+    #[test]
+    fn test_clean_options_standardize_quotes() {
+        let input = "\u{201C}Hello\u{201D} \u{2018}world\u{2019}";
+        assert_eq!(
+            clean_text_with_options(input, &options()),
+            "\"Hello\" 'world'"
+        );
+        let keep = CleanOptions {
+            standardize_quotes: false,
+            ..options()
+        };
+        assert_eq!(clean_text_with_options(input, &keep), input);
+    }
+
+    #[test]
+    fn test_clean_options_standardize_dashes() {
+        let input = "2019–2023 — up";
+        assert_eq!(clean_text_with_options(input, &options()), "2019-2023 - up");
+        let keep = CleanOptions {
+            standardize_dashes: false,
+            ..options()
+        };
+        assert_eq!(clean_text_with_options(input, &keep), input);
+    }
+
+    #[test]
+    fn test_clean_options_fix_mojibake() {
+        // The "™" in the apostrophe's mojibake would become "TM" under NFKC if
+        // it were not repaired first
+        let input = "ï»¿\u{E2}\u{20AC}\u{153}CafÃ©\u{E2}\u{20AC}\u{9D} \u{E2}\u{20AC}\u{201C} \
+                     ACME\u{E2}\u{20AC}\u{2122}s";
+        let fix = CleanOptions {
+            fix_mojibake: true,
+            ..options()
+        };
+        assert_eq!(clean_text_with_options(input, &fix), "\"Café\" - ACME's");
+        let unfixed = clean_text_with_options(input, &options());
+        assert!(
+            unfixed.contains("CafÃ©") && unfixed.ends_with("TMs"),
+            "{unfixed}"
+        );
+    }
+
+    #[test]
+    fn test_clean_options_strip_invisible() {
+        let input = "\u{FEFF}Fi\u{00AD}nan\u{00AD}cial re\u{200B}sults\u{2060} for Q3.";
+        let cleaned = clean_text_with_options(input, &options());
+        assert_eq!(cleaned, "Financial results for Q3.");
+        assert!(cleaned.contains("Financial"));
+        let keep = CleanOptions {
+            strip_invisible: false,
+            ..options()
+        };
+        assert_eq!(clean_text_with_options(input, &keep), input);
+    }
+
+    #[test]
+    fn test_clean_options_strip_bidi_controls() {
+        // An RLO-reversed file name and isolate-wrapped text, next to combining
+        // marks (Devanagari vowel signs, a decomposed accent) that must survive
+        let input = "invoice\u{202E}fdp.exe \u{2066}\u{200F}ok\u{2069} \u{202A}x\u{202C} \
+                     \u{0928}\u{092E}\u{0938}\u{094D}\u{0924}\u{0947} cafe\u{0301}";
+        let keep_form = CleanOptions {
+            unicode_normalization: UnicodeForm::None,
+            ..options()
+        };
+        assert_eq!(
+            clean_text_with_options(input, &keep_form),
+            "invoicefdp.exe ok x \u{0928}\u{092E}\u{0938}\u{094D}\u{0924}\u{0947} cafe\u{0301}"
+        );
+        let keep = CleanOptions {
+            strip_invisible: false,
+            ..keep_form
+        };
+        assert_eq!(clean_text_with_options(input, &keep), input);
+    }
+
+    #[test]
+    fn test_clean_options_keep_joiners() {
+        // ZWNJ in Persian "می\u{200C}خواهم" and a ZWJ emoji family
+        let input = "\u{FEFF}\u{0645}\u{06CC}\u{200C}\u{062E}\u{0648}\u{0627}\u{0647}\u{0645} \
+                     \u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}\u{200B}";
+        assert_eq!(
+            clean_text_with_options(input, &options()),
+            "\u{0645}\u{06CC}\u{062E}\u{0648}\u{0627}\u{0647}\u{0645} \u{1F468}\u{1F469}\u{1F467}"
+        );
+        let joiners = CleanOptions {
+            keep_joiners: true,
+            ..options()
+        };
+        assert_eq!(
+            clean_text_with_options(input, &joiners),
+            input
+                .trim_start_matches('\u{FEFF}')
+                .trim_end_matches('\u{200B}')
+        );
+    }
+
+    #[test]
+    fn test_standardize_dashes_code_points() {
+        let input = "2019\u{2013}2023 \u{2014} net \u{2212}5, co\u{2010}op, \
+                     non\u{2011}GAAP \u{2015} end";
+        let cleaned = clean_text_with_options(input, &options());
+        assert_eq!(cleaned, "2019-2023 - net -5, co-op, non-GAAP - end");
+        assert!(cleaned.is_ascii());
+        let nfc = CleanOptions {
+            unicode_normalization: UnicodeForm::Nfc,
+            ..options()
+        };
+        assert_eq!(clean_text_with_options(input, &nfc), cleaned);
+    }
+
+    #[test]
+    fn test_standardize_dashes_mojibake() {
+        // "2019–2023 — net −5" encoded as UTF-8 and decoded as Windows-1252
+        let input =
+            "2019\u{E2}\u{20AC}\u{201C}2023 \u{E2}\u{20AC}\u{201D} net \u{E2}\u{2C6}\u{2019}5";
+        assert_eq!(
+            clean_text_with_options(input, &options()),
+            "2019-2023 - net -5"
+        );
+        // Lone curly quotes are still only standardized as quotes
+        assert_eq!(
+            clean_text_with_options("\u{201C}net\u{201D}", &options()),
+            "\"net\""
+        );
+    }
+
+    #[test]
+    fn test_clean_options_collapse_whitespace() {
+        let input = "  Hello   world.\n\n\n\nNext\tpara. ";
+        assert_eq!(
+            clean_text_with_options(input, &options()),
+            "Hello world.\n\nNext para."
+        );
+        let keep = CleanOptions {
+            collapse_whitespace: false,
+            ..options()
+        };
+        assert_eq!(clean_text_with_options(input, &keep), input);
+    }
+
+    #[test]
+    fn test_clean_options_preserve_single_newlines() {
+        let input = "Acme Corp\n  1 Main   St\nSpringfield\n\nNext para.";
+        assert_eq!(
+            clean_text_with_options(input, &options()),
+            "Acme Corp 1 Main St Springfield\n\nNext para."
+        );
+        let keep = CleanOptions {
+            preserve_single_newlines: true,
+            ..options()
+        };
+        assert_eq!(
+            clean_text_with_options(input, &keep),
+            "Acme Corp\n1 Main St\nSpringfield\n\nNext para."
+        );
+    }
+
+    #[test]
+    fn test_clean_options_tab_policy() {
+        let input = "Segment\tQ1\tQ2\n\tRetail  \t 10\t12\n\nNext   para.";
+        let with = |tab_policy| CleanOptions {
+            tab_policy,
+            preserve_single_newlines: true,
+            ..options()
+        };
+        assert_eq!(
+            clean_text_with_options(input, &with(TabPolicy::Collapse)),
+            "Segment Q1 Q2\nRetail 10 12\n\nNext para."
+        );
+        assert_eq!(
+            clean_text_with_options(input, &with(TabPolicy::Preserve)),
+            "Segment\tQ1\tQ2\n\tRetail \t 10\t12\n\nNext para."
+        );
+        assert_eq!(
+            clean_text_with_options(input, &with(TabPolicy::ToSpaces(4))),
+            "Segment    Q1    Q2\n    Retail      10    12\n\nNext para."
+        );
+        // Without preserve_single_newlines the lines are still joined
+        let joined = CleanOptions {
+            tab_policy: TabPolicy::Preserve,
+            ..options()
+        };
+        assert_eq!(clean_text_with_options("a\tb\n\tc", &joined), "a\tb \tc");
+    }
+
+    #[test]
+    fn test_tab_policy_parse() {
+        assert_eq!(TabPolicy::parse("collapse"), Some(TabPolicy::Collapse));
+        assert_eq!(TabPolicy::parse("preserve"), Some(TabPolicy::Preserve));
+        assert_eq!(
+            TabPolicy::parse("to_spaces(4)"),
+            Some(TabPolicy::ToSpaces(4))
+        );
+        assert_eq!(TabPolicy::ToSpaces(2).name(), "to_spaces(2)");
+        for name in [
+            "to_spaces(0)",
+            "to_spaces()",
+            "to_spaces(-1)",
+            "to_spaces 4",
+            "keep",
+        ] {
+            assert_eq!(TabPolicy::parse(name), None, "{name}");
+        }
+    }
+
+    #[test]
+    fn test_clean_options_preserve_lists() {
+        let input = "Highlights for the quarter:\n- Revenue rose 12%\n  across all regions\n\
+                     * Costs   fell\n\u{2022} Margins held\n1. Buy back shares\n2) Raise the dividend\n\
+                     Overall a strong quarter.\n\nNext paragraph.";
+        assert_eq!(
+            clean_text_with_options(input, &options()),
+            "Highlights for the quarter: - Revenue rose 12% across all regions * Costs fell \
+             \u{2022} Margins held 1. Buy back shares 2) Raise the dividend Overall a strong \
+             quarter.\n\nNext paragraph."
+        );
+        let keep = CleanOptions {
+            preserve_lists: true,
+            ..options()
+        };
+        assert_eq!(
+            clean_text_with_options(input, &keep),
+            "Highlights for the quarter:\n- Revenue rose 12% across all regions\n* Costs fell\n\
+             \u{2022} Margins held\n1. Buy back shares\n2) Raise the dividend\n\
+             Overall a strong quarter.\n\nNext paragraph."
+        );
+    }
+
+    #[test]
+    fn test_clean_options_paragraph_sep() {
+        let input = "Revenue rose 12% in the\nquarter.\n\nCosts fell.\n \t\nMargins held.";
+        assert_eq!(
+            clean_text_with_options(input, &options()),
+            "Revenue rose 12% in the quarter.\n\nCosts fell. Margins held."
+        );
+        let blank_line = CleanOptions {
+            paragraph_sep: ParagraphSep::BlankLine,
+            ..options()
+        };
+        assert_eq!(
+            clean_text_with_options(input, &blank_line),
+            "Revenue rose 12% in the quarter.\n\nCosts fell.\n\nMargins held."
+        );
+
+        // One paragraph per line, as PDF extraction often leaves them
+        let pdf = "First paragraph of the letter.\nSecond paragraph.\n\n\nThird  one.";
+        let single = CleanOptions {
+            paragraph_sep: ParagraphSep::Single,
+            ..options()
+        };
+        assert_eq!(
+            clean_text_with_options(pdf, &single),
+            "First paragraph of the letter.\n\nSecond paragraph.\n\nThird one."
+        );
+        assert_eq!(
+            clean_text_with_options(pdf, &options()),
+            "First paragraph of the letter. Second paragraph.\n\nThird one."
+        );
+    }
+
+    #[test]
+    fn test_chunk_preserve_lists() {
+        let text = "Results were mixed. Highlights:\n- Revenue rose. Demand was strong\n\
+                    - Costs fell\n- Margins held\nThe outlook is stable.";
+        let units = UnitOptions {
+            preserve_lists: true,
+            ..UnitOptions::default()
+        };
+        let chunks = chunk_text_with_min_size(
+            text,
+            50,
+            Overlap::Sentences(0),
+            &units,
+            SizeUnit::Chars,
+            0,
+            false,
+        )
+        .unwrap();
+        assert_eq!(
+            chunks,
+            vec![
+                "Results were mixed. Highlights:",
+                "- Revenue rose. Demand was strong\n- Costs fell",
+                "- Margins held\nThe outlook is stable.",
+            ]
+        );
+        // Without it, the first item is split at its period and run into the prose
+        let plain =
+            chunk_text_with_overlap(text, 50, Overlap::Sentences(0), false, SizeUnit::Chars)
+                .unwrap();
+        assert_eq!(plain[0], "Results were mixed. Highlights:\n- Revenue rose.");
+    }
+
+    #[test]
+    fn test_clean_options_strip_control_chars() {
+        let input = "bell\u{0007}here";
+        assert_eq!(clean_text_with_options(input, &options()), "bellhere");
+        let keep = CleanOptions {
+            strip_control_chars: false,
+            ..options()
+        };
+        assert_eq!(clean_text_with_options(input, &keep), input);
+    }
+
+    #[test]
+    fn test_normalize_money_mixed_case_multipliers() {
+        assert_eq!(parse_money("$1.2 million"), Some(1_200_000.0));
+        assert_eq!(parse_money("$1.2 Million"), Some(1_200_000.0));
+        assert_eq!(parse_money("$3 BILLION"), Some(3e9));
+        assert_eq!(parse_money("$7m"), Some(7e6));
+        assert_eq!(parse_money("$250k"), Some(250_000.0));
+        assert_eq!(parse_money("$500,000"), Some(500_000.0));
+        assert_eq!(parse_money("$500,000.25"), Some(500_000.25));
+        assert_eq!(parse_money("$12 gazillion"), None);
+        assert_eq!(parse_money("twelve dollars"), None);
+    }
+
+    #[test]
+    fn test_monetary_values_align_with_amounts() {
+        let text = "We spent $500,000 and $12.50, then raised $2 Million.";
+        let metadata = extract_metadata_impl(text, false);
+        let values: Vec<Option<f64>> = metadata.monetary_amounts.iter().map(|m| m.value).collect();
+        assert_eq!(values, vec![Some(500_000.0), Some(12.5), Some(2_000_000.0)]);
+    }
+
+    #[test]
+    fn test_process_document_on_empty_modes() {
+        let junk = "\u{0}\u{1}\u{7}\u{1b}\u{7f}";
+        let config = |on_empty| ProcessConfig {
+            on_empty,
+            ..ProcessConfig::default()
+        };
+
+        assert_eq!(
+            process_document_impl(junk, &config(OnEmpty::EmptyList)),
+            Ok(vec![])
+        );
+
+        let placeholder = process_document_impl(junk, &config(OnEmpty::Placeholder)).unwrap();
+        assert_eq!(placeholder.len(), 1);
+        assert_eq!(placeholder[0].text, "");
+        assert_eq!(placeholder[0].char_count, 0);
+        assert_eq!(placeholder[0].reason, Some(CLEANED_TO_EMPTY));
+
+        assert_eq!(
+            process_document_impl(junk, &config(OnEmpty::Error)),
+            Err(ProcessError::EmptyDocument)
+        );
+    }
+
+    #[test]
+    fn test_process_document_on_empty_ignores_normal_input() {
+        let text = "Revenue hit $5 million.";
+        let expected = process_document_impl(text, &ProcessConfig::default()).unwrap();
+        for on_empty in [OnEmpty::Placeholder, OnEmpty::Error] {
+            let config = ProcessConfig {
+                on_empty,
+                ..ProcessConfig::default()
+            };
+            let chunks = process_document_impl(text, &config).unwrap();
+            assert_eq!(chunks, expected);
+            assert!(chunks.iter().all(|c| c.reason.is_none()));
+        }
+    }
+
+    #[test]
+    fn test_on_empty_parse() {
+        assert_eq!(OnEmpty::parse("empty_list"), Some(OnEmpty::EmptyList));
+        assert_eq!(OnEmpty::parse("placeholder"), Some(OnEmpty::Placeholder));
+        assert_eq!(OnEmpty::parse("error"), Some(OnEmpty::Error));
+        assert_eq!(OnEmpty::parse("raise"), None);
+    }
+
+    #[test]
+    fn test_clean_options_strip_html() {
+        let html = "<html><head><title>Q3</title><style>p{}</style></head><body>\n\
+                    <p>Revenue&nbsp;rose to $5&nbsp;million.</p>\n\
+                    <ul><li>AT&amp;T&#8217;s share</li><li>Margins</li></ul></body></html>";
+        let strip = CleanOptions {
+            strip_html: true,
+            ..options()
+        };
+        assert_eq!(
+            clean_text_with_options(html, &strip),
+            "Q3\n\nRevenue rose to $5 million.\n\nAT&T's share\n\nMargins"
+        );
+        // Off by default: markup is left alone
+        assert!(clean_text_with_options(html, &options()).contains("<p>"));
+    }
+
+    #[test]
+    fn test_extract_percentages_word_and_negative_forms() {
+        let text = "Margins rose 12.5 percent, churn was -3% and FX was (2.5%). \
+                    Guidance is 4-6% growth.";
+        let metadata = extract_metadata_impl(text, false);
+        assert_eq!(
+            raws(&metadata.percentages, |p| &p.raw),
+            vec!["12.5 percent", "-3%", "(2.5%)", "6%"]
+        );
+        let values: Vec<Option<f64>> = metadata.percentages.iter().map(|p| p.value).collect();
+        assert_eq!(values, vec![Some(12.5), Some(-3.0), Some(-2.5), Some(6.0)]);
+    }
+
+    #[test]
+    fn test_extract_percentages_pct_and_basis_points() {
+        let text = "Sales rose 12 percent and margins were up 3.5 pct, while spreads \
+                    declined 40 basis points (another 15bps after the quarter). Churn \
+                    was -3.5% and FX cost (3.5)%, or (25 bps) on rates. Retention hit 98%.";
+        let metadata = extract_metadata_impl(text, false);
+        assert_eq!(
+            raws(&metadata.percentages, |p| &p.raw),
+            vec![
+                "12 percent",
+                "3.5 pct",
+                "40 basis points",
+                "15bps",
+                "-3.5%",
+                "(3.5)%",
+                "(25 bps)",
+                "98%"
+            ]
+        );
+        let values: Vec<Option<f64>> = metadata.percentages.iter().map(|p| p.value).collect();
+        assert_eq!(
+            values,
+            vec![
+                Some(12.0),
+                Some(3.5),
+                Some(0.4),
+                Some(0.15),
+                Some(-3.5),
+                Some(-3.5),
+                Some(-0.25),
+                Some(98.0)
+            ]
+        );
+        let units: Vec<Option<&str>> = metadata.percentages.iter().map(|p| p.unit).collect();
+        assert_eq!(
+            units,
+            [
+                "percent",
+                "percent",
+                "basis_points",
+                "basis_points",
+                "percent",
+                "percent",
+                "basis_points",
+                "percent"
+            ]
+            .map(Some)
+        );
+    }
+
+    #[test]
+    fn test_slash_dates_follow_date_format() {
+        let text = "Signed 03/04/2024, amended 13/04/2024.";
+        let us = extract_metadata_impl(text, false);
+        let eu =
+            extract_metadata_with_options(text, false, &TickerOptions::default(), DateFormat::Eu);
+        assert_eq!(
+            raws(&us.dates, |d| &d.raw),
+            vec!["03/04/2024", "13/04/2024"]
+        );
+        let canonical = |m: &Metadata| -> Vec<Option<String>> {
+            m.dates.iter().map(|d| d.value?.canonical()).collect()
+        };
+        assert_eq!(
+            canonical(&us),
+            vec![Some("2024-03-04".into()), Some("2024-04-13".into())]
+        );
+        assert_eq!(
+            canonical(&eu),
+            vec![Some("2024-04-03".into()), Some("2024-04-13".into())]
+        );
+        let overridden =
+            |m: &Metadata| -> Vec<bool> { m.dates.iter().map(|d| d.format_overridden).collect() };
+        assert_eq!(overridden(&us), vec![false, true]);
+        assert_eq!(overridden(&eu), vec![false, false]);
+    }
+
+    #[test]
+    fn test_dates_parsed() {
+        let text = "In Q3 2024, on 2024-01-15 and on January 15, 2024 we filed; \
+                    the February 30, 2024 date is a typo.";
+        let metadata = extract_metadata_impl(text, false);
+        let values: Vec<Option<DateValue>> = metadata.dates.iter().map(|d| d.value).collect();
+        assert_eq!(
+            values,
+            vec![
+                Some(DateValue::Quarter {
+                    year: 2024,
+                    quarter: 3
+                }),
+                Some(DateValue::Day {
+                    year: 2024,
+                    month: 1,
+                    day: 15
+                }),
+                Some(DateValue::Day {
+                    year: 2024,
+                    month: 1,
+                    day: 15
+                }),
+                Some(DateValue::Day {
+                    year: 2024,
+                    month: 2,
+                    day: 30
+                }),
+            ]
+        );
+        let valid: Vec<bool> = values.iter().map(|v| v.unwrap().is_valid()).collect();
+        assert_eq!(valid, vec![true, true, true, false]);
+        let canonical: Vec<Option<String>> = metadata
+            .dates
+            .iter()
+            .map(|d| d.value?.canonical())
+            .collect();
+        assert_eq!(
+            canonical,
+            vec![
+                Some("2024-Q3".to_string()),
+                Some("2024-01-15".to_string()),
+                Some("2024-01-15".to_string()),
+                None,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_extract_dates_broader_formats() {
+        let text = "Filed Jan. 15, 2024 and Sept 3, 2024, signed 15 January 2024, \
+                    due March 2024. Paid 1/15/2024 and 15.1.2024. Fiscal 2023 \
+                    beat FY2024 guidance and fiscal year 2022.";
+        let metadata = extract_metadata_impl(text, false);
+        assert_eq!(
+            raws(&metadata.dates, |d| &d.raw),
+            vec![
+                "Jan. 15, 2024",
+                "Sept 3, 2024",
+                "15 January 2024",
+                "March 2024",
+                "1/15/2024",
+                "15.1.2024",
+                "Fiscal 2023",
+                "FY2024",
+                "fiscal year 2022",
+            ]
+        );
+        let normalized: Vec<Option<String>> = metadata
+            .dates
+            .iter()
+            .map(|d| d.value.and_then(|v| v.iso()))
+            .collect();
+        assert_eq!(
+            normalized,
+            [
+                "2024-01-15",
+                "2024-09-03",
+                "2024-01-15",
+                "2024-03",
+                "2024-01-15",
+                "2024-01-15",
+                "2023",
+                "2024",
+                "2022",
+            ]
+            .map(|s| Some(s.to_string()))
+        );
+    }
+
+    #[test]
+    fn test_extract_dates_ignores_bare_years() {
+        let metadata = extract_metadata_impl("Revenue was $2,024 on page 2024 in 2024.", false);
+        assert!(metadata.dates.is_empty(), "{:?}", metadata.dates);
+    }
+
+    #[test]
+    fn test_dehyphenate_joins_line_break_hyphenation() {
+        assert_eq!(
+            dehyphenate_impl("an inter-\nnational firm with im-  \r\n  portant news"),
+            "an international firm with important news"
+        );
+        assert_eq!(
+            dehyphenate_impl("a well-\nknown brand"),
+            "a wellknown brand"
+        );
+    }
+
+    #[test]
+    fn test_dehyphenate_keeps_compounds() {
+        for text in [
+            "a state-\nof-the-art plant",
+            "a state-of-\nthe-art plant",
+            "Coca-\nCola and COVID-\n19",
+            "net sales-\n\nrevenue",
+            "well-known on one line",
+        ] {
+            assert_eq!(dehyphenate_impl(text), text);
+        }
+    }
+
+    #[test]
+    fn test_clean_options_dehyphenate_pdf_line_breaks() {
+        let text = "Risk manage-\nment and finan-\ncial results on a non-\nGAAP,\n\
+                    year-over-\nyear basis at Coca-\nCola.";
+        let options = CleanOptions {
+            dehyphenate: true,
+            ..CleanOptions::default()
+        };
+        assert_eq!(
+            clean_text_with_options(text, &options),
+            "Risk management and financial results on a non- GAAP, \
+             year-over- year basis at Coca- Cola."
+        );
+    }
+
+    #[test]
+    fn test_clean_options_dehyphenate() {
+        let text = "Our inter-\nnational sales\ngrew.";
+        assert_eq!(
+            clean_text_with_options(text, &CleanOptions::default()),
+            "Our inter- national sales grew."
+        );
+        let options = CleanOptions {
+            dehyphenate: true,
+            ..CleanOptions::default()
+        };
+        assert_eq!(
+            clean_text_with_options(text, &options),
+            "Our international sales grew."
+        );
+    }
+
+    #[test]
+    fn test_fiscal_periods() {
+        let metadata = extract_metadata_impl("In FY2024 revenue rose YoY.", false);
+        assert_eq!(metadata.fiscal_periods, vec!["FY2024", "YoY"]);
+
+        let text = "FY23 sales grew QoQ and YTD; fiscal year 2023 ended, YoY again, \
+                    FY 2025 looks better. FY2024 beat fiscal 2023.";
+        let metadata = extract_metadata_impl(text, false);
+        assert_eq!(
+            metadata.fiscal_periods,
+            vec![
+                "FY23",
+                "QoQ",
+                "YTD",
+                "fiscal year 2023",
+                "YoY",
+                "FY 2025",
+                "FY2024",
+                "fiscal 2023"
+            ]
+        );
+        assert!(!metadata
+            .dates
+            .iter()
+            .any(|d| d.raw == "FY23" || d.raw == "QoQ"));
+        assert!(extract_metadata_impl("FY202 and YoYo", false)
+            .fiscal_periods
+            .is_empty());
+    }
+
+    #[test]
+    fn test_fiscal_periods_in_10q() {
+        let text = "For the three months ended June 30, 2024, net sales rose 5% to \
+                    $85.8 billion, and for the nine months ended June 30, 2024 they \
+                    were flat. We expect the second quarter of fiscal 2025 to \
+                    improve on H1 2024 and on the first half of 2023; see Q4 FY24.";
+        let metadata = extract_metadata(text);
+        assert_eq!(
+            metadata.fiscal_periods,
+            vec![
+                "three months ended June 30, 2024",
+                "nine months ended June 30, 2024",
+                "second quarter of fiscal 2025",
+                "H1 2024",
+                "first half of 2023",
+                "Q4 FY24",
+            ]
+        );
+        let parsed: Vec<_> = metadata
+            .fiscal_periods_parsed
+            .iter()
+            .map(|p| (p.kind, p.number, p.year))
+            .collect();
+        assert_eq!(
+            parsed,
+            vec![
+                ("Q", Some(2), Some(2024)),
+                ("9M", None, Some(2024)),
+                ("Q", Some(2), Some(2025)),
+                ("H", Some(1), Some(2024)),
+                ("H", Some(1), Some(2023)),
+                ("Q", Some(4), Some(2024)),
+            ]
+        );
+        // The end dates are still dates
+        assert_eq!(
+            raws(&metadata.dates, |d| &d.raw),
+            vec!["June 30, 2024", "June 30, 2024", "fiscal 2025"]
+        );
+    }
+
+    #[test]
+    fn test_extract_metadata_security_ids() {
+        let metadata = extract_metadata(
+            "Notes due 2030 (CUSIP 037833100, ISIN US0378331005; SEDOL B0YBKJ7). \
+             Not ISIN US0378331006.",
+        );
+        assert_eq!(metadata.isins, vec!["US0378331005"]);
+        assert_eq!(metadata.cusips, vec!["037833100"]);
+        assert_eq!(metadata.sedols, vec!["B0YBKJ7"]);
+        assert_eq!(
+            metadata.security_ids_unvalidated,
+            vec![UnvalidatedId {
+                raw: "US0378331006".to_string(),
+                kind: "isin",
+            }]
+        );
+        // The labels are not tickers
+        assert!(metadata.potential_tickers.is_empty());
+    }
+
+    #[test]
+    fn test_extract_metadata_urls_and_emails() {
+        let text = "Email ir@acme.com or press@acme.com (again ir@acme.com); \
+                    see http://x.com. Slides at www.acme.com/q3, then http://x.com.";
+        let metadata = extract_metadata_impl(text, false);
+        assert_eq!(metadata.emails, vec!["ir@acme.com", "press@acme.com"]);
+        assert_eq!(metadata.urls, vec!["http://x.com", "www.acme.com/q3"]);
+    }
+
+    #[test]
+    fn test_tickers_confident_bypass_stopwords() {
+        let metadata = extract_metadata_impl(
+            "Acme Corp. (NASDAQ: ACME) today announced $ONE and THE.",
+            false,
+        );
+        let symbols: Vec<&str> = metadata
+            .tickers_confident
+            .iter()
+            .map(|t| t.symbol.as_str())
+            .collect();
+        assert_eq!(symbols, vec!["ACME", "ONE"]);
+        assert_eq!(
+            metadata.tickers_confident[0].exchange.as_deref(),
+            Some("NASDAQ")
+        );
+        assert_eq!(metadata.potential_tickers, vec!["ACME"]);
+    }
+
+    #[test]
+    fn test_extra_stopwords_filter_potential_tickers() {
+        let text = "EBITDA grew while AAPL rose and GAAP EPS held.";
+        let options = TickerOptions {
+            extra_stopwords: ["EBITDA", "GAAP", "EPS"].map(String::from).into(),
+            ..TickerOptions::default()
+        };
+        let metadata = extract_metadata_with_options(text, false, &options, DateFormat::Us);
+        assert_eq!(metadata.potential_tickers, vec!["AAPL"]);
+        assert_eq!(
+            extract_metadata_impl(text, false).potential_tickers,
+            vec!["AAPL", "EPS", "GAAP"]
+        );
+    }
+
+    #[test]
+    fn test_allowlist_restricts_potential_tickers() {
+        let text = "NOTE: AAPL, MSFT and IBM rose; NYSE: KO and NASDAQ: MSFT traded; THE END.";
+        let options = TickerOptions {
+            allowlist: Some(["AAPL", "MSFT", "THE"].map(String::from).into()),
+            ..TickerOptions::default()
+        };
+        let metadata = extract_metadata_with_options(text, false, &options, DateFormat::Us);
+        assert_eq!(metadata.potential_tickers, vec!["AAPL", "MSFT", "THE"]);
+        assert_eq!(
+            metadata.potential_tickers_detailed[1].exchange.as_deref(),
+            Some("NASDAQ")
+        );
+    }
+
+    #[test]
+    fn test_ticker_counts_and_document_order() {
+        let text = "MSFT rose. AAPL fell, AAPL recovered; NYSE: IBM and MSFT and AAPL.";
+        let metadata = extract_metadata_impl(text, false);
+        assert_eq!(metadata.potential_tickers, vec!["AAPL", "IBM", "MSFT"]);
+        let counts: Vec<(&str, usize)> = metadata
+            .ticker_counts
+            .iter()
+            .map(|t| (t.symbol.as_str(), t.count))
+            .collect();
+        assert_eq!(counts, vec![("AAPL", 3), ("IBM", 1), ("MSFT", 2)]);
+        // Other categories keep duplicates
+        let metadata = extract_metadata_impl("Up 5% and 5% again.", false);
+        assert_eq!(raws(&metadata.percentages, |p| &p.raw), vec!["5%", "5%"]);
+
+        let options = TickerOptions {
+            order: TickerOrder::Document,
+            ..TickerOptions::default()
+        };
+        let metadata = extract_metadata_with_options(text, false, &options, DateFormat::Us);
+        assert_eq!(metadata.potential_tickers, vec!["MSFT", "AAPL", "IBM"]);
+        assert_eq!(
+            metadata.potential_tickers_detailed[2].exchange.as_deref(),
+            Some("NYSE")
+        );
+        assert_eq!(metadata.ticker_counts[0].count, 2);
+    }
+
+    #[test]
+    fn test_known_tickers_keep_only_dictionary_symbols() {
+        let text = "AAPL and MSFT rose, IBM fell; GAAP and CEO comments followed.";
+        let known: HashSet<String> = ["MSFT", "AAPL", "TSLA"].map(String::from).into();
+        let metadata = extract_metadata_with_options(
+            text,
+            false,
+            &TickerOptions::known_tickers(known),
+            DateFormat::Us,
+        );
+        assert_eq!(metadata.potential_tickers, vec!["AAPL", "MSFT"]);
+
+        let fallback = extract_metadata_with_options(
+            text,
+            false,
+            &TickerOptions::known_tickers(HashSet::new()),
+            DateFormat::Us,
+        );
+        assert_eq!(fallback, extract_metadata_impl(text, false));
+    }
+
+    #[test]
+    fn test_document_metadata_keeps_entities_split_by_chunking() {
+        let config = ProcessConfig {
+            chunk_size: 20,
+            chunk_overlap: 0,
+            ..ProcessConfig::default()
+        };
+        let text = "Revenue reached $1.5 billion in total. ACME and MSFT beat; ACME rose 5%.";
+        let (chunks, document) = process_document_with_metadata_impl(text, &config).unwrap();
+        assert_eq!(chunks, process_document_impl(text, &config).unwrap());
+        // The chunk boundary falls between "$1.5" and "billion"
+        assert_eq!(chunks[0].text, "Revenue reached $1.5");
+        assert_eq!(
+            raws(
+                &chunks[0].metadata.as_ref().unwrap().monetary_amounts,
+                |m| &m.raw
+            ),
+            vec!["$1.5"]
+        );
+        assert!(!chunks.iter().any(|c| c
+            .metadata
+            .as_ref()
+            .unwrap()
+            .monetary_amounts
+            .iter()
+            .any(|m| m.raw == "$1.5 billion")));
+
+        let categories: Vec<(&str, Vec<&str>, usize)> = document
+            .categories
+            .iter()
+            .map(|(name, values, total)| {
+                (*name, values.iter().map(String::as_str).collect(), *total)
+            })
+            .collect();
+        assert_eq!(
+            categories,
+            vec![
+                ("monetary_amounts", vec!["$1.5 billion"], 1),
+                ("percentages", vec!["5%"], 1),
+                ("dates", vec![], 0),
+                ("fiscal_periods", vec![], 0),
+                ("potential_tickers", vec!["ACME", "MSFT"], 3),
+            ]
+        );
+        assert_eq!(document.ticker_counts[0].count, 2);
+    }
+
+    #[test]
+    fn test_process_document_uses_ticker_options() {
+        let extractor = ExtractorConfig {
+            ticker_options: TickerOptions {
+                extra_stopwords: ["GAAP".to_string()].into(),
+                ..TickerOptions::default()
+            },
+            patterns: Vec::new(),
+        };
+        let config = ProcessConfig {
+            extractor: Arc::new(extractor),
+            ..ProcessConfig::default()
+        };
+        let chunks = process_document_impl("GAAP results for AAPL.", &config).unwrap();
+        assert_eq!(
+            chunks[0].metadata.as_ref().unwrap().potential_tickers,
+            vec!["AAPL"]
+        );
+    }
+
+    #[test]
+    fn test_extractor_config_add_pattern() {
+        let mut extractor = ExtractorConfig::default();
+        extractor.add_pattern("contract_id", r"CT-\d{6}").unwrap();
+        extractor.add_pattern("sku", r"SKU\d+").unwrap();
+        extractor.add_pattern("contract_id", r"CT-\d{4}").unwrap();
+        let names: Vec<&str> = extractor.patterns.iter().map(|(n, _)| n.as_str()).collect();
+        assert_eq!(names, vec!["contract_id", "sku"]);
+
+        let err = extractor.add_pattern("dates", "x").unwrap_err();
+        assert!(err.contains("built-in"), "{err}");
+        let err = extractor.add_pattern("broken", "(").unwrap_err();
+        assert!(err.starts_with("invalid pattern for \"broken\""), "{err}");
+        assert!(METADATA_KEYS
+            .iter()
+            .all(|key| extractor.add_pattern(key, "x").is_err()));
+    }
+
+    #[test]
+    fn test_extractor_config_custom_matches() {
+        let mut extractor = ExtractorConfig::default();
+        extractor.add_pattern("contract_id", r"CT-\d{6}").unwrap();
+        let metadata = extractor.extract("Contracts CT-123456 and CT-654321 cost $5M.", false);
+        assert_eq!(raws(&metadata.monetary_amounts, |m| &m.raw), vec!["$5M"]);
+        assert_eq!(
+            metadata.custom,
+            vec![(
+                "contract_id".to_string(),
+                vec!["CT-123456".to_string(), "CT-654321".to_string()]
+            )]
+        );
+        assert!(extract_metadata_impl("CT-123456", false).custom.is_empty());
+    }
+
+    #[test]
+    fn test_process_document_with_extractor_patterns() {
+        let mut extractor = ExtractorConfig::default();
+        extractor.add_pattern("contract_id", r"CT-\d{6}").unwrap();
+        let config = ProcessConfig {
+            chunk_size: 40,
+            chunk_overlap: 0,
+            extractor: Arc::new(extractor),
+            ..ProcessConfig::default()
+        };
+        let text = "Contract CT-111111 was signed. Contract CT-222222 was renewed.";
+        let chunks = process_document_impl(text, &config).unwrap();
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(
+            chunks[0].metadata.as_ref().unwrap().custom[0].1,
+            vec!["CT-111111"]
+        );
+        assert_eq!(
+            chunks[1].metadata.as_ref().unwrap().custom[0].1,
+            vec!["CT-222222"]
+        );
+    }
+
+    #[test]
+    fn test_potential_tickers_exchange_qualified() {
+        let text = "Apple (NASDAQ:AAPL) and NYSE: MSFT traded with IBM and AAPL.";
+        let metadata = extract_metadata_impl(text, false);
+        assert_eq!(metadata.potential_tickers, vec!["AAPL", "IBM", "MSFT"]);
+        let exchanges: Vec<Option<&str>> = metadata
+            .potential_tickers_detailed
+            .iter()
+            .map(|t| t.exchange.as_deref())
+            .collect();
+        assert_eq!(exchanges, vec![Some("NASDAQ"), None, Some("NYSE")]);
+    }
+
+    #[test]
+    fn test_matchers_money() {
+        let text = "Revenue rose to $1.2 billion from \u{20AC}900M, up 12%.";
+        let money = Matchers::compiled().money(text);
+        assert_eq!(money, vec!["$1.2 billion", "\u{20AC}900M"]);
+        let metadata = extract_metadata_impl(text, false);
+        assert_eq!(money, raws(&metadata.monetary_amounts, |m| &m.raw));
+        assert!(Matchers::compiled().money("No amounts here.").is_empty());
+    }
+
+    #[test]
+    fn test_matchers_percentages() {
+        let text = "Margins were 12.5%, down 40 bps and 3 percent below plan; cost $5M.";
+        let percentages = Matchers::compiled().percentages(text);
+        let metadata = extract_metadata_impl(text, false);
+        assert_eq!(percentages, raws(&metadata.percentages, |p| &p.raw));
+        assert_eq!(percentages.len(), 3);
+        assert!(!percentages.iter().any(|p| p.contains('$')));
+    }
+
+    #[test]
+    fn test_matchers_dates() {
+        let text = "Filed March 5, 2024 for Q3 2023 at 12% on $5M.";
+        let dates = Matchers::compiled().dates(text);
+        assert_eq!(dates, vec!["March 5, 2024", "Q3 2023"]);
+        let metadata = extract_metadata_impl(text, false);
+        assert_eq!(dates, raws(&metadata.dates, |d| &d.raw));
+    }
+
+    #[test]
+    fn test_matchers_tickers() {
+        let text = "Apple (NASDAQ:AAPL) and NYSE: MSFT traded with IBM and AAPL; THE CEO spoke.";
+        let tickers = Matchers::compiled().tickers(text);
+        assert_eq!(tickers, vec!["AAPL", "IBM", "MSFT"]);
+        assert_eq!(
+            tickers,
+            extract_metadata_impl(text, false).potential_tickers
+        );
+    }
+
+    const CODE_DOC: &str = "Install the  \u{201C}client\u{201D} first.\n\n\
+        ```python\n\
+        def greet(name):\n\
+        \x20   msg = \u{201C}hi\u{201D}  \u{2014}  name\n\
+        \n\
+        \n\
+        \x20   return msg\n\
+        ```\n\
+        Then   run it.";
+    const CODE_BLOCK: &str = "```python\ndef greet(name):\n    msg = \u{201C}hi\u{201D}  \u{2014}  name\n\n\n    return msg\n```";
+
+    #[test]
+    fn test_clean_text_preserves_code_blocks() {
+        let options = CleanOptions {
+            preserve_code_blocks: true,
+            ..CleanOptions::default()
+        };
+        let cleaned = clean_text_with_options(CODE_DOC, &options);
+        assert_eq!(
+            cleaned,
+            format!("Install the \"client\" first.\n\n{CODE_BLOCK}\n\nThen run it.")
+        );
+        // Without the flag the block is flattened like any other text
+        let flattened = clean_text_with_options(CODE_DOC, &CleanOptions::default());
+        assert!(!flattened.contains(CODE_BLOCK));
+    }
+
+    #[test]
+    fn test_chunk_text_keeps_code_blocks_whole() {
+        let text = format!(
+            "{}\n{CODE_BLOCK}\nAfter the block. Done here.",
+            "Some words here. ".repeat(3)
+        );
+        for target in [20, 60, 200] {
+            let chunks =
+                chunk_text_with_overlap(&text, target, Overlap::Size(10), true, SizeUnit::Chars)
+                    .unwrap();
+            let with_code: Vec<&String> = chunks.iter().filter(|c| c.contains("```")).collect();
+            assert_eq!(with_code.len(), 1, "target={target}: {chunks:?}");
+            assert!(with_code[0].contains(CODE_BLOCK));
+            for chunk in chunks.iter().filter(|c| !c.contains("```")) {
+                assert!(char_len(chunk) <= target);
+            }
+        }
+        let chunks =
+            chunk_text_with_overlap(&text, 200, Overlap::Size(0), true, SizeUnit::Chars).unwrap();
+        assert_eq!(
+            chunks,
+            vec![format!(
+                "Some words here. Some words here. Some words here.\n\n{CODE_BLOCK}\n\nAfter the block. Done here."
+            )]
+        );
+    }
+
+    #[test]
+    fn test_chunk_text_hard_splits_oversized_sentence() {
+        let sentence = format!("{}end.", "revenue grew ".repeat(385));
+        assert!(char_len(&sentence) >= 5000);
+        let text = format!("Short intro. {sentence} Short outro.");
+        let chunks = chunk_text_impl(&text, 1000, 200).unwrap();
+        assert!(chunks.len() > 5);
+        for chunk in &chunks {
+            assert!(
+                char_len(chunk) <= 1000,
+                "chunk of {} chars",
+                char_len(chunk)
+            );
+            // Windows break between words
+            assert!(!chunk.starts_with("ew") && !chunk.ends_with("reven"));
+        }
+        assert!(chunks[0].starts_with("Short intro."));
+        assert!(chunks.last().unwrap().ends_with("Short outro."));
+    }
+
+    #[test]
+    fn test_split_by_chars_breaks_long_words() {
+        let word = "x".repeat(25);
+        assert_eq!(
+            split_by_chars(&format!("ab {word} cd"), 10),
+            vec!["ab", "xxxxxxxxxx", "xxxxxxxxxx", "xxxxx cd"]
+        );
+        assert_eq!(split_by_chars("   ", 2), Vec::<String>::new());
+    }
+
+    const FLAG: &str = "\u{1F1E9}\u{1F1EA}";
+    const FAMILY: &str = "\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}\u{200D}\u{1F466}";
+
+    /// Chunks of `text` that are cut only between grapheme clusters: splitting
+    /// inside one would count it once whole but twice in halves.
+    fn assert_cuts_between_clusters(chunks: &[String], target: usize) {
+        let joined = chunks.concat();
+        let pieces: usize = chunks.iter().map(|c| char_len(c)).sum();
+        assert_eq!(char_len(&joined), pieces, "{chunks:?}");
+        assert!(chunks.iter().all(|c| char_len(c) <= target), "{chunks:?}");
+    }
+
+    #[test]
+    fn test_split_by_chars_keeps_grapheme_clusters() {
+        for word in [
+            FLAG.repeat(9),
+            FAMILY.repeat(9),
+            "cafe\u{301}".repeat(4),
+            format!("{FLAG}a{FAMILY}e\u{301}").repeat(3),
+        ] {
+            for target in 1..=4 {
+                assert_cuts_between_clusters(&split_by_chars(&word, target), target);
+            }
+        }
+        assert_eq!(
+            split_by_chars(&FLAG.repeat(3), 2),
+            vec![FLAG.repeat(2), FLAG.to_string()]
+        );
+    }
+
+    #[test]
+    fn test_chunk_text_never_splits_grapheme_clusters() {
+        let text = format!(
+            "Families {} flags {} and a cafe\u{301}{} sentence.",
+            FAMILY.repeat(12),
+            FLAG.repeat(12),
+            "e\u{301}".repeat(12)
+        );
+        let chunks = chunk_text_impl(&text, 5, 0).unwrap();
+        assert_cuts_between_clusters(&chunks, 5);
+        assert!(chunks
+            .iter()
+            .all(|c| !c.starts_with(['\u{200D}', '\u{301}'])));
+    }
+
+    #[test]
+    fn test_process_document_char_count_is_graphemes() {
+        let text = format!("The {FAMILY} met in the cafe\u{301} under a {FLAG}.");
+        // ZWJ sequences only survive cleaning with keep_joiners
+        let config = ProcessConfig {
+            clean_options: CleanOptions {
+                keep_joiners: true,
+                ..CleanOptions::default()
+            },
+            ..ProcessConfig::default()
+        };
+        let chunks = process_document_impl(&text, &config).unwrap();
+        assert!(chunks[0].text.contains(FAMILY) && chunks[0].text.contains(FLAG));
+        assert_eq!(chunks[0].char_count, 32);
+    }
+
+    #[test]
+    fn test_resolve_overlap() {
+        let chars = Overlap::Size;
+        assert_eq!(
+            resolve_overlap(1500, None, None, None),
+            Ok(chars(DEFAULT_OVERLAP))
+        );
+        assert_eq!(
+            resolve_overlap(1500, Some(OverlapArg::Size(50)), None, None),
+            Ok(chars(50))
+        );
+        assert_eq!(resolve_overlap(1000, None, Some(0.1), None), Ok(chars(100)));
+        assert_eq!(resolve_overlap(1000, None, Some(0.0), None), Ok(chars(0)));
+        assert!(resolve_overlap(1000, None, Some(1.0), None).is_err());
+        assert!(resolve_overlap(1000, None, Some(-0.1), None).is_err());
+        assert!(resolve_overlap(1000, None, Some(f64::NAN), None).is_err());
+        assert!(resolve_overlap(1000, Some(OverlapArg::Size(100)), Some(0.1), None).is_err());
+        assert_eq!(
+            resolve_overlap(1000, None, None, Some(2)),
+            Ok(Overlap::Sentences(2))
+        );
+        assert!(resolve_overlap(1000, Some(OverlapArg::Size(100)), None, Some(2)).is_err());
+        assert!(resolve_overlap(1000, None, Some(0.1), Some(2)).is_err());
+    }
+
+    #[test]
+    fn test_check_strict_overlap() {
+        assert_eq!(check_strict_overlap(1500, Overlap::Size(1499)), Ok(()));
+        assert!(check_strict_overlap(1500, Overlap::Size(1500)).is_err());
+        assert!(check_strict_overlap(1500, Overlap::Size(5000)).is_err());
+        assert_eq!(check_strict_overlap(10, Overlap::Sentences(20)), Ok(()));
+        // Without strict_overlap, the same overlap is clamped instead
+        let text = "One two three. Four five six. Seven eight nine.";
+        assert_eq!(chunk_text_impl(text, 20, 20), chunk_text_impl(text, 20, 19));
+    }
+
+    #[test]
+    fn test_overlap_arg_fraction_of_target() {
+        assert_eq!(OverlapArg::Size(300).resolve(2000), Ok(300));
+        assert_eq!(OverlapArg::Fraction(0.15).resolve(2000), Ok(300));
+        assert_eq!(OverlapArg::Fraction(0.0).resolve(2000), Ok(0));
+        assert!(OverlapArg::Fraction(1.0).resolve(2000).is_err());
+        assert!(OverlapArg::Fraction(1.5).resolve(2000).is_err());
+        assert!(OverlapArg::Fraction(-0.1).resolve(2000).is_err());
+        assert!(OverlapArg::Fraction(f64::NAN).resolve(2000).is_err());
+        assert_eq!(
+            resolve_overlap(2000, Some(OverlapArg::Fraction(0.15)), None, None),
+            Ok(Overlap::Size(300))
+        );
+        assert!(resolve_overlap(2000, Some(OverlapArg::Fraction(0.15)), Some(0.1), None).is_err());
+    }
+
+    #[test]
+    fn test_monetary_amounts_parsed() {
+        let text = "Revenue was $1,250 million, costs EUR 3.5B and a fee of $12.50.";
+        let parsed = extract_metadata_impl(text, false).monetary_amounts;
+        let summary: Vec<(Option<f64>, Option<&str>, Option<&str>)> = parsed
+            .iter()
+            .map(|p| (p.value, p.currency.as_deref(), p.scale))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (Some(1_250_000_000.0), Some("USD"), Some("million")),
+                (Some(3.5e9), Some("EUR"), Some("billion")),
+                (Some(12.5), Some("USD"), None),
+            ]
+        );
+        assert_eq!(
+            parsed.len(),
+            extract_metadata_impl(text, false).monetary_amounts.len()
+        );
+    }
+
+    #[test]
+    fn test_monetary_amounts_decimals() {
+        let text = "Sales of $1.5 million, a $2.375B deal, a $5M grant, a $.50 fee, \
+                    $1,234.56 in cash and $0.8M in other income. It cost $12.";
+        let metadata = extract_metadata_impl(text, false);
+        assert_eq!(
+            raws(&metadata.monetary_amounts, |m| &m.raw),
+            vec![
+                "$1.5 million",
+                "$2.375B",
+                "$5M",
+                "$.50",
+                "$1,234.56",
+                "$0.8M",
+                "$12"
+            ]
+        );
+        let values: Vec<Option<f64>> = metadata.monetary_amounts.iter().map(|m| m.value).collect();
+        assert_eq!(
+            values,
+            vec![
+                Some(1.5e6),
+                Some(2.375e9),
+                Some(5e6),
+                Some(0.5),
+                Some(1234.56),
+                Some(0.8e6),
+                Some(12.0)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_monetary_amounts_income_statement_negatives() {
+        let text = "Operating income was $4.2 million, up from $(1.3) million a year earlier. \
+                    Other expense, net was ($0.8 million) and FX losses of -$150k offset a \
+                    $2.1M gain on the sale of assets. Gross margin improved 2.5% while \
+                    operating margin was (1.2)% and EPS growth was (4%). Guidance is \
+                    $4-$6 million.";
+        let metadata = extract_metadata_impl(text, false);
+        let summary: Vec<(&str, Option<f64>, Option<&str>)> = metadata
+            .monetary_amounts
+            .iter()
+            .map(|p| (p.raw.as_str(), p.value, p.currency.as_deref()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("$4.2 million", Some(4.2e6), Some("USD")),
+                ("$(1.3) million", Some(-1.3e6), Some("USD")),
+                ("($0.8 million)", Some(-0.8e6), Some("USD")),
+                ("-$150k", Some(-150_000.0), Some("USD")),
+                ("$2.1M", Some(2.1e6), Some("USD")),
+                ("$4", Some(4.0), Some("USD")),
+                ("$6 million", Some(6e6), Some("USD")),
+            ]
+        );
+        assert_eq!(
+            raws(&metadata.percentages, |p| &p.raw),
+            vec!["2.5%", "(1.2)%", "(4%)"]
+        );
+        let values: Vec<Option<f64>> = metadata.percentages.iter().map(|p| p.value).collect();
+        assert_eq!(values, vec![Some(2.5), Some(-1.2), Some(-4.0)]);
+    }
+
+    #[test]
+    fn test_ranges_before_and_after_cleaning() {
+        let text = "FY2025 guidance: revenue of $5.0 to $5.2 billion, capex of $5–7 million \
+                    and margins between 3% and 4%, with spreads 40—60 bps wider.";
+        let bounds = |text: &str| -> Vec<(&'static str, Option<f64>, Option<f64>)> {
+            extract_metadata_impl(text, false)
+                .ranges
+                .iter()
+                .map(|r| (r.kind, r.low, r.high))
+                .collect()
+        };
+        let expected = vec![
+            ("money", Some(5.0e9), Some(5.2e9)),
+            ("money", Some(5e6), Some(7e6)),
+            ("percentage", Some(3.0), Some(4.0)),
+            ("percentage", Some(0.4), Some(0.6)),
+        ];
+        assert_eq!(bounds(text), expected);
+        assert_eq!(bounds(&clean_text(text)), expected);
+    }
+
+    #[test]
+    fn test_monetary_amounts_european_report() {
+        let text = "Der Umsatz stieg auf 5,2 Mio. € (Vorjahr: 4,8 Mio. €). The US subsidiary \
+                    contributed $310 million. Net debt fell to 2,1 Mrd. EUR; the UK unit sold \
+                    assets for £30m and paid 12 million pounds sterling in fees. Swiss costs \
+                    were 300 CHF per unit, or 45 million dollars overall. Headcount was 2024 \
+                    in 2024.";
+        let metadata = extract_metadata_impl(text, false);
+        let summary: Vec<(&str, Option<f64>, Option<&str>)> = metadata
+            .monetary_amounts
+            .iter()
+            .map(|p| (p.raw.as_str(), p.value, p.currency.as_deref()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("5,2 Mio. €", Some(5_200_000.0), Some("EUR")),
+                ("4,8 Mio. €", Some(4_800_000.0), Some("EUR")),
+                ("$310 million", Some(310e6), Some("USD")),
+                ("2,1 Mrd. EUR", Some(2.1e9), Some("EUR")),
+                ("£30m", Some(30e6), Some("GBP")),
+                ("12 million pounds sterling", Some(12e6), Some("GBP")),
+                ("300 CHF", Some(300.0), Some("CHF")),
+                ("45 million dollars", Some(45e6), Some("USD")),
+            ]
+        );
+        let currencies: Vec<&str> = metadata
+            .monetary_amounts
+            .iter()
+            .filter_map(|m| m.currency.as_deref())
+            .collect();
+        assert_eq!(
+            currencies,
+            vec!["EUR", "EUR", "USD", "EUR", "GBP", "GBP", "CHF", "USD"]
+        );
+    }
+
+    #[test]
+    fn test_chunk_text_overlap_sentences() {
+        let sentences: Vec<String> = (0..40)
+            .map(|i| format!("Sentence {i} is{} here.", " long".repeat(i % 5)))
+            .collect();
+        let text = sentences.join(" ");
+        for n in [0, 1, 2, 3] {
+            let chunks =
+                chunk_text_with_overlap(&text, 200, Overlap::Sentences(n), false, SizeUnit::Chars)
+                    .unwrap();
+            assert!(chunks.len() > 3);
+            for pair in chunks.windows(2) {
+                let prev = split_sentences_preserve_punct(&pair[0]);
+                let next = split_sentences_preserve_punct(&pair[1]);
+                assert_eq!(next[..n], prev[prev.len() - n..], "n={n}");
+                // The sentence after the overlap is new
+                assert!(!prev.contains(&next[n]));
+            }
+        }
+    }
+
+    #[test]
+    fn test_overlap_respects_paragraphs() {
+        let text = "Alpha one here. Alpha two here. Alpha three here.\n\n\
+                    Beta one here. Beta two here.";
+        let chunk = |respect_paragraphs, overlap| {
+            let units = UnitOptions {
+                respect_paragraphs,
+                ..UnitOptions::default()
+            };
+            chunk_text_with_min_size(text, 40, overlap, &units, SizeUnit::Chars, 0, false).unwrap()
+        };
+        // Overlap reaches back across the blank line by default
+        assert_eq!(
+            chunk(false, Overlap::Sentences(2)),
+            vec![
+                "Alpha one here. Alpha two here.",
+                "Alpha two here. Alpha three here.",
+                "Alpha three here. Beta one here.",
+                "Beta one here. Beta two here.",
+            ]
+        );
+        assert_eq!(
+            chunk(true, Overlap::Sentences(2)),
+            vec![
+                "Alpha one here. Alpha two here.",
+                "Alpha two here. Alpha three here.",
+                "Beta one here. Beta two here.",
+            ]
+        );
+        for chunk in chunk(true, Overlap::Size(20)) {
+            assert!(
+                !(chunk.contains("Alpha") && chunk.contains("Beta")),
+                "{chunk}"
+            );
+        }
+        // A paragraph break within a chunk is kept as a space, as before
+        assert_eq!(
+            chunk(true, Overlap::Size(0))[0],
+            "Alpha one here. Alpha two here."
+        );
+    }
+
+    #[test]
+    fn test_min_chunk_size_merges_tiny_tail() {
+        let text =
+            "Revenue grew strongly this quarter. Margins improved across every region. Thanks.";
+        let chunks =
+            chunk_text_with_overlap(text, 80, Overlap::Sentences(0), false, SizeUnit::Chars)
+                .unwrap();
+        assert_eq!(chunks.last().unwrap(), "Thanks.");
+
+        let merged = chunk_text_with_min_size(
+            text,
+            80,
+            Overlap::Sentences(0),
+            &UnitOptions::default(),
+            SizeUnit::Chars,
+            20,
+            false,
+        )
+        .unwrap();
+        assert_eq!(merged, vec![text]);
+        assert!(merged.iter().all(|c| char_len(c) >= 20));
+
+        // The overlap the tail carried is not repeated in the merged chunk
+        let with_overlap = chunk_text_impl(text, 80, 40).unwrap();
+        assert_eq!(with_overlap.len(), 2);
+        assert_eq!(
+            with_overlap.last().unwrap(),
+            "Margins improved across every region. Thanks."
+        );
+        let merged = chunk_text_with_min_size(
+            text,
+            80,
+            Overlap::Size(40),
+            &UnitOptions::default(),
+            SizeUnit::Chars,
+            50,
+            false,
+        )
+        .unwrap();
+        assert_eq!(merged, vec![text]);
+
+        // Below the threshold nothing changes
+        let unmerged = chunk_text_with_min_size(
+            text,
+            80,
+            Overlap::Sentences(0),
+            &UnitOptions::default(),
+            SizeUnit::Chars,
+            7,
+            false,
+        )
+        .unwrap();
+        assert_eq!(unmerged, chunks);
+    }
+
+    #[test]
+    fn test_min_chunk_size_absorbs_forty_char_tail() {
+        let text = "Revenue grew twelve percent to five million. \
+                    Margins widened in every operating region. \
+                    Questions were taken from four analysts.";
+        let chunks =
+            chunk_text_with_overlap(text, 100, Overlap::Sentences(0), false, SizeUnit::Chars)
+                .unwrap();
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(char_len(&chunks[1]), 40);
+
+        let merged = chunk_text_with_min_size(
+            text,
+            100,
+            Overlap::Sentences(0),
+            &UnitOptions::default(),
+            SizeUnit::Chars,
+            60,
+            false,
+        )
+        .unwrap();
+        // Slightly over target_size rather than a 40-character chunk
+        assert_eq!(merged, vec![text]);
+        assert_eq!(char_len(&merged[0]), 128);
+    }
+
+    #[test]
+    fn test_min_chunk_size_merge_all_small() {
+        let text = "Alpha beta gamma delta eps. Hi there. Zeta eta theta iota kappa.";
+        let chunks =
+            chunk_text_with_overlap(text, 30, Overlap::Sentences(0), false, SizeUnit::Chars)
+                .unwrap();
+        assert_eq!(
+            chunks,
+            vec![
+                "Alpha beta gamma delta eps.",
+                "Hi there.",
+                "Zeta eta theta iota kappa."
+            ]
+        );
+        let tail_only = chunk_text_with_min_size(
+            text,
+            30,
+            Overlap::Sentences(0),
+            &UnitOptions::default(),
+            SizeUnit::Chars,
+            10,
+            false,
+        );
+        assert_eq!(tail_only.unwrap(), chunks);
+        let all = chunk_text_with_min_size(
+            text,
+            30,
+            Overlap::Sentences(0),
+            &UnitOptions::default(),
+            SizeUnit::Chars,
+            10,
+            true,
+        )
+        .unwrap();
+        assert_eq!(
+            all,
+            vec![
+                "Alpha beta gamma delta eps. Hi there.",
+                "Zeta eta theta iota kappa."
+            ]
+        );
+    }
+
+    #[test]
+    fn test_min_chunk_size_keeps_code_block_separator() {
+        let text = "Intro sentence here.\n\n```\ncode\n```\n\nOk.";
+        let chunks =
+            chunk_text_with_overlap(text, 36, Overlap::Sentences(0), true, SizeUnit::Chars)
+                .unwrap();
+        assert_eq!(
+            chunks,
+            vec!["Intro sentence here.\n\n```\ncode\n```", "Ok."]
+        );
+        let merged = chunk_text_with_min_size(
+            text,
+            36,
+            Overlap::Sentences(0),
+            &UnitOptions {
+                preserve_code_blocks: true,
+                ..UnitOptions::default()
+            },
+            SizeUnit::Chars,
+            5,
+            false,
+        )
+        .unwrap();
+        assert_eq!(merged, vec![text]);
+    }
+
+    #[test]
+    fn test_split_sentence_units_marks_line_breaks() {
+        let units = split_sentence_units("One. Two.\nThree.\n\nFour.  \n  Five");
+        let flags: Vec<(&str, bool)> = units
+            .iter()
+            .map(|u| (u.text.as_str(), u.after_line_break))
+            .collect();
+        assert_eq!(
+            flags,
+            vec![
+                ("One.", false),
+                ("Two.", false),
+                ("Three.", true),
+                ("Four.", false),
+                ("Five", true),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_custom_boundary_pattern_splits_on_semicolons() {
+        let boundary = compile_boundary_pattern(r";\s*").unwrap();
+        let text = "Seller delivers the goods; Buyer pays within 30 days; \
+                    either party may terminate. Notices go in writing";
+        let units = UnitOptions {
+            boundary: Some(&boundary),
+            ..UnitOptions::default()
+        };
+        let sentences: Vec<String> = split_sentence_units_at(text, &units)
+            .into_iter()
+            .map(|u| u.text)
+            .collect();
+        assert_eq!(
+            sentences,
+            vec![
+                "Seller delivers the goods;",
+                "Buyer pays within 30 days;",
+                "either party may terminate. Notices go in writing",
+            ]
+        );
+
+        let chunks = chunk_text_with_min_size(
+            text,
+            60,
+            Overlap::Size(0),
+            &UnitOptions {
+                boundary: Some(&boundary),
+                ..UnitOptions::default()
+            },
+            SizeUnit::Chars,
+            0,
+            false,
+        )
+        .unwrap();
+        assert_eq!(
+            chunks,
+            vec![
+                "Seller delivers the goods; Buyer pays within 30 days;",
+                "either party may terminate. Notices go in writing",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_custom_boundary_pattern_starts_sentences_with_markers() {
+        let boundary = compile_boundary_pattern(r"\s+\([a-z]\)").unwrap();
+        let text = "The Licensee shall (a) keep records, (b) allow audits and (c) pay fees.";
+        let units = UnitOptions {
+            boundary: Some(&boundary),
+            ..UnitOptions::default()
+        };
+        let sentences: Vec<String> = split_sentence_units_at(text, &units)
+            .into_iter()
+            .map(|u| u.text)
+            .collect();
+        assert_eq!(
+            sentences,
+            vec![
+                "The Licensee shall",
+                "(a) keep records,",
+                "(b) allow audits and",
+                "(c) pay fees.",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_compile_boundary_pattern_errors() {
+        let err = compile_boundary_pattern("(").unwrap_err();
+        assert!(err.starts_with("invalid boundary_pattern"), "{err}");
+        let err = compile_boundary_pattern(r";?").unwrap_err();
+        assert!(err.contains("matches empty text"), "{err}");
+    }
+
+    #[test]
+    fn test_chunks_keep_line_breaks_from_preserved_newlines() {
+        let keep = CleanOptions {
+            preserve_single_newlines: true,
+            ..CleanOptions::default()
+        };
+        let cleaned = clean_text_with_options(
+            "Ship to:\n  Acme Corp  \n1 Main   St\nSpringfield, IL 62704\n\n\n\
+             Thanks. Please call.\nBye now.",
+            &keep,
+        );
+        assert_eq!(
+            cleaned,
+            "Ship to:\nAcme Corp\n1 Main St\nSpringfield, IL 62704\n\nThanks. Please call.\nBye now."
+        );
+        assert_eq!(
+            chunk_text_impl(&cleaned, 1000, 0).unwrap(),
+            vec!["Ship to:\nAcme Corp\n1 Main St\nSpringfield, IL 62704\n\nThanks. Please call.\nBye now."]
+        );
+        // The newline counts as one separator character, like a space
+        assert_eq!(
+            chunk_text_with_overlap(
+                "Please call.\nBye now.",
+                13,
+                Overlap::Sentences(0),
+                false,
+                SizeUnit::Chars
+            )
+            .unwrap(),
+            vec!["Please call.", "Bye now."]
+        );
+        let merged = chunk_text_with_min_size(
+            "Please call.\nBye now.",
+            13,
+            Overlap::Sentences(0),
+            &UnitOptions::default(),
+            SizeUnit::Chars,
+            10,
+            false,
+        )
+        .unwrap();
+        assert_eq!(merged, vec!["Please call.\nBye now."]);
+    }
+
+    /// Three sentences of 3, 4 and 2 words.
+    const WORD_SENTENCES: &str = "Alpha beta gamma. Delta epsilon zeta eta. Theta iota.";
+
+    #[test]
+    fn test_word_count_treats_hyphenated_words_and_numbers_as_one() {
+        assert_eq!(
+            word_count("Year-over-year revenue rose 12.5% to $1,234.5 million"),
+            7
+        );
+        assert_eq!(word_count("  \n "), 0);
+    }
+
+    #[test]
+    fn test_chunk_in_words() {
+        let words = |target, overlap| {
+            chunk_text_with_overlap(WORD_SENTENCES, target, overlap, false, SizeUnit::Words)
+                .unwrap()
+        };
+        assert_eq!(
+            words(7, Overlap::Sentences(0)),
+            vec!["Alpha beta gamma. Delta epsilon zeta eta.", "Theta iota."]
+        );
+        assert_eq!(
+            words(7, Overlap::Size(5)),
+            vec![
+                "Alpha beta gamma. Delta epsilon zeta eta.",
+                "Delta epsilon zeta eta. Theta iota.",
+            ]
+        );
+        let merged = chunk_text_with_min_size(
+            WORD_SENTENCES,
+            7,
+            Overlap::Sentences(0),
+            &UnitOptions::default(),
+            SizeUnit::Words,
+            3,
+            false,
+        )
+        .unwrap();
+        assert_eq!(merged, vec![WORD_SENTENCES]);
+    }
+
+    #[test]
+    fn test_chunk_in_words_splits_long_sentence_between_words() {
+        let text = "One two three four five six seven eight nine ten eleven twelve.";
+        assert_eq!(
+            chunk_text_with_overlap(text, 5, Overlap::Sentences(0), false, SizeUnit::Words)
+                .unwrap(),
+            vec![
+                "One two three four five",
+                "six seven eight nine ten",
+                "eleven twelve."
+            ]
+        );
+        assert_eq!(
+            chunk_text_with_overlap(
+                "no punctuation at all here",
+                2,
+                Overlap::Size(0),
+                false,
+                SizeUnit::Words
+            )
+            .unwrap(),
+            vec!["no punctuation", "at all", "here"]
+        );
     }
 }
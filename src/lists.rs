@@ -0,0 +1,115 @@
+//! Bulleted and numbered lists, kept one item per line by
+//! `CleanOptions(preserve_lists=True)` and packed as units of their own by
+//! `chunk_text(preserve_lists=True)`.
+//!
+//! An item is a line starting with "-", "*" or "•", or with a number followed
+//! by "." or ")" ("1.", "12)"), then whitespace and the item text. Indented
+//! lines right after it continue the item, as when a long item was wrapped;
+//! any other line ends it.
+
+use regex::Regex;
+use std::sync::LazyLock;
+
+// A list marker at the start of a line, followed by whitespace and the item
+// text; ordered items have at most three digits, so "2024. Revenue" is prose
+static LIST_ITEM_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^[ \t]*(?:[-*•]|\d{1,3}[.)])[ \t]+\S").unwrap());
+
+/// Whether `line` starts a list item.
+pub(crate) fn is_list_item(line: &str) -> bool {
+    LIST_ITEM_RE.is_match(line)
+}
+
+/// Whether `line` continues the list item above it.
+fn is_continuation(line: &str) -> bool {
+    line.starts_with([' ', '\t']) && !line.trim().is_empty() && !is_list_item(line)
+}
+
+/// A piece of text cut by `split_list_items`.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Block<'a> {
+    Prose(&'a str),
+    /// An item with its continuation lines and trailing line break.
+    Item(&'a str),
+}
+
+/// Cut text into list items and the prose between them, in order; the
+/// blocks concatenated give back `text`.
+pub(crate) fn split_list_items(text: &str) -> Vec<Block<'_>> {
+    let mut blocks = Vec::new();
+    let mut block_start = 0;
+    let mut in_item = false;
+    let mut offset = 0;
+    for line in text.split_inclusive('\n') {
+        let starts_item = is_list_item(line);
+        if starts_item || (in_item && !is_continuation(line)) {
+            if offset > block_start {
+                let block = &text[block_start..offset];
+                blocks.push(if in_item {
+                    Block::Item(block)
+                } else {
+                    Block::Prose(block)
+                });
+            }
+            block_start = offset;
+            in_item = starts_item;
+        }
+        offset += line.len();
+    }
+    if offset > block_start {
+        let block = &text[block_start..];
+        blocks.push(if in_item {
+            Block::Item(block)
+        } else {
+            Block::Prose(block)
+        });
+    }
+    blocks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_list_markers() {
+        for line in [
+            "- Revenue",
+            "* Costs",
+            "• Margins",
+            "1. First",
+            "12) Twelfth",
+            "  - nested",
+        ] {
+            assert!(is_list_item(line), "{line}");
+        }
+        for line in [
+            "-5% decline",
+            "2024. Revenue rose",
+            "*emphasis*",
+            "1.5 million",
+            "Plain",
+        ] {
+            assert!(!is_list_item(line), "{line}");
+        }
+    }
+
+    #[test]
+    fn test_split_list_items() {
+        let text = "Highlights:\n- Revenue rose\n  across regions\n- Costs fell\nOverall strong.";
+        assert_eq!(
+            split_list_items(text),
+            vec![
+                Block::Prose("Highlights:\n"),
+                Block::Item("- Revenue rose\n  across regions\n"),
+                Block::Item("- Costs fell\n"),
+                Block::Prose("Overall strong."),
+            ]
+        );
+        assert_eq!(
+            split_list_items("No list here."),
+            vec![Block::Prose("No list here.")]
+        );
+        assert!(split_list_items("").is_empty());
+    }
+}
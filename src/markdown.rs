@@ -0,0 +1,496 @@
+//! Markdown-aware chunking, backing `chunk_markdown`, and Markdown syntax
+//! stripping for `CleanOptions(strip_markdown=True)`.
+//!
+//! The document is cut into sections at heading lines, each section is packed
+//! with the regular sentence-based chunker, and every chunk is prefixed with the
+//! trail of headings it sits under ("## Revenue > ### North America"). Chunks
+//! never span two sections, and a heading is always kept whole.
+//!
+//! Stripping is line based rather than a full CommonMark parser: it handles
+//! headings, emphasis, links, images, inline code, lists, block quotes and
+//! thematic breaks, and leaves fenced code blocks alone.
+
+use crate::{char_len, chunk_text_impl};
+use regex::{Captures, Regex};
+use std::sync::LazyLock;
+
+// An inline code span, whose contents are kept verbatim
+static INLINE_CODE_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"`([^`\n]+)`").unwrap());
+// An image or inline link: the alt or anchor text, then a URL that may hold
+// one level of parentheses and be followed by a title
+static LINK_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"!?\[([^\]\n]*)\]\((?:[^()\s]|\([^()\s]*\))*(?:\s+(?:"[^"]*"|'[^']*'))?\)"#)
+        .unwrap()
+});
+// An autolink ("<https://example.com>")
+static AUTOLINK_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"<((?:https?|ftp|mailto):[^<>\s]+)>").unwrap());
+// Strong, emphasis or strikethrough; the text may not start or end with a space
+static EMPHASIS_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(concat!(
+        r"\*\*([^*\s](?:[^*\n]*?[^*\s])?)\*\*|__([^_\s](?:[^_\n]*?[^_\s])?)__",
+        r"|~~([^~\s](?:[^~\n]*?[^~\s])?)~~",
+        r"|\*([^*\s](?:[^*\n]*?[^*\s])?)\*|_([^_\s](?:[^_\n]*?[^_\s])?)_",
+    ))
+    .unwrap()
+});
+// A backslash-escaped ASCII punctuation character
+static ESCAPE_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\\([!-/:-@\[-`{-~])").unwrap());
+// A list item marker: a bullet, or a number of up to three digits (so a line
+// starting with a year like "2023." is left alone)
+static LIST_ITEM_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(?:[-*+]|\d{1,3}[.)])[ \t]+").unwrap());
+
+/// Joins the headings of a trail.
+const TRAIL_SEPARATOR: &str = " > ";
+
+/// Separates the heading trail from the chunk text.
+const PREFIX_SEPARATOR: &str = "\n\n";
+
+/// Text between two headings, with the headings it is nested under.
+#[derive(Debug, PartialEq)]
+struct Section {
+    /// Heading trail, outermost first; empty for text before the first heading.
+    trail: String,
+    body: String,
+}
+
+/// Heading level (1-6) of an ATX heading line like "## Revenue", or None.
+/// A heading needs whitespace after its `#`s, so "#hashtag" is body text.
+fn heading_level(line: &str) -> Option<usize> {
+    let level = line.bytes().take_while(|&b| b == b'#').count();
+    let rest = &line[level..];
+    let is_heading = (1..=6).contains(&level) && rest.starts_with(char::is_whitespace);
+    is_heading.then_some(level)
+}
+
+/// Whether `line` opens or closes a fenced code block.
+fn is_fence(line: &str) -> bool {
+    let line = line.trim_start();
+    line.starts_with("```") || line.starts_with("~~~")
+}
+
+/// A piece of a document: prose, or a fenced code block from its opening fence
+/// line through its closing fence (without the newline after it).
+#[derive(Debug, PartialEq)]
+pub(crate) enum Block<'a> {
+    Prose(&'a str),
+    Code(&'a str),
+}
+
+/// Whether `unit` is a whole fenced code block as produced by `split_code_blocks`.
+pub(crate) fn is_code_block(unit: &str) -> bool {
+    is_fence(unit) && unit.contains('\n')
+}
+
+/// Split `text` into prose and fenced code blocks. A block opened with ``` or
+/// ~~~ is closed by the next fence line using the same character; an unclosed
+/// block runs to the end of the text.
+pub(crate) fn split_code_blocks(text: &str) -> Vec<Block<'_>> {
+    let mut blocks = Vec::new();
+    let mut prose_start = 0;
+    // Byte offset and fence character of the open block, if any
+    let mut open: Option<(usize, char)> = None;
+    let mut offset = 0;
+
+    for line in text.split_inclusive('\n') {
+        let line_start = offset;
+        offset += line.len();
+        if !is_fence(line) {
+            continue;
+        }
+        let fence_char = line.trim_start().chars().next().unwrap_or('`');
+        match open {
+            None => {
+                if prose_start < line_start {
+                    blocks.push(Block::Prose(&text[prose_start..line_start]));
+                }
+                open = Some((line_start, fence_char));
+            }
+            Some((start, c)) if c == fence_char => {
+                let end = line_start + line.trim_end_matches(['\n', '\r']).len();
+                blocks.push(Block::Code(&text[start..end]));
+                prose_start = end;
+                open = None;
+            }
+            Some(_) => {}
+        }
+    }
+
+    match open {
+        Some((start, _)) => blocks.push(Block::Code(&text[start..])),
+        None if prose_start < text.len() => blocks.push(Block::Prose(&text[prose_start..])),
+        None => {}
+    }
+    blocks
+}
+
+/// Cut `text` into sections at heading lines outside fenced code blocks.
+fn split_sections(text: &str) -> Vec<Section> {
+    let mut sections = vec![Section {
+        trail: String::new(),
+        body: String::new(),
+    }];
+    // (level, heading line) for each heading enclosing the current position
+    let mut headings: Vec<(usize, &str)> = Vec::new();
+    let mut in_fence = false;
+
+    for line in text.split_inclusive('\n') {
+        if is_fence(line) {
+            in_fence = !in_fence;
+        }
+        let level = if in_fence { None } else { heading_level(line) };
+        let Some(level) = level else {
+            if let Some(section) = sections.last_mut() {
+                section.body.push_str(line);
+            }
+            continue;
+        };
+
+        headings.retain(|&(l, _)| l < level);
+        headings.push((level, line.trim()));
+        sections.push(Section {
+            trail: headings
+                .iter()
+                .map(|&(_, heading)| heading)
+                .collect::<Vec<_>>()
+                .join(TRAIL_SEPARATOR),
+            body: String::new(),
+        });
+    }
+
+    sections
+}
+
+/// Chunk Markdown section by section, prefixing each chunk with its heading trail.
+///
+/// The trail counts toward `target_size`; only a trail longer than half of it
+/// can push a chunk past `target_size`, since the section text always keeps at
+/// least half. Sections with no text of their own (a heading directly followed
+/// by a subheading) produce no chunks. Text without headings is chunked exactly
+/// like `chunk_text_impl`. Returns None if `target_size` is 0.
+pub fn chunk_markdown_impl(text: &str, target_size: usize, overlap: usize) -> Option<Vec<String>> {
+    if target_size == 0 {
+        return None;
+    }
+    let sections = split_sections(text);
+    if sections.len() == 1 {
+        return chunk_text_impl(text, target_size, overlap);
+    }
+
+    let mut chunks = Vec::new();
+    for section in sections {
+        let body = section.body.trim();
+        if body.is_empty() {
+            continue;
+        }
+        if section.trail.is_empty() {
+            chunks.extend(chunk_text_impl(body, target_size, overlap)?);
+            continue;
+        }
+        let prefix_chars = char_len(&section.trail) + PREFIX_SEPARATOR.len();
+        let budget = target_size
+            .saturating_sub(prefix_chars)
+            .max(target_size / 2)
+            .max(1);
+        chunks.extend(
+            chunk_text_impl(body, budget, overlap)?
+                .into_iter()
+                .map(|chunk| format!("{}{PREFIX_SEPARATOR}{chunk}", section.trail)),
+        );
+    }
+
+    Some(chunks)
+}
+
+/// Remove Markdown syntax from `text`, keeping the text it marks up.
+///
+/// Heading markers are dropped and each heading becomes its own paragraph;
+/// emphasis, strikethrough and inline code lose their markers; links and
+/// images keep only their anchor or alt text; autolinks keep their URL; every
+/// list item starts with "- "; block quote markers, thematic breaks and setext
+/// underlines are removed; backslash escapes are resolved. Fenced code blocks
+/// are copied unchanged, each as its own paragraph. Emphasis markers touching a letter or digit on the
+/// outside ("a*b*c", "snake_case_name") are not emphasis and are kept.
+pub(crate) fn strip_markdown(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for block in split_code_blocks(text) {
+        match block {
+            Block::Prose(prose) => strip_prose(prose, &mut out),
+            Block::Code(code) => {
+                paragraph_break(&mut out);
+                out.push_str(code);
+                // The prose after a block starts with the fence line's newline
+                out.push('\n');
+            }
+        }
+    }
+    out
+}
+
+/// End `out` with a blank line unless it is empty.
+fn paragraph_break(out: &mut String) {
+    while !out.is_empty() && !out.ends_with("\n\n") {
+        out.push('\n');
+    }
+}
+
+/// Whether `line` (without indentation) is a thematic break like "---" or "* * *".
+fn is_thematic_break(line: &str) -> bool {
+    let marks: String = line.chars().filter(|c| !c.is_whitespace()).collect();
+    marks.len() >= 3
+        && ['-', '*', '_']
+            .iter()
+            .any(|&mark| marks.chars().all(|c| c == mark))
+}
+
+/// Strip the block-level syntax of prose lines, appending the result to `out`.
+fn strip_prose(prose: &str, out: &mut String) {
+    // Whether the previous line had text, so a "===" line underlines it
+    let mut previous_has_text = false;
+    // Whether a heading was just written and needs a blank line after it
+    let mut after_heading = false;
+
+    for line in prose.split_inclusive('\n') {
+        let content = line.trim_end_matches(['\n', '\r']);
+        let newline = &line[content.len()..];
+        let mut content = content.trim_start();
+        while let Some(quoted) = content.strip_prefix('>') {
+            content = quoted.trim_start();
+        }
+        if content.trim().is_empty() {
+            out.push_str(newline);
+            previous_has_text = false;
+            after_heading = false;
+            continue;
+        }
+
+        let setext_underline = previous_has_text && content.trim_end().chars().all(|c| c == '=');
+        if setext_underline || is_thematic_break(content) {
+            out.push_str(newline);
+            previous_has_text = false;
+            after_heading = false;
+            continue;
+        }
+
+        if after_heading {
+            paragraph_break(out);
+            after_heading = false;
+        }
+        if let Some(level) = heading_level(content) {
+            paragraph_break(out);
+            out.push_str(&strip_inline(heading_text(&content[level..])));
+            out.push_str(newline);
+            after_heading = true;
+        } else if let Some(marker) = LIST_ITEM_RE.find(content) {
+            out.push_str("- ");
+            out.push_str(&strip_inline(&content[marker.end()..]));
+            out.push_str(newline);
+        } else {
+            out.push_str(&strip_inline(content));
+            out.push_str(newline);
+        }
+        previous_has_text = true;
+    }
+}
+
+/// Text of an ATX heading after its opening `#`s, without an optional closing
+/// run of `#`s ("Revenue ##"); a `#` ending a word ("C#") is kept.
+fn heading_text(rest: &str) -> &str {
+    let rest = rest.trim();
+    let unclosed = rest.trim_end_matches('#');
+    if unclosed.is_empty() || unclosed.ends_with(char::is_whitespace) {
+        unclosed.trim_end()
+    } else {
+        rest
+    }
+}
+
+/// Strip inline syntax from one line, leaving code spans verbatim.
+fn strip_inline(line: &str) -> String {
+    let mut out = String::with_capacity(line.len());
+    let mut last = 0;
+    for caps in INLINE_CODE_RE.captures_iter(line) {
+        let span = caps.get(0).unwrap();
+        out.push_str(&strip_inline_text(&line[last..span.start()]));
+        out.push_str(&caps[1]);
+        last = span.end();
+    }
+    out.push_str(&strip_inline_text(&line[last..]));
+    out
+}
+
+/// Strip links, emphasis and escapes from text without code spans.
+fn strip_inline_text(text: &str) -> String {
+    let text = LINK_RE.replace_all(text, "$1");
+    let mut text = AUTOLINK_RE.replace_all(&text, "$1").into_owned();
+    // Nested emphasis ("**bold _and italic_**") takes one pass per level
+    loop {
+        let stripped = strip_emphasis(&text);
+        if stripped == text {
+            break;
+        }
+        text = stripped;
+    }
+    ESCAPE_RE.replace_all(&text, "$1").into_owned()
+}
+
+/// Remove one level of emphasis markers, keeping those flanked by a letter or
+/// digit on the outside or escaped with a backslash.
+fn strip_emphasis(text: &str) -> String {
+    EMPHASIS_RE
+        .replace_all(text, |caps: &Captures| {
+            let whole = caps.get(0).unwrap();
+            let before = text[..whole.start()].chars().next_back();
+            let after = text[whole.end()..].chars().next();
+            let literal = before.is_some_and(|c| c.is_alphanumeric() || c == '\\')
+                || after.is_some_and(char::is_alphanumeric);
+            if literal {
+                return whole.as_str().to_string();
+            }
+            let inner = caps.iter().skip(1).flatten().next().unwrap();
+            inner.as_str().to_string()
+        })
+        .into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const REPORT: &str = "Acme Corp annual report.\n\n\
+        # Results\n\
+        ## Revenue\n\
+        Revenue grew 12% to $1.2 billion. Growth was broad based.\n\n\
+        ### North America\n\
+        North America rose 15%. Retail led the gains.\n\n\
+        ### Europe\n\
+        Europe was flat.\n\n\
+        ## Costs\n\
+        Costs fell 3%.\n\n\
+        # Outlook\n\
+        We expect further growth.\n";
+
+    #[test]
+    fn test_chunk_markdown_nested_headings() {
+        let chunks = chunk_markdown_impl(REPORT, 1500, 200).unwrap();
+        assert_eq!(
+            chunks,
+            vec![
+                "Acme Corp annual report.",
+                "# Results > ## Revenue\n\nRevenue grew 12% to $1.2 billion. Growth was broad based.",
+                "# Results > ## Revenue > ### North America\n\nNorth America rose 15%. Retail led the gains.",
+                "# Results > ## Revenue > ### Europe\n\nEurope was flat.",
+                "# Results > ## Costs\n\nCosts fell 3%.",
+                "# Outlook\n\nWe expect further growth.",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_chunk_markdown_packs_within_sections() {
+        let body = "This sentence has some words. ".repeat(20);
+        let text = format!("## Long section\n{body}\n## Next\nShort.");
+        let chunks = chunk_markdown_impl(&text, 120, 0).unwrap();
+        assert!(chunks.len() > 2);
+        let (last, long) = chunks.split_last().unwrap();
+        assert_eq!(last, "## Next\n\nShort.");
+        for chunk in long {
+            assert!(chunk.starts_with("## Long section\n\n"), "{chunk:?}");
+            assert!(char_len(chunk) <= 120, "{chunk:?}");
+        }
+    }
+
+    #[test]
+    fn test_chunk_markdown_without_headings_matches_chunk_text() {
+        let text = "No headings here. #hashtag is not one either. ".repeat(10);
+        assert_eq!(
+            chunk_markdown_impl(&text, 100, 20),
+            chunk_text_impl(&text, 100, 20)
+        );
+        assert_eq!(chunk_markdown_impl(&text, 0, 20), None);
+    }
+
+    #[test]
+    fn test_split_code_blocks() {
+        let text = "Intro.\n```py\nx = 1\n\n~~~\ny = 2\n```\nOutro.\n~~~\nunclosed";
+        assert_eq!(
+            split_code_blocks(text),
+            vec![
+                Block::Prose("Intro.\n"),
+                Block::Code("```py\nx = 1\n\n~~~\ny = 2\n```"),
+                Block::Prose("\nOutro.\n"),
+                Block::Code("~~~\nunclosed"),
+            ]
+        );
+        assert_eq!(
+            split_code_blocks("No code."),
+            vec![Block::Prose("No code.")]
+        );
+        assert!(is_code_block("```py\nx = 1\n```"));
+        assert!(!is_code_block("```inline```"));
+    }
+
+    #[test]
+    fn test_strip_markdown_document() {
+        let text = "# Q3 Results #\n\
+            Revenue grew **12%** to *$1.2 billion*, driven by \
+            [cloud sales](https://example.com/cloud_(2024) \"Cloud\").\n\
+            ![Revenue chart](chart.png)\n\n\
+            ## Highlights\n\
+            - Margins rose to __31%__.\n\
+            * New `fast_path` API shipped.\n\
+            2) See <https://example.com/ir>.\n\n\
+            > Guidance **raised**.\n\n\
+            ---\n\
+            Outlook\n\
+            =======\n\
+            Steady.";
+        assert_eq!(
+            strip_markdown(text),
+            "Q3 Results\n\n\
+             Revenue grew 12% to $1.2 billion, driven by cloud sales.\n\
+             Revenue chart\n\n\
+             Highlights\n\n\
+             - Margins rose to 31%.\n\
+             - New fast_path API shipped.\n\
+             - See https://example.com/ir.\n\n\
+             Guidance raised.\n\n\n\
+             Outlook\n\n\
+             Steady."
+        );
+    }
+
+    #[test]
+    fn test_strip_markdown_keeps_literal_markers() {
+        let text = "5 * 3 * 2 = 30, a*b*c, snake_case_name, \\*escaped\\*, C# and\n\
+                    2023. Revenue rose.";
+        assert_eq!(
+            strip_markdown(text),
+            "5 * 3 * 2 = 30, a*b*c, snake_case_name, *escaped*, C# and\n2023. Revenue rose."
+        );
+        assert_eq!(strip_markdown("# Learning C#"), "Learning C#");
+        assert_eq!(
+            strip_markdown("***both*** and **bold _italic_**"),
+            "both and bold italic"
+        );
+    }
+
+    #[test]
+    fn test_strip_markdown_leaves_code_blocks() {
+        let text = "Run **this**:\n```py\nx = f(**kwargs)  # `a`\n```\nDone.";
+        assert_eq!(
+            strip_markdown(text),
+            "Run this:\n\n```py\nx = f(**kwargs)  # `a`\n```\n\nDone."
+        );
+    }
+
+    #[test]
+    fn test_chunk_markdown_ignores_headings_in_code_fences() {
+        let text = "## Setup\nRun this:\n```bash\n# install deps\nmake install\n```\nDone.";
+        let chunks = chunk_markdown_impl(text, 1500, 0).unwrap();
+        assert_eq!(chunks.len(), 1);
+        assert!(chunks[0].starts_with("## Setup\n\nRun this:"));
+        assert!(chunks[0].contains("# install deps"));
+    }
+}
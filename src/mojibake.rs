@@ -0,0 +1,176 @@
+//! Repair of UTF-8 text that was decoded as Windows-1252 or Latin-1, backing
+//! `repair_mojibake` and `CleanOptions(fix_mojibake=True)`.
+//!
+//! Each character of such text stands for one byte of the original UTF-8, so
+//! "é" (C3 A9) shows up as "Ã©" and a curly quote (E2 80 9C) as "â€œ". Only
+//! the byte patterns behind the usual damage are recognized: two-byte
+//! sequences for Latin-1 and Latin Extended-A letters and symbols, three-byte
+//! sequences for general punctuation, currency signs and letterlike symbols
+//! ("™"), and the byte order mark ("ï»¿"). Anything else, including a lone
+//! "Ã" or "â" in correctly decoded text, is left alone.
+
+/// Windows-1252 bytes 0x80-0x9F whose character differs from Latin-1's; the
+/// five bytes Windows-1252 leaves undefined decode to C1 controls either way.
+const CP1252_HIGH: [(char, u8); 27] = [
+    ('€', 0x80),
+    ('‚', 0x82),
+    ('ƒ', 0x83),
+    ('„', 0x84),
+    ('…', 0x85),
+    ('†', 0x86),
+    ('‡', 0x87),
+    ('ˆ', 0x88),
+    ('‰', 0x89),
+    ('Š', 0x8A),
+    ('‹', 0x8B),
+    ('Œ', 0x8C),
+    ('Ž', 0x8E),
+    ('\u{2018}', 0x91),
+    ('\u{2019}', 0x92),
+    ('\u{201C}', 0x93),
+    ('\u{201D}', 0x94),
+    ('•', 0x95),
+    ('–', 0x96),
+    ('—', 0x97),
+    ('˜', 0x98),
+    ('™', 0x99),
+    ('š', 0x9A),
+    ('›', 0x9B),
+    ('œ', 0x9C),
+    ('ž', 0x9E),
+    ('Ÿ', 0x9F),
+];
+
+/// Mojibake passes at most: text damaged twice ("Ã¢â‚¬â„¢") needs two.
+const MAX_PASSES: usize = 3;
+
+/// The byte `c` was decoded from, if it is one a Windows-1252 or Latin-1
+/// decoder produces.
+fn byte_of(c: char) -> Option<u8> {
+    match c as u32 {
+        0..=0xFF => Some(c as u8),
+        _ => CP1252_HIGH
+            .iter()
+            .find(|&&(k, _)| k == c)
+            .map(|&(_, byte)| byte),
+    }
+}
+
+fn is_continuation(byte: u8) -> bool {
+    (0x80..=0xBF).contains(&byte)
+}
+
+/// Whether `bytes` is one of the recognized sequences: a lead byte followed
+/// by the continuation bytes it announces.
+fn is_recognized(bytes: &[u8]) -> bool {
+    match bytes {
+        // U+0080-U+017F: Latin-1 Supplement and Latin Extended-A
+        [0xC2..=0xC5, b] => is_continuation(*b),
+        // U+2000-U+213F: punctuation, super/subscripts, currency, letterlike
+        [0xE2, 0x80..=0x84, b] => is_continuation(*b),
+        // U+FEFF byte order mark
+        [0xEF, 0xBB, 0xBF] => true,
+        _ => false,
+    }
+}
+
+/// Repair one layer of mojibake, or None when there is none.
+fn repair_pass(text: &str) -> Option<String> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut out = String::with_capacity(text.len());
+    let mut repaired = false;
+    let mut i = 0;
+    while i < chars.len() {
+        let len = match byte_of(chars[i]) {
+            Some(0xC2..=0xC5) => 2,
+            Some(0xE2 | 0xEF) => 3,
+            _ => 0,
+        };
+        let bytes: Option<Vec<u8>> = chars
+            .get(i..i + len)
+            .filter(|_| len > 0)
+            .and_then(|seq| seq.iter().map(|&c| byte_of(c)).collect());
+        let decoded = bytes
+            .filter(|bytes| is_recognized(bytes))
+            .and_then(|bytes| String::from_utf8(bytes).ok());
+        match decoded {
+            Some(decoded) => {
+                out.push_str(&decoded);
+                repaired = true;
+                i += len;
+            }
+            None => {
+                out.push(chars[i]);
+                i += 1;
+            }
+        }
+    }
+    repaired.then_some(out)
+}
+
+/// Restore UTF-8 text that was decoded as Windows-1252 or Latin-1, undoing up
+/// to `MAX_PASSES` layers of damage. Text without mojibake is returned as is.
+pub(crate) fn repair_mojibake(text: &str) -> String {
+    let mut text = text.to_string();
+    for _ in 0..MAX_PASSES {
+        if !text.contains(['Â', 'Ã', 'Ä', 'Å', 'â', 'ï']) {
+            break;
+        }
+        match repair_pass(&text) {
+            Some(repaired) => text = repaired,
+            None => break,
+        }
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Mojibake of `text`: its UTF-8 bytes decoded as Windows-1252 (Latin-1
+    /// for the bytes Windows-1252 leaves undefined).
+    fn garble(text: &str) -> String {
+        text.bytes()
+            .map(|byte| {
+                CP1252_HIGH
+                    .iter()
+                    .find(|&&(_, b)| b == byte)
+                    .map_or(byte as char, |&(c, _)| c)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_repairs_quotes_and_accents() {
+        assert_eq!(
+            repair_mojibake("\u{E2}\u{20AC}\u{153}Hello\u{E2}\u{20AC}\u{9D}"),
+            "\u{201C}Hello\u{201D}"
+        );
+        assert_eq!(repair_mojibake("CafÃ© dÃ©jÃ\u{A0} vu"), "Café déjà vu");
+        assert_eq!(repair_mojibake("ï»¿Revenue"), "\u{FEFF}Revenue");
+    }
+
+    #[test]
+    fn test_round_trips_garbled_text() {
+        for text in [
+            "Revenue rose 12% \u{2013} to €5M \u{2014} said the CFO\u{2019}s “update”…",
+            "Zürich, Málaga, Kraków and São Paulo: 25°C, ±3, ½, ™ and š",
+        ] {
+            assert_eq!(repair_mojibake(&garble(text)), text);
+            assert_eq!(repair_mojibake(&garble(&garble(text))), text);
+        }
+    }
+
+    #[test]
+    fn test_leaves_correct_text_alone() {
+        for text in [
+            "Plain ASCII text.",
+            "Ça va? À bientôt, Å and Ä, â la carte, Ã alone.",
+            "Ångström, Ölfeld, naïve, ï at the end",
+            "Prices in € and £, “quoted” — fine.",
+        ] {
+            assert_eq!(repair_mojibake(text), text);
+        }
+    }
+}
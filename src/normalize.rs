@@ -0,0 +1,488 @@
+//! Numeric normalization for extracted monetary amounts and percentages.
+//!
+//! The extractors in `lib.rs` return matches exactly as written. This module
+//! turns those raw strings into numbers and, when asked, infers a negative
+//! sign from direction cue words that directly govern the value
+//! ("down $200 million", "a decline of 5%").
+
+use serde::Serialize;
+
+/// A raw match paired with its parsed numeric value.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct NormalizedValue {
+    /// The match exactly as it appeared in the text.
+    pub raw: String,
+    /// Parsed value, or `None` if the match could not be parsed.
+    pub value: Option<f64>,
+    /// True when the sign of `value` was flipped by a direction cue.
+    pub sign_inferred: bool,
+}
+
+/// Words that signal a decrease when they directly precede a value.
+const NEGATIVE_CUES: &[&str] = &[
+    "down",
+    "decline",
+    "declined",
+    "declines",
+    "decrease",
+    "decreased",
+    "decreases",
+    "loss",
+    "losses",
+    "fell",
+    "drop",
+    "dropped",
+    "lower",
+    "minus",
+];
+
+/// Words allowed between a cue and the value it governs ("decline of", "fell by about").
+const CUE_FILLERS: &[&str] = &[
+    "of",
+    "by",
+    "approximately",
+    "about",
+    "roughly",
+    "nearly",
+    "almost",
+    "around",
+];
+
+/// How many tokens before a value are inspected for a governing cue.
+const CUE_WINDOW_TOKENS: usize = 3;
+
+/// Scale suffixes recognized after a monetary amount, with their canonical name
+/// and multiplier. Matching is case-insensitive; a trailing "." is ignored
+/// ("Mio.", "Mrd.").
+const SCALES: &[(&[&str], &str, f64)] = &[
+    (&["k", "thousand"], "thousand", 1e3),
+    (&["m", "mn", "million", "mio"], "million", 1e6),
+    (&["b", "bn", "billion", "mrd"], "billion", 1e9),
+    (&["万"], "ten thousand", 1e4),
+    (&["億"], "hundred million", 1e8),
+];
+
+/// Look up a scale suffix: `Some(None)` for no suffix, `None` if it is unknown.
+fn lookup_scale(suffix: &str) -> Option<Option<(&'static str, f64)>> {
+    let suffix = suffix.trim().trim_end_matches('.').to_lowercase();
+    if suffix.is_empty() {
+        return Some(None);
+    }
+    SCALES
+        .iter()
+        .find(|(words, _, _)| words.contains(&suffix.as_str()))
+        .map(|(_, name, multiplier)| Some((*name, *multiplier)))
+}
+
+/// Return the scale multiplier for a money suffix such as "million" or "K".
+pub(crate) fn scale_multiplier(suffix: &str) -> Option<f64> {
+    lookup_scale(suffix).map(|scale| scale.map_or(1.0, |(_, multiplier)| multiplier))
+}
+
+/// Canonical name of a money scale suffix ("M" -> "million"), None when unscaled.
+fn scale_name(suffix: &str) -> Option<&'static str> {
+    lookup_scale(suffix).flatten().map(|(name, _)| name)
+}
+
+/// Currency symbols recognized before or after an amount, with the code reported
+/// for each. "¥" is shared by yen and renminbi, so it is reported as the bare symbol.
+const CURRENCY_SYMBOLS: &[(char, &str)] = &[
+    ('$', "USD"),
+    ('€', "EUR"),
+    ('£', "GBP"),
+    ('¥', "¥"),
+    ('₹', "INR"),
+];
+
+/// ISO 4217 codes recognized before or after an amount ("USD 5 million", "5 million USD").
+const CURRENCY_CODES: &[&str] = &["USD", "EUR", "GBP", "JPY", "CNY", "INR", "CHF"];
+
+/// Spelled-out currency names recognized after an amount ("5 million dollars"),
+/// longest first so "pounds sterling" wins over "pounds".
+const CURRENCY_WORDS: &[(&str, &str)] = &[
+    ("pounds sterling", "GBP"),
+    ("dollars", "USD"),
+    ("dollar", "USD"),
+    ("euros", "EUR"),
+    ("euro", "EUR"),
+    ("pounds", "GBP"),
+    ("pound", "GBP"),
+    ("rupees", "INR"),
+    ("rupee", "INR"),
+    ("francs", "CHF"),
+    ("franc", "CHF"),
+    ("yen", "JPY"),
+];
+
+/// Split a currency marker off the end of `raw` ("5,2 Mio. €", "5 million dollars").
+fn split_currency_suffix(raw: &str) -> Option<(&'static str, &str)> {
+    if let Some(&(symbol, currency)) = CURRENCY_SYMBOLS.iter().find(|(c, _)| raw.ends_with(*c)) {
+        return Some((currency, raw[..raw.len() - symbol.len_utf8()].trim_end()));
+    }
+    if let Some(code) = CURRENCY_CODES.iter().find(|code| raw.ends_with(**code)) {
+        return Some((code, raw[..raw.len() - code.len()].trim_end()));
+    }
+    let lower = raw.to_lowercase();
+    CURRENCY_WORDS.iter().find_map(|(word, currency)| {
+        let amount = lower.strip_suffix(word)?;
+        // Only whole words: "5 dollars", not "5 xdollars"
+        amount
+            .ends_with(|c: char| c.is_whitespace() || c.is_ascii_digit() || c == '.')
+            .then(|| (*currency, raw[..amount.len()].trim_end()))
+    })
+}
+
+/// Strip the sign off a monetary match written as a negative: a leading minus
+/// ("-$5M", "−$5M") or accounting-style parentheses around the whole amount
+/// ("($1.2 million)"). Returns the unsigned amount and whether it was negative.
+fn strip_negative(raw: &str) -> (&str, bool) {
+    let raw = raw.trim();
+    if let Some(inner) = raw
+        .strip_prefix('(')
+        .and_then(|rest| rest.strip_suffix(')'))
+    {
+        return (inner.trim(), true);
+    }
+    match raw.strip_prefix(['-', '−']) {
+        Some(rest) => (rest.trim_start(), true),
+        None => (raw, false),
+    }
+}
+
+/// Split a monetary match into its currency and the remaining amount text.
+/// The currency may come before ("$5", "USD 5") or after ("5 €", "5 dollars") the
+/// amount; the sign of a negative amount ("-$5", "($5)") is dropped.
+pub(crate) fn split_currency(raw: &str) -> Option<(&'static str, &str)> {
+    let (raw, _) = strip_negative(raw);
+    if let Some(&(_, currency)) = CURRENCY_SYMBOLS.iter().find(|(c, _)| raw.starts_with(*c)) {
+        let symbol_len = raw.chars().next()?.len_utf8();
+        return Some((currency, raw[symbol_len..].trim_start()));
+    }
+    CURRENCY_CODES
+        .iter()
+        .find(|code| raw.starts_with(**code))
+        .map(|code| (*code, raw[code.len()..].trim_start()))
+        .or_else(|| split_currency_suffix(raw))
+}
+
+/// Parse a number written with either "," or "." as the thousands separator.
+///
+/// When both appear, the last one is the decimal separator ("1,234.56",
+/// "1.234,56"), and a repeated separator always groups thousands ("1.250.000").
+/// A lone "," is a decimal comma unless exactly three digits follow ("5,2" vs
+/// "1,250"); a lone "." is always a decimal point ("2.375").
+pub(crate) fn parse_number(number: &str) -> Option<f64> {
+    let Some(last_sep) = number.rfind([',', '.']) else {
+        return number.parse().ok();
+    };
+    let sep = number.as_bytes()[last_sep] as char;
+    let other = if sep == ',' { '.' } else { ',' };
+    let decimals = number.len() - last_sep - 1;
+    let is_decimal = number.contains(other)
+        || (number.matches(sep).count() == 1 && (sep == '.' || decimals != 3));
+    if is_decimal {
+        let (int, frac) = number.split_at(last_sep);
+        format!("{}.{}", int.replace([',', '.'], ""), &frac[1..])
+            .parse()
+            .ok()
+    } else {
+        number.replace([',', '.'], "").parse().ok()
+    }
+}
+
+/// Split a monetary match into its currency, number text, scale suffix and
+/// whether it is negative. The sign may sit outside the currency ("-$5M",
+/// "($5M)") or on the number itself ("$-5M", "$(5)M").
+fn split_money(raw: &str) -> Option<(&'static str, &str, &str, bool)> {
+    let (_, outer_negative) = strip_negative(raw);
+    let (currency, body) = split_currency(raw)?;
+    let (body, inner_negative) = match body.strip_prefix(['(', '-', '−']) {
+        Some(rest) => (rest, true),
+        None => (body, false),
+    };
+    let number_end = body
+        .find(|c: char| !(c.is_ascii_digit() || c == ',' || c == '.'))
+        .unwrap_or(body.len());
+    let suffix = body[number_end..].trim_start_matches(')').trim();
+    Some((
+        currency,
+        &body[..number_end],
+        suffix,
+        outer_negative || inner_negative,
+    ))
+}
+
+/// Parse a monetary match like "$1,250 million", "EUR 1.2B", "5,2 Mio. €" or
+/// "$(1.2) million" into base units, negative for minus signs and parentheses.
+pub(crate) fn parse_money(raw: &str) -> Option<f64> {
+    let (_, number, suffix, negative) = split_money(raw)?;
+    let value = parse_number(number)? * scale_multiplier(suffix)?;
+    Some(if negative { -value } else { value })
+}
+
+/// The scale word of a monetary match ("thousand", "million", "billion", ...), if any.
+pub(crate) fn money_scale(raw: &str) -> Option<&'static str> {
+    split_money(raw).and_then(|(_, _, suffix, _)| scale_name(suffix))
+}
+
+/// Percentage unit words and symbols, with the canonical unit reported for each
+/// and the divisor converting a value in that unit into percent.
+const PERCENT_UNITS: &[(&str, &str, f64)] = &[
+    ("%", "percent", 1.0),
+    ("percent", "percent", 1.0),
+    ("pct", "percent", 1.0),
+    ("basis points", "basis_points", 100.0),
+    ("basis point", "basis_points", 100.0),
+    ("bps", "basis_points", 100.0),
+    ("bp", "basis_points", 100.0),
+];
+
+/// Split a percentage match into its value as written, canonical unit and the
+/// divisor to percent. Accounting-style parentheses, around the whole match
+/// ("(3%)") or just the number ("(3.5)%"), negate the value.
+fn split_percentage(raw: &str) -> Option<(f64, &'static str, f64)> {
+    let raw = raw.trim();
+    let (body, negated) = match raw.strip_prefix('(') {
+        Some(rest) => match rest.strip_suffix(')') {
+            Some(inner) => (inner.trim().to_string(), true),
+            None => (rest.replacen(')', "", 1), true),
+        },
+        None => (raw.to_string(), false),
+    };
+    // "basis\npoints" matches as one unit; "−3%" is written with a Unicode minus
+    let body = body
+        .replace('−', "-")
+        .to_lowercase()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    let (number, unit, divisor) = PERCENT_UNITS.iter().find_map(|(suffix, unit, divisor)| {
+        let number = body.strip_suffix(suffix)?.trim();
+        Some((number, *unit, *divisor))
+    })?;
+    let value: f64 = number.parse().ok()?;
+    Some((if negated { -value } else { value }, unit, divisor))
+}
+
+/// Parse a percentage match like "12.5%", "3.5 pct", "(3%)" or "40 bps" into a
+/// value in percent units (basis points are divided by 100).
+pub(crate) fn parse_percentage(raw: &str) -> Option<f64> {
+    split_percentage(raw).map(|(value, _, divisor)| value / divisor)
+}
+
+/// The unit a percentage match was written in: "percent" or "basis_points".
+pub(crate) fn percentage_unit(raw: &str) -> Option<&'static str> {
+    split_percentage(raw).map(|(_, unit, _)| unit)
+}
+
+/// Check whether a negative direction cue directly governs the value starting at `start`.
+///
+/// Conservative by design: scanning backwards, only filler words may sit between the
+/// cue and the value, and any punctuation ends the scan. So "down $5M" and "a decline
+/// of 5%" are governed, while "down from $5M to $6M" governs neither amount.
+pub(crate) fn governed_by_negative_cue(text: &str, start: usize) -> bool {
+    for token in text[..start]
+        .split_whitespace()
+        .rev()
+        .take(CUE_WINDOW_TOKENS)
+    {
+        if !token.chars().all(|c| c.is_alphabetic()) {
+            return false;
+        }
+        let token = token.to_lowercase();
+        if NEGATIVE_CUES.contains(&token.as_str()) {
+            return true;
+        }
+        if !CUE_FILLERS.contains(&token.as_str()) {
+            return false;
+        }
+    }
+    false
+}
+
+/// Normalize a single regex match found at byte offset `start` in `text`.
+pub(crate) fn normalize_match(
+    text: &str,
+    raw: &str,
+    start: usize,
+    parse: fn(&str) -> Option<f64>,
+    infer_sign: bool,
+) -> NormalizedValue {
+    let mut value = parse(raw);
+    let mut sign_inferred = false;
+
+    if infer_sign {
+        if let Some(v) = value.filter(|v| *v > 0.0) {
+            if governed_by_negative_cue(text, start) {
+                value = Some(-v);
+                sign_inferred = true;
+            }
+        }
+    }
+
+    NormalizedValue {
+        raw: raw.to_string(),
+        value,
+        sign_inferred,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn normalize_at(text: &str, raw: &str, parse: fn(&str) -> Option<f64>) -> NormalizedValue {
+        let start = text.find(raw).unwrap();
+        normalize_match(text, raw, start, parse, true)
+    }
+
+    #[test]
+    fn test_parse_money_scales() {
+        assert_eq!(parse_money("$1,250 million"), Some(1_250_000_000.0));
+        assert_eq!(parse_money("$500,000"), Some(500_000.0));
+        assert_eq!(parse_money("$2B"), Some(2e9));
+        assert_eq!(parse_money("$12.50 "), Some(12.5));
+        assert_eq!(parse_money("12.50"), None);
+    }
+
+    #[test]
+    fn test_parse_money_other_currencies() {
+        assert_eq!(parse_money("€4,200"), Some(4200.0));
+        assert_eq!(parse_money("EUR 1.2B"), Some(1.2e9));
+        assert_eq!(parse_money("GBP 500k"), Some(500_000.0));
+        assert_eq!(split_currency("¥1,000"), Some(("¥", "1,000")));
+        assert_eq!(split_currency("USD 5 million"), Some(("USD", "5 million")));
+        assert_eq!(split_currency("₹20"), Some(("INR", "20")));
+        assert_eq!(split_currency("5 million"), None);
+    }
+
+    #[test]
+    fn test_parse_money_suffix_currencies() {
+        assert_eq!(parse_money("5,2 Mio. €"), Some(5_200_000.0));
+        assert_eq!(parse_money("1.234,56 €"), Some(1234.56));
+        assert_eq!(parse_money("3 Mrd. EUR"), Some(3e9));
+        assert_eq!(parse_money("5 million dollars"), Some(5e6));
+        assert_eq!(parse_money("40 euros"), Some(40.0));
+        assert_eq!(parse_money("2.5bn pounds sterling"), Some(2.5e9));
+        assert_eq!(parse_money("¥1,000億"), Some(1e11));
+        assert_eq!(parse_money("£30m"), Some(30e6));
+        assert_eq!(split_currency("5 million USD"), Some(("USD", "5 million")));
+        assert_eq!(split_currency("12 Dollars"), Some(("USD", "12")));
+        assert_eq!(split_currency("700 yen"), Some(("JPY", "700")));
+        assert_eq!(split_currency("5 xdollars"), None);
+    }
+
+    #[test]
+    fn test_parse_money_negatives() {
+        assert_eq!(parse_money("$(1.2) million"), Some(-1_200_000.0));
+        assert_eq!(parse_money("($1.2 million)"), Some(-1_200_000.0));
+        assert_eq!(parse_money("$(1,234)"), Some(-1234.0));
+        assert_eq!(parse_money("-$5M"), Some(-5e6));
+        assert_eq!(parse_money("−€5M"), Some(-5e6));
+        assert_eq!(parse_money("$-5"), Some(-5.0));
+        assert_eq!(parse_money("(USD 40k)"), Some(-40_000.0));
+        assert_eq!(parse_money("-5 million dollars"), Some(-5e6));
+        assert_eq!(
+            split_currency("($1.2 million)"),
+            Some(("USD", "1.2 million"))
+        );
+        assert_eq!(split_currency("-€5M"), Some(("EUR", "5M")));
+        assert_eq!(money_scale("$(1.2) million"), Some("million"));
+    }
+
+    #[test]
+    fn test_parse_number_separators() {
+        assert_eq!(parse_number("1,250"), Some(1250.0));
+        assert_eq!(parse_number("1.250.000"), Some(1_250_000.0));
+        assert_eq!(parse_number("1,234,567.89"), Some(1_234_567.89));
+        assert_eq!(parse_number("1.234,56"), Some(1234.56));
+        assert_eq!(parse_number("2.375"), Some(2.375));
+        assert_eq!(parse_number("5,2"), Some(5.2));
+        assert_eq!(parse_number("12.50"), Some(12.5));
+    }
+
+    #[test]
+    fn test_money_scale() {
+        assert_eq!(money_scale("$1,250 million"), Some("million"));
+        assert_eq!(money_scale("EUR 1.2B"), Some("billion"));
+        assert_eq!(money_scale("$250k"), Some("thousand"));
+        assert_eq!(money_scale("$500,000"), None);
+    }
+
+    #[test]
+    fn test_parse_percentage() {
+        assert_eq!(parse_percentage("12.5%"), Some(12.5));
+        assert_eq!(parse_percentage("7%"), Some(7.0));
+        assert_eq!(parse_percentage("7"), None);
+        assert_eq!(parse_percentage("12.5 percent"), Some(12.5));
+        assert_eq!(parse_percentage("7 Percent"), Some(7.0));
+        assert_eq!(parse_percentage("-3%"), Some(-3.0));
+        assert_eq!(parse_percentage("−3%"), Some(-3.0));
+        assert_eq!(parse_percentage("(3%)"), Some(-3.0));
+        assert_eq!(parse_percentage("(0.5 percent)"), Some(-0.5));
+        assert_eq!(parse_percentage("(3)"), None);
+        assert_eq!(parse_percentage("(3.5)%"), Some(-3.5));
+        assert_eq!(parse_percentage("3.5 pct"), Some(3.5));
+        assert_eq!(parse_percentage("40 bps"), Some(0.4));
+        assert_eq!(parse_percentage("25bp"), Some(0.25));
+        assert_eq!(parse_percentage("-15 basis points"), Some(-0.15));
+        assert_eq!(parse_percentage("1 basis\npoint"), Some(0.01));
+        assert_eq!(parse_percentage("(40 bps)"), Some(-0.4));
+        assert_eq!(percentage_unit("40 bps"), Some("basis_points"));
+        assert_eq!(percentage_unit("3 pct"), Some("percent"));
+        assert_eq!(percentage_unit("3 pts"), None);
+    }
+
+    #[test]
+    fn test_infer_sign_governed_cues() {
+        let text = "Margin saw a decline of 5% this year.";
+        let v = normalize_at(text, "5%", parse_percentage);
+        assert_eq!(v.value, Some(-5.0));
+        assert!(v.sign_inferred);
+        assert_eq!(v.raw, "5%");
+
+        let text = "Revenue was down $200 million.";
+        let v = normalize_at(text, "$200 million", parse_money);
+        assert_eq!(v.value, Some(-200_000_000.0));
+        assert!(v.sign_inferred);
+
+        let text = "We booked a net loss of approximately $3 million.";
+        let v = normalize_at(text, "$3 million", parse_money);
+        assert_eq!(v.value, Some(-3_000_000.0));
+    }
+
+    #[test]
+    fn test_infer_sign_keeps_written_negatives() {
+        let text = "We booked a net loss of $(3) million.";
+        let v = normalize_at(text, "$(3) million", parse_money);
+        assert_eq!(v.value, Some(-3_000_000.0));
+        assert!(!v.sign_inferred);
+    }
+
+    #[test]
+    fn test_infer_sign_ignores_non_governing_cues() {
+        let text = "Costs went down from $5M to $6M.";
+        let from = normalize_at(text, "$5M", parse_money);
+        let to = normalize_at(text, "$6M", parse_money);
+        assert_eq!(from.value, Some(5e6));
+        assert_eq!(to.value, Some(6e6));
+        assert!(!from.sign_inferred && !to.sign_inferred);
+    }
+
+    #[test]
+    fn test_infer_sign_stops_at_sentence_boundary() {
+        let text = "Volume was down. 5% of customers churned.";
+        let v = normalize_at(text, "5%", parse_percentage);
+        assert_eq!(v.value, Some(5.0));
+        assert!(!v.sign_inferred);
+    }
+
+    #[test]
+    fn test_infer_sign_disabled_keeps_value_positive() {
+        let text = "Revenue was down $200 million.";
+        let start = text.find('$').unwrap();
+        let v = normalize_match(text, "$200 million", start, parse_money, false);
+        assert_eq!(v.value, Some(200_000_000.0));
+        assert!(!v.sign_inferred);
+    }
+}
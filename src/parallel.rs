@@ -0,0 +1,422 @@
+//! Intra-document parallelism for very large documents.
+//!
+//! Batch parallelism doesn't help when a single multi-megabyte filing hogs one
+//! core. For large cleaned texts, `process_document` instead:
+//!
+//! 1. cuts the text at paragraph breaks that are also sentence boundaries, so
+//!    splitting each segment into sentences yields exactly the sentences the
+//!    whole text would,
+//! 2. packs each segment into chunks in parallel, assuming a chunk starts at the
+//!    segment's first sentence,
+//! 3. stitches the segments serially: the true packing state is carried across
+//!    each seam and replayed sentence by sentence until it breaks a chunk at the
+//!    same sentence as the segment's own run. From that point the two runs are in
+//!    the same state, so the rest of the segment's chunks are adopted as-is.
+//!
+//! Step 3 relies on the overlap buffer in `chunk_text_impl` being history
+//! independent: after sentence `j` it always holds the longest run of trailing
+//! sentences that fits in `overlap` (at least one), and a chunk started at `j`
+//! carries the longest part of it that still leaves room for sentence `j`. A
+//! chunk break at `j` therefore fully determines the packing state, and the
+//! output is identical to the sequential algorithm.
+
+use crate::{
+    char_len, chunk_text_impl, split_oversized_sentences, split_sentence_units, unit_separator,
+    SizeUnit, Unit,
+};
+use rayon::prelude::*;
+
+/// Cleaned texts at least this large are chunked and scanned in parallel.
+pub(crate) const PARALLEL_MIN_BYTES: usize = 1 << 20;
+
+/// Segments smaller than this aren't worth a task of their own.
+const MIN_SEGMENT_BYTES: usize = 64 * 1024;
+
+/// Find byte offsets where the text can be cut into independently splittable
+/// segments: just after a paragraph break that follows sentence punctuation.
+fn segment_cuts(text: &str, n_segments: usize) -> Vec<usize> {
+    let mut cuts = Vec::new();
+    let step = text.len() / n_segments.max(1);
+
+    for k in 1..n_segments {
+        let mut from = (k * step).max(cuts.last().copied().unwrap_or(0));
+        while !text.is_char_boundary(from) {
+            from += 1;
+        }
+        let cut = text[from..].match_indices("\n\n").find_map(|(i, _)| {
+            let p = from + i;
+            let ends_sentence = text[..p].ends_with(['.', '!', '?', '。', '！', '？']);
+            // Skip the whole whitespace run, as SENTENCE_BOUNDARY_RE's `\s+` would
+            let ws_len: usize = text[p..]
+                .chars()
+                .take_while(|c| c.is_whitespace())
+                .map(char::len_utf8)
+                .sum();
+            ends_sentence.then_some(p + ws_len)
+        });
+        match cut {
+            Some(cut) if cut < text.len() => cuts.push(cut),
+            _ => break,
+        }
+    }
+
+    cuts
+}
+
+/// Split text into sentences segment by segment, in parallel.
+fn split_sentences_segmented(text: &str, n_segments: usize) -> Vec<Unit> {
+    let cuts = segment_cuts(text, n_segments);
+    let bounds: Vec<(usize, usize)> = std::iter::once(0)
+        .chain(cuts.iter().copied())
+        .zip(cuts.iter().copied().chain(std::iter::once(text.len())))
+        .collect();
+
+    bounds
+        .par_iter()
+        .enumerate()
+        .map(|(i, &(start, end))| {
+            let mut units = split_sentence_units(&text[start..end]);
+            // Every cut follows a paragraph break
+            if let Some(first) = units.first_mut().filter(|_| i > 0) {
+                first.after_paragraph_break = true;
+            }
+            units
+        })
+        .flatten()
+        .collect()
+}
+
+/// Join a chunk's sentences with the separators `chunk_text_impl` puts between them.
+fn join_units(units: &[Unit]) -> String {
+    let mut joined = String::new();
+    for (i, unit) in units.iter().enumerate() {
+        if i > 0 {
+            joined.push_str(unit_separator(false, unit, false));
+        }
+        joined.push_str(&unit.text);
+    }
+    joined
+}
+
+/// Packing state: the chunk being built has `chars` characters.
+#[derive(Debug, Clone, Copy)]
+struct PackState {
+    chars: usize,
+    empty: bool,
+}
+
+/// Result of packing one range of sentences.
+struct SegmentPlan {
+    /// Sentence indices at which a new chunk begins.
+    breaks: Vec<usize>,
+    end_state: PackState,
+}
+
+/// Index-based replica of the packing loop in `chunk_text_impl`.
+struct Packer {
+    lengths: Vec<usize>,
+    /// `prefix[i]` is the total length of sentences `0..i`.
+    prefix: Vec<usize>,
+    /// `overlap_starts[j]` is the first sentence in the overlap buffer after sentence `j`.
+    overlap_starts: Vec<usize>,
+    target_size: usize,
+}
+
+impl Packer {
+    fn new(lengths: Vec<usize>, target_size: usize, overlap: usize) -> Self {
+        let mut prefix = Vec::with_capacity(lengths.len() + 1);
+        prefix.push(0);
+        for len in &lengths {
+            prefix.push(prefix.last().unwrap() + len);
+        }
+
+        // Mirrors the VecDeque trimming: keep trailing sentences while they fit
+        // (counting a separator each), but never drop the newest one.
+        let mut overlap_starts = Vec::with_capacity(lengths.len());
+        let mut start = 0;
+        let mut buffered = 0;
+        for (j, len) in lengths.iter().enumerate() {
+            buffered += len + 1;
+            while buffered > overlap && j > start {
+                buffered -= lengths[start] + 1;
+                start += 1;
+            }
+            overlap_starts.push(start);
+        }
+
+        Packer {
+            lengths,
+            prefix,
+            overlap_starts,
+            target_size,
+        }
+    }
+
+    /// Characters in sentences `a..b` joined by single spaces.
+    fn span_chars(&self, a: usize, b: usize) -> usize {
+        self.prefix[b] - self.prefix[a] + (b - a).saturating_sub(1)
+    }
+
+    /// First sentence of the chunk started by a break at `j`: the overlap buffer
+    /// after `j - 1`, minus its oldest sentences while sentence `j` would not fit.
+    fn restart(&self, j: usize) -> usize {
+        let mut start = self.overlap_starts[j - 1];
+        while start < j && self.span_chars(start, j) + 1 + self.lengths[j] > self.target_size {
+            start += 1;
+        }
+        start
+    }
+
+    /// Add sentence `j` to the chunk being built. Returns true if it had to start a new chunk.
+    fn step(&self, state: &mut PackState, j: usize) -> bool {
+        let len = self.lengths[j];
+        if !state.empty && state.chars + 1 + len > self.target_size {
+            *state = self.state_after_break(j);
+            return true;
+        }
+        if !state.empty {
+            state.chars += 1;
+        }
+        state.chars += len;
+        state.empty = false;
+        false
+    }
+
+    /// State right after a chunk break at sentence `j` has been taken.
+    fn state_after_break(&self, j: usize) -> PackState {
+        PackState {
+            chars: self.span_chars(self.restart(j), j + 1),
+            empty: false,
+        }
+    }
+
+    /// Pack sentences `from..to` starting from `state`.
+    fn plan(&self, from: usize, to: usize, mut state: PackState) -> SegmentPlan {
+        let mut breaks = Vec::new();
+        for j in from..to {
+            if self.step(&mut state, j) {
+                breaks.push(j);
+            }
+        }
+        SegmentPlan {
+            breaks,
+            end_state: state,
+        }
+    }
+
+    /// Pack all sentences, splitting the work at the given sentence indices.
+    fn plan_segmented(&self, seams: &[usize]) -> Vec<usize> {
+        let n = self.lengths.len();
+        let bounds: Vec<(usize, usize)> = std::iter::once(0)
+            .chain(seams.iter().copied())
+            .zip(seams.iter().copied().chain(std::iter::once(n)))
+            .filter(|(a, b)| a < b)
+            .collect();
+
+        // Each segment assumes a chunk starts at its first sentence
+        let plans: Vec<SegmentPlan> = bounds
+            .par_iter()
+            .map(|&(a, b)| {
+                if a == 0 {
+                    let empty = PackState {
+                        chars: 0,
+                        empty: true,
+                    };
+                    self.plan(0, b, empty)
+                } else {
+                    let mut plan = self.plan(a + 1, b, self.state_after_break(a));
+                    plan.breaks.insert(0, a);
+                    plan
+                }
+            })
+            .collect();
+
+        // Stitch: replay the true state from each seam until it agrees with the segment's run
+        let mut plans = plans.into_iter();
+        let Some(first) = plans.next() else {
+            return Vec::new();
+        };
+        let mut breaks = first.breaks;
+        let mut state = first.end_state;
+
+        for (&(a, b), plan) in bounds.iter().skip(1).zip(plans) {
+            let mut j = a;
+            while j < b {
+                if self.step(&mut state, j) {
+                    breaks.push(j);
+                    if let Ok(pos) = plan.breaks.binary_search(&j) {
+                        breaks.extend_from_slice(&plan.breaks[pos + 1..]);
+                        state = plan.end_state;
+                        break;
+                    }
+                }
+                j += 1;
+            }
+        }
+
+        breaks
+    }
+
+    /// Sentence ranges for each chunk given the chunk break indices.
+    fn chunk_ranges(&self, breaks: &[usize]) -> Vec<(usize, usize)> {
+        let starts = std::iter::once(0).chain(breaks.iter().map(|&b| self.restart(b)));
+        let ends = breaks
+            .iter()
+            .copied()
+            .chain(std::iter::once(self.lengths.len()));
+        starts.zip(ends).collect()
+    }
+}
+
+/// Chunk text using `n_segments`-way parallelism, with output identical to `chunk_text_impl`.
+fn chunk_text_segmented(
+    text: &str,
+    target_size: usize,
+    overlap: usize,
+    n_segments: usize,
+) -> Option<Vec<String>> {
+    if target_size == 0 {
+        return None;
+    }
+    let overlap = overlap.min(target_size.saturating_sub(1));
+
+    let sentences = split_oversized_sentences(
+        split_sentences_segmented(text, n_segments),
+        target_size,
+        SizeUnit::Chars,
+    );
+    if sentences.is_empty() {
+        // Empty or punctuation-free text takes chunk_text_impl's fallback paths
+        return chunk_text_impl(text, target_size, overlap);
+    }
+
+    let lengths: Vec<usize> = sentences.par_iter().map(|s| char_len(&s.text)).collect();
+    let packer = Packer::new(lengths, target_size, overlap);
+
+    let n = sentences.len();
+    let seams: Vec<usize> = (1..n_segments).map(|k| k * n / n_segments).collect();
+    let breaks = packer.plan_segmented(&seams);
+
+    Some(
+        packer
+            .chunk_ranges(&breaks)
+            .into_par_iter()
+            .map(|(a, b)| join_units(&sentences[a..b]))
+            .collect(),
+    )
+}
+
+/// Chunk a (large) cleaned text in parallel. Output is identical to `chunk_text_impl`.
+pub(crate) fn chunk_text_parallel(
+    text: &str,
+    target_size: usize,
+    overlap: usize,
+) -> Option<Vec<String>> {
+    let n_segments = (rayon::current_num_threads() * 4)
+        .min(text.len() / MIN_SEGMENT_BYTES)
+        .max(1);
+    chunk_text_segmented(text, target_size, overlap, n_segments)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{clean_text_with_options, CleanOptions};
+
+    /// The bundled transcripts repeated until the cleaned text is at least `min_bytes`.
+    fn large_fixture(min_bytes: usize) -> String {
+        large_fixture_with(min_bytes, &CleanOptions::default())
+    }
+
+    /// `large_fixture` cleaned with `options`.
+    fn large_fixture_with(min_bytes: usize, options: &CleanOptions) -> String {
+        let samples = [
+            include_str!("../data/sample_transcripts/acme_q3_2025.txt"),
+            include_str!("../data/sample_transcripts/globalhealth_investor_day_2025.txt"),
+            include_str!("../data/sample_transcripts/techflow_q2_2025.txt"),
+        ];
+        let mut text = String::new();
+        let mut i = 0;
+        while text.len() < min_bytes {
+            text.push_str(samples[i % samples.len()]);
+            text.push_str("\n\n");
+            i += 1;
+        }
+        clean_text_with_options(&text, options)
+    }
+
+    #[test]
+    fn test_segment_cuts_follow_sentence_punctuation() {
+        let text = "Heading\n\nFirst para. Ends here.\n\nSecond para!\n\nThird?";
+        let cuts = segment_cuts(text, 8);
+        for cut in &cuts {
+            assert!(text[..*cut].trim_end().ends_with(['.', '!', '?']));
+        }
+        assert!(!cuts.contains(&"Heading\n\n".len()));
+    }
+
+    #[test]
+    fn test_split_sentences_segmented_matches_sequential() {
+        let text = large_fixture(200_000);
+        let expected = split_sentence_units(&text);
+        for n_segments in [1, 2, 7, 64] {
+            assert_eq!(split_sentences_segmented(&text, n_segments), expected);
+        }
+    }
+
+    #[test]
+    fn test_chunk_text_segmented_matches_sequential() {
+        let text = large_fixture(200_000);
+        for (target_size, overlap) in [(1500, 200), (300, 250), (80, 0), (1000, 999), (50, 40)] {
+            let expected = chunk_text_impl(&text, target_size, overlap);
+            for n_segments in [2, 5, 32] {
+                assert_eq!(
+                    chunk_text_segmented(&text, target_size, overlap, n_segments),
+                    expected,
+                    "target_size={target_size} overlap={overlap} n_segments={n_segments}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_chunk_text_segmented_keeps_line_breaks_like_sequential() {
+        let options = CleanOptions {
+            preserve_single_newlines: true,
+            ..CleanOptions::default()
+        };
+        let text = large_fixture_with(200_000, &options);
+        let expected = chunk_text_impl(&text, 300, 100);
+        assert!(expected.as_ref().unwrap().iter().any(|c| c.contains('\n')));
+        for n_segments in [2, 5, 32] {
+            assert_eq!(chunk_text_segmented(&text, 300, 100, n_segments), expected);
+        }
+    }
+
+    #[test]
+    fn test_chunk_text_parallel_matches_sequential_on_large_fixture() {
+        let text = large_fixture(4 * PARALLEL_MIN_BYTES);
+        assert_eq!(
+            chunk_text_parallel(&text, 1500, 200),
+            chunk_text_impl(&text, 1500, 200)
+        );
+    }
+
+    #[test]
+    fn test_chunk_text_segmented_edge_cases() {
+        assert_eq!(chunk_text_segmented("", 100, 10, 4), Some(vec![]));
+        assert_eq!(chunk_text_segmented("abc", 0, 10, 4), None);
+        let no_punct = "word ".repeat(100);
+        assert_eq!(
+            chunk_text_segmented(&no_punct, 64, 8, 4),
+            chunk_text_impl(&no_punct, 64, 8)
+        );
+        let long_sentences = format!("{}.\n\n", "word ".repeat(300)).repeat(20);
+        for (target_size, overlap) in [(64, 8), (500, 450), (1000, 200)] {
+            assert_eq!(
+                chunk_text_segmented(&long_sentences, target_size, overlap, 4),
+                chunk_text_impl(&long_sentences, target_size, overlap)
+            );
+        }
+    }
+}
@@ -0,0 +1,162 @@
+//! Per-stage timings of the processing pipeline, backing `profile_run`.
+//!
+//! Each iteration cleans the input, splits it into sentences, chunks it and scans
+//! every chunk for metadata, timing the stages separately so a regression can be
+//! pinned to one of them. Medians are reported alongside means because a single
+//! descheduled iteration can skew a mean badly on shared hardware.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use crate::{
+    chunk_text_impl, clean_text_with_options, extract_metadata_impl,
+    split_sentences_preserve_punct, CleanOptions,
+};
+
+/// Timings collected for one stage, one sample per iteration.
+#[derive(Debug, Clone)]
+pub(crate) struct StageTimings {
+    pub name: &'static str,
+    pub samples: Vec<Duration>,
+}
+
+impl StageTimings {
+    fn new(name: &'static str, iterations: usize) -> Self {
+        StageTimings {
+            name,
+            samples: Vec::with_capacity(iterations),
+        }
+    }
+
+    pub fn min(&self) -> Duration {
+        self.samples.iter().copied().min().unwrap_or_default()
+    }
+
+    pub fn median(&self) -> Duration {
+        let mut sorted = self.samples.clone();
+        sorted.sort();
+        match sorted.len() {
+            0 => Duration::ZERO,
+            n if n % 2 == 1 => sorted[n / 2],
+            n => (sorted[n / 2 - 1] + sorted[n / 2]) / 2,
+        }
+    }
+
+    pub fn mean(&self) -> Duration {
+        match self.samples.len() {
+            0 => Duration::ZERO,
+            n => self.samples.iter().sum::<Duration>() / n as u32,
+        }
+    }
+}
+
+/// Result of `profile_run_impl`.
+#[derive(Debug, Clone)]
+pub(crate) struct ProfileReport {
+    pub iterations: usize,
+    pub input_chars: usize,
+    pub chunk_count: usize,
+    /// clean, split_sentences, chunk, extract_metadata and total, in pipeline order.
+    pub stages: Vec<StageTimings>,
+}
+
+/// Time each pipeline stage over `iterations` runs. `iterations` must be non-zero
+/// and `chunk_size` must be greater than 0; None is returned otherwise.
+pub(crate) fn profile_run_impl(
+    text: &str,
+    iterations: usize,
+    chunk_size: usize,
+    chunk_overlap: usize,
+) -> Option<ProfileReport> {
+    if iterations == 0 || chunk_size == 0 {
+        return None;
+    }
+    let options = CleanOptions::default();
+    let mut clean = StageTimings::new("clean", iterations);
+    let mut split = StageTimings::new("split_sentences", iterations);
+    let mut chunk = StageTimings::new("chunk", iterations);
+    let mut extract = StageTimings::new("extract_metadata", iterations);
+    let mut total = StageTimings::new("total", iterations);
+    let mut chunk_count = 0;
+
+    for _ in 0..iterations {
+        let started = Instant::now();
+
+        let cleaned = black_box(clean_text_with_options(black_box(text), &options));
+        let t_clean = started.elapsed();
+
+        black_box(split_sentences_preserve_punct(&cleaned));
+        let t_split = started.elapsed();
+
+        let chunks = black_box(chunk_text_impl(&cleaned, chunk_size, chunk_overlap)?);
+        let t_chunk = started.elapsed();
+
+        for c in &chunks {
+            black_box(extract_metadata_impl(c, false));
+        }
+        let t_extract = started.elapsed();
+
+        clean.samples.push(t_clean);
+        split.samples.push(t_split - t_clean);
+        chunk.samples.push(t_chunk - t_split);
+        extract.samples.push(t_extract - t_chunk);
+        total.samples.push(t_extract);
+        chunk_count = chunks.len();
+    }
+
+    Some(ProfileReport {
+        iterations,
+        input_chars: text.chars().count(),
+        chunk_count,
+        stages: vec![clean, split, chunk, extract, total],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profile_run_impl_reports_every_stage() {
+        let text = include_str!("../benches/fixtures/news_brief.txt");
+        let report = profile_run_impl(text, 3, 200, 50).unwrap();
+        assert_eq!(report.iterations, 3);
+        assert_eq!(report.input_chars, text.chars().count());
+        assert!(report.chunk_count > 1);
+        let names: Vec<&str> = report.stages.iter().map(|s| s.name).collect();
+        assert_eq!(
+            names,
+            vec![
+                "clean",
+                "split_sentences",
+                "chunk",
+                "extract_metadata",
+                "total"
+            ]
+        );
+        for stage in &report.stages {
+            assert_eq!(stage.samples.len(), 3);
+            assert!(stage.min() <= stage.median());
+        }
+        let total = report.stages.last().unwrap();
+        let parts: Duration = report.stages[..4].iter().map(|s| s.samples[0]).sum();
+        assert_eq!(parts, total.samples[0]);
+    }
+
+    #[test]
+    fn test_profile_run_impl_rejects_invalid_arguments() {
+        assert!(profile_run_impl("Text.", 0, 100, 10).is_none());
+        assert!(profile_run_impl("Text.", 1, 0, 10).is_none());
+    }
+
+    #[test]
+    fn test_stage_timings_median() {
+        let stage = StageTimings {
+            name: "x",
+            samples: [4, 1, 3, 2].map(Duration::from_millis).to_vec(),
+        };
+        assert_eq!(stage.median(), Duration::from_micros(2500));
+        assert_eq!(stage.min(), Duration::from_millis(1));
+        assert_eq!(stage.mean(), Duration::from_micros(2500));
+    }
+}
@@ -0,0 +1,3056 @@
+//! PyO3 bindings: the `rag_rust_core` Python module.
+//!
+//! Each `#[pyfunction]` parses its arguments, releases the GIL around the pure
+//! implementation in the crate root and converts the result to Python objects.
+//! Errors become `PyErr`s only here.
+
+use super::*;
+use dates::normalize_date;
+use files::ReadError;
+use jsonl::JsonlFields;
+use pyo3::create_exception;
+use pyo3::exceptions::{
+    PyDeprecationWarning, PyOSError, PyTypeError, PyUnicodeDecodeError, PyValueError,
+};
+use pyo3::prelude::*;
+#[cfg(feature = "arrow")]
+use pyo3::types::PyCapsule;
+use pyo3::types::{PyDict, PyFloat, PyIterator, PyString};
+use std::path::{Path, PathBuf};
+
+impl UnicodeForm {
+    fn parse_py(name: &str) -> PyResult<Self> {
+        Self::parse(name).ok_or_else(|| {
+            InvalidOption::new_err(format!(
+                "unicode_normalization must be \"nfkc\", \"nfc\" or \"none\", got {name:?}"
+            ))
+        })
+    }
+}
+
+impl ParagraphSep {
+    fn parse_py(name: &str) -> PyResult<Self> {
+        Self::parse(name).ok_or_else(|| {
+            InvalidOption::new_err(format!(
+                "paragraph_sep must be \"double\", \"single\" or \"blank-line\", got {name:?}"
+            ))
+        })
+    }
+}
+
+impl TabPolicy {
+    fn parse_py(name: &str) -> PyResult<Self> {
+        Self::parse(name).ok_or_else(|| {
+            InvalidOption::new_err(format!(
+                "tab_policy must be \"collapse\", \"preserve\" or \"to_spaces(n)\" with n \
+                 at least 1, got {name:?}"
+            ))
+        })
+    }
+}
+
+// `CleanOptions.unicode_normalization`, `.paragraph_sep` and `.tab_policy`
+// read and write these as their names
+impl<'py> IntoPyObject<'py> for UnicodeForm {
+    type Target = PyString;
+    type Output = Bound<'py, PyString>;
+    type Error = std::convert::Infallible;
+
+    fn into_pyobject(self, py: Python<'py>) -> Result<Self::Output, Self::Error> {
+        Ok(PyString::new(py, self.name()))
+    }
+}
+
+impl<'py> FromPyObject<'py> for UnicodeForm {
+    fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
+        UnicodeForm::parse_py(&ob.extract::<String>()?)
+    }
+}
+
+impl<'py> IntoPyObject<'py> for ParagraphSep {
+    type Target = PyString;
+    type Output = Bound<'py, PyString>;
+    type Error = std::convert::Infallible;
+
+    fn into_pyobject(self, py: Python<'py>) -> Result<Self::Output, Self::Error> {
+        Ok(PyString::new(py, self.name()))
+    }
+}
+
+impl<'py> FromPyObject<'py> for ParagraphSep {
+    fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
+        ParagraphSep::parse_py(&ob.extract::<String>()?)
+    }
+}
+
+impl<'py> IntoPyObject<'py> for TabPolicy {
+    type Target = PyString;
+    type Output = Bound<'py, PyString>;
+    type Error = std::convert::Infallible;
+
+    fn into_pyobject(self, py: Python<'py>) -> Result<Self::Output, Self::Error> {
+        Ok(PyString::new(py, &self.name()))
+    }
+}
+
+impl<'py> FromPyObject<'py> for TabPolicy {
+    fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
+        TabPolicy::parse_py(&ob.extract::<String>()?)
+    }
+}
+
+#[pymethods]
+impl CleanOptions {
+    #[new]
+    #[pyo3(signature = (
+        *,
+        unicode_normalization="nfkc",
+        fold_to_ascii=false,
+        fix_mojibake=false,
+        standardize_quotes=true,
+        standardize_dashes=true,
+        collapse_whitespace=true,
+        preserve_single_newlines=None,
+        keep_single_newlines=None,
+        preserve_lists=false,
+        tab_policy="collapse",
+        paragraph_sep="double",
+        strip_control_chars=true,
+        strip_invisible=true,
+        keep_joiners=false,
+        strip_html=false,
+        strip_markdown=false,
+        preserve_code_blocks=false,
+        preserve_tables=false,
+        dehyphenate=false
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        unicode_normalization: &str,
+        fold_to_ascii: bool,
+        fix_mojibake: bool,
+        standardize_quotes: bool,
+        standardize_dashes: bool,
+        collapse_whitespace: bool,
+        preserve_single_newlines: Option<bool>,
+        keep_single_newlines: Option<bool>,
+        preserve_lists: bool,
+        tab_policy: &str,
+        paragraph_sep: &str,
+        strip_control_chars: bool,
+        strip_invisible: bool,
+        keep_joiners: bool,
+        strip_html: bool,
+        strip_markdown: bool,
+        preserve_code_blocks: bool,
+        preserve_tables: bool,
+        dehyphenate: bool,
+    ) -> PyResult<Self> {
+        if preserve_single_newlines.is_some() && keep_single_newlines.is_some() {
+            return Err(InvalidOption::new_err(
+                "preserve_single_newlines and keep_single_newlines name the same option; \
+                 pass only one",
+            ));
+        }
+        let preserve_single_newlines = preserve_single_newlines
+            .or(keep_single_newlines)
+            .unwrap_or_default();
+        Ok(CleanOptions {
+            unicode_normalization: UnicodeForm::parse_py(unicode_normalization)?,
+            fold_to_ascii,
+            fix_mojibake,
+            standardize_quotes,
+            standardize_dashes,
+            collapse_whitespace,
+            preserve_single_newlines,
+            preserve_lists,
+            tab_policy: TabPolicy::parse_py(tab_policy)?,
+            paragraph_sep: ParagraphSep::parse_py(paragraph_sep)?,
+            strip_control_chars,
+            strip_invisible,
+            keep_joiners,
+            strip_html,
+            strip_markdown,
+            preserve_code_blocks,
+            preserve_tables,
+            dehyphenate,
+        })
+    }
+
+    fn __repr__(&self) -> String {
+        let py_bool = |b: bool| if b { "True" } else { "False" };
+        format!(
+            "CleanOptions(unicode_normalization='{}', fold_to_ascii={}, fix_mojibake={}, \
+             standardize_quotes={}, standardize_dashes={}, collapse_whitespace={}, \
+             preserve_single_newlines={}, preserve_lists={}, tab_policy='{}', \
+             paragraph_sep='{}', \
+             strip_control_chars={}, strip_invisible={}, keep_joiners={}, strip_html={}, \
+             strip_markdown={}, \
+             preserve_code_blocks={}, preserve_tables={}, dehyphenate={})",
+            self.unicode_normalization.name(),
+            py_bool(self.fold_to_ascii),
+            py_bool(self.fix_mojibake),
+            py_bool(self.standardize_quotes),
+            py_bool(self.standardize_dashes),
+            py_bool(self.collapse_whitespace),
+            py_bool(self.preserve_single_newlines),
+            py_bool(self.preserve_lists),
+            self.tab_policy.name(),
+            self.paragraph_sep.name(),
+            py_bool(self.strip_control_chars),
+            py_bool(self.strip_invisible),
+            py_bool(self.keep_joiners),
+            py_bool(self.strip_html),
+            py_bool(self.strip_markdown),
+            py_bool(self.preserve_code_blocks),
+            py_bool(self.preserve_tables),
+            py_bool(self.dehyphenate),
+        )
+    }
+}
+
+/// Rejoin words hyphenated across line breaks, as in OCR or PDF-extracted text.
+///
+/// "inter-\nnational" becomes "international". Only lowercase alphabetic
+/// fragments on both sides of the break are joined, so "Coca-\nCola",
+/// "COVID-\n19" and "state-\nof-the-art" are kept; a two-part compound like
+/// "well-\nknown" is joined too, since it looks the same as hyphenation.
+///
+/// The same step runs inside `clean_text` with `CleanOptions(dehyphenate=True)`.
+#[pyfunction]
+fn dehyphenate(py: Python<'_>, text: &str) -> String {
+    py.allow_threads(|| dehyphenate_impl(text))
+}
+
+/// Mask personal data before text leaves your infrastructure, for example
+/// before sending chunks to a third-party embedding API.
+///
+/// Emails and US phone numbers are found as by `extract_metadata`; with
+/// `ssns=True`, so are US Social Security numbers ("123-45-6789" or "123 45
+/// 6789", skipping never-issued ones such as area 000 or 666). Each category
+/// is toggled by its keyword-only flag and only its matches are replaced, so
+/// the surrounding text is unchanged. A one-character `mask` ("*", "█") is
+/// repeated to the length of each match, keeping every character offset
+/// valid; a longer mask replaces each match whole.
+///
+/// Raises:
+///     InvalidOption: If `mask` is empty or contains whitespace or
+///         sentence-ending punctuation, which would change how the text is
+///         split into sentences for chunking
+#[pyfunction]
+#[pyo3(
+    name = "redact_pii",
+    signature = (text, mask="[REDACTED]", *, emails=true, phones=true, ssns=false)
+)]
+fn redact_pii_py(
+    py: Python<'_>,
+    text: &str,
+    mask: &str,
+    emails: bool,
+    phones: bool,
+    ssns: bool,
+) -> PyResult<String> {
+    redact::check_mask(mask).map_err(InvalidOption::new_err)?;
+    let categories = redact::PiiCategories {
+        emails,
+        phones,
+        ssns,
+    };
+    Ok(py.allow_threads(|| redact::redact_pii(text, mask, categories)))
+}
+
+/// Repair text that was UTF-8 but got decoded as Windows-1252 or Latin-1.
+///
+/// "CafÃ©" becomes "Café", "â€œHelloâ€\x9d" becomes "“Hello”" and "â€“" an
+/// en dash. Only the byte patterns typical of such damage are recognized
+/// (accented Latin letters and Latin-1 symbols, general punctuation, currency
+/// and letterlike symbols, and a leading "ï»¿"), so correctly decoded text is
+/// returned unchanged; text garbled twice is repaired too.
+///
+/// The same step runs inside `clean_text` with `CleanOptions(fix_mojibake=True)`.
+#[pyfunction]
+fn repair_mojibake(py: Python<'_>, text: &str) -> String {
+    py.allow_threads(|| mojibake::repair_mojibake(text))
+}
+
+/// Remove running headers, footers and page numbers from page-extracted text.
+///
+/// A line is dropped when it holds only a page number ("12", "- 12 -",
+/// "Page 12 of 40"), or when it has letters, is at most `max_line_length`
+/// characters long and occurs at least `min_occurrences` times in the
+/// document, its digits ignored so that "Confidential — Page 12" on every
+/// page is recognized. Only one number may differ between the repeats, so
+/// lines of a template filled in with figures ("Region 3 revenue rose 5%")
+/// stay. Longer lines are body text even when repeated, such
+/// as a disclaimer restated in each section. Short lines repeated as often
+/// go, table headers repeated on every page included. Documents with fewer
+/// than `min_lines` non-blank lines are returned unchanged. Blank lines are
+/// kept.
+///
+/// Works on lines, so run it on the extracted text before `clean_text`, which
+/// joins them.
+///
+/// Raises:
+///     InvalidOption: If min_occurrences is below 2
+#[pyfunction]
+#[pyo3(
+    name = "remove_boilerplate",
+    signature = (text, *, min_occurrences=3, min_lines=20, max_line_length=100)
+)]
+fn remove_boilerplate_py(
+    py: Python<'_>,
+    text: &str,
+    min_occurrences: usize,
+    min_lines: usize,
+    max_line_length: usize,
+) -> PyResult<String> {
+    if min_occurrences < 2 {
+        return Err(InvalidOption::new_err(format!(
+            "min_occurrences must be at least 2, got {min_occurrences}"
+        )));
+    }
+    Ok(py.allow_threads(|| {
+        boilerplate::remove_boilerplate(text, min_occurrences, min_lines, max_line_length)
+    }))
+}
+
+/// Clean and normalize text for downstream processing.
+///
+/// Performs:
+/// - Unicode normalization (NFKC) - converts compatibility characters to canonical forms
+/// - Whitespace collapsing
+/// - Quote/dash standardization
+/// - Control character removal
+///
+/// Pass a `CleanOptions` as `clean_options` to turn individual steps off or
+/// change the normalization form; omitting it keeps the defaults above.
+/// `options` is accepted as another name for it, and giving both raises
+/// `InvalidOption`.
+///
+/// The GIL is released while cleaning, so calls from several Python threads run in parallel.
+#[pyfunction]
+#[pyo3(name = "clean_text", signature = (text, *, clean_options=None, options=None))]
+fn clean_text_py(
+    py: Python<'_>,
+    text: &str,
+    clean_options: Option<CleanOptions>,
+    options: Option<CleanOptions>,
+) -> PyResult<String> {
+    if clean_options.is_some() && options.is_some() {
+        return Err(InvalidOption::new_err(
+            "clean_options and options name the same argument; pass only one",
+        ));
+    }
+    let options = clean_options.or(options).unwrap_or_default();
+    Ok(py.allow_threads(|| clean_text_with_options(text, &options)))
+}
+
+impl SizeUnit {
+    fn parse_py(name: &str) -> PyResult<Self> {
+        Self::parse(name).ok_or_else(|| {
+            InvalidOption::new_err(format!(
+                "size_unit must be \"chars\" or \"words\", got {name:?}"
+            ))
+        })
+    }
+}
+
+impl ChunkStrategy {
+    fn parse_py(name: &str) -> PyResult<Self> {
+        Self::parse(name).ok_or_else(|| {
+            InvalidOption::new_err(format!(
+                "strategy must be \"sentence\" or \"window\", got {name:?}"
+            ))
+        })
+    }
+}
+
+impl<'py> FromPyObject<'py> for OverlapArg {
+    fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
+        if ob.is_instance_of::<PyFloat>() {
+            ob.extract().map(OverlapArg::Fraction)
+        } else {
+            ob.extract().map(OverlapArg::Size)
+        }
+    }
+}
+
+/// Split text into chunks suitable for embedding.
+///
+/// Args:
+///     text: The input text to chunk
+///     target_size: Target chunk size in characters, or in words with
+///         size_unit="words" (default: 1500, roughly ~375 tokens)
+///     overlap: Number of characters (or words) to overlap between chunks
+///         (default: 200), or a float in [0, 1) for a fraction of target_size:
+///         `chunk_text(text, target_size=2000, overlap=0.15)` overlaps 300.
+///         An overlap of target_size or more is silently clamped to
+///         target_size - 1, so consecutive chunks repeat almost everything
+///         (see strict_overlap)
+///     overlap_ratio: Overlap as a fraction of target_size in [0, 1), e.g. 0.1 for
+///         10%; convenient when sweeping target_size
+///     overlap_sentences: Carry exactly this many whole trailing sentences into
+///         the next chunk instead of a character budget (fewer only when they
+///         would not leave room for the next sentence)
+///     preserve_code_blocks: Keep each ```-fenced (or ~~~-fenced) code block
+///         verbatim and whole, set off from surrounding sentences by blank lines
+///     preserve_lists: Pack each list item (a line starting with "-", "*",
+///         "•", "1." or "1)", with any indented lines after it) as one unit on
+///         its own line, like a sentence, so no item is split between chunks
+///         unless it is longer than target_size on its own
+///     min_chunk_size: Append a last chunk shorter than this many characters
+///         to the previous chunk instead of returning it on its own (default: 0,
+///         never). Its overlap with the previous chunk is not repeated, and the
+///         merged chunk may exceed target_size
+///     merge_all_small: Apply min_chunk_size to every chunk but the first,
+///         not just the last (default: False)
+///     size_unit: "chars" (default) or "words": what target_size, overlap,
+///         overlap_ratio and min_chunk_size count. Words are whitespace-delimited
+///         tokens, so "year-over-year" and "1,234.5" are one word each; the same
+///         sentence packing applies, and a sentence longer than target_size
+///         words is split between words
+///     boundary_pattern: A regex whose matches end sentences instead of the
+///         built-in punctuation rules, e.g. r";\s*" or r"\s+\([a-z]\)" for
+///         enumerated clauses. A sentence keeps the part of each match before
+///         its first whitespace; the part after it starts the next sentence
+///     ellipsis_terminal: End a sentence at every "..." or "…" followed by
+///         whitespace. By default an ellipsis is a pause within the sentence
+///         ("He paused... then continued.") unless a capitalized word or a
+///         line break follows it
+///     newline_is_boundary: End a sentence at every line break too, so lines
+///         without terminal punctuation, like headings, slide bullets or
+///         transcript lines ("Q3 Highlights\nRevenue up 12%"), become sentences
+///         of their own instead of fusing into one. The lines stay on separate
+///         lines within a chunk. Hard-wrapped prose is split at each wrap, so
+///         use it for line-oriented text
+///     respect_paragraphs: Carry no sentences from before a paragraph break
+///         (a blank line, or a code block kept by preserve_code_blocks) into
+///         the next chunk as overlap (default: False). A chunk whose first new
+///         sentence starts a paragraph then has no overlap, so the context it
+///         repeats always comes from its own paragraph
+///     strict_overlap: Raise InvalidChunkParams when overlap (or the overlap
+///         overlap_ratio gives) is not smaller than target_size, instead of
+///         clamping it to target_size - 1 (default: False, clamp)
+///     strategy: "sentence" (default) packs whole sentences as described
+///         above. "window" slices the text as written into windows of exactly
+///         target_size characters (or words), each starting target_size -
+///         overlap after the previous one, regardless of sentences; only the
+///         last window may be shorter. overlap must then be smaller than
+///         target_size, and the options that concern sentences
+///         (overlap_sentences, preserve_code_blocks, preserve_lists,
+///         min_chunk_size, merge_all_small, boundary_pattern, ellipsis_terminal,
+///         newline_is_boundary and respect_paragraphs) cannot be used
+///
+/// At most one of overlap, overlap_ratio and overlap_sentences may be given.
+///
+/// Sentences separated by a single line break, as cleaning with
+/// `CleanOptions(preserve_single_newlines=True)` leaves them, are joined with
+/// a newline rather than a space, so line-oriented text keeps its lines.
+///
+/// Everything after `text` is keyword-only, so `chunk_text(text, 1000, 100)` raises
+/// TypeError instead of silently mixing up sizes.
+///
+/// Returns:
+///     List of text chunks with sentence-boundary awareness. No chunk is longer
+///     than target_size: a sentence that is longer on its own is split into
+///     windows at word boundaries (mid-word only for a single overlong word).
+///     The exceptions are a code block kept whole by preserve_code_blocks and
+///     a chunk that small chunks were merged into.
+///
+/// The GIL is released while chunking.
+///
+/// Raises:
+///     InvalidChunkParams: If target_size is 0, overlap_ratio or a float
+///         overlap is outside [0, 1), more than one overlap argument is given,
+///         overlap is not smaller than target_size with strict_overlap=True
+///         or strategy="window", or, with strategy="window", overlap_sentences
+///         is given
+///     InvalidOption: If size_unit is not "chars" or "words", strategy is not
+///         "sentence" or "window", boundary_pattern is not a valid regex or
+///         matches empty text, or a sentence option is used with
+///         strategy="window"
+#[pyfunction]
+#[pyo3(name = "chunk_text", signature = (
+    text,
+    *,
+    target_size=1500,
+    overlap=None,
+    overlap_ratio=None,
+    overlap_sentences=None,
+    preserve_code_blocks=false,
+    preserve_lists=false,
+    min_chunk_size=0,
+    merge_all_small=false,
+    size_unit="chars",
+    boundary_pattern=None,
+    ellipsis_terminal=false,
+    newline_is_boundary=false,
+    respect_paragraphs=false,
+    strict_overlap=false,
+    strategy="sentence"
+))]
+#[allow(clippy::too_many_arguments)]
+fn chunk_text_py(
+    py: Python<'_>,
+    text: &str,
+    target_size: usize,
+    overlap: Option<OverlapArg>,
+    overlap_ratio: Option<f64>,
+    overlap_sentences: Option<usize>,
+    preserve_code_blocks: bool,
+    preserve_lists: bool,
+    min_chunk_size: usize,
+    merge_all_small: bool,
+    size_unit: &str,
+    boundary_pattern: Option<&str>,
+    ellipsis_terminal: bool,
+    newline_is_boundary: bool,
+    respect_paragraphs: bool,
+    strict_overlap: bool,
+    strategy: &str,
+) -> PyResult<Vec<String>> {
+    let overlap = resolve_overlap(target_size, overlap, overlap_ratio, overlap_sentences)
+        .map_err(InvalidChunkParams::new_err)?;
+    if strict_overlap {
+        check_strict_overlap(target_size, overlap).map_err(InvalidChunkParams::new_err)?;
+    }
+    let size_unit = SizeUnit::parse_py(size_unit)?;
+    if ChunkStrategy::parse_py(strategy)? == ChunkStrategy::Window {
+        let sentence_options = [
+            ("preserve_code_blocks", preserve_code_blocks),
+            ("preserve_lists", preserve_lists),
+            ("min_chunk_size", min_chunk_size > 0),
+            ("merge_all_small", merge_all_small),
+            ("boundary_pattern", boundary_pattern.is_some()),
+            ("ellipsis_terminal", ellipsis_terminal),
+            ("newline_is_boundary", newline_is_boundary),
+            ("respect_paragraphs", respect_paragraphs),
+        ];
+        if let Some((name, _)) = sentence_options.iter().find(|(_, set)| *set) {
+            return Err(InvalidOption::new_err(format!(
+                "{name} only applies to strategy=\"sentence\""
+            )));
+        }
+        let Overlap::Size(overlap) = overlap else {
+            return Err(InvalidChunkParams::new_err(
+                "overlap_sentences only applies to strategy=\"sentence\"",
+            ));
+        };
+        return py
+            .allow_threads(|| window::window_chunks(text, target_size, overlap, size_unit))
+            .map_err(InvalidChunkParams::new_err);
+    }
+    let boundary = boundary_pattern
+        .map(compile_boundary_pattern)
+        .transpose()
+        .map_err(InvalidOption::new_err)?;
+    py.allow_threads(|| {
+        chunk_text_with_min_size(
+            text,
+            target_size,
+            overlap,
+            &UnitOptions {
+                preserve_code_blocks,
+                preserve_lists,
+                boundary: boundary.as_ref(),
+                ellipsis_terminal,
+                newline_is_boundary,
+                respect_paragraphs,
+                ..UnitOptions::default()
+            },
+            size_unit,
+            min_chunk_size,
+            merge_all_small,
+        )
+    })
+    .ok_or_else(|| InvalidChunkParams::new_err("target_size must be greater than 0"))
+}
+
+/// Split text into chunks of whole sentences, sized in words.
+///
+/// Sentences are found as in `chunk_text` and packed until the next one would
+/// take a chunk past max_words whitespace-delimited words ("year-over-year" and
+/// "$1,234.5" are one word each).
+///
+/// Args:
+///     text: The input text to chunk
+///     max_words: Most words in a chunk (default: 300)
+///     overlap_words: Words of trailing sentences to repeat at the start of the
+///         next chunk (default: 50). Only whole sentences are carried, so the
+///         overlap approximates this: the last sentence is always carried, and
+///         further ones only while they fit
+///
+/// Unlike `chunk_text(size_unit="words")`, a single sentence longer than
+/// max_words is never split: it is returned whole as a chunk of its own, the
+/// only case where a chunk exceeds max_words.
+///
+/// The GIL is released while chunking.
+///
+/// Raises:
+///     InvalidChunkParams: If max_words is 0
+#[pyfunction]
+#[pyo3(signature = (text, *, max_words=300, overlap_words=50))]
+fn chunk_text_by_words(
+    py: Python<'_>,
+    text: &str,
+    max_words: usize,
+    overlap_words: usize,
+) -> PyResult<Vec<String>> {
+    py.allow_threads(|| chunk_text_by_words_impl(text, max_words, overlap_words))
+        .ok_or_else(|| InvalidChunkParams::new_err("max_words must be greater than 0"))
+}
+
+/// Split Markdown into chunks that respect its section structure.
+///
+/// The text is cut at heading lines ("# ..." to "###### ...", ignoring those
+/// inside fenced code blocks) and each section is chunked like `chunk_text`, so
+/// no chunk mixes text from two sections. Every chunk is prefixed with its
+/// heading trail and a blank line, e.g. "## Revenue > ### North America\n\n...".
+/// Text before the first heading gets no prefix, and a document without
+/// headings is chunked exactly like `chunk_text`.
+///
+/// Args:
+///     text: The Markdown to chunk
+///     target_size: Target chunk size in characters, heading trail included
+///         (default: 1500); only a trail longer than half of it can push a
+///         chunk past target_size
+///     overlap: Characters to overlap between chunks of the same section
+///         (default: 200)
+///
+/// The GIL is released while chunking.
+///
+/// Raises:
+///     InvalidChunkParams: If target_size is 0
+#[pyfunction]
+#[pyo3(signature = (text, *, target_size=1500, overlap=DEFAULT_OVERLAP))]
+fn chunk_markdown(
+    py: Python<'_>,
+    text: &str,
+    target_size: usize,
+    overlap: usize,
+) -> PyResult<Vec<String>> {
+    py.allow_threads(|| markdown::chunk_markdown_impl(text, target_size, overlap))
+        .ok_or_else(|| InvalidChunkParams::new_err("target_size must be greater than 0"))
+}
+
+/// Split text into chunks along its structure, LangChain-style.
+///
+/// The text is split at the first separator that occurs in it, and only pieces
+/// still longer than target_size are split again with the next ones, so a
+/// paragraph that fits is never divided. Consecutive pieces that fit are
+/// packed into chunks and joined with the separator that divided them.
+///
+/// Args:
+///     text: The input text to chunk
+///     target_size: Maximum chunk size in characters (default: 1500)
+///     overlap: Characters to overlap between chunks (default: 200), carried
+///         as whole pieces exactly like `chunk_text`'s overlap; nothing is
+///         carried across a piece that had to be split further
+///     separators: Separators to try, largest first (default: ["\n\n",
+///         "\n", SENTENCE_SEPARATOR, " ", ""]). `rag_rust_core.SENTENCE_SEPARATOR`
+///         splits with `chunk_text`'s sentence rules, "" between characters;
+///         any other string splits at each occurrence. A piece none of them
+///         divides is returned whole, even when longer than target_size
+///     min_chunk_size: Append any chunk but the first that is shorter than
+///         this many characters to the previous chunk, which may then exceed
+///         target_size (default: 0, never). Unlike `chunk_text`, this is not
+///         limited to the last chunk, since a short paragraph or heading next
+///         to one that had to be split ends up alone mid-document too
+///
+/// With the default separators, text without line breaks whose sentences each
+/// fit in target_size is chunked exactly like `chunk_text`.
+///
+/// The GIL is released while chunking.
+///
+/// Raises:
+///     InvalidChunkParams: If target_size is 0
+#[pyfunction]
+#[pyo3(signature = (
+    text,
+    *,
+    target_size=1500,
+    overlap=DEFAULT_OVERLAP,
+    separators=None,
+    min_chunk_size=0
+))]
+fn chunk_text_recursive(
+    py: Python<'_>,
+    text: &str,
+    target_size: usize,
+    overlap: usize,
+    separators: Option<Vec<String>>,
+    min_chunk_size: usize,
+) -> PyResult<Vec<String>> {
+    let separators = match separators {
+        Some(list) => list
+            .iter()
+            .map(|s| recursive::Separator::parse(s))
+            .collect(),
+        None => recursive::Separator::defaults(),
+    };
+    py.allow_threads(|| {
+        recursive::chunk_text_recursive_impl(
+            text,
+            target_size,
+            overlap,
+            &separators,
+            min_chunk_size,
+        )
+    })
+    .ok_or_else(|| InvalidChunkParams::new_err("target_size must be greater than 0"))
+}
+
+/// Iterate over the chunks `chunk_text` would return, one at a time.
+///
+/// `ChunkIterator(text, *, target_size=1500, overlap=None, ...)` takes the same
+/// arguments as `chunk_text` except `preserve_code_blocks`, `preserve_lists`
+/// and `strategy` (sentence packing only), and yields exactly the chunks it
+/// returns for them. Sentences are found and packed only as chunks are
+/// requested, so instead of the whole list the iterator holds its copy of the
+/// text, the chunk being built and the sentences carried over as overlap,
+/// which keeps memory flat when streaming a multi-megabyte document into an
+/// embedding queue.
+///
+/// `text` may also be an iterable of str pieces, such as blocks read from a
+/// file, which are taken only as they are needed and need not end at sentence
+/// boundaries: the chunks are those of the pieces joined with no separator.
+/// Only the text not yet packed is kept, so memory stays on the order of a
+/// chunk (plus a piece) however long the whole text is.
+///
+/// The GIL is released while each chunk is packed.
+///
+/// Raises:
+///     InvalidChunkParams: As `chunk_text`, when the iterator is created
+///     InvalidOption: As `chunk_text`, when the iterator is created
+///     TypeError: If text is neither a str nor an iterable, or, while
+///         iterating, a piece is not a str
+#[pyclass(module = "rag_rust_core")]
+pub struct ChunkIterator {
+    stream: stream::ChunkStream,
+    /// The rest of an iterable `text`, until it is exhausted.
+    pieces: Option<Py<PyIterator>>,
+}
+
+#[pymethods]
+impl ChunkIterator {
+    #[new]
+    #[pyo3(signature = (
+        text,
+        *,
+        target_size=1500,
+        overlap=None,
+        overlap_ratio=None,
+        overlap_sentences=None,
+        min_chunk_size=0,
+        merge_all_small=false,
+        size_unit="chars",
+        boundary_pattern=None,
+        ellipsis_terminal=false,
+        newline_is_boundary=false
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        text: &Bound<'_, PyAny>,
+        target_size: usize,
+        overlap: Option<OverlapArg>,
+        overlap_ratio: Option<f64>,
+        overlap_sentences: Option<usize>,
+        min_chunk_size: usize,
+        merge_all_small: bool,
+        size_unit: &str,
+        boundary_pattern: Option<&str>,
+        ellipsis_terminal: bool,
+        newline_is_boundary: bool,
+    ) -> PyResult<Self> {
+        let overlap = resolve_overlap(target_size, overlap, overlap_ratio, overlap_sentences)
+            .map_err(InvalidChunkParams::new_err)?;
+        let size_unit = SizeUnit::parse_py(size_unit)?;
+        let boundary = boundary_pattern
+            .map(compile_boundary_pattern)
+            .transpose()
+            .map_err(InvalidOption::new_err)?;
+        let (text, pieces) = match text.downcast::<PyString>() {
+            Ok(text) => (text.to_str()?.to_string(), None),
+            Err(_) => (String::new(), Some(text.try_iter()?.unbind())),
+        };
+        let stream = stream::ChunkStream::new(
+            text,
+            target_size,
+            overlap,
+            size_unit,
+            min_chunk_size,
+            merge_all_small,
+            boundary,
+            ellipsis_terminal,
+            newline_is_boundary,
+        )
+        .ok_or_else(|| InvalidChunkParams::new_err("target_size must be greater than 0"))?;
+        let stream = match pieces {
+            Some(_) => stream.awaiting_input(),
+            None => stream,
+        };
+        Ok(ChunkIterator { stream, pieces })
+    }
+
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__(&mut self, py: Python<'_>) -> PyResult<Option<String>> {
+        loop {
+            let stream = &mut self.stream;
+            if let Ok(chunk) = py.allow_threads(|| stream.poll_next()) {
+                return Ok(chunk);
+            }
+            // The stream needs the next piece of the text, or to know there is none
+            let piece = match &self.pieces {
+                Some(pieces) => pieces.bind(py).clone().next().transpose()?,
+                None => None,
+            };
+            match piece {
+                Some(piece) => self
+                    .stream
+                    .push_text(piece.downcast::<PyString>()?.to_str()?),
+                None => {
+                    self.pieces = None;
+                    self.stream.end_input();
+                }
+            }
+        }
+    }
+}
+
+/// Iterate lazily over the chunks of `text`, a str or an iterable of str
+/// pieces; the same as `ChunkIterator(text, ...)`, which it returns.
+///
+/// Feeding a large file in blocks keeps only about a chunk of it in memory:
+///
+/// ```text
+/// with open(path) as f:
+///     for chunk in chunk_text_iter(iter(lambda: f.read(1 << 16), "")):
+///         ...
+/// ```
+///
+/// Raises:
+///     InvalidChunkParams: As `chunk_text`
+///     InvalidOption: As `chunk_text`
+///     TypeError: As `ChunkIterator`
+#[pyfunction]
+#[pyo3(signature = (
+    text,
+    *,
+    target_size=1500,
+    overlap=None,
+    overlap_ratio=None,
+    overlap_sentences=None,
+    min_chunk_size=0,
+    merge_all_small=false,
+    size_unit="chars",
+    boundary_pattern=None,
+    ellipsis_terminal=false,
+    newline_is_boundary=false
+))]
+#[allow(clippy::too_many_arguments)]
+fn chunk_text_iter(
+    text: &Bound<'_, PyAny>,
+    target_size: usize,
+    overlap: Option<OverlapArg>,
+    overlap_ratio: Option<f64>,
+    overlap_sentences: Option<usize>,
+    min_chunk_size: usize,
+    merge_all_small: bool,
+    size_unit: &str,
+    boundary_pattern: Option<&str>,
+    ellipsis_terminal: bool,
+    newline_is_boundary: bool,
+) -> PyResult<ChunkIterator> {
+    ChunkIterator::new(
+        text,
+        target_size,
+        overlap,
+        overlap_ratio,
+        overlap_sentences,
+        min_chunk_size,
+        merge_all_small,
+        size_unit,
+        boundary_pattern,
+        ellipsis_terminal,
+        newline_is_boundary,
+    )
+}
+
+/// Convert parsed amounts into a list of `{"raw", "value", "currency", "scale"}` dicts.
+fn parsed_money_to_py(py: Python<'_>, parsed: Vec<ParsedMoney>) -> PyResult<Vec<Py<PyDict>>> {
+    parsed
+        .into_iter()
+        .map(|p| {
+            let dict = PyDict::new(py);
+            dict.set_item("raw", p.raw)?;
+            dict.set_item("value", p.value)?;
+            dict.set_item("currency", p.currency)?;
+            dict.set_item("scale", p.scale)?;
+            Ok(dict.into())
+        })
+        .collect()
+}
+
+/// Convert parsed percentages into a list of `{"raw", "value", "unit"}` dicts.
+fn parsed_percentages_to_py(
+    py: Python<'_>,
+    parsed: Vec<ParsedPercentage>,
+) -> PyResult<Vec<Py<PyDict>>> {
+    parsed
+        .into_iter()
+        .map(|p| {
+            let dict = PyDict::new(py);
+            dict.set_item("raw", p.raw)?;
+            dict.set_item("value", p.value)?;
+            dict.set_item("unit", p.unit)?;
+            Ok(dict.into())
+        })
+        .collect()
+}
+
+/// Convert ranges into a list of `{"raw", "kind", "low", "high", "currency", "unit"}` dicts.
+fn parsed_ranges_to_py(py: Python<'_>, parsed: Vec<ParsedRange>) -> PyResult<Vec<Py<PyDict>>> {
+    parsed
+        .into_iter()
+        .map(|r| {
+            let dict = PyDict::new(py);
+            dict.set_item("raw", r.raw)?;
+            dict.set_item("kind", r.kind)?;
+            dict.set_item("low", r.low)?;
+            dict.set_item("high", r.high)?;
+            dict.set_item("currency", r.currency)?;
+            dict.set_item("unit", r.unit)?;
+            Ok(dict.into())
+        })
+        .collect()
+}
+
+/// Convert per-share amounts into a list of `{"raw", "value", "currency", "basis"}` dicts.
+fn per_share_amounts_to_py(
+    py: Python<'_>,
+    amounts: Vec<PerShareAmount>,
+) -> PyResult<Vec<Py<PyDict>>> {
+    amounts
+        .into_iter()
+        .map(|a| {
+            let dict = PyDict::new(py);
+            dict.set_item("raw", a.raw)?;
+            dict.set_item("value", a.value)?;
+            dict.set_item("currency", a.currency)?;
+            dict.set_item("basis", a.basis)?;
+            Ok(dict.into())
+        })
+        .collect()
+}
+
+/// Convert share counts into a list of `{"raw", "value", "basis"}` dicts.
+fn share_counts_to_py(py: Python<'_>, counts: Vec<ShareCount>) -> PyResult<Vec<Py<PyDict>>> {
+    counts
+        .into_iter()
+        .map(|c| {
+            let dict = PyDict::new(py);
+            dict.set_item("raw", c.raw)?;
+            dict.set_item("value", c.value)?;
+            dict.set_item("basis", c.basis)?;
+            Ok(dict.into())
+        })
+        .collect()
+}
+
+/// Convert parsed dates into a list of `{"raw", "kind", "normalized", "valid"}` dicts.
+///
+/// `normalized` is "YYYY-MM-DD" for days, "YYYY-MM" for months, "YYYY" for
+/// years and a `{"year", "quarter"}` dict for quarters; it is None when the
+/// date is invalid.
+fn parsed_dates_to_py(py: Python<'_>, parsed: Vec<ParsedDate>) -> PyResult<Vec<Py<PyDict>>> {
+    parsed
+        .into_iter()
+        .map(|p| {
+            let dict = PyDict::new(py);
+            dict.set_item("raw", p.raw)?;
+            dict.set_item("kind", p.value.map(|v| v.kind()))?;
+            let valid = p.value.filter(DateValue::is_valid);
+            match valid {
+                Some(DateValue::Quarter { year, quarter }) => {
+                    let normalized = PyDict::new(py);
+                    normalized.set_item("year", year)?;
+                    normalized.set_item("quarter", quarter)?;
+                    dict.set_item("normalized", normalized)?;
+                }
+                _ => dict.set_item("normalized", valid.and_then(|v| v.iso()))?,
+            }
+            dict.set_item("valid", valid.is_some())?;
+            Ok(dict.into())
+        })
+        .collect()
+}
+
+/// Convert fiscal periods into a list of `{"raw", "kind", "number", "year"}` dicts.
+fn parsed_fiscal_periods_to_py(
+    py: Python<'_>,
+    parsed: Vec<ParsedFiscalPeriod>,
+) -> PyResult<Vec<Py<PyDict>>> {
+    parsed
+        .into_iter()
+        .map(|p| {
+            let dict = PyDict::new(py);
+            dict.set_item("raw", p.raw)?;
+            dict.set_item("kind", p.kind)?;
+            dict.set_item("number", p.number)?;
+            dict.set_item("year", p.year)?;
+            Ok(dict.into())
+        })
+        .collect()
+}
+
+/// Convert tickers into a list of `{"symbol", "exchange"}` dicts.
+fn explicit_tickers_to_py(
+    py: Python<'_>,
+    tickers: Vec<ExplicitTicker>,
+) -> PyResult<Vec<Py<PyDict>>> {
+    tickers
+        .into_iter()
+        .map(|t| {
+            let dict = PyDict::new(py);
+            dict.set_item("symbol", t.symbol)?;
+            dict.set_item("exchange", t.exchange)?;
+            Ok(dict.into())
+        })
+        .collect()
+}
+
+/// Convert unvalidated security identifiers into a list of `{"raw", "kind"}` dicts.
+fn unvalidated_ids_to_py(py: Python<'_>, ids: Vec<UnvalidatedId>) -> PyResult<Vec<Py<PyDict>>> {
+    ids.into_iter()
+        .map(|id| {
+            let dict = PyDict::new(py);
+            dict.set_item("raw", id.raw)?;
+            dict.set_item("kind", id.kind)?;
+            Ok(dict.into())
+        })
+        .collect()
+}
+
+/// Convert the amounts that carry a currency into a list of `{"raw", "currency"}` dicts.
+fn money_details_to_py(py: Python<'_>, amounts: &[ParsedMoney]) -> PyResult<Vec<Py<PyDict>>> {
+    amounts
+        .iter()
+        .filter_map(|m| Some((&m.raw, m.currency.as_ref()?)))
+        .map(|(raw, currency)| {
+            let dict = PyDict::new(py);
+            dict.set_item("raw", raw)?;
+            dict.set_item("currency", currency)?;
+            Ok(dict.into())
+        })
+        .collect()
+}
+
+/// Convert (raw, value, sign_inferred) triples into a list of
+/// `{"raw", "value", "sign_inferred"}` dicts.
+fn normalized_values_to_py<'a>(
+    py: Python<'_>,
+    values: impl Iterator<Item = (&'a str, Option<f64>, bool)>,
+) -> PyResult<Vec<Py<PyDict>>> {
+    values
+        .map(|(raw, value, sign_inferred)| {
+            let dict = PyDict::new(py);
+            dict.set_item("raw", raw)?;
+            dict.set_item("value", value)?;
+            dict.set_item("sign_inferred", sign_inferred)?;
+            Ok(dict.into())
+        })
+        .collect()
+}
+
+impl Metadata {
+    /// Build the dict returned by `extract_metadata`.
+    fn into_py_dict(self, py: Python<'_>) -> PyResult<Py<PyDict>> {
+        let dict = PyDict::new(py);
+        // Every monetary_amounts*, percentage* and dates* key is read off one record per match
+        let money = self.monetary_amounts;
+        dict.set_item(
+            "monetary_amounts",
+            money.iter().map(|m| m.raw.as_str()).collect::<Vec<_>>(),
+        )?;
+        dict.set_item(
+            "monetary_amounts_detailed",
+            money_details_to_py(py, &money)?,
+        )?;
+        dict.set_item(
+            "monetary_amounts_normalized",
+            normalized_values_to_py(
+                py,
+                money
+                    .iter()
+                    .map(|m| (m.raw.as_str(), m.value, m.sign_inferred)),
+            )?,
+        )?;
+        dict.set_item(
+            "monetary_values",
+            money.iter().map(|m| m.value).collect::<Vec<_>>(),
+        )?;
+        dict.set_item("monetary_amounts_parsed", parsed_money_to_py(py, money)?)?;
+        dict.set_item(
+            "monetary_amounts_spelled",
+            parsed_money_to_py(py, self.monetary_amounts_spelled)?,
+        )?;
+        let percentages = self.percentages;
+        dict.set_item(
+            "percentages",
+            percentages
+                .iter()
+                .map(|p| p.raw.as_str())
+                .collect::<Vec<_>>(),
+        )?;
+        dict.set_item(
+            "percentages_normalized",
+            normalized_values_to_py(
+                py,
+                percentages
+                    .iter()
+                    .map(|p| (p.raw.as_str(), p.value, p.sign_inferred)),
+            )?,
+        )?;
+        dict.set_item(
+            "percentage_values",
+            percentages.iter().map(|p| p.value).collect::<Vec<_>>(),
+        )?;
+        dict.set_item(
+            "percentages_parsed",
+            parsed_percentages_to_py(py, percentages)?,
+        )?;
+        dict.set_item("ranges", parsed_ranges_to_py(py, self.ranges)?)?;
+        dict.set_item(
+            "per_share_amounts",
+            per_share_amounts_to_py(py, self.per_share_amounts)?,
+        )?;
+        dict.set_item("share_counts", share_counts_to_py(py, self.share_counts)?)?;
+        let dates = self.dates;
+        dict.set_item(
+            "dates",
+            dates.iter().map(|d| d.raw.as_str()).collect::<Vec<_>>(),
+        )?;
+        dict.set_item(
+            "dates_normalized",
+            dates
+                .iter()
+                .map(|d| d.value.and_then(|v| v.canonical()))
+                .collect::<Vec<_>>(),
+        )?;
+        dict.set_item(
+            "dates_format_overridden",
+            dates
+                .iter()
+                .filter(|d| d.format_overridden)
+                .map(|d| d.raw.as_str())
+                .collect::<Vec<_>>(),
+        )?;
+        dict.set_item("dates_parsed", parsed_dates_to_py(py, dates)?)?;
+        dict.set_item("fiscal_periods", self.fiscal_periods)?;
+        dict.set_item(
+            "fiscal_periods_parsed",
+            parsed_fiscal_periods_to_py(py, self.fiscal_periods_parsed)?,
+        )?;
+        dict.set_item("potential_tickers", self.potential_tickers)?;
+        dict.set_item(
+            "potential_tickers_detailed",
+            explicit_tickers_to_py(py, self.potential_tickers_detailed)?,
+        )?;
+        let ticker_counts = PyDict::new(py);
+        for t in self.ticker_counts {
+            ticker_counts.set_item(t.symbol, t.count)?;
+        }
+        dict.set_item("ticker_counts", ticker_counts)?;
+        dict.set_item(
+            "tickers_confident",
+            explicit_tickers_to_py(py, self.tickers_confident)?,
+        )?;
+        dict.set_item("urls", self.urls)?;
+        dict.set_item("emails", self.emails)?;
+        dict.set_item("phone_numbers", self.phone_numbers)?;
+        dict.set_item("isins", self.isins)?;
+        dict.set_item("cusips", self.cusips)?;
+        dict.set_item("sedols", self.sedols)?;
+        for (name, matches) in self.custom {
+            dict.set_item(name, matches)?;
+        }
+        Ok(dict.into())
+    }
+}
+
+/// Extract financial metadata from text.
+///
+/// Finds monetary amounts, percentages, ranges, dates, fiscal periods,
+/// per-share figures, share counts, tickers, URLs, email addresses, phone
+/// numbers and security identifiers, each category under the keys listed
+/// below. Everything after `text` is keyword-only.
+///
+/// Args:
+///     infer_sign: Let a direction cue that directly governs a value ("down
+///         $200 million", "a decline of 5%") make its normalized `value`
+///         negative and set `sign_inferred`; `raw` always stays as written
+///     extra_stopwords: Symbols dropped from both ticker lists on top of the
+///         built-in stopwords (a set or list)
+///     allowlist: Keep only these symbols in both ticker lists, stopwords
+///         included, and drop everything else (a set or list)
+///     ticker_order: "alphabetical" (default) orders `potential_tickers`,
+///         `potential_tickers_detailed` and `ticker_counts` by symbol;
+///         "document" by first appearance
+///     date_format: "us" (default) reads slash dates month first ("03/04/2024"
+///         is March 4), "eu" day first (April 3)
+///     frequencies: Add the `*_counts` dicts below
+///     include_unvalidated: Add `security_ids_unvalidated`
+///
+/// To reuse one ticker configuration across many calls without converting the
+/// sets each time, build a `MetadataExtractor` instead.
+///
+/// Metadata keys:
+///     monetary_amounts: Amounts with the currency before or after the number
+///         ($X million, €4.2 billion, EUR 1.2B, £30m, 5,2 Mio. €, 300 CHF,
+///         5 million dollars), including negatives ("-$5M", "$(1.2) million",
+///         "($1.2 million)")
+///     monetary_amounts_detailed: `monetary_amounts` as `{"raw", "currency"}`
+///         dicts. Currency is an ISO code, except "¥" which is reported as the
+///         bare symbol since it is used for both yen and renminbi. Spelled-out
+///         names map to their code ("dollars" -> "USD", "pounds sterling" ->
+///         "GBP")
+///     monetary_amounts_normalized, percentages_normalized: One `{"raw",
+///         "value", "sign_inferred"}` dict per match, aligned with the raw
+///         lists, with the sign inferred under `infer_sign`
+///     monetary_values, percentage_values: Plain lists of those values aligned
+///         with `monetary_amounts` and `percentages`: floats in base units, or
+///         in percent units for percentages (so "40 bps" is 0.4), None where a
+///         match could not be parsed
+///     monetary_amounts_parsed: One `{"raw", "value", "currency", "scale"}`
+///         dict per amount, where `scale` is "thousand", "million", "billion"
+///         (also for "bn", "Mio.", "Mrd.") or None; unparseable amounts keep
+///         their entry with `value` None. European decimal commas ("5,2 Mio.
+///         €") parse as decimals
+///     monetary_amounts_spelled: Amounts written in words ("five million
+///         dollars", "twenty-five thousand euros", "two hundred and fifty
+///         million pounds sterling") in the same dict form, with `scale` the
+///         largest scale word. Only number words from "one" up, "hundred" and
+///         "thousand" to "trillion" followed by a currency name are recognized:
+///         not "a million dollars", fractions, or amounts without a currency
+///         word ("two billion in revenue"). Words that do not form a number
+///         ("five five dollars") give `value` None. These amounts are never in
+///         `monetary_amounts`
+///     percentages: "12.5%", "12.5 percent", "3.5 pct", "40 basis points", "40
+///         bps", "-3%", "(3%)", "(3.5)%"
+///     percentages_parsed: One `{"raw", "value", "unit"}` dict per
+///         percentage, where `unit` is "percent" or "basis_points". Negative
+///         ("-3%", "-$5M") and parenthesized ("(3%)", "(3.5)%", "$(1.2)
+///         million", "($1.2 million)") amounts and percentages parse as
+///         negative, with `raw` keeping the sign and parentheses as written
+///     ranges: Ranges of two amounts or two percentages ("$5.0 to $5.2
+///         billion", "$5–7 million", "between 3% and 4%", "40–60 bps"), of
+///         years ("2020–2024", "2019-23") and of plain numbers ("10 to 12
+///         million units", "3–5 years") as `{"raw", "kind", "low", "high",
+///         "currency", "unit"}` dicts in document order. `kind` is "money",
+///         "percentage", "year" or "number"; a bound written without the
+///         currency, scale or unit of the other one takes it over, so `low`
+///         and `high` are in base units, percent units, years ("2019-23" ends
+///         in 2023.0) or numbers with the scale applied. `currency` is set for
+///         money ranges and `unit` ("percent" or "basis_points") for
+///         percentage ranges. The bounds may be joined by "to", a hyphen, an en
+///         or em dash, or "between ... and"; year and number ranges must
+///         ascend, and a number range is only reported where no amount,
+///         percentage, year range, date or phone number covers it. Each bound
+///         of a money or percentage range also stays in `monetary_amounts` or
+///         `percentages` when it matches there on its own, so count either the
+///         range or its bounds, not both. Amounts with the currency after the
+///         number ("5–7 Mio. €") are not read as ranges
+///     per_share_amounts: Monetary amounts stated per share as `{"raw",
+///         "value", "currency", "basis"}` dicts in document order: those
+///         followed by "per share", "per diluted share" or "a share" ("$0.45
+///         per share"), or preceded in their own clause, with no other amount
+///         in between, by "EPS" or "earnings per share" ("diluted EPS of
+///         $1.23"). `basis` is "basic" or "diluted" when the cue says which,
+///         else None. These amounts also stay in `monetary_amounts`
+///     share_counts: Numbers of shares as `{"raw", "value", "basis"}` dicts: a
+///         number right before "shares", optionally with qualifiers
+///         ("3,400,000 shares", "2.1 million diluted shares"), or after
+///         "shares ... of/were" ("weighted average diluted shares were 105.3
+///         million"), with the scale word applied to `value`
+///     dates: Q1 2024, January 15, 2024, etc. Slash dates are read as
+///         `date_format` says, except that one only real in the other order
+///         ("13/04/2024") is read in that order. Dotted dates are always day
+///         first ("15.1.2024"). Bare years are only dates after "fiscal",
+///         "calendar" or "FY" ("fiscal 2023", "FY2024"), never on their own
+///     dates_format_overridden: The slash dates read in the order
+///         `date_format` does not say
+///     dates_parsed: `dates` as `{"raw", "kind", "normalized", "valid"}`
+///         dicts: `kind` is "day", "month", "quarter" or "year", and
+///         `normalized` is "YYYY-MM-DD", "YYYY-MM", `{"year", "quarter"}` or
+///         "YYYY" respectively. Impossible dates ("February 30, 2024") have
+///         `valid` False and `normalized` None
+///     dates_normalized: Plain list aligned with `dates` for sorting and range
+///         filters: "YYYY-MM-DD" for days, "YYYY-Qn" for quarters ("Q3 2023"
+///         -> "2023-Q3"), and None for dates without a day ("January 2024") or
+///         that are impossible
+///     fiscal_periods: "FY2024", "FY23", "fiscal year 2023", "Q1 FY2025",
+///         "3Q24", "H1 2023", "first half of 2024", "the second quarter of
+///         fiscal 2025", "three months ended June 30, 2024" and "QoQ", "YoY",
+///         "YTD" references, deduplicated in document order. Two-digit fiscal
+///         years and the period tokens are not dates, so they never appear in
+///         `dates`; a bare "Q1 2024" is a date only
+///     fiscal_periods_parsed: `fiscal_periods` as `{"raw", "kind", "number",
+///         "year"}` dicts: `kind` is "FY", "H", "Q", "9M" (nine months ended)
+///         or the token itself ("QoQ", "YoY", "YTD"), `number` the half or
+///         quarter, and `year` the four-digit year (two-digit ones read as
+///         20xx), each None where the text does not say. "Three months ended"
+///         a date is the calendar quarter of that date, "six months ended" June
+///         or December the first or second half, and "twelve months ended" the
+///         fiscal year ending then
+///     potential_tickers: Potential ticker symbols, including
+///         exchange-qualified ones ("NASDAQ:AAPL", "NYSE: MSFT"), ordered as
+///         `ticker_order` says and filtered by the stopword list
+///     potential_tickers_detailed: One `{"symbol", "exchange"}` dict per
+///         symbol of `potential_tickers`; `exchange` is set when the symbol was
+///         written exchange-qualified anywhere in the text
+///     ticker_counts: How many times each symbol in `potential_tickers` occurs
+///     tickers_confident: One `{"symbol", "exchange"}` dict per distinct
+///         cashtag ("$AAPL") or exchange-qualified symbol ("NASDAQ: AAPL",
+///         "NYSE:BRK.B") in document order, with `exchange` None for cashtags.
+///         These forms bypass the stopword list
+///     urls: http/https links and bare www. domains, deduplicated in document
+///         order, with trailing sentence punctuation stripped
+///     emails, phone_numbers: Email addresses and US phone numbers ("(212)
+///         555-0123", "212-555-0123", "+1 212 555 0123"), deduplicated in
+///         document order
+///     isins, cusips, sedols: Security identifiers ("US0378331005",
+///         "037833100", "B0YBKJ7"), deduplicated in document order and only
+///         with a correct check digit. A SEDOL of digits only must follow a
+///         "SEDOL" label, as any seven-digit number has its shape
+///     security_ids_unvalidated: With `include_unvalidated=True`,
+///         identifier-shaped strings failing the check as `{"raw", "kind"}`
+///         dicts in document order, `kind` being "isin", "cusip" or "sedol"
+///     *_counts: With `frequencies=True`, how many times each amount,
+///         percentage, date and fiscal period occurs, under
+///         `monetary_amounts_counts`, `percentages_counts`, `dates_counts` and
+///         `fiscal_periods_counts`, as dicts with their keys in sorted order
+///         (`{"$5 million": 2}`). The lists themselves keep every occurrence
+///
+/// Returns:
+///     A dict with the keys above. The GIL is released while scanning and
+///     only re-acquired to build the dict.
+#[pyfunction]
+#[pyo3(name = "extract_metadata", signature = (
+    text,
+    *,
+    infer_sign=false,
+    extra_stopwords=None,
+    allowlist=None,
+    ticker_order="alphabetical",
+    date_format="us",
+    frequencies=false,
+    include_unvalidated=false
+))]
+#[allow(clippy::too_many_arguments)]
+fn extract_metadata_py(
+    py: Python<'_>,
+    text: &str,
+    infer_sign: bool,
+    extra_stopwords: Option<Bound<'_, PyAny>>,
+    allowlist: Option<Bound<'_, PyAny>>,
+    ticker_order: &str,
+    date_format: &str,
+    frequencies: bool,
+    include_unvalidated: bool,
+) -> PyResult<Py<PyDict>> {
+    let ticker_options = ticker_options_from_py(extra_stopwords, allowlist, ticker_order)?;
+    let date_format = date_format_from_py(date_format)?;
+    let (mut metadata, counts) = py.allow_threads(|| {
+        let metadata =
+            extract_metadata_with_options(text, infer_sign, &ticker_options, date_format);
+        let counts = frequencies.then(|| metadata.entity_counts());
+        (metadata, counts)
+    });
+    let unvalidated = std::mem::take(&mut metadata.security_ids_unvalidated);
+    let dict = metadata.into_py_dict(py)?;
+    for (key, counts) in counts.into_iter().flatten() {
+        dict.bind(py).set_item(key, counts)?;
+    }
+    if include_unvalidated {
+        dict.bind(py).set_item(
+            "security_ids_unvalidated",
+            unvalidated_ids_to_py(py, unvalidated)?,
+        )?;
+    }
+    Ok(dict)
+}
+
+/// Extract metadata keeping only potential tickers found in `known_tickers`.
+///
+/// `known_tickers` is a set (or list) of valid symbols, such as an exchange's
+/// listing loaded once; `potential_tickers` becomes its intersection with the
+/// candidates found in `text`, still deduplicated and sorted, and each
+/// candidate costs one set lookup. With `known_tickers` None or empty this is
+/// exactly `extract_metadata`. For repeated calls with the same dictionary,
+/// `MetadataExtractor(allowlist=known_tickers)` avoids converting it each time.
+#[pyfunction]
+#[pyo3(signature = (text, known_tickers=None, *, infer_sign=false))]
+fn extract_metadata_with_tickers(
+    py: Python<'_>,
+    text: &str,
+    known_tickers: Option<Bound<'_, PyAny>>,
+    infer_sign: bool,
+) -> PyResult<Py<PyDict>> {
+    let known = known_tickers
+        .map(|k| symbol_set_from_py("known_tickers", &k))
+        .transpose()?
+        .unwrap_or_default();
+    let ticker_options = TickerOptions::known_tickers(known);
+    py.allow_threads(|| {
+        extract_metadata_with_options(text, infer_sign, &ticker_options, DateFormat::default())
+    })
+    .into_py_dict(py)
+}
+
+/// Collect a set, frozenset, list or tuple of str into a `HashSet`. A bare str
+/// is rejected rather than read as a set of characters.
+fn symbol_set_from_py(name: &str, symbols: &Bound<'_, PyAny>) -> PyResult<HashSet<String>> {
+    if symbols.is_instance_of::<PyString>() {
+        return Err(PyTypeError::new_err(format!(
+            "{name} must be a set or list of str, not a str"
+        )));
+    }
+    symbols.try_iter()?.map(|s| s?.extract()).collect()
+}
+
+/// Build a `TickerOptions` from the optional `extra_stopwords` and `allowlist`
+/// arguments and the `ticker_order` name.
+fn ticker_options_from_py(
+    extra_stopwords: Option<Bound<'_, PyAny>>,
+    allowlist: Option<Bound<'_, PyAny>>,
+    ticker_order: &str,
+) -> PyResult<TickerOptions> {
+    let order = TickerOrder::parse(ticker_order).ok_or_else(|| {
+        InvalidOption::new_err(format!(
+            "ticker_order must be \"alphabetical\" or \"document\", got {ticker_order:?}"
+        ))
+    })?;
+    Ok(TickerOptions {
+        order,
+        extra_stopwords: extra_stopwords
+            .map(|s| symbol_set_from_py("extra_stopwords", &s))
+            .transpose()?
+            .unwrap_or_default(),
+        allowlist: allowlist
+            .map(|s| symbol_set_from_py("allowlist", &s))
+            .transpose()?,
+    })
+}
+
+/// Reusable metadata extractor with a ticker configuration and custom patterns.
+///
+/// Takes the same keyword-only `extra_stopwords`, `allowlist` and
+/// `ticker_order` as `extract_metadata`, but converts them once at
+/// construction. `add_pattern(name, pattern)` registers a regex whose matches
+/// are reported under `name`; it is compiled right away, so an invalid pattern
+/// raises `InvalidOption` there rather than on every call. `extract(text)`
+/// returns the same dict as `extract_metadata` with that configuration, plus
+/// one list per registered pattern holding every match in document order.
+///
+/// Pass an extractor as `extractor=` to `process_document` or
+/// `process_documents` to use it for every chunk. Calls only read the
+/// extractor, so one instance can serve many threads; patterns added later do
+/// not affect calls already running.
+#[pyclass(module = "rag_rust_core")]
+pub struct MetadataExtractor {
+    config: Arc<ExtractorConfig>,
+}
+
+#[pymethods]
+impl MetadataExtractor {
+    #[new]
+    #[pyo3(signature = (*, extra_stopwords=None, allowlist=None, ticker_order="alphabetical"))]
+    fn new(
+        extra_stopwords: Option<Bound<'_, PyAny>>,
+        allowlist: Option<Bound<'_, PyAny>>,
+        ticker_order: &str,
+    ) -> PyResult<Self> {
+        let config = ExtractorConfig {
+            ticker_options: ticker_options_from_py(extra_stopwords, allowlist, ticker_order)?,
+            patterns: Vec::new(),
+        };
+        Ok(MetadataExtractor {
+            config: Arc::new(config),
+        })
+    }
+
+    /// Register a regex whose matches are returned under `name`.
+    ///
+    /// Raises:
+    ///     InvalidOption: If `pattern` is not a valid regex or `name` is a
+    ///         built-in metadata key
+    fn add_pattern(&mut self, name: &str, pattern: &str) -> PyResult<()> {
+        Arc::make_mut(&mut self.config)
+            .add_pattern(name, pattern)
+            .map_err(InvalidOption::new_err)
+    }
+
+    /// Names of the registered patterns, in registration order.
+    #[getter]
+    fn pattern_names(&self) -> Vec<String> {
+        self.config
+            .patterns
+            .iter()
+            .map(|(name, _)| name.clone())
+            .collect()
+    }
+
+    /// Extract metadata from `text`; see `extract_metadata`.
+    #[pyo3(signature = (text, *, infer_sign=false))]
+    fn extract(&self, py: Python<'_>, text: &str, infer_sign: bool) -> PyResult<Py<PyDict>> {
+        let config = Arc::clone(&self.config);
+        py.allow_threads(|| config.extract(text, infer_sign))
+            .into_py_dict(py)
+    }
+}
+
+/// The extractor config for `process_document(s)`: the `extractor` argument's,
+/// or one built from the ticker arguments, which may not be combined with it.
+fn extractor_config_from_py(
+    extractor: Option<PyRef<'_, MetadataExtractor>>,
+    extra_stopwords: Option<Bound<'_, PyAny>>,
+    allowlist: Option<Bound<'_, PyAny>>,
+    ticker_order: &str,
+) -> PyResult<Arc<ExtractorConfig>> {
+    let Some(extractor) = extractor else {
+        let ticker_options = ticker_options_from_py(extra_stopwords, allowlist, ticker_order)?;
+        return Ok(Arc::new(ExtractorConfig {
+            ticker_options,
+            patterns: Vec::new(),
+        }));
+    };
+    if extra_stopwords.is_some() || allowlist.is_some() || ticker_order != "alphabetical" {
+        return Err(InvalidOption::new_err(
+            "extra_stopwords, allowlist and ticker_order cannot be combined with extractor; \
+             pass them to the MetadataExtractor instead",
+        ));
+    }
+    Ok(Arc::clone(&extractor.config))
+}
+
+/// The `ProcessConfig` for the arguments the `process_*` functions share,
+/// with extraction on and no spans, sections or forced parallelism; callers
+/// set the options they take on top of it.
+#[allow(clippy::too_many_arguments)]
+fn process_config_from_py(
+    chunk_size: usize,
+    chunk_overlap: OverlapArg,
+    size_unit: &str,
+    infer_sign: bool,
+    extractor: Option<PyRef<'_, MetadataExtractor>>,
+    extra_stopwords: Option<Bound<'_, PyAny>>,
+    allowlist: Option<Bound<'_, PyAny>>,
+    ticker_order: &str,
+    clean_options: Option<CleanOptions>,
+    on_empty: &str,
+) -> PyResult<ProcessConfig> {
+    if chunk_size == 0 {
+        return Err(InvalidChunkParams::new_err(
+            "chunk_size must be greater than 0",
+        ));
+    }
+    Ok(ProcessConfig {
+        chunk_size,
+        chunk_overlap: chunk_overlap
+            .resolve(chunk_size)
+            .map_err(InvalidChunkParams::new_err)?,
+        size_unit: SizeUnit::parse_py(size_unit)?,
+        infer_sign,
+        extractor: extractor_config_from_py(extractor, extra_stopwords, allowlist, ticker_order)?,
+        clean_options: clean_options.unwrap_or_default(),
+        on_empty: OnEmpty::parse_py(on_empty)?,
+        ..ProcessConfig::default()
+    })
+}
+
+#[pymethods]
+impl Matchers {
+    #[new]
+    fn new() -> Self {
+        Matchers::compiled()
+    }
+
+    /// Monetary amounts in `text`, as in `extract_metadata`'s `monetary_amounts`.
+    fn find_money(&self, py: Python<'_>, text: &str) -> Vec<String> {
+        py.allow_threads(|| self.money(text))
+    }
+
+    /// Percentages in `text`, as in `extract_metadata`'s `percentages`.
+    fn find_percentages(&self, py: Python<'_>, text: &str) -> Vec<String> {
+        py.allow_threads(|| self.percentages(text))
+    }
+
+    /// Dates in `text`, as in `extract_metadata`'s `dates`.
+    fn find_dates(&self, py: Python<'_>, text: &str) -> Vec<String> {
+        py.allow_threads(|| self.dates(text))
+    }
+
+    /// Potential tickers in `text`, as in `extract_metadata`'s
+    /// `potential_tickers` with the default stopwords: deduplicated and sorted.
+    fn find_tickers(&self, py: Python<'_>, text: &str) -> Vec<String> {
+        py.allow_threads(|| self.tickers(text))
+    }
+}
+
+/// Parse a `date_format` name into a `DateFormat`.
+fn date_format_from_py(name: &str) -> PyResult<DateFormat> {
+    DateFormat::parse(name).ok_or_else(|| {
+        InvalidOption::new_err(format!(
+            "date_format must be \"us\" or \"eu\", got {name:?}"
+        ))
+    })
+}
+
+/// Hash text into the stable hex digest used for chunk IDs.
+///
+/// The digest is xxHash64 with seed 0 over the UTF-8 encoding of `text`,
+/// formatted as 16 lowercase hex digits; it is the same across releases and
+/// platforms. `process_document` sets each chunk's `chunk_id` to
+/// `hash_text(f"{chunk_index}:{text}")`.
+#[pyfunction]
+fn hash_text(text: &str) -> String {
+    hash_text_impl(text)
+}
+
+/// Remove duplicate chunks from a batch, keeping the first occurrence of each
+/// in its original order.
+///
+/// With `exact=True` (default) only byte-identical chunks are duplicates. With
+/// `exact=False` chunks are compared by a hash of their text lowercased with
+/// whitespace collapsed, so copies differing only in case or spacing (a
+/// disclaimer re-wrapped in another filing) are dropped too.
+///
+/// Returns:
+///     The deduplicated list of chunks, or with `return_dropped=True` a tuple
+///     of that list and a dict mapping the index of each dropped chunk to the
+///     index of the earlier chunk it duplicates, both in the input list
+#[pyfunction]
+#[pyo3(signature = (chunks, *, exact=true, return_dropped=false))]
+fn dedupe_chunks(
+    py: Python<'_>,
+    chunks: Vec<String>,
+    exact: bool,
+    return_dropped: bool,
+) -> PyResult<PyObject> {
+    let duplicates = py.allow_threads(|| dedupe::find_duplicates(&chunks, exact));
+    let dropped = PyDict::new(py);
+    let mut kept = Vec::with_capacity(chunks.len());
+    for (i, (chunk, duplicate_of)) in chunks.into_iter().zip(duplicates).enumerate() {
+        match duplicate_of {
+            Some(first) => dropped.set_item(i, first)?,
+            None => kept.push(chunk),
+        }
+    }
+    if return_dropped {
+        Ok((kept, dropped).into_pyobject(py)?.into_any().unbind())
+    } else {
+        Ok(kept.into_pyobject(py)?.into_any().unbind())
+    }
+}
+
+/// Normalize a date like "March 5, 2024" or "Q3 2023" to a sortable string:
+/// "2024-03-05" for a day, "2023-Q3" for a quarter. Returns None for dates
+/// without a day ("January 2024"), impossible dates and non-dates.
+///
+/// `date_format` ("us" or "eu", keyword-only) orders slash dates as in
+/// `extract_metadata`.
+#[pyfunction]
+#[pyo3(name = "normalize_date", signature = (s, *, date_format="us"))]
+fn normalize_date_py(s: &str, date_format: &str) -> PyResult<Option<String>> {
+    Ok(normalize_date(s, date_format_from_py(date_format)?))
+}
+
+/// Parse a monetary amount like "$1.2 million" or "$500,000" into a float in base units.
+///
+/// The currency may come before or after the number ("$5", "5 €", "5 dollars").
+/// Thousands separators are dropped, with a comma read as the decimal separator
+/// in European notation ("1.234,56 €", "5,2 Mio. €"), and scale words applied
+/// case-insensitively (K/thousand = 1e3, M/mn/million/Mio. = 1e6,
+/// B/bn/billion/Mrd. = 1e9). A leading minus ("-$5M") or accounting-style
+/// parentheses ("$(1.2) million", "($1.2 million)") give a negative value.
+/// Returns None if the string is not a recognizable amount.
+#[pyfunction]
+fn normalize_money(s: &str) -> Option<f64> {
+    parse_money(s)
+}
+
+/// Parse a percentage like "12.5%", "12.5 percent" or "3.5 pct" into a float in
+/// percent units. Basis points are converted ("40 bps" and "40 basis points" give 0.4).
+///
+/// A leading minus ("-3%") or accounting-style parentheses ("(3%)", "(3.5)%") give
+/// a negative value. Returns None if the string is not a recognizable percentage.
+#[pyfunction]
+#[pyo3(name = "parse_percentage")]
+fn parse_percentage_py(s: &str) -> Option<f64> {
+    parse_percentage(s)
+}
+
+impl Span {
+    fn into_py_dict(self, py: Python<'_>) -> PyResult<Py<PyDict>> {
+        let dict = PyDict::new(py);
+        dict.set_item("text", self.text)?;
+        dict.set_item("start", self.start)?;
+        dict.set_item("end", self.end)?;
+        dict.set_item("kind", self.kind)?;
+        Ok(dict.into())
+    }
+}
+
+/// Convert spans into a list of `{"text", "start", "end", "kind"}` dicts.
+fn spans_to_py(py: Python<'_>, spans: Vec<Span>) -> PyResult<Vec<Py<PyDict>>> {
+    spans.into_iter().map(|s| s.into_py_dict(py)).collect()
+}
+
+impl MetadataSpans {
+    /// Build the dict returned by `extract_metadata_spans`.
+    fn into_py_dict(self, py: Python<'_>) -> PyResult<Py<PyDict>> {
+        let dict = PyDict::new(py);
+        dict.set_item("spans", spans_to_py(py, self.clone().into_sorted())?)?;
+        dict.set_item("monetary_amounts", spans_to_py(py, self.monetary_amounts)?)?;
+        dict.set_item("percentages", spans_to_py(py, self.percentages)?)?;
+        dict.set_item("dates", spans_to_py(py, self.dates)?)?;
+        dict.set_item(
+            "potential_tickers",
+            spans_to_py(py, self.potential_tickers)?,
+        )?;
+        Ok(dict.into())
+    }
+}
+
+/// Extract financial metadata along with where each entity occurs.
+///
+/// Returns a dict with the same category keys as `extract_metadata`, where each
+/// entry is a `{"text", "start", "end", "kind"}` dict with `kind` one of
+/// "money", "percentage", "date" or "ticker". Offsets are character (code
+/// point) offsets into `text`, so `text[start:end] == entry["text"]` in Python.
+/// Every occurrence is reported in document order, including repeated tickers.
+/// The `spans` key holds all of them in one list ordered by `start`; matches
+/// of different kinds that overlap are each reported.
+#[pyfunction]
+fn extract_metadata_spans(py: Python<'_>, text: &str) -> PyResult<Py<PyDict>> {
+    py.allow_threads(|| extract_metadata_spans_impl(text, &TickerOptions::default()))
+        .into_py_dict(py)
+}
+
+create_exception!(
+    rag_rust_core,
+    RagCoreError,
+    PyValueError,
+    "Base class of the errors raised by rag_rust_core; a ValueError subclass."
+);
+create_exception!(
+    rag_rust_core,
+    InvalidChunkParams,
+    RagCoreError,
+    "Raised for an unusable chunk size or overlap, such as target_size=0."
+);
+create_exception!(
+    rag_rust_core,
+    InvalidOption,
+    RagCoreError,
+    "Raised for an unknown option value or a conflicting combination of arguments."
+);
+create_exception!(
+    rag_rust_core,
+    EmptyDocument,
+    RagCoreError,
+    "Raised by process_document(on_empty=\"error\") when cleaning leaves no text."
+);
+
+impl OnEmpty {
+    fn parse_py(name: &str) -> PyResult<Self> {
+        Self::parse(name).ok_or_else(|| {
+            InvalidOption::new_err(format!(
+                "on_empty must be \"empty_list\", \"placeholder\" or \"error\", got {name:?}"
+            ))
+        })
+    }
+}
+
+impl ProcessError {
+    fn into_py_err(self) -> PyErr {
+        match self {
+            ProcessError::InvalidChunkSize => InvalidChunkParams::new_err(self.to_string()),
+            ProcessError::EmptyDocument => EmptyDocument::new_err(self.to_string()),
+        }
+    }
+}
+
+impl ProcessedChunk {
+    /// Build the per-chunk dict returned by `process_document`.
+    fn into_py_dict(self, py: Python<'_>) -> PyResult<Py<PyDict>> {
+        let dict = PyDict::new(py);
+        dict.set_item("chunk_index", self.chunk_index)?;
+        dict.set_item("chunk_id", self.chunk_id)?;
+        dict.set_item("text", self.text)?;
+        dict.set_item("char_count", self.char_count)?; // Grapheme clusters, not bytes
+        dict.set_item("word_count", self.word_count)?;
+        if let Some(metadata) = self.metadata {
+            dict.set_item("metadata", metadata.into_py_dict(py)?)?;
+        }
+        if let Some(spans) = self.spans {
+            dict.set_item("spans", spans_to_py(py, spans.into_sorted())?)?;
+        }
+        if let Some(section) = self.section {
+            dict.set_item("section", section)?;
+        }
+        if let Some(is_table) = self.is_table {
+            dict.set_item("is_table", is_table)?;
+        }
+        if let Some(reason) = self.reason {
+            dict.set_item("reason", reason)?;
+        }
+        Ok(dict.into())
+    }
+}
+
+impl ChunkReport {
+    /// Build the report dict returned by `process_document(return_report=True)`.
+    fn into_py_dict(self, py: Python<'_>) -> PyResult<Py<PyDict>> {
+        let dict = PyDict::new(py);
+        dict.set_item("chunk_count", self.chunk_count)?;
+        dict.set_item("chunk_size", self.chunk_size)?;
+        dict.set_item("exceeded", self.exceeded.len())?;
+        dict.set_item("exceeded_chunks", self.exceeded)?;
+        dict.set_item("max_size", self.max_size)?;
+        dict.set_item("floor", self.floor)?;
+        dict.set_item("below_floor", self.below_floor)?;
+        Ok(dict.into())
+    }
+}
+
+impl DocumentMetadata {
+    /// Build the `document_metadata` dict returned by `process_document`.
+    fn into_py_dict(self, py: Python<'_>) -> PyResult<Py<PyDict>> {
+        let dict = PyDict::new(py);
+        let counts = PyDict::new(py);
+        for (name, values, total) in self.categories {
+            dict.set_item(name, values)?;
+            counts.set_item(name, total)?;
+        }
+        dict.set_item("counts", counts)?;
+        let ticker_counts = PyDict::new(py);
+        for t in self.ticker_counts {
+            ticker_counts.set_item(t.symbol, t.count)?;
+        }
+        dict.set_item("ticker_counts", ticker_counts)?;
+        Ok(dict.into())
+    }
+}
+
+/// Convert processed chunks into the list of dicts returned to Python.
+fn processed_chunks_to_py(
+    py: Python<'_>,
+    chunks: Vec<ProcessedChunk>,
+) -> PyResult<Vec<Py<PyDict>>> {
+    chunks.into_iter().map(|c| c.into_py_dict(py)).collect()
+}
+
+/// Process a document through the full pipeline: clean, chunk, and extract metadata.
+///
+/// This is a convenience function that runs all three steps and returns
+/// a list of dicts, one per chunk, each containing the chunk text and its metadata.
+/// `infer_sign`, `extra_stopwords`, `allowlist` and `ticker_order` are forwarded to
+/// `extract_metadata` and `clean_options` (a `CleanOptions`) to `clean_text`.
+/// Everything after `text` is keyword-only.
+///
+/// Every chunk dict has the chunk's `char_count` (grapheme clusters, so an
+/// emoji sequence counts once) and `word_count`. With
+/// `size_unit="words"`, `chunk_size` and `chunk_overlap` count words instead of
+/// characters, as in `chunk_text`. A float `chunk_overlap` in [0, 1) is a
+/// fraction of `chunk_size`, as for `chunk_text`'s `overlap`.
+///
+/// Each chunk's `chunk_id` is `hash_text(f"{chunk_index}:{text}")`, so
+/// reprocessing an unchanged document reproduces the same IDs, while the same
+/// text at another index gets a different one.
+///
+/// Pass a `MetadataExtractor` as `extractor` to extract each chunk's metadata
+/// with it instead, custom patterns included; the ticker arguments then belong
+/// on the extractor, and giving both raises `InvalidOption`.
+///
+/// With `extract_meta=False`, chunks are only cleaned and split: no metadata
+/// is extracted and the `metadata` key is left out of every chunk dict, which
+/// is markedly cheaper for large batches. The cleaned `text` of each chunk can
+/// be passed to `extract_metadata` later as-is; it is not cleaned again.
+///
+/// With `include_spans=True`, each chunk dict also has a `spans` list as
+/// returned under that key by `extract_metadata_spans`, with offsets into the
+/// chunk's `text`.
+///
+/// With `include_sections=True`, each chunk dict also has a `section`: the
+/// title of the last SEC filing heading (as found by `detect_sections` in the
+/// cleaned text) at or before where the chunk starts, or None for chunks
+/// before the first heading.
+///
+/// Large documents (1 MiB or more after cleaning) are chunked and scanned for
+/// metadata on all cores; the output is identical to the sequential path.
+/// Pass `parallel=True` or `parallel=False` to force either path. The GIL is
+/// released for all of the processing and only re-acquired to build the dicts.
+///
+/// With `CleanOptions(preserve_code_blocks=True)`, fenced code blocks are also
+/// kept whole by chunking, as with `chunk_text(preserve_code_blocks=True)`;
+/// such documents are always chunked sequentially.
+///
+/// With `CleanOptions(preserve_tables=True)`, each plain-text table is a chunk
+/// of its own, never split or packed with prose and carrying no overlap, and
+/// every chunk dict has an `is_table` flag. Such documents are also always
+/// chunked sequentially.
+///
+/// With `include_document_metadata=True` the result is instead a dict with the
+/// chunk list under `chunks` and `document_metadata`: metadata extracted once
+/// from the whole cleaned text, before chunking, so an entity cut in two by a
+/// chunk boundary ("$1.5" | "billion") is still found whole. It holds the
+/// `monetary_amounts`, `percentages`, `dates`, `fiscal_periods` and
+/// `potential_tickers` found anywhere in the document, each deduplicated and
+/// sorted, the total number of occurrences of each under `counts`, and
+/// `ticker_counts` as in `extract_metadata`.
+///
+/// `on_empty` picks the result for a document that cleans down to nothing:
+/// "empty_list" (default) returns `[]`, "placeholder" returns a single chunk
+/// with empty text, `char_count` 0 and `"reason": "cleaned_to_empty"`, and
+/// "error" raises `EmptyDocument` (a `RagCoreError` subclass).
+///
+/// With `return_report=True` the result is a tuple of the usual result and a
+/// report on how the chunks fit the budget, sizes measured in `size_unit`:
+/// `chunk_count`, `chunk_size`, `exceeded` (the number of chunks larger than
+/// `chunk_size`: sentences are split to fit, so these are code blocks and
+/// tables kept whole by `CleanOptions(preserve_code_blocks=True)` and
+/// `CleanOptions(preserve_tables=True)`) with their indices in
+/// `exceeded_chunks`, `max_size`, and `below_floor`, the number of chunks
+/// smaller than `floor` (a tenth of `chunk_size`). A placeholder chunk is not
+/// counted.
+#[pyfunction]
+#[pyo3(signature = (
+    text,
+    *,
+    chunk_size=1500,
+    chunk_overlap=OverlapArg::Size(200),
+    size_unit="chars",
+    infer_sign=false,
+    extra_stopwords=None,
+    allowlist=None,
+    ticker_order="alphabetical",
+    extractor=None,
+    extract_meta=true,
+    include_spans=false,
+    include_sections=false,
+    include_document_metadata=false,
+    parallel=None,
+    clean_options=None,
+    on_empty="empty_list",
+    return_report=false
+))]
+#[allow(clippy::too_many_arguments)]
+fn process_document(
+    py: Python<'_>,
+    text: &str,
+    chunk_size: usize,
+    chunk_overlap: OverlapArg,
+    size_unit: &str,
+    infer_sign: bool,
+    extra_stopwords: Option<Bound<'_, PyAny>>,
+    allowlist: Option<Bound<'_, PyAny>>,
+    ticker_order: &str,
+    extractor: Option<PyRef<'_, MetadataExtractor>>,
+    extract_meta: bool,
+    include_spans: bool,
+    include_sections: bool,
+    include_document_metadata: bool,
+    parallel: Option<bool>,
+    clean_options: Option<CleanOptions>,
+    on_empty: &str,
+    return_report: bool,
+) -> PyResult<PyObject> {
+    let config = ProcessConfig {
+        extract_meta,
+        include_spans,
+        include_sections,
+        parallel,
+        ..process_config_from_py(
+            chunk_size,
+            chunk_overlap,
+            size_unit,
+            infer_sign,
+            extractor,
+            extra_stopwords,
+            allowlist,
+            ticker_order,
+            clean_options,
+            on_empty,
+        )?
+    };
+    let with_report =
+        |chunks: &[ProcessedChunk]| return_report.then(|| ChunkReport::new(chunks, &config));
+    let (result, report): (PyObject, _) = if include_document_metadata {
+        let (chunks, document_metadata, report) = py
+            .allow_threads(|| {
+                process_document_with_metadata_impl(text, &config).map(|(chunks, metadata)| {
+                    let report = with_report(&chunks);
+                    (chunks, metadata, report)
+                })
+            })
+            .map_err(ProcessError::into_py_err)?;
+        let dict = PyDict::new(py);
+        dict.set_item("chunks", processed_chunks_to_py(py, chunks)?)?;
+        dict.set_item("document_metadata", document_metadata.into_py_dict(py)?)?;
+        (dict.into_any().unbind(), report)
+    } else {
+        let (chunks, report) = py
+            .allow_threads(|| {
+                process_document_impl(text, &config).map(|chunks| {
+                    let report = with_report(&chunks);
+                    (chunks, report)
+                })
+            })
+            .map_err(ProcessError::into_py_err)?;
+        let chunks = processed_chunks_to_py(py, chunks)?;
+        (chunks.into_pyobject(py)?.into_any().unbind(), report)
+    };
+    match report {
+        Some(report) => Ok((result, report.into_py_dict(py)?)
+            .into_pyobject(py)?
+            .into_any()
+            .unbind()),
+        None => Ok(result),
+    }
+}
+
+/// Process a document as `process_document` does, returning the result as a
+/// JSON string instead of Python objects.
+///
+/// The JSON has the same structure, key for key: an array of chunk objects,
+/// or with `include_document_metadata=True` an object with `chunks` and
+/// `document_metadata`. `json.loads` of the result equals what
+/// `process_document` returns for the same arguments. Object keys are
+/// sorted, and NaN or infinite values are written as null.
+///
+/// Building thousands of dicts under the GIL dominates `process_document` on
+/// large documents; here chunks are serialized with the GIL released, leaving
+/// a single str to hand to `orjson.loads` or straight to a message queue.
+///
+/// Takes the same arguments as `process_document` and raises the same
+/// exceptions.
+#[pyfunction]
+#[pyo3(signature = (
+    text,
+    *,
+    chunk_size=1500,
+    chunk_overlap=OverlapArg::Size(200),
+    size_unit="chars",
+    infer_sign=false,
+    extra_stopwords=None,
+    allowlist=None,
+    ticker_order="alphabetical",
+    extractor=None,
+    extract_meta=true,
+    include_spans=false,
+    include_sections=false,
+    include_document_metadata=false,
+    parallel=None,
+    clean_options=None,
+    on_empty="empty_list"
+))]
+#[allow(clippy::too_many_arguments)]
+fn process_document_json(
+    py: Python<'_>,
+    text: &str,
+    chunk_size: usize,
+    chunk_overlap: OverlapArg,
+    size_unit: &str,
+    infer_sign: bool,
+    extra_stopwords: Option<Bound<'_, PyAny>>,
+    allowlist: Option<Bound<'_, PyAny>>,
+    ticker_order: &str,
+    extractor: Option<PyRef<'_, MetadataExtractor>>,
+    extract_meta: bool,
+    include_spans: bool,
+    include_sections: bool,
+    include_document_metadata: bool,
+    parallel: Option<bool>,
+    clean_options: Option<CleanOptions>,
+    on_empty: &str,
+) -> PyResult<String> {
+    let config = ProcessConfig {
+        extract_meta,
+        include_spans,
+        include_sections,
+        parallel,
+        ..process_config_from_py(
+            chunk_size,
+            chunk_overlap,
+            size_unit,
+            infer_sign,
+            extractor,
+            extra_stopwords,
+            allowlist,
+            ticker_order,
+            clean_options,
+            on_empty,
+        )?
+    };
+    py.allow_threads(|| {
+        let value = if include_document_metadata {
+            let (chunks, document_metadata) = process_document_with_metadata_impl(text, &config)?;
+            serde_json::json!({
+                "chunks": chunks,
+                "document_metadata": document_metadata,
+            })
+        } else {
+            json::to_json(process_document_impl(text, &config)?)
+        };
+        Ok(value.to_string())
+    })
+    .map_err(ProcessError::into_py_err)
+}
+
+/// Processed chunks as an Arrow record batch, returned by
+/// `process_document_arrow` and `process_documents_arrow`.
+///
+/// Implements the Arrow PyCapsule interface (`__arrow_c_schema__` and
+/// `__arrow_c_array__`), so Arrow libraries import it without copying:
+/// `pyarrow.record_batch(batch)`, `polars.DataFrame(batch)`, or `to_pyarrow()`.
+/// The columns are `document_id` (int32, batches only: the document's index in
+/// the input), `chunk_index` (int32), `text` (utf8), `char_count` (int32) and,
+/// unless metadata extraction was turned off, `monetary_amounts`,
+/// `percentages`, `dates` and `potential_tickers` (list<utf8>).
+#[cfg(feature = "arrow")]
+#[pyclass(module = "rag_rust_core", frozen)]
+pub struct ArrowChunks {
+    table: arrow::ChunkTable,
+}
+
+#[cfg(feature = "arrow")]
+#[pymethods]
+impl ArrowChunks {
+    /// Number of chunks (rows).
+    #[getter]
+    fn num_rows(&self) -> usize {
+        self.table.num_rows()
+    }
+
+    /// Column names, in order.
+    #[getter]
+    fn column_names(&self) -> Vec<&'static str> {
+        self.table.column_names()
+    }
+
+    fn __len__(&self) -> usize {
+        self.table.num_rows()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "ArrowChunks(num_rows={}, columns={:?})",
+            self.table.num_rows(),
+            self.table.column_names()
+        )
+    }
+
+    /// Export the schema as an "arrow_schema" PyCapsule.
+    fn __arrow_c_schema__<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyCapsule>> {
+        PyCapsule::new(py, self.table.schema(), Some(c"arrow_schema".to_owned()))
+    }
+
+    /// Export the record batch as "arrow_schema" and "arrow_array" PyCapsules.
+    /// `requested_schema` is ignored: the batch is always exported as is.
+    #[pyo3(signature = (requested_schema=None))]
+    fn __arrow_c_array__<'py>(
+        &self,
+        py: Python<'py>,
+        requested_schema: Option<Bound<'py, PyAny>>,
+    ) -> PyResult<(Bound<'py, PyCapsule>, Bound<'py, PyCapsule>)> {
+        let _ = requested_schema;
+        let (schema, array) = self.table.export();
+        Ok((
+            PyCapsule::new(py, schema, Some(c"arrow_schema".to_owned()))?,
+            PyCapsule::new(py, array, Some(c"arrow_array".to_owned()))?,
+        ))
+    }
+
+    /// The batch as a `pyarrow.RecordBatch`; requires pyarrow 14 or later.
+    fn to_pyarrow<'py>(slf: &Bound<'py, Self>) -> PyResult<Bound<'py, PyAny>> {
+        slf.py()
+            .import("pyarrow")?
+            .call_method1("record_batch", (slf,))
+    }
+}
+
+/// Process a document as `process_document` does, returning the chunks as an
+/// Arrow record batch (an `ArrowChunks`) instead of a list of dicts.
+///
+/// Only available when the module is built with the `arrow` cargo feature.
+/// Takes the same arguments as `process_document`, except `include_spans` and
+/// `include_document_metadata`, and raises the same exceptions; with
+/// `extract_meta=False` the metadata columns are left out. The GIL is
+/// released while the document is processed and the columns are built.
+#[cfg(feature = "arrow")]
+#[pyfunction]
+#[pyo3(signature = (
+    text,
+    *,
+    chunk_size=1500,
+    chunk_overlap=OverlapArg::Size(200),
+    size_unit="chars",
+    infer_sign=false,
+    extra_stopwords=None,
+    allowlist=None,
+    ticker_order="alphabetical",
+    extractor=None,
+    extract_meta=true,
+    parallel=None,
+    clean_options=None,
+    on_empty="empty_list"
+))]
+#[allow(clippy::too_many_arguments)]
+fn process_document_arrow(
+    py: Python<'_>,
+    text: &str,
+    chunk_size: usize,
+    chunk_overlap: OverlapArg,
+    size_unit: &str,
+    infer_sign: bool,
+    extra_stopwords: Option<Bound<'_, PyAny>>,
+    allowlist: Option<Bound<'_, PyAny>>,
+    ticker_order: &str,
+    extractor: Option<PyRef<'_, MetadataExtractor>>,
+    extract_meta: bool,
+    parallel: Option<bool>,
+    clean_options: Option<CleanOptions>,
+    on_empty: &str,
+) -> PyResult<ArrowChunks> {
+    let config = ProcessConfig {
+        extract_meta,
+        parallel,
+        ..process_config_from_py(
+            chunk_size,
+            chunk_overlap,
+            size_unit,
+            infer_sign,
+            extractor,
+            extra_stopwords,
+            allowlist,
+            ticker_order,
+            clean_options,
+            on_empty,
+        )?
+    };
+    let table = py
+        .allow_threads(|| {
+            process_document_impl(text, &config)
+                .map(|chunks| arrow::ChunkTable::from_document(chunks, extract_meta))
+        })
+        .map_err(ProcessError::into_py_err)?
+        .map_err(RagCoreError::new_err)?;
+    Ok(ArrowChunks { table })
+}
+
+/// Process a batch of documents as `process_documents` does, returning all of
+/// their chunks as one Arrow record batch (an `ArrowChunks`) with a
+/// `document_id` column holding each chunk's document index in `texts`.
+///
+/// Only available when the module is built with the `arrow` cargo feature.
+/// Takes the same arguments as `process_documents` except `include_spans`.
+/// Documents that `process_documents` would return None for contribute no
+/// rows.
+#[cfg(feature = "arrow")]
+#[pyfunction]
+#[pyo3(signature = (
+    texts,
+    *,
+    chunk_size=1500,
+    chunk_overlap=OverlapArg::Size(200),
+    size_unit="chars",
+    num_threads=None,
+    n_threads=None,
+    infer_sign=false,
+    extra_stopwords=None,
+    allowlist=None,
+    ticker_order="alphabetical",
+    extractor=None,
+    extract_meta=true,
+    clean_options=None,
+    on_empty="empty_list"
+))]
+#[allow(clippy::too_many_arguments)]
+fn process_documents_arrow(
+    py: Python<'_>,
+    texts: Vec<Bound<'_, PyAny>>,
+    chunk_size: usize,
+    chunk_overlap: OverlapArg,
+    size_unit: &str,
+    num_threads: Option<usize>,
+    n_threads: Option<usize>,
+    infer_sign: bool,
+    extra_stopwords: Option<Bound<'_, PyAny>>,
+    allowlist: Option<Bound<'_, PyAny>>,
+    ticker_order: &str,
+    extractor: Option<PyRef<'_, MetadataExtractor>>,
+    extract_meta: bool,
+    clean_options: Option<CleanOptions>,
+    on_empty: &str,
+) -> PyResult<ArrowChunks> {
+    let num_threads = num_threads_from_py(py, num_threads, n_threads)?;
+    #[cfg(feature = "parallel")]
+    let pool = build_thread_pool(num_threads)?;
+    #[cfg(not(feature = "parallel"))]
+    if num_threads == Some(0) {
+        return Err(InvalidOption::new_err("num_threads must be greater than 0"));
+    }
+    let config = ProcessConfig {
+        extract_meta,
+        ..process_config_from_py(
+            chunk_size,
+            chunk_overlap,
+            size_unit,
+            infer_sign,
+            extractor,
+            extra_stopwords,
+            allowlist,
+            ticker_order,
+            clean_options,
+            on_empty,
+        )?
+    };
+
+    let texts: Vec<Option<String>> = texts.iter().map(|t| t.extract().ok()).collect();
+
+    let table = py
+        .allow_threads(|| {
+            let run = || process_documents_impl(&texts, &config);
+            #[cfg(feature = "parallel")]
+            let results = match &pool {
+                Some(pool) => pool.install(run),
+                None => run(),
+            };
+            #[cfg(not(feature = "parallel"))]
+            let results = run();
+            arrow::ChunkTable::from_documents(results, extract_meta)
+        })
+        .map_err(RagCoreError::new_err)?;
+    Ok(ArrowChunks { table })
+}
+
+/// The thread count given as `num_threads` or as `n_threads`, its name before
+/// the `parallel` feature, which still works but warns.
+fn num_threads_from_py(
+    py: Python<'_>,
+    num_threads: Option<usize>,
+    n_threads: Option<usize>,
+) -> PyResult<Option<usize>> {
+    match (num_threads, n_threads) {
+        (Some(_), Some(_)) => Err(InvalidOption::new_err(
+            "num_threads and n_threads name the same argument; pass only num_threads",
+        )),
+        (None, Some(n)) => {
+            PyErr::warn(
+                py,
+                &py.get_type::<PyDeprecationWarning>(),
+                c"n_threads is deprecated; use num_threads",
+                1,
+            )?;
+            Ok(Some(n))
+        }
+        (num_threads, None) => Ok(num_threads),
+    }
+}
+
+/// Build a dedicated rayon pool when a thread count is requested, so batch calls
+/// don't resize the global pool other code may be relying on.
+#[cfg(feature = "parallel")]
+fn build_thread_pool(num_threads: Option<usize>) -> PyResult<Option<rayon::ThreadPool>> {
+    match num_threads {
+        None => Ok(None),
+        Some(0) => Err(InvalidOption::new_err("num_threads must be greater than 0")),
+        Some(n) => rayon::ThreadPoolBuilder::new()
+            .num_threads(n)
+            .build()
+            .map(Some)
+            .map_err(|e| RagCoreError::new_err(format!("failed to build thread pool: {e}"))),
+    }
+}
+
+/// Process a batch of documents in parallel.
+///
+/// Args:
+///     texts: Documents to process (the only positional argument)
+///     chunk_size: Target chunk size in characters (default: 1500)
+///     chunk_overlap: Overlap between chunks in characters (default: 200), or
+///         a float in [0, 1) for a fraction of chunk_size
+///     size_unit: Forwarded to `process_document`
+///     num_threads: Worker threads to use, in a pool dedicated to this call;
+///         None uses rayon's global pool. Only validated when the module is
+///         built without the `parallel` feature, which processes the batch on
+///         the calling thread. `n_threads`, its former name, is still
+///         accepted with a DeprecationWarning
+///     infer_sign: Forwarded to `extract_metadata`
+///     extra_stopwords: Forwarded to `extract_metadata`
+///     allowlist: Forwarded to `extract_metadata`
+///     ticker_order: Forwarded to `extract_metadata`
+///     extractor: Forwarded to `process_document`
+///     extract_meta: Forwarded to `process_document`
+///     include_spans: Forwarded to `process_document`
+///     clean_options: A `CleanOptions` forwarded to `clean_text`
+///     on_empty: Forwarded to `process_document`; with "error", a document that
+///         cleans to nothing yields None instead of raising
+///
+/// Returns:
+///     One entry per input document, in input order: the same list of chunk
+///     dicts `process_document` returns, or None if that document could not be
+///     processed (e.g. it was not a str). One bad document never aborts the batch.
+///
+/// The GIL is released while documents are cleaned, chunked and scanned for
+/// metadata; it is only re-acquired to build the result dicts.
+///
+/// Raises:
+///     InvalidChunkParams: If chunk_size is 0, or a float chunk_overlap is
+///         outside [0, 1)
+///     InvalidOption: If num_threads is 0, or `extractor` is combined with the
+///         ticker arguments
+#[pyfunction]
+#[pyo3(signature = (
+    texts,
+    *,
+    chunk_size=1500,
+    chunk_overlap=OverlapArg::Size(200),
+    size_unit="chars",
+    num_threads=None,
+    n_threads=None,
+    infer_sign=false,
+    extra_stopwords=None,
+    allowlist=None,
+    ticker_order="alphabetical",
+    extractor=None,
+    extract_meta=true,
+    include_spans=false,
+    clean_options=None,
+    on_empty="empty_list"
+))]
+#[allow(clippy::too_many_arguments)]
+fn process_documents(
+    py: Python<'_>,
+    texts: Vec<Bound<'_, PyAny>>,
+    chunk_size: usize,
+    chunk_overlap: OverlapArg,
+    size_unit: &str,
+    num_threads: Option<usize>,
+    n_threads: Option<usize>,
+    infer_sign: bool,
+    extra_stopwords: Option<Bound<'_, PyAny>>,
+    allowlist: Option<Bound<'_, PyAny>>,
+    ticker_order: &str,
+    extractor: Option<PyRef<'_, MetadataExtractor>>,
+    extract_meta: bool,
+    include_spans: bool,
+    clean_options: Option<CleanOptions>,
+    on_empty: &str,
+) -> PyResult<Vec<Option<Vec<Py<PyDict>>>>> {
+    let num_threads = num_threads_from_py(py, num_threads, n_threads)?;
+    #[cfg(feature = "parallel")]
+    let pool = build_thread_pool(num_threads)?;
+    #[cfg(not(feature = "parallel"))]
+    if num_threads == Some(0) {
+        return Err(InvalidOption::new_err("num_threads must be greater than 0"));
+    }
+    let config = ProcessConfig {
+        extract_meta,
+        include_spans,
+        ..process_config_from_py(
+            chunk_size,
+            chunk_overlap,
+            size_unit,
+            infer_sign,
+            extractor,
+            extra_stopwords,
+            allowlist,
+            ticker_order,
+            clean_options,
+            on_empty,
+        )?
+    };
+
+    // Copy inputs into owned Rust strings so the GIL can be released
+    let texts: Vec<Option<String>> = texts.iter().map(|t| t.extract().ok()).collect();
+
+    let results = py.allow_threads(|| {
+        let run = || process_documents_impl(&texts, &config);
+        #[cfg(feature = "parallel")]
+        if let Some(pool) = &pool {
+            return pool.install(run);
+        }
+        run()
+    });
+
+    results
+        .into_iter()
+        .map(|doc| {
+            doc.map(|chunks| processed_chunks_to_py(py, chunks))
+                .transpose()
+        })
+        .collect()
+}
+
+/// Parse an `encoding` name into an `Encoding`.
+fn encoding_from_py(name: &str) -> PyResult<Encoding> {
+    Encoding::parse(name).ok_or_else(|| {
+        InvalidOption::new_err(format!(
+            "encoding must be \"utf-8\", \"utf-8-lossy\", \"latin-1\" or \"utf-8-or-latin-1\", got {name:?}"
+        ))
+    })
+}
+
+/// Convert an I/O error on `path` into the exception Python's `open` would
+/// raise: an `OSError` subclass picked from the errno (`FileNotFoundError`,
+/// `PermissionError`, ...) with `filename` set.
+fn io_error_into_py(err: std::io::Error, path: &Path) -> PyErr {
+    match err.raw_os_error() {
+        Some(errno) => {
+            let message = err.to_string();
+            let strerror = message
+                .strip_suffix(&format!(" (os error {errno})"))
+                .unwrap_or(&message)
+                .to_string();
+            PyOSError::new_err((errno, strerror, path.to_path_buf()))
+        }
+        None => err.into(),
+    }
+}
+
+/// Convert a failed read of `path` into an `OSError` subclass as for
+/// `io_error_into_py`, or `UnicodeDecodeError`.
+fn read_error_into_py(py: Python<'_>, err: ReadError, path: &Path) -> PyErr {
+    match err {
+        ReadError::Io(err) => io_error_into_py(err, path),
+        ReadError::Decode { bytes, start, end } => {
+            match PyUnicodeDecodeError::new(py, c"utf-8", &bytes, start..end, c"invalid utf-8") {
+                Ok(exc) => PyErr::from_value(exc.into_any()),
+                Err(err) => err,
+            }
+        }
+    }
+}
+
+/// Read, clean, chunk and extract metadata from a text file.
+///
+/// Args:
+///     path: Path to the file, as a str or `os.PathLike` (the only positional
+///         argument)
+///     encoding: How the file's bytes are decoded: "utf-8" (default; invalid
+///         bytes raise `UnicodeDecodeError`), "utf-8-lossy" (invalid bytes
+///         become U+FFFD), "latin-1", or "utf-8-or-latin-1" (Latin-1 when the
+///         file is not valid UTF-8). A UTF-8 byte order mark is dropped
+///
+/// All other arguments are forwarded to `process_document`, and the result is
+/// the list of chunk dicts it returns for the file's text. The GIL is released
+/// while the file is read and processed.
+///
+/// Raises:
+///     OSError: If the file cannot be read; the subclass follows the error, as
+///         with `open` (`FileNotFoundError`, `PermissionError`, ...)
+///     UnicodeDecodeError: If the file is not valid UTF-8 and encoding is "utf-8"
+///     InvalidChunkParams: If chunk_size is 0, or a float chunk_overlap is
+///         outside [0, 1)
+///     InvalidOption: If encoding is unknown, or as for `process_document`
+///     EmptyDocument: With `on_empty="error"`, if the file cleans to nothing
+#[pyfunction]
+#[pyo3(signature = (
+    path,
+    *,
+    chunk_size=1500,
+    chunk_overlap=OverlapArg::Size(200),
+    encoding="utf-8",
+    size_unit="chars",
+    infer_sign=false,
+    extra_stopwords=None,
+    allowlist=None,
+    ticker_order="alphabetical",
+    extractor=None,
+    extract_meta=true,
+    include_spans=false,
+    parallel=None,
+    clean_options=None,
+    on_empty="empty_list"
+))]
+#[allow(clippy::too_many_arguments)]
+fn process_file(
+    py: Python<'_>,
+    path: PathBuf,
+    chunk_size: usize,
+    chunk_overlap: OverlapArg,
+    encoding: &str,
+    size_unit: &str,
+    infer_sign: bool,
+    extra_stopwords: Option<Bound<'_, PyAny>>,
+    allowlist: Option<Bound<'_, PyAny>>,
+    ticker_order: &str,
+    extractor: Option<PyRef<'_, MetadataExtractor>>,
+    extract_meta: bool,
+    include_spans: bool,
+    parallel: Option<bool>,
+    clean_options: Option<CleanOptions>,
+    on_empty: &str,
+) -> PyResult<Vec<Py<PyDict>>> {
+    let encoding = encoding_from_py(encoding)?;
+    let config = ProcessConfig {
+        extract_meta,
+        include_spans,
+        parallel,
+        ..process_config_from_py(
+            chunk_size,
+            chunk_overlap,
+            size_unit,
+            infer_sign,
+            extractor,
+            extra_stopwords,
+            allowlist,
+            ticker_order,
+            clean_options,
+            on_empty,
+        )?
+    };
+    let chunks = py
+        .allow_threads(|| {
+            files::read_text_file(&path, encoding).map(|text| process_document_impl(&text, &config))
+        })
+        .map_err(|err| read_error_into_py(py, err, &path))?
+        .map_err(ProcessError::into_py_err)?;
+    processed_chunks_to_py(py, chunks)
+}
+
+/// Process a batch of text files in parallel, for corpus ingestion.
+///
+/// Args:
+///     paths: Files to process, as str or `os.PathLike` (the only positional
+///         argument)
+///     encoding: Forwarded to `process_file`
+///
+/// All other arguments are as for `process_documents`.
+///
+/// Returns:
+///     One entry per path, in input order: the list of chunk dicts
+///     `process_file` returns for it, or None if the file could not be read or
+///     decoded, or the entry was not a path. One bad file never aborts the batch.
+///
+/// The GIL is released while files are read and processed; it is only
+/// re-acquired to build the result dicts.
+///
+/// Raises:
+///     InvalidChunkParams: If chunk_size is 0, or a float chunk_overlap is
+///         outside [0, 1)
+///     InvalidOption: If encoding is unknown, or as for `process_documents`
+#[pyfunction]
+#[pyo3(signature = (
+    paths,
+    *,
+    chunk_size=1500,
+    chunk_overlap=OverlapArg::Size(200),
+    encoding="utf-8",
+    size_unit="chars",
+    num_threads=None,
+    n_threads=None,
+    infer_sign=false,
+    extra_stopwords=None,
+    allowlist=None,
+    ticker_order="alphabetical",
+    extractor=None,
+    extract_meta=true,
+    include_spans=false,
+    clean_options=None,
+    on_empty="empty_list"
+))]
+#[allow(clippy::too_many_arguments)]
+fn process_files(
+    py: Python<'_>,
+    paths: Vec<Bound<'_, PyAny>>,
+    chunk_size: usize,
+    chunk_overlap: OverlapArg,
+    encoding: &str,
+    size_unit: &str,
+    num_threads: Option<usize>,
+    n_threads: Option<usize>,
+    infer_sign: bool,
+    extra_stopwords: Option<Bound<'_, PyAny>>,
+    allowlist: Option<Bound<'_, PyAny>>,
+    ticker_order: &str,
+    extractor: Option<PyRef<'_, MetadataExtractor>>,
+    extract_meta: bool,
+    include_spans: bool,
+    clean_options: Option<CleanOptions>,
+    on_empty: &str,
+) -> PyResult<Vec<Option<Vec<Py<PyDict>>>>> {
+    let encoding = encoding_from_py(encoding)?;
+    let num_threads = num_threads_from_py(py, num_threads, n_threads)?;
+    #[cfg(feature = "parallel")]
+    let pool = build_thread_pool(num_threads)?;
+    #[cfg(not(feature = "parallel"))]
+    if num_threads == Some(0) {
+        return Err(InvalidOption::new_err("num_threads must be greater than 0"));
+    }
+    let config = ProcessConfig {
+        extract_meta,
+        include_spans,
+        ..process_config_from_py(
+            chunk_size,
+            chunk_overlap,
+            size_unit,
+            infer_sign,
+            extractor,
+            extra_stopwords,
+            allowlist,
+            ticker_order,
+            clean_options,
+            on_empty,
+        )?
+    };
+
+    let paths: Vec<Option<PathBuf>> = paths.iter().map(|p| p.extract().ok()).collect();
+
+    let results = py.allow_threads(|| {
+        let run = || process_files_impl(&paths, encoding, &config);
+        #[cfg(feature = "parallel")]
+        if let Some(pool) = &pool {
+            return pool.install(run);
+        }
+        run()
+    });
+
+    results
+        .into_iter()
+        .map(|file| {
+            file.map(|chunks| processed_chunks_to_py(py, chunks))
+                .transpose()
+        })
+        .collect()
+}
+
+/// Process a JSONL corpus file by file, writing chunks as JSON lines.
+///
+/// Each line of `input_path` should be a JSON object holding a document's text
+/// under `text_field` and its id under `id_field`. Every document goes
+/// through `process_document`, and each of its chunks is written to
+/// `output_path` (created or truncated) as one JSON line: the chunk dict
+/// `process_document` would return, with the document's id added under "id"
+/// (null when the record has none). Lines are read and chunks written one at a
+/// time, so the file is never loaded whole, and the GIL is released
+/// throughout: several files can be processed at once from Python threads.
+///
+/// A line that is not a JSON object with a string under `text_field` is
+/// skipped and counted; blank lines are ignored.
+///
+/// Args:
+///     input_path: JSONL file to read, as a str or `os.PathLike`
+///     output_path: JSONL file to write, as a str or `os.PathLike`
+///     text_field: Key of each record's text (default: "text")
+///     id_field: Key of each record's id, copied as-is (default: "id")
+///
+/// All other arguments are forwarded to `process_document`.
+///
+/// Returns:
+///     A dict with "records" (documents processed), "chunks" (lines written),
+///     "skipped" (malformed lines) and "skipped_lines" (their 1-based numbers)
+///
+/// Raises:
+///     OSError: If either file cannot be opened, read or written; the subclass
+///         follows the error and `filename` names the file
+///     InvalidChunkParams: If chunk_size is 0, or a float chunk_overlap is
+///         outside [0, 1)
+///     InvalidOption: As for `process_document`
+#[pyfunction]
+#[pyo3(signature = (
+    input_path,
+    output_path,
+    *,
+    text_field="text",
+    id_field="id",
+    chunk_size=1500,
+    chunk_overlap=OverlapArg::Size(200),
+    size_unit="chars",
+    infer_sign=false,
+    extra_stopwords=None,
+    allowlist=None,
+    ticker_order="alphabetical",
+    extractor=None,
+    extract_meta=true,
+    include_spans=false,
+    clean_options=None
+))]
+#[allow(clippy::too_many_arguments)]
+fn process_jsonl(
+    py: Python<'_>,
+    input_path: PathBuf,
+    output_path: PathBuf,
+    text_field: &str,
+    id_field: &str,
+    chunk_size: usize,
+    chunk_overlap: OverlapArg,
+    size_unit: &str,
+    infer_sign: bool,
+    extra_stopwords: Option<Bound<'_, PyAny>>,
+    allowlist: Option<Bound<'_, PyAny>>,
+    ticker_order: &str,
+    extractor: Option<PyRef<'_, MetadataExtractor>>,
+    extract_meta: bool,
+    include_spans: bool,
+    clean_options: Option<CleanOptions>,
+) -> PyResult<Py<PyDict>> {
+    let config = ProcessConfig {
+        extract_meta,
+        include_spans,
+        ..process_config_from_py(
+            chunk_size,
+            chunk_overlap,
+            size_unit,
+            infer_sign,
+            extractor,
+            extra_stopwords,
+            allowlist,
+            ticker_order,
+            clean_options,
+            "empty_list",
+        )?
+    };
+    let fields = JsonlFields {
+        text_field: text_field.to_string(),
+        id_field: id_field.to_string(),
+    };
+
+    let summary = py
+        .allow_threads(|| jsonl::process_jsonl_impl(&input_path, &output_path, &fields, &config))
+        .map_err(|err| io_error_into_py(err.error, &err.path))?;
+
+    let dict = PyDict::new(py);
+    dict.set_item("records", summary.records)?;
+    dict.set_item("chunks", summary.chunks)?;
+    dict.set_item("skipped", summary.skipped_lines.len())?;
+    dict.set_item("skipped_lines", summary.skipped_lines)?;
+    Ok(dict.into())
+}
+
+/// Measure a document before choosing chunking parameters.
+///
+/// The text is cleaned first (with `clean_options`, a `CleanOptions`, if
+/// given), so every count describes what `chunk_text` would receive.
+///
+/// Returns:
+///     A dict with "char_count" (grapheme clusters), "byte_count" (UTF-8),
+///     "word_count" (whitespace-delimited tokens), "sentence_count" (as split
+///     for chunking), "avg_sentence_length" (mean words per sentence, 0.0 for
+///     no sentences) and "paragraph_count" (blocks separated by a blank line).
+///     Empty input gives all zeros.
+#[pyfunction]
+#[pyo3(signature = (text, *, clean_options=None))]
+fn text_stats(
+    py: Python<'_>,
+    text: &str,
+    clean_options: Option<CleanOptions>,
+) -> PyResult<Py<PyDict>> {
+    let options = clean_options.unwrap_or_default();
+    let stats = py.allow_threads(|| stats::text_stats_impl(text, &options));
+
+    let dict = PyDict::new(py);
+    dict.set_item("char_count", stats.char_count)?;
+    dict.set_item("byte_count", stats.byte_count)?;
+    dict.set_item("word_count", stats.word_count)?;
+    dict.set_item("sentence_count", stats.sentence_count)?;
+    dict.set_item("avg_sentence_length", stats.avg_sentence_length)?;
+    dict.set_item("paragraph_count", stats.paragraph_count)?;
+    Ok(dict.into())
+}
+
+/// Split `text` into sentences exactly as `chunk_text` does by default.
+///
+/// Each sentence keeps its closing punctuation ("Hello!", "How are you?") and
+/// loses the whitespace around it. Decimals ("$4.5 billion"), initials ("U.S.",
+/// "J. Smith") and common abbreviations ("approx.", "e.g.", "No. 1") do not end
+/// a sentence, while "。", "！" and "？" do even without a following space.
+/// Useful to see where chunk boundaries can fall.
+#[pyfunction]
+fn split_sentences(py: Python<'_>, text: &str) -> Vec<String> {
+    py.allow_threads(|| split_sentences_preserve_punct(text))
+}
+
+/// Find the section headings of an SEC filing in `text`.
+///
+/// Returns (title, start_char) tuples in document order, `start_char` being
+/// the character offset of the heading in `text`. Recognized are item
+/// headings ("Item 1A. Risk Factors", "ITEM 7 - MANAGEMENT'S DISCUSSION AND
+/// ANALYSIS", also split across lines), "PART I" to "PART IV", and the common
+/// item names on their own ("Risk Factors", "Controls and Procedures").
+/// Titles come out the same however the heading was written: "Item 1A. Risk
+/// Factors", "Part II".
+///
+/// References to a section ("see Item 7. Management's Discussion and
+/// Analysis") are not headings: a heading must start a line or follow the end
+/// of a sentence, and a name without an item number must stand alone on its
+/// line unless it is in capitals.
+#[pyfunction]
+#[pyo3(name = "detect_sections")]
+fn detect_sections_py(py: Python<'_>, text: &str) -> Vec<(String, usize)> {
+    py.allow_threads(|| sections::detect_sections(text))
+}
+
+/// Find plain-text tables in `text`, such as financial statements pasted as
+/// whitespace-aligned columns.
+///
+/// Returns one list of rows per table, in document order, each row a list of
+/// its cells as strings. Cells are separated by a tab or by two or more
+/// spaces, so "$ 1,234" stays one cell. A table needs at least three such
+/// rows, each sharing a column edge with the row before it (right-aligned
+/// figures or left-aligned columns; tabs count as stops of 8), which keeps
+/// ordinary prose out. Up to two lines of a single cell between rows
+/// ("Current assets:") are kept as one-cell rows.
+#[pyfunction]
+fn extract_tables(py: Python<'_>, text: &str) -> Vec<Vec<Vec<String>>> {
+    py.allow_threads(|| {
+        tables::find_tables(text)
+            .into_iter()
+            .map(|table| table.rows)
+            .collect()
+    })
+}
+
+/// Guess the language of `text` from its script and, for Latin script, its
+/// most common function words.
+///
+/// Returns the ISO 639-1 code of the most likely language ("en", "de", "fr",
+/// "es", "it", "pt", "nl", "ru", "uk", "el", "he", "ar", "fa", "hi", "th",
+/// "ko", "ja", "zh"), or "und" when there is too little evidence. A
+/// heuristic, not a model: scripts are reliable from a sentence on, Latin
+/// languages need a few sentences of prose.
+#[pyfunction]
+#[pyo3(name = "detect_language")]
+fn detect_language_py(py: Python<'_>, text: &str) -> &'static str {
+    py.allow_threads(|| language::detect_language(text))
+}
+
+/// Time each processing stage on `path_or_text`, to compare performance on your hardware.
+///
+/// Args:
+///     path_or_text: Path to a UTF-8 text file, or the text itself
+///     iterations: Number of timed runs (default: 10)
+///     chunk_size: Target chunk size in characters (default: 1500)
+///     chunk_overlap: Overlap between chunks in characters (default: 200)
+///
+/// Returns:
+///     A dict with "iterations", "input_chars", "chunk_count" and "stages", which maps
+///     "clean", "split_sentences", "chunk", "extract_metadata" and "total" to
+///     `{"min_s", "median_s", "mean_s"}` timings in seconds. Compare medians
+///     across commits; they are far less noisy than means.
+///
+/// Raises:
+///     InvalidChunkParams: If iterations or chunk_size is 0
+///     OSError: If `path_or_text` names a file that cannot be read
+#[pyfunction]
+#[pyo3(signature = (path_or_text, *, iterations=10, chunk_size=1500, chunk_overlap=200))]
+fn profile_run(
+    py: Python<'_>,
+    path_or_text: &str,
+    iterations: usize,
+    chunk_size: usize,
+    chunk_overlap: usize,
+) -> PyResult<Py<PyDict>> {
+    // Only short single-line strings are tried as paths
+    let is_path = !path_or_text.contains('\n')
+        && path_or_text.len() < 4096
+        && Path::new(path_or_text).is_file();
+    let text = if is_path {
+        std::fs::read_to_string(path_or_text)?
+    } else {
+        path_or_text.to_string()
+    };
+
+    let report = py
+        .allow_threads(|| profile::profile_run_impl(&text, iterations, chunk_size, chunk_overlap))
+        .ok_or_else(|| {
+            InvalidChunkParams::new_err("iterations and chunk_size must be greater than 0")
+        })?;
+
+    let stages = PyDict::new(py);
+    for stage in &report.stages {
+        let timings = PyDict::new(py);
+        timings.set_item("min_s", stage.min().as_secs_f64())?;
+        timings.set_item("median_s", stage.median().as_secs_f64())?;
+        timings.set_item("mean_s", stage.mean().as_secs_f64())?;
+        stages.set_item(stage.name, timings)?;
+    }
+    let dict = PyDict::new(py);
+    dict.set_item("iterations", report.iterations)?;
+    dict.set_item("input_chars", report.input_chars)?;
+    dict.set_item("chunk_count", report.chunk_count)?;
+    dict.set_item("stages", stages)?;
+    Ok(dict.into())
+}
+
+/// Rust-powered text cleaning, chunking and metadata extraction for RAG pipelines.
+///
+/// Invalid arguments raise exceptions from one hierarchy, all subclasses of
+/// `ValueError` so existing `except ValueError` handlers keep working:
+///
+/// ```text
+/// RagCoreError
+/// ├── InvalidChunkParams  unusable chunk size or overlap (target_size=0,
+/// │                       overlap_ratio outside [0, 1), ...)
+/// ├── InvalidOption       unknown option value (on_empty, ticker_order,
+/// │                       date_format, ...) or conflicting arguments
+/// └── EmptyDocument       process_document(on_empty="error") on a
+///                         document that cleans to nothing
+/// ```
+#[pymodule]
+fn rag_rust_core(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<CleanOptions>()?;
+    m.add_class::<MetadataExtractor>()?;
+    m.add_class::<Matchers>()?;
+    m.add_class::<ChunkIterator>()?;
+    #[cfg(feature = "arrow")]
+    {
+        m.add_class::<ArrowChunks>()?;
+        m.add_function(wrap_pyfunction!(process_document_arrow, m)?)?;
+        m.add_function(wrap_pyfunction!(process_documents_arrow, m)?)?;
+    }
+    m.add("RagCoreError", m.py().get_type::<RagCoreError>())?;
+    m.add(
+        "InvalidChunkParams",
+        m.py().get_type::<InvalidChunkParams>(),
+    )?;
+    m.add("InvalidOption", m.py().get_type::<InvalidOption>())?;
+    m.add("EmptyDocument", m.py().get_type::<EmptyDocument>())?;
+    m.add_function(wrap_pyfunction!(clean_text_py, m)?)?;
+    m.add_function(wrap_pyfunction!(dehyphenate, m)?)?;
+    m.add_function(wrap_pyfunction!(repair_mojibake, m)?)?;
+    m.add_function(wrap_pyfunction!(redact_pii_py, m)?)?;
+    m.add_function(wrap_pyfunction!(remove_boilerplate_py, m)?)?;
+    m.add_function(wrap_pyfunction!(chunk_text_py, m)?)?;
+    m.add_function(wrap_pyfunction!(chunk_text_by_words, m)?)?;
+    m.add_function(wrap_pyfunction!(chunk_text_iter, m)?)?;
+    m.add_function(wrap_pyfunction!(chunk_markdown, m)?)?;
+    m.add_function(wrap_pyfunction!(chunk_text_recursive, m)?)?;
+    m.add("SENTENCE_SEPARATOR", recursive::SENTENCE_SEPARATOR)?;
+    m.add_function(wrap_pyfunction!(extract_metadata_py, m)?)?;
+    m.add_function(wrap_pyfunction!(extract_metadata_with_tickers, m)?)?;
+    m.add_function(wrap_pyfunction!(extract_metadata_spans, m)?)?;
+    m.add_function(wrap_pyfunction!(normalize_money, m)?)?;
+    m.add_function(wrap_pyfunction!(parse_percentage_py, m)?)?;
+    m.add_function(wrap_pyfunction!(normalize_date_py, m)?)?;
+    m.add_function(wrap_pyfunction!(hash_text, m)?)?;
+    m.add_function(wrap_pyfunction!(dedupe_chunks, m)?)?;
+    m.add_function(wrap_pyfunction!(process_document, m)?)?;
+    m.add_function(wrap_pyfunction!(process_document_json, m)?)?;
+    m.add_function(wrap_pyfunction!(process_documents, m)?)?;
+    m.add_function(wrap_pyfunction!(process_file, m)?)?;
+    m.add_function(wrap_pyfunction!(process_files, m)?)?;
+    m.add_function(wrap_pyfunction!(process_jsonl, m)?)?;
+    m.add_function(wrap_pyfunction!(profile_run, m)?)?;
+    m.add_function(wrap_pyfunction!(text_stats, m)?)?;
+    m.add_function(wrap_pyfunction!(split_sentences, m)?)?;
+    m.add_function(wrap_pyfunction!(detect_sections_py, m)?)?;
+    m.add_function(wrap_pyfunction!(extract_tables, m)?)?;
+    m.add_function(wrap_pyfunction!(detect_language_py, m)?)?;
+    Ok(())
+}
@@ -0,0 +1,398 @@
+//! Ranges of monetary amounts and percentages, as written in guidance
+//! ("revenue of $5.0 to $5.2 billion", "$5–7 million", "between 3% and 4%",
+//! "40–60 bps"), and of years ("2020–2024", "2019-23") and plain numbers
+//! ("10 to 12 million units").
+//!
+//! `MONEY_RE` and `PERCENTAGE_RE` see the two bounds as unrelated matches,
+//! or only the second one when the first carries no currency or unit. Here a
+//! range is one entry whose bounds share what is written only once: the
+//! currency, scale or unit of the other bound.
+//!
+//! A plain number range is only one where nothing else claims the text: not
+//! part of a money, percentage or year range, a date or a phone number.
+
+use crate::entities::PHONE_RE;
+use crate::normalize::{
+    parse_money, parse_number, parse_percentage, percentage_unit, scale_multiplier, split_currency,
+};
+use crate::{DATE_RE, MONEY_RE, MONEY_SCALE, PERCENTAGE_RE, PERCENT_UNIT};
+use regex::{Captures, Regex};
+use serde::Serialize;
+use std::ops::Range;
+use std::sync::LazyLock;
+
+/// A range of two monetary amounts or two percentages.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ParsedRange {
+    pub raw: String,
+    /// "money", "percentage", "year" or "number".
+    pub kind: &'static str,
+    /// Bounds in base units for money, percent units for percentages
+    /// ("40–60 bps" -> 0.4 and 0.6), years for years ("2020-24" -> 2020.0
+    /// and 2024.0) and numbers with any scale word applied, or None if one
+    /// could not be parsed.
+    pub low: Option<f64>,
+    pub high: Option<f64>,
+    /// Currency of a money range, as in `ParsedMoney`.
+    pub currency: Option<String>,
+    /// "percent" or "basis_points" for a percentage range.
+    pub unit: Option<&'static str>,
+}
+
+// "to" and "and" need whitespace around them; dashes (hyphen, en dash, em
+// dash) may have it or not. "and" only connects bounds after "between".
+const CONNECTOR: &str = r"(?:\s*[-–—]\s*|\s+(?i:to|and)\s+)";
+
+// A currency-prefixed amount, then a connector and a second amount whose
+// currency and scale may be left out ("$5.0 to $5.2 billion", "$5–7 million")
+static MONEY_RANGE_RE: LazyLock<Regex> = LazyLock::new(|| {
+    let currency = r"(?:[$€£¥₹]|\b(?:USD|EUR|GBP|JPY|CNY|INR|CHF)\s?)";
+    let number = r"\d[\d,]*(?:\.\d+)?";
+    Regex::new(&format!(
+        r"(?P<between>(?i:\bbetween)\s+)?(?P<low>{currency}{number}(?:\s*(?:{MONEY_SCALE}))?)(?P<connector>{CONNECTOR})(?P<high>{currency}?{number}(?:\s*(?:{MONEY_SCALE}))?)"
+    ))
+    .unwrap()
+});
+
+// Two numbers, the second with a percent unit the first may leave out
+// ("3-4%", "3% to 4%", "40–60 bps")
+static PERCENTAGE_RANGE_RE: LazyLock<Regex> = LazyLock::new(|| {
+    let number = r"\b\d+(?:\.\d+)?";
+    Regex::new(&format!(
+        r"(?P<between>(?i:\bbetween)\s+)?(?P<low>{number}(?:{PERCENT_UNIT})?)(?P<connector>{CONNECTOR})(?P<high>{number}(?:{PERCENT_UNIT}))"
+    ))
+    .unwrap()
+});
+
+// A four-digit year, then a connector and a second year written in full or
+// with its last two digits ("2020–2024", "2019-23")
+static YEAR_RANGE_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(&format!(
+        r"(?P<between>(?i:\bbetween)\s+)?\b(?P<low>(?:19|20)\d{{2}})(?P<connector>{CONNECTOR})(?P<high>(?:19|20)\d{{2}}|\d{{2}})\b"
+    ))
+    .unwrap()
+});
+
+// Two numbers, either of which may have a scale word the other leaves out
+// ("10 to 12 million", "5-7")
+static NUMBER_RANGE_RE: LazyLock<Regex> = LazyLock::new(|| {
+    let number = r"\d[\d,]*(?:\.\d+)?";
+    let scale = r"(?:\s*(?i:thousand|million|billion|trillion)\b)?";
+    Regex::new(&format!(
+        r"(?P<between>(?i:\bbetween)\s+)?\b(?P<low>{number}{scale})(?P<connector>{CONNECTOR})(?P<high>{number}{scale})"
+    ))
+    .unwrap()
+});
+
+/// Whether a range match is well formed: "and" only after "between", and no
+/// minus before the first bound ("-3 to 5%" is not a range from 3).
+fn is_range(text: &str, caps: &Captures) -> bool {
+    let connector = caps["connector"].trim().to_lowercase();
+    let start = caps.get(0).map_or(0, |m| m.start());
+    (connector != "and" || caps.name("between").is_some()) && !text[..start].ends_with(['-', '−'])
+}
+
+/// Money range from its match, with the currency and scale of one bound
+/// carried over to the other where it was left out.
+fn money_range(caps: &Captures) -> Option<ParsedRange> {
+    let (low_currency, low_amount) = split_currency(&caps["low"])?;
+    let (high_currency, high_amount) = match split_currency(&caps["high"]) {
+        Some(split) => split,
+        None => (low_currency, caps["high"].trim()),
+    };
+    if high_currency != low_currency {
+        return None;
+    }
+    let scale = |amount: &str| {
+        let start = amount.find(|c: char| !(c.is_ascii_digit() || c == ',' || c == '.'));
+        start.map(|i| amount[i..].trim().to_string())
+    };
+    let low_scale = scale(low_amount);
+    let high_scale = scale(high_amount);
+    let low = match (&low_scale, &high_scale) {
+        (None, Some(suffix)) => parse_money(&format!("{} {suffix}", &caps["low"])),
+        _ => parse_money(&caps["low"]),
+    };
+    let high = match (&low_scale, &high_scale) {
+        (Some(suffix), None) => parse_money(&format!("{low_currency} {high_amount} {suffix}")),
+        _ => parse_money(&format!("{low_currency} {high_amount}")),
+    };
+    Some(ParsedRange {
+        raw: caps[0].to_string(),
+        kind: "money",
+        low,
+        high,
+        currency: Some(low_currency.to_string()),
+        unit: None,
+    })
+}
+
+/// Percentage range from its match, with the unit of the second bound
+/// carried over to the first where it was left out.
+fn percentage_range(caps: &Captures) -> Option<ParsedRange> {
+    let high_raw = &caps["high"];
+    let unit = percentage_unit(high_raw)?;
+    let number_end = high_raw
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(high_raw.len());
+    let low = match percentage_unit(&caps["low"]) {
+        Some(low_unit) if low_unit != unit => return None,
+        Some(_) => parse_percentage(&caps["low"]),
+        None => parse_percentage(&format!("{}{}", &caps["low"], &high_raw[number_end..])),
+    };
+    Some(ParsedRange {
+        raw: caps[0].to_string(),
+        kind: "percentage",
+        low,
+        high: parse_percentage(high_raw),
+        currency: None,
+        unit: Some(unit),
+    })
+}
+
+/// Whether the match from `start` to `end` runs on into a longer run of
+/// digits and separators, as in a date ("2010-12-31") or an identifier.
+fn runs_on(text: &str, start: usize, end: usize) -> bool {
+    let before = text[..start].chars().next_back();
+    let mut after = text[end..].chars();
+    matches!(before, Some(c) if c.is_ascii_digit() || "-–—/.$€£¥₹".contains(c))
+        || matches!(
+            (after.next(), after.next()),
+            (Some('-' | '/' | '.' | ','), Some(c)) if c.is_ascii_digit()
+        )
+}
+
+/// Year range from its match; a two-digit second year is in the century of
+/// the first, and the second year must come after the first.
+fn year_range(caps: &Captures) -> Option<ParsedRange> {
+    let low: u32 = caps["low"].parse().ok()?;
+    let high: u32 = match &caps["high"] {
+        short if short.len() == 2 => low / 100 * 100 + short.parse::<u32>().ok()?,
+        full => full.parse().ok()?,
+    };
+    (high > low).then(|| ParsedRange {
+        raw: caps[0].to_string(),
+        kind: "year",
+        low: Some(low as f64),
+        high: Some(high as f64),
+        currency: None,
+        unit: None,
+    })
+}
+
+/// A number bound split into its value and scale word, if any.
+fn split_scale(bound: &str) -> (Option<f64>, Option<&str>) {
+    let end = bound
+        .find(|c: char| !(c.is_ascii_digit() || c == ',' || c == '.'))
+        .unwrap_or(bound.len());
+    let scale = bound[end..].trim();
+    (
+        parse_number(&bound[..end]),
+        (!scale.is_empty()).then_some(scale),
+    )
+}
+
+/// Number range from its match, with the scale word of one bound carried
+/// over to the other where it was left out. The bounds must be ascending.
+fn number_range(caps: &Captures) -> Option<ParsedRange> {
+    let (low, low_scale) = split_scale(&caps["low"]);
+    let (high, high_scale) = split_scale(&caps["high"]);
+    let multiplier = |scale: Option<&str>| scale.map_or(Some(1.0), scale_multiplier);
+    let low = low
+        .zip(multiplier(low_scale.or(high_scale)))
+        .map(|(n, m)| n * m);
+    let high = high
+        .zip(multiplier(high_scale.or(low_scale)))
+        .map(|(n, m)| n * m);
+    match (low, high) {
+        (Some(l), Some(h)) if l >= h => None,
+        _ => Some(ParsedRange {
+            raw: caps[0].to_string(),
+            kind: "number",
+            low,
+            high,
+            currency: None,
+            unit: None,
+        }),
+    }
+}
+
+/// Money, percentage, year and number ranges in `text`, in document order.
+pub(crate) fn extract_ranges(text: &str) -> Vec<ParsedRange> {
+    let money = MONEY_RANGE_RE
+        .captures_iter(text)
+        .filter(|caps| is_range(text, caps))
+        .filter_map(|caps| Some((caps.get(0)?.range(), money_range(&caps)?)));
+    let percentages = PERCENTAGE_RANGE_RE
+        .captures_iter(text)
+        .filter(|caps| is_range(text, caps))
+        .filter_map(|caps| Some((caps.get(0)?.range(), percentage_range(&caps)?)));
+    let years = YEAR_RANGE_RE
+        .captures_iter(text)
+        .filter(|caps| is_range(text, caps))
+        .filter_map(|caps| {
+            let m = caps.get(0)?;
+            if runs_on(text, m.start(), m.end()) {
+                return None;
+            }
+            Some((m.range(), year_range(&caps)?))
+        });
+    let mut ranges: Vec<(Range<usize>, ParsedRange)> =
+        money.chain(percentages).chain(years).collect();
+
+    // Numbers last: the text they may take is what nothing else has claimed
+    let mut claimed: Vec<Range<usize>> = ranges.iter().map(|(span, _)| span.clone()).collect();
+    for re in [&*MONEY_RE, &*PERCENTAGE_RE, &*DATE_RE, &*PHONE_RE] {
+        claimed.extend(re.find_iter(text).map(|m| m.range()));
+    }
+    let numbers = NUMBER_RANGE_RE
+        .captures_iter(text)
+        .filter(|caps| is_range(text, caps))
+        .filter_map(|caps| {
+            let m = caps.get(0)?;
+            let overlaps = claimed
+                .iter()
+                .any(|span| span.start < m.end() && m.start() < span.end);
+            if overlaps || runs_on(text, m.start(), m.end()) {
+                return None;
+            }
+            Some((m.range(), number_range(&caps)?))
+        })
+        .collect::<Vec<_>>();
+    ranges.extend(numbers);
+    ranges.sort_by_key(|(span, _)| span.start);
+    ranges.into_iter().map(|(_, range)| range).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Raw text, bounds, and currency or unit of a range.
+    type Entry = (String, Option<f64>, Option<f64>, Option<String>);
+
+    fn summary(text: &str) -> Vec<Entry> {
+        extract_ranges(text)
+            .into_iter()
+            .map(|r| {
+                (
+                    r.raw,
+                    r.low,
+                    r.high,
+                    r.currency.or(r.unit.map(String::from)),
+                )
+            })
+            .collect()
+    }
+
+    fn entry(raw: &str, low: f64, high: f64, label: &str) -> Entry {
+        (
+            raw.to_string(),
+            Some(low),
+            Some(high),
+            Some(label.to_string()),
+        )
+    }
+
+    #[test]
+    fn test_money_ranges() {
+        let text = "We expect revenue of $5.0 to $5.2 billion, capex of $5–7 million, \
+                    costs between €300 million and €350 million and a charge of \
+                    $40-$60M.";
+        assert_eq!(
+            summary(text),
+            vec![
+                entry("$5.0 to $5.2 billion", 5.0e9, 5.2e9, "USD"),
+                entry("$5–7 million", 5e6, 7e6, "USD"),
+                entry("between €300 million and €350 million", 300e6, 350e6, "EUR"),
+                entry("$40-$60M", 40e6, 60e6, "USD"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_percentage_ranges() {
+        let text = "Margins of between 3% and 4%, growth of 5-6%, \
+                    spreads 40–60 bps wider and churn of 2.5 to 3.0 percent.";
+        assert_eq!(
+            summary(text),
+            vec![
+                entry("between 3% and 4%", 3.0, 4.0, "percent"),
+                entry("5-6%", 5.0, 6.0, "percent"),
+                entry("40–60 bps", 0.4, 0.6, "basis_points"),
+                entry("2.5 to 3.0 percent", 2.5, 3.0, "percent"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_year_ranges() {
+        let text = "Targets for 2020–2024 and 2019-23, between 2025 and 2030, \
+                    from 2021 to 2022.";
+        assert_eq!(
+            extract_ranges(text)
+                .into_iter()
+                .map(|r| (r.raw, r.kind, r.low, r.high))
+                .collect::<Vec<_>>(),
+            vec![
+                ("2020–2024".to_string(), "year", Some(2020.0), Some(2024.0)),
+                ("2019-23".to_string(), "year", Some(2019.0), Some(2023.0)),
+                (
+                    "between 2025 and 2030".to_string(),
+                    "year",
+                    Some(2025.0),
+                    Some(2030.0)
+                ),
+                (
+                    "2021 to 2022".to_string(),
+                    "year",
+                    Some(2021.0),
+                    Some(2022.0)
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_number_ranges() {
+        let text = "We expect to ship 10 to 12 million units and hire 1,000-1,500 people \
+                    over 3–5 years, with $5–10 million of capex and margins of 3–5%.";
+        assert_eq!(
+            extract_ranges(text)
+                .into_iter()
+                .map(|r| (r.raw, r.kind, r.low, r.high))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    "10 to 12 million".to_string(),
+                    "number",
+                    Some(10e6),
+                    Some(12e6)
+                ),
+                (
+                    "1,000-1,500".to_string(),
+                    "number",
+                    Some(1000.0),
+                    Some(1500.0)
+                ),
+                ("3–5".to_string(), "number", Some(3.0), Some(5.0)),
+                ("$5–10 million".to_string(), "money", Some(5e6), Some(10e6)),
+                ("3–5%".to_string(), "percentage", Some(3.0), Some(5.0)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_not_year_or_number_ranges() {
+        let text = "Filed 2010-12-31 and 2024-03-15. Call 212-555-0123. Scores were 5-3 \
+                    and 2024-2020. See Form 10-K and Rule 10b-5.";
+        assert_eq!(summary(text), vec![]);
+    }
+
+    #[test]
+    fn test_not_ranges() {
+        let text = "Revenue was $5 and costs $3 million. Rates moved from -3 to 5%. \
+                    Sales moved from $5 million to €7 million. Fees were 3% to 40 bps.";
+        assert_eq!(summary(text), vec![]);
+    }
+}
@@ -0,0 +1,363 @@
+//! Recursive chunking over a separator hierarchy, backing `chunk_text_recursive`.
+//!
+//! The text is split at the first separator of the list that occurs in it, and
+//! only pieces still longer than `target_size` are split again with the
+//! separators after it: paragraphs, then lines, then sentences, then words,
+//! then characters by default. Consecutive pieces that fit are packed into
+//! chunks with the same overlap rules as `chunk_text`. Unlike `chunk_text`,
+//! which always packs sentences, a paragraph that fits in a chunk is never
+//! divided, and one that does not fit never shares a chunk with its neighbours.
+
+use crate::{
+    char_len, finish_chunk, graphemes, merge_small_chunks, split_sentences_preserve_punct,
+    PackedChunk, SizeUnit,
+};
+use std::collections::VecDeque;
+
+/// Marker in a separator list for the sentence level, split with
+/// `split_sentences_preserve_punct`.
+pub(crate) const SENTENCE_SEPARATOR: &str = "<sentence>";
+
+/// Separators tried when none are given, largest first.
+pub(crate) const DEFAULT_SEPARATORS: [&str; 5] = ["\n\n", "\n", SENTENCE_SEPARATOR, " ", ""];
+
+/// One level of the separator hierarchy.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Separator {
+    /// Split at every occurrence of the string, which joins the pieces again.
+    Literal(String),
+    /// Split into sentences, joined with a space as in `chunk_text`.
+    Sentences,
+    /// Split between grapheme clusters (given as "").
+    Characters,
+}
+
+impl Separator {
+    pub fn parse(separator: &str) -> Self {
+        match separator {
+            "" => Separator::Characters,
+            SENTENCE_SEPARATOR => Separator::Sentences,
+            literal => Separator::Literal(literal.to_string()),
+        }
+    }
+
+    pub fn defaults() -> Vec<Self> {
+        DEFAULT_SEPARATORS
+            .iter()
+            .map(|s| Separator::parse(s))
+            .collect()
+    }
+
+    /// What goes between two pieces this separator split apart.
+    fn joiner(&self) -> &str {
+        match self {
+            Separator::Literal(literal) => literal,
+            Separator::Sentences => " ",
+            Separator::Characters => "",
+        }
+    }
+
+    /// The non-empty pieces of `text`, whitespace-trimmed except for characters.
+    fn split(&self, text: &str) -> Vec<String> {
+        match self {
+            Separator::Literal(literal) => text
+                .split(literal.as_str())
+                .map(str::trim)
+                .filter(|piece| !piece.is_empty())
+                .map(str::to_string)
+                .collect(),
+            Separator::Sentences => split_sentences_preserve_punct(text),
+            Separator::Characters => graphemes::clusters(text).map(str::to_string).collect(),
+        }
+    }
+}
+
+/// Split `text` at the first of `separators` that divides it and push its
+/// chunks: runs of pieces that fit are packed together, and each longer piece
+/// is split again with the separators after that one. A piece no separator
+/// divides becomes a chunk of its own, however long.
+fn split_recursive<'a>(
+    text: &str,
+    separators: &'a [Separator],
+    target_size: usize,
+    overlap: usize,
+    chunks: &mut Vec<PackedChunk<'a>>,
+) {
+    let split = separators.iter().enumerate().find_map(|(i, separator)| {
+        let parts = separator.split(text);
+        (parts.len() > 1).then_some((i, separator, parts))
+    });
+    let Some((i, separator, parts)) = split else {
+        chunks.push(finish_chunk(text, 0, ""));
+        return;
+    };
+    let joiner = separator.joiner();
+    // The first chunk of each run or split piece follows the previous one
+    // across this level's separator
+    let start_segment = |chunks: &mut Vec<PackedChunk<'a>>, first: usize| {
+        if let Some(chunk) = chunks.get_mut(first) {
+            chunk.joiner = joiner;
+        }
+    };
+
+    let mut run: Vec<String> = Vec::new();
+    for part in parts {
+        if char_len(&part) <= target_size {
+            run.push(part);
+            continue;
+        }
+        let first = chunks.len();
+        pack(
+            std::mem::take(&mut run),
+            joiner,
+            target_size,
+            overlap,
+            chunks,
+        );
+        start_segment(chunks, first);
+        let first = chunks.len();
+        split_recursive(&part, &separators[i + 1..], target_size, overlap, chunks);
+        start_segment(chunks, first);
+    }
+    let first = chunks.len();
+    pack(run, joiner, target_size, overlap, chunks);
+    start_segment(chunks, first);
+}
+
+/// Pack pieces of at most `target_size` characters, joined with `joiner`, into
+/// chunks with `chunk_text`'s overlap rules.
+fn pack<'a>(
+    pieces: Vec<String>,
+    joiner: &'a str,
+    target_size: usize,
+    overlap: usize,
+    chunks: &mut Vec<PackedChunk<'a>>,
+) {
+    let joiner_size = char_len(joiner);
+    let mut current: Vec<&str> = Vec::new();
+    let mut current_size = 0;
+    // Where the current chunk's own pieces start
+    let mut current_new_start = 0;
+    // Trailing pieces that may be carried into the next chunk, and their
+    // joined length
+    let mut overlap_buffer: VecDeque<&str> = VecDeque::new();
+    let mut overlap_len = 0;
+
+    for piece in &pieces {
+        let piece_size = char_len(piece);
+        if !current.is_empty() && current_size + joiner_size + piece_size > target_size {
+            chunks.push(finish_chunk(
+                &current.join(joiner),
+                current_new_start,
+                joiner,
+            ));
+
+            // Leave out the oldest carried pieces if this one would not fit
+            let mut carried_size = overlap_len + joiner_size;
+            while !overlap_buffer.is_empty() && carried_size + piece_size > target_size {
+                let dropped = overlap_buffer.pop_front().unwrap_or_default();
+                carried_size -= char_len(dropped) + joiner_size;
+                overlap_len = carried_size.saturating_sub(joiner_size);
+            }
+            current = overlap_buffer.iter().copied().collect();
+            current_size = overlap_len;
+            current_new_start = if current.is_empty() {
+                0
+            } else {
+                current.join(joiner).len() + joiner.len()
+            };
+        }
+
+        if !current.is_empty() {
+            current_size += joiner_size;
+        }
+        current.push(piece);
+        current_size += piece_size;
+
+        if !overlap_buffer.is_empty() {
+            overlap_len += joiner_size;
+        }
+        overlap_len += piece_size;
+        overlap_buffer.push_back(piece);
+
+        // The budget also covers the joiner before the next piece
+        while overlap_len + joiner_size > overlap && overlap_buffer.len() > 1 {
+            if let Some(removed) = overlap_buffer.pop_front() {
+                overlap_len -= char_len(removed) + joiner_size;
+            }
+        }
+    }
+
+    let last = current.join(joiner);
+    if !last.trim().is_empty() {
+        chunks.push(finish_chunk(&last, current_new_start, joiner));
+    }
+}
+
+/// Chunk `text` by splitting it recursively at `separators` (largest first)
+/// and packing the pieces into chunks of at most `target_size` characters.
+///
+/// Overlap works as in `chunk_text` within each run of pieces packed together:
+/// the trailing pieces of a chunk that fit in `overlap` characters, or at
+/// least the last one, start the next chunk, as long as they leave room for
+/// its first new piece. Nothing is carried across a piece that had to be split
+/// further.
+///
+/// Any chunk but the first that is smaller than `min_chunk_size` is appended
+/// to the previous one, without the overlap it carried: here small chunks are
+/// not only the tail but also, say, a heading or short paragraph cut off by a
+/// long one. Returns None if `target_size` is 0.
+pub fn chunk_text_recursive_impl(
+    text: &str,
+    target_size: usize,
+    overlap: usize,
+    separators: &[Separator],
+    min_chunk_size: usize,
+) -> Option<Vec<String>> {
+    if target_size == 0 {
+        return None;
+    }
+    let text = text.trim();
+    if text.is_empty() {
+        return Some(vec![]);
+    }
+    if char_len(text) <= target_size {
+        return Some(vec![text.to_string()]);
+    }
+    let overlap = overlap.min(target_size - 1);
+    let mut chunks = Vec::new();
+    split_recursive(text, separators, target_size, overlap, &mut chunks);
+    Some(merge_small_chunks(
+        chunks,
+        SizeUnit::Chars,
+        min_chunk_size,
+        true,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chunk_text_impl;
+
+    fn separators(list: &[&str]) -> Vec<Separator> {
+        list.iter().map(|s| Separator::parse(s)).collect()
+    }
+
+    const REPORT: &str = "# Results\n\n\
+        Revenue grew in every region. Europe led with strong demand.\n\n\
+        ## Outlook\n\n\
+        Guidance is unchanged. Margins should hold.";
+
+    #[test]
+    fn test_parse_separators() {
+        assert_eq!(
+            Separator::defaults(),
+            vec![
+                Separator::Literal("\n\n".to_string()),
+                Separator::Literal("\n".to_string()),
+                Separator::Sentences,
+                Separator::Literal(" ".to_string()),
+                Separator::Characters,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_keeps_paragraphs_that_fit() {
+        let chunks = chunk_text_recursive_impl(REPORT, 70, 0, &Separator::defaults(), 0).unwrap();
+        assert_eq!(
+            chunks,
+            vec![
+                "# Results",
+                "Revenue grew in every region. Europe led with strong demand.",
+                "## Outlook\n\nGuidance is unchanged. Margins should hold.",
+            ]
+        );
+        // Sentence packing runs the heading into the first paragraph instead
+        let flat = chunk_text_impl(REPORT, 70, 0).unwrap();
+        assert!(flat[0].starts_with("# Results\n\nRevenue grew in every region."));
+    }
+
+    #[test]
+    fn test_recurses_into_oversized_pieces_only() {
+        let text = "Short intro.\n\nOne sentence here. Another sentence there. A third one.";
+        let chunks = chunk_text_recursive_impl(text, 30, 0, &Separator::defaults(), 0).unwrap();
+        assert_eq!(
+            chunks,
+            vec![
+                "Short intro.",
+                "One sentence here.",
+                "Another sentence there.",
+                "A third one.",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_long_unbroken_line_splits_by_characters() {
+        let line = "x".repeat(95);
+        let text = format!("Intro paragraph.\n\n{line}\n\nOutro.");
+        let chunks = chunk_text_recursive_impl(&text, 40, 0, &Separator::defaults(), 0).unwrap();
+        assert_eq!(chunks[0], "Intro paragraph.");
+        // As in chunk_text, the last piece is carried over even without overlap
+        assert_eq!(
+            &chunks[1..4],
+            ["x".repeat(40), "x".repeat(40), "x".repeat(17)]
+        );
+        assert_eq!(chunks[4], "Outro.");
+        assert!(chunks.iter().all(|c| char_len(c) <= 40));
+
+        // Without the character level the line is kept whole
+        let no_chars = separators(&["\n\n", "\n", SENTENCE_SEPARATOR, " "]);
+        let chunks = chunk_text_recursive_impl(&text, 40, 0, &no_chars, 0).unwrap();
+        assert_eq!(chunks, vec!["Intro paragraph.", line.as_str(), "Outro."]);
+    }
+
+    #[test]
+    fn test_overlap_matches_chunk_text_on_sentences() {
+        let text = "Aa. Bb. Cc. Dd. Ee. Ff. Gg.";
+        for (target, overlap) in [(8, 0), (12, 4), (12, 8), (20, 10)] {
+            assert_eq!(
+                chunk_text_recursive_impl(text, target, overlap, &Separator::defaults(), 0),
+                chunk_text_impl(text, target, overlap),
+                "target {target}, overlap {overlap}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_min_chunk_size_merges_small_chunks_mid_document() {
+        let text = format!(
+            "Revenue grew in every region this year.\n\n## Appendix\n\n{}\n\nThanks.",
+            "x".repeat(60)
+        );
+        let chunks = chunk_text_recursive_impl(&text, 40, 0, &Separator::defaults(), 0).unwrap();
+        assert_eq!(chunks[1], "## Appendix");
+        assert_eq!(chunks.last().unwrap(), "Thanks.");
+
+        let merged = chunk_text_recursive_impl(&text, 40, 0, &Separator::defaults(), 15).unwrap();
+        assert_eq!(
+            merged,
+            vec![
+                "Revenue grew in every region this year.\n\n## Appendix".to_string(),
+                "x".repeat(40),
+                format!("{}\n\nThanks.", "x".repeat(21)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_custom_separators_and_edge_cases() {
+        let chunks = chunk_text_recursive_impl("a;b;c;d;e", 5, 0, &separators(&[";"]), 0).unwrap();
+        assert_eq!(chunks, vec!["a;b;c", "c;d;e"]);
+        assert_eq!(
+            chunk_text_recursive_impl("text", 0, 0, &Separator::defaults(), 0),
+            None
+        );
+        assert_eq!(
+            chunk_text_recursive_impl("  ", 10, 0, &Separator::defaults(), 0),
+            Some(vec![])
+        );
+    }
+}
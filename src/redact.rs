@@ -0,0 +1,184 @@
+//! Masking of personal data (emails, phone numbers, SSNs) before text leaves
+//! the pipeline, backing `redact_pii`.
+//!
+//! Emails and phone numbers are found exactly as `extract_metadata` finds
+//! them. Only the matches change; the text around them, including the
+//! punctuation that ends a sentence, is copied as is.
+
+use crate::entities::{is_standalone_number, EMAIL_RE, PHONE_RE};
+use regex::Regex;
+use std::ops::Range;
+use std::sync::LazyLock;
+
+// US Social Security numbers, dashed or spaced ("123-45-6789", "123 45 6789");
+// the field rules are checked in `is_plausible_ssn`
+static SSN_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\b\d{3}-\d{2}-\d{4}\b|\b\d{3} \d{2} \d{4}\b").unwrap());
+
+/// Characters a mask may not contain, as they end a sentence for chunking.
+const SENTENCE_END: &[char] = &['.', '!', '?', '…', '。', '！', '？'];
+
+/// Which kinds of personal data `redact_pii` masks.
+#[derive(Debug, Clone, Copy)]
+pub struct PiiCategories {
+    pub emails: bool,
+    pub phones: bool,
+    pub ssns: bool,
+}
+
+/// Check that `mask` is non-empty and cannot add or remove a sentence
+/// boundary: no whitespace and no sentence-ending punctuation.
+pub fn check_mask(mask: &str) -> Result<(), String> {
+    if mask.is_empty() {
+        return Err("mask must not be empty".to_string());
+    }
+    if mask
+        .chars()
+        .any(|c| c.is_whitespace() || SENTENCE_END.contains(&c))
+    {
+        return Err(format!(
+            "mask must not contain whitespace or sentence-ending punctuation, got {mask:?}"
+        ));
+    }
+    Ok(())
+}
+
+/// Area 000, 666 and 900-999, group 00 and serial 0000 are never issued.
+fn is_plausible_ssn(ssn: &str) -> bool {
+    let digits: String = ssn.chars().filter(char::is_ascii_digit).collect();
+    let (area, rest) = digits.split_at(3);
+    let (group, serial) = rest.split_at(2);
+    area != "000" && area != "666" && !area.starts_with('9') && group != "00" && serial != "0000"
+}
+
+/// Byte ranges of the personal data in `text`, sorted, with overlaps merged.
+fn pii_ranges(text: &str, categories: PiiCategories) -> Vec<Range<usize>> {
+    let mut ranges: Vec<Range<usize>> = Vec::new();
+    if categories.emails {
+        ranges.extend(EMAIL_RE.find_iter(text).map(|m| m.range()));
+    }
+    if categories.phones {
+        ranges.extend(
+            PHONE_RE
+                .find_iter(text)
+                .filter(|m| is_standalone_number(text, m.start(), m.end()))
+                .map(|m| m.range()),
+        );
+    }
+    if categories.ssns {
+        ranges.extend(
+            SSN_RE
+                .find_iter(text)
+                .filter(|m| is_standalone_number(text, m.start(), m.end()))
+                .filter(|m| is_plausible_ssn(m.as_str()))
+                .map(|m| m.range()),
+        );
+    }
+    ranges.sort_by_key(|r| r.start);
+    let mut merged: Vec<Range<usize>> = Vec::new();
+    for range in ranges {
+        match merged.last_mut() {
+            Some(last) if range.start < last.end => last.end = last.end.max(range.end),
+            _ => merged.push(range),
+        }
+    }
+    merged
+}
+
+/// Replace the personal data of the enabled `categories` with `mask`.
+///
+/// A one-character mask is repeated once per character of the match, so
+/// character offsets into the text stay the same; longer masks replace each
+/// match whole.
+pub fn redact_pii(text: &str, mask: &str, categories: PiiCategories) -> String {
+    let repeat = mask.chars().count() == 1;
+    let mut out = String::with_capacity(text.len());
+    let mut last = 0;
+    for range in pii_ranges(text, categories) {
+        out.push_str(&text[last..range.start]);
+        if repeat {
+            out.push_str(&mask.repeat(text[range.clone()].chars().count()));
+        } else {
+            out.push_str(mask);
+        }
+        last = range.end;
+    }
+    out.push_str(&text[last..]);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::split_sentences_preserve_punct;
+
+    const ALL: PiiCategories = PiiCategories {
+        emails: true,
+        phones: true,
+        ssns: true,
+    };
+
+    const TEXT: &str = "Email ir@acme.com or call (212) 555-0123. SSN 123-45-6789 is on file.";
+
+    #[test]
+    fn test_each_category() {
+        let only = |emails, phones, ssns| {
+            redact_pii(
+                TEXT,
+                "[REDACTED]",
+                PiiCategories {
+                    emails,
+                    phones,
+                    ssns,
+                },
+            )
+        };
+        assert_eq!(
+            only(true, false, false),
+            "Email [REDACTED] or call (212) 555-0123. SSN 123-45-6789 is on file."
+        );
+        assert_eq!(
+            only(false, true, false),
+            "Email ir@acme.com or call [REDACTED]. SSN 123-45-6789 is on file."
+        );
+        assert_eq!(
+            only(false, false, true),
+            "Email ir@acme.com or call (212) 555-0123. SSN [REDACTED] is on file."
+        );
+        assert_eq!(only(false, false, false), TEXT);
+    }
+
+    #[test]
+    fn test_single_character_mask_keeps_offsets() {
+        let redacted = redact_pii(TEXT, "█", ALL);
+        assert_eq!(redacted.chars().count(), TEXT.chars().count());
+        assert_eq!(
+            redacted,
+            "Email ███████████ or call ██████████████. SSN ███████████ is on file."
+        );
+    }
+
+    #[test]
+    fn test_sentence_boundaries_kept() {
+        let redacted = redact_pii(TEXT, "[REDACTED]", ALL);
+        assert_eq!(
+            split_sentences_preserve_punct(&redacted).len(),
+            split_sentences_preserve_punct(TEXT).len()
+        );
+    }
+
+    #[test]
+    fn test_implausible_ssns_and_longer_numbers_kept() {
+        let text = "IDs 000-12-3456, 666-12-3456, 912-34-5678, 123-00-4567 and 1123-45-6789.";
+        assert_eq!(redact_pii(text, "X", ALL), text);
+    }
+
+    #[test]
+    fn test_check_mask() {
+        assert!(check_mask("[REDACTED]").is_ok());
+        assert!(check_mask("*").is_ok());
+        for mask in ["", "[PII REMOVED]", "N/A.", "?", "a\nb"] {
+            assert!(check_mask(mask).is_err(), "{mask:?}");
+        }
+    }
+}
@@ -0,0 +1,312 @@
+//! Section headings of SEC filings ("Item 1A. Risk Factors", "PART II",
+//! "Management's Discussion and Analysis") for `detect_sections` and the
+//! per-chunk `section` of `process_document`.
+//!
+//! Filings are full of references to their own sections ("see Item 1A. Risk
+//! Factors"), so a match only counts as a heading where a heading can start:
+//! at the start of a line, or after the end of a sentence, which is where a
+//! heading ends up once cleaning joins its line to the text around it. A
+//! named section without an item number must also stand on a line of its own
+//! unless it is written in capitals.
+
+use crate::CharOffsets;
+use regex::Regex;
+use std::sync::LazyLock;
+
+/// Names of the items of Forms 10-K and 10-Q, as titles are written, and
+/// whether the name is distinctive enough to be a heading without "Item N.".
+const SECTION_NAMES: &[(&str, bool)] = &[
+    ("Management's Discussion and Analysis of Financial Condition and Results of Operations", true),
+    ("Management's Discussion and Analysis", true),
+    ("Quantitative and Qualitative Disclosures About Market Risk", true),
+    ("Financial Statements and Supplementary Data", true),
+    ("Changes in and Disagreements with Accountants on Accounting and Financial Disclosure", true),
+    ("Market for Registrant's Common Equity, Related Stockholder Matters and Issuer Purchases of Equity Securities", true),
+    ("Security Ownership of Certain Beneficial Owners and Management and Related Stockholder Matters", true),
+    ("Certain Relationships and Related Transactions, and Director Independence", true),
+    ("Directors, Executive Officers and Corporate Governance", true),
+    ("Unregistered Sales of Equity Securities and Use of Proceeds", true),
+    ("Exhibits and Financial Statement Schedules", true),
+    ("Principal Accountant Fees and Services", true),
+    ("Defaults Upon Senior Securities", true),
+    ("Unresolved Staff Comments", true),
+    ("Mine Safety Disclosures", true),
+    ("Controls and Procedures", true),
+    ("Executive Compensation", true),
+    ("Selected Financial Data", true),
+    ("Legal Proceedings", true),
+    ("Risk Factors", true),
+    ("Form 10-K Summary", false),
+    ("Financial Statements", false),
+    ("Other Information", false),
+    ("Cybersecurity", false),
+    ("Properties", false),
+    ("Business", false),
+    ("Exhibits", false),
+    ("[Reserved]", false),
+];
+
+/// Pattern matching `name` in any case, split across lines, with a straight
+/// or curly apostrophe, "&" for "and", and optional commas and brackets.
+fn name_pattern(name: &str) -> String {
+    name.split(' ')
+        .map(|word| match word {
+            "and" => "(?:and|&)".to_string(),
+            _ => regex::escape(word)
+                .replace('\'', "['’]?")
+                .replace(',', ",?")
+                .replace(r"\[", r"\[?")
+                .replace(r"\]", r"\]?"),
+        })
+        .collect::<Vec<_>>()
+        .join(r"\s+")
+}
+
+/// Alternation of `names` with one group `n{i}` per name, the `i` of its
+/// index in `SECTION_NAMES`. Longer names come first so that a name is not
+/// cut short at a shorter one it starts with.
+fn names_alternation(standalone_only: bool) -> String {
+    SECTION_NAMES
+        .iter()
+        .enumerate()
+        .filter(|(_, (_, standalone))| *standalone || !standalone_only)
+        .map(|(i, (name, _))| format!("(?P<n{i}>{})", name_pattern(name)))
+        .collect::<Vec<_>>()
+        .join("|")
+}
+
+// "Item 1A. Risk Factors", "ITEM 7 — MANAGEMENT'S DISCUSSION AND ANALYSIS"
+static ITEM_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(&format!(
+        r"(?i:\bitem)\s+(?P<number>\d{{1,2}}[A-Ca-c]?)\s*[.:\-–—]?\s*(?i:{})",
+        names_alternation(false)
+    ))
+    .unwrap()
+});
+
+// "PART II", "Part I"
+static PART_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i:\bpart)\s+(?P<numeral>(?i:iv|iii|ii|i))\b").unwrap());
+
+// "Risk Factors" without an item number
+static NAMED_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(&format!(r"(?i:{})", names_alternation(true))).unwrap());
+
+/// Whether `start` is at the start of a line, indentation aside.
+fn starts_line(text: &str, start: usize) -> bool {
+    let before = text[..start].trim_end_matches([' ', '\t']);
+    before.is_empty() || before.ends_with('\n')
+}
+
+/// Whether `end` is at the end of a line, trailing spaces aside.
+fn ends_line(text: &str, end: usize) -> bool {
+    let after = text[end..].trim_start_matches([' ', '\t']);
+    after.is_empty() || after.starts_with(['\n', '\r'])
+}
+
+/// Whether a heading can start at `start`: at the start of a line or after
+/// the end of a sentence.
+fn starts_heading(text: &str, start: usize) -> bool {
+    let before = &text[..start];
+    starts_line(text, start)
+        || (before.ends_with(char::is_whitespace)
+            && before.trim_end().ends_with(['.', '!', '?', ':']))
+}
+
+/// Whether the name matched up to `end` ends there, not inside a word.
+fn ends_word(text: &str, end: usize) -> bool {
+    !text[end..].starts_with(char::is_alphanumeric)
+}
+
+fn is_capitals(text: &str) -> bool {
+    text.chars().any(char::is_uppercase) && !text.chars().any(char::is_lowercase)
+}
+
+/// Title in `SECTION_NAMES` of the name group that took part in a match.
+fn matched_name(caps: &regex::Captures) -> Option<&'static str> {
+    SECTION_NAMES
+        .iter()
+        .enumerate()
+        .find(|(i, _)| caps.name(&format!("n{i}")).is_some())
+        .map(|(_, (name, _))| *name)
+}
+
+/// Section headings in `text` as (title, start byte), in document order.
+///
+/// Titles are written the same way however the heading was: "Item 1A. Risk
+/// Factors" for "ITEM 1A - RISK FACTORS" and "Part II" for "PART II".
+pub(crate) fn find_headings(text: &str) -> Vec<(String, usize)> {
+    let mut headings: Vec<(usize, usize, String)> = Vec::new();
+    for caps in ITEM_RE.captures_iter(text) {
+        let m = caps.get(0).unwrap();
+        if !starts_heading(text, m.start()) || !ends_word(text, m.end()) {
+            continue;
+        }
+        if let Some(name) = matched_name(&caps) {
+            let number = caps["number"].to_uppercase();
+            headings.push((m.start(), m.end(), format!("Item {number}. {name}")));
+        }
+    }
+    for caps in PART_RE.captures_iter(text) {
+        let m = caps.get(0).unwrap();
+        let capitals = m.as_str().starts_with("PART");
+        if starts_line(text, m.start()) || (capitals && starts_heading(text, m.start())) {
+            let numeral = caps["numeral"].to_uppercase();
+            headings.push((m.start(), m.end(), format!("Part {numeral}")));
+        }
+    }
+    for caps in NAMED_RE.captures_iter(text) {
+        let m = caps.get(0).unwrap();
+        let alone = starts_line(text, m.start()) && ends_line(text, m.end());
+        let capitals = is_capitals(m.as_str()) && starts_heading(text, m.start());
+        if (alone || capitals) && ends_word(text, m.end()) {
+            if let Some(name) = matched_name(&caps) {
+                headings.push((m.start(), m.end(), name.to_string()));
+            }
+        }
+    }
+    // A name inside an item heading ("Item 1A. RISK FACTORS") is part of it
+    headings.sort_by_key(|(start, _, _)| *start);
+    let mut end = 0;
+    headings
+        .into_iter()
+        .filter(|(start, heading_end, _)| {
+            let keep = *start >= end;
+            if keep {
+                end = *heading_end;
+            }
+            keep
+        })
+        .map(|(start, _, title)| (title, start))
+        .collect()
+}
+
+/// Section headings in `text` as (title, start character), in document order.
+pub fn detect_sections(text: &str) -> Vec<(String, usize)> {
+    let mut offsets = CharOffsets::new(text);
+    find_headings(text)
+        .into_iter()
+        .map(|(title, start)| (title, offsets.char_offset(start)))
+        .collect()
+}
+
+/// Bytes of a chunk's first line searched for in the text it came from.
+const LOCATE_PREFIX: usize = 64;
+
+/// Section of each of `chunks`, cut from `text` in order: the title of the
+/// last heading at or before where the chunk starts, or None before the first.
+///
+/// Chunks are located by their first line, searching forward from where the
+/// previous chunk started; a chunk not found is given its predecessor's start.
+pub(crate) fn chunk_sections<'a>(
+    text: &str,
+    chunks: impl IntoIterator<Item = &'a str>,
+) -> Vec<Option<String>> {
+    let headings = find_headings(text);
+    let mut from = 0;
+    let mut start = 0;
+    chunks
+        .into_iter()
+        .map(|chunk| {
+            let line = chunk.trim_start().lines().next().unwrap_or("").trim_end();
+            let mut len = line.len().min(LOCATE_PREFIX);
+            while !line.is_char_boundary(len) {
+                len -= 1;
+            }
+            if let Some(offset) = text[from..].find(&line[..len]) {
+                start = from + offset;
+                from = start + text[start..].chars().next().map_or(0, char::len_utf8);
+            }
+            headings
+                .iter()
+                .take_while(|(_, heading)| *heading <= start)
+                .last()
+                .map(|(title, _)| title.clone())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FILING: &str = "Forward-looking statements are discussed below.\n\n\
+                          PART I\n\n\
+                          Item 1A. Risk Factors\n\
+                          Our business is subject to risks. See Item 7. Management's Discussion \
+                          and Analysis for more.\n\n\
+                          ITEM 7. MANAGEMENT'S DISCUSSION AND ANALYSIS OF\n\
+                          FINANCIAL CONDITION AND RESULTS OF OPERATIONS\n\
+                          Revenue rose 12%.\n\n\
+                          Controls and Procedures\n\n\
+                          We maintain disclosure controls.";
+
+    fn titles(text: &str) -> Vec<String> {
+        find_headings(text)
+            .into_iter()
+            .map(|(title, _)| title)
+            .collect()
+    }
+
+    #[test]
+    fn test_find_headings() {
+        assert_eq!(
+            titles(FILING),
+            vec![
+                "Part I",
+                "Item 1A. Risk Factors",
+                "Item 7. Management's Discussion and Analysis of Financial Condition and Results of Operations",
+                "Controls and Procedures",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_headings_in_flowing_text() {
+        let text = "Prior text ends here. ITEM 1A - RISK FACTORS Our business faces risks. \
+                    These risk factors are described in the prospectus. As noted in Part II, \
+                    Item 5. Market for Registrant’s Common Equity, Related Stockholder \
+                    Matters and Issuer Purchases of Equity Securities applies.";
+        assert_eq!(titles(text), vec!["Item 1A. Risk Factors"]);
+    }
+
+    #[test]
+    fn test_named_heading_must_stand_alone() {
+        assert_eq!(titles("Risk factors\nWe face risks."), vec!["Risk Factors"]);
+        assert_eq!(titles("Our risk factors\nare many."), Vec::<String>::new());
+        assert_eq!(titles("Item 6. [Reserved]\n"), vec!["Item 6. [Reserved]"]);
+        assert_eq!(titles("Item 1. Businesses abroad"), Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_detect_sections_char_offsets() {
+        let text = "Résumé — intro.\nItem 2. Properties\nWe own offices.";
+        assert_eq!(
+            detect_sections(text),
+            vec![("Item 2. Properties".to_string(), 16)]
+        );
+    }
+
+    #[test]
+    fn test_chunk_sections() {
+        let text =
+            "Intro text.\n\nItem 1A. Risk Factors\nRisks here.\n\nItem 2. Properties\nOffices.";
+        let chunks = [
+            "Intro text.",
+            "Item 1A. Risk Factors\nRisks here.",
+            "Risks here.",
+            "Offices.",
+            "Missing",
+        ];
+        assert_eq!(
+            chunk_sections(text, chunks),
+            vec![
+                None,
+                Some("Item 1A. Risk Factors".to_string()),
+                Some("Item 1A. Risk Factors".to_string()),
+                Some("Item 2. Properties".to_string()),
+                Some("Item 2. Properties".to_string()),
+            ]
+        );
+    }
+}
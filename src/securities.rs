@@ -0,0 +1,233 @@
+//! Security identifiers (ISIN, CUSIP, SEDOL) for `extract_metadata`.
+//!
+//! Each code carries a check digit, so unlike potential tickers these are
+//! reported only when the check digit is right. Candidates that have the
+//! shape of an identifier but fail the check are kept apart as unvalidated,
+//! for callers that want to see typos or truncated codes.
+
+use crate::entities::dedupe_in_order;
+use regex::Regex;
+use std::sync::LazyLock;
+
+// Country code, nine-character national code, check digit
+static ISIN_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\b[A-Z]{2}[0-9A-Z]{9}[0-9]\b").unwrap());
+
+// Issuer (starting with a digit, as all US and Canadian issuers do), issue,
+// check digit
+static CUSIP_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\b[0-9]{3}[0-9A-Z]{5}[0-9]\b").unwrap());
+
+// Six characters without vowels, check digit
+static SEDOL_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\b[0-9B-DF-HJ-NP-TV-Z]{6}[0-9]\b").unwrap());
+
+/// Bytes before an all-digit SEDOL candidate searched for a "SEDOL" label.
+const SEDOL_LABEL_WINDOW: usize = 24;
+
+/// Identifiers whose check digit is right, each deduplicated in
+/// first-occurrence order.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct SecurityIds {
+    pub isins: Vec<String>,
+    pub cusips: Vec<String>,
+    pub sedols: Vec<String>,
+    /// Identifier-shaped strings failing their check digit, in document order.
+    pub unvalidated: Vec<UnvalidatedId>,
+}
+
+/// A candidate identifier whose check digit is wrong.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnvalidatedId {
+    pub raw: String,
+    /// "isin", "cusip" or "sedol".
+    pub kind: &'static str,
+}
+
+/// Value of an identifier character: digits as themselves, letters from
+/// A = 10 to Z = 35.
+fn char_value(c: u8) -> u32 {
+    match c {
+        b'0'..=b'9' => (c - b'0') as u32,
+        _ => (c - b'A') as u32 + 10,
+    }
+}
+
+/// Luhn check over the digits of `code` with letters expanded to two digits,
+/// check digit included (ISO 6166).
+pub(crate) fn is_valid_isin(code: &str) -> bool {
+    let digits: Vec<u32> = code
+        .bytes()
+        .flat_map(|c| {
+            let v = char_value(c);
+            if v < 10 {
+                vec![v]
+            } else {
+                vec![v / 10, v % 10]
+            }
+        })
+        .collect();
+    let sum: u32 = digits
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(i, &d)| {
+            if i % 2 == 1 {
+                let d = d * 2;
+                d / 10 + d % 10
+            } else {
+                d
+            }
+        })
+        .sum();
+    sum.is_multiple_of(10)
+}
+
+/// Modulus 10 "double add double" check: every second value is doubled and
+/// the digits of all values are summed.
+pub(crate) fn is_valid_cusip(code: &str) -> bool {
+    let bytes = code.as_bytes();
+    let sum: u32 = bytes[..8]
+        .iter()
+        .enumerate()
+        .map(|(i, &c)| {
+            let v = char_value(c) * if i % 2 == 1 { 2 } else { 1 };
+            v / 10 + v % 10
+        })
+        .sum();
+    (10 - sum % 10) % 10 == char_value(bytes[8])
+}
+
+/// Weighted sum with weights 1, 3, 1, 7, 3, 9 and the check digit making it
+/// a multiple of 10.
+pub(crate) fn is_valid_sedol(code: &str) -> bool {
+    const WEIGHTS: [u32; 6] = [1, 3, 1, 7, 3, 9];
+    let bytes = code.as_bytes();
+    let sum: u32 = bytes[..6]
+        .iter()
+        .zip(WEIGHTS)
+        .map(|(&c, w)| char_value(c) * w)
+        .sum();
+    (10 - sum % 10) % 10 == char_value(bytes[6])
+}
+
+/// Whether "SEDOL" appears shortly before byte offset `start`.
+fn has_sedol_label(text: &str, start: usize) -> bool {
+    let mut from = start.saturating_sub(SEDOL_LABEL_WINDOW);
+    while !text.is_char_boundary(from) {
+        from += 1;
+    }
+    text[from..start].to_ascii_uppercase().contains("SEDOL")
+}
+
+/// Find ISINs, CUSIPs and SEDOLs in `text`.
+///
+/// All-digit SEDOL candidates are only considered after a "SEDOL" label,
+/// since any seven-digit number has that shape and one in ten would pass
+/// the check.
+pub(crate) fn extract_security_ids(text: &str) -> SecurityIds {
+    let mut candidates: Vec<(usize, &str, &'static str, bool)> = Vec::new();
+    for m in ISIN_RE.find_iter(text) {
+        candidates.push((m.start(), m.as_str(), "isin", is_valid_isin(m.as_str())));
+    }
+    for m in CUSIP_RE.find_iter(text) {
+        candidates.push((m.start(), m.as_str(), "cusip", is_valid_cusip(m.as_str())));
+    }
+    for m in SEDOL_RE.find_iter(text) {
+        let all_digits = m.as_str().bytes().all(|c| c.is_ascii_digit());
+        if all_digits && !has_sedol_label(text, m.start()) {
+            continue;
+        }
+        candidates.push((m.start(), m.as_str(), "sedol", is_valid_sedol(m.as_str())));
+    }
+    candidates.sort_by_key(|&(start, ..)| start);
+
+    let valid = |kind: &str| {
+        dedupe_in_order(
+            candidates
+                .iter()
+                .filter(|&&(_, _, k, ok)| k == kind && ok)
+                .map(|&(_, raw, ..)| raw.to_string()),
+        )
+    };
+    SecurityIds {
+        isins: valid("isin"),
+        cusips: valid("cusip"),
+        sedols: valid("sedol"),
+        unvalidated: candidates
+            .iter()
+            .filter(|&&(.., ok)| !ok)
+            .map(|&(_, raw, kind, _)| UnvalidatedId {
+                raw: raw.to_string(),
+                kind,
+            })
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_digits() {
+        for isin in [
+            "US0378331005",
+            "GB0002634946",
+            "DE000BAY0017",
+            "AU0000XVGZA3",
+        ] {
+            assert!(is_valid_isin(isin), "{isin}");
+        }
+        for isin in ["US0378331006", "GB0002634945", "DE000BAY0018"] {
+            assert!(!is_valid_isin(isin), "{isin}");
+        }
+        for cusip in ["037833100", "38259P508", "594918104"] {
+            assert!(is_valid_cusip(cusip), "{cusip}");
+        }
+        for cusip in ["037833101", "38259P507", "594918105"] {
+            assert!(!is_valid_cusip(cusip), "{cusip}");
+        }
+        for sedol in ["0263494", "B0YBKJ7", "2046251"] {
+            assert!(is_valid_sedol(sedol), "{sedol}");
+        }
+        for sedol in ["0263495", "B0YBKJ8", "2046252"] {
+            assert!(!is_valid_sedol(sedol), "{sedol}");
+        }
+    }
+
+    #[test]
+    fn test_extract_security_ids() {
+        let text = "Apple (ISIN US0378331005, CUSIP 037833100) and BAE Systems \
+                    (SEDOL 0263494, ISIN GB0002634946); also B0YBKJ7. \
+                    Again US0378331005. Typo: US0378331006, CUSIP 037833101.";
+        let ids = extract_security_ids(text);
+        assert_eq!(ids.isins, vec!["US0378331005", "GB0002634946"]);
+        assert_eq!(ids.cusips, vec!["037833100"]);
+        assert_eq!(ids.sedols, vec!["0263494", "B0YBKJ7"]);
+        assert_eq!(
+            ids.unvalidated,
+            vec![
+                UnvalidatedId {
+                    raw: "US0378331006".to_string(),
+                    kind: "isin"
+                },
+                UnvalidatedId {
+                    raw: "037833101".to_string(),
+                    kind: "cusip"
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unlabeled_numbers_are_not_sedols() {
+        // A valid SEDOL check digit, but nothing says it is one
+        let ids = extract_security_ids("Order 0263494 shipped; revenue was 2046251 units.");
+        assert_eq!(ids, SecurityIds::default());
+        // Words that merely have the shape fail the check
+        let ids = extract_security_ids("Codes ABCDEFGHIJK1 and 123ABCDE9.");
+        assert!(ids.isins.is_empty() && ids.cusips.is_empty());
+        assert_eq!(ids.unvalidated.len(), 2);
+    }
+}
@@ -0,0 +1,211 @@
+//! Per-share figures ("diluted EPS of $1.23", "$0.45 per share") and share
+//! counts ("2.1 million shares outstanding", "repurchased 3,400,000 shares")
+//! for `extract_metadata`.
+//!
+//! A per-share amount and an aggregate amount are both money to `MONEY_RE`,
+//! so they stay in `monetary_amounts`; these lists tell them apart. Both are
+//! conservative: an amount is per share only when a cue governs it in its own
+//! clause, and a count only when the number sits next to "shares".
+
+use crate::normalize::{parse_money, parse_number, scale_multiplier, split_currency};
+use crate::MONEY_RE;
+use regex::Regex;
+use serde::Serialize;
+use std::sync::LazyLock;
+
+/// A monetary amount stated per share.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PerShareAmount {
+    pub raw: String,
+    /// Value in base units, or None if the amount could not be parsed.
+    pub value: Option<f64>,
+    pub currency: Option<String>,
+    /// "basic" or "diluted" when the text says which.
+    pub basis: Option<&'static str>,
+}
+
+/// A number of shares.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ShareCount {
+    pub raw: String,
+    /// Number of shares with any scale word applied ("2.1 million" -> 2100000.0).
+    pub value: Option<f64>,
+    /// "basic" or "diluted" when the text says which.
+    pub basis: Option<&'static str>,
+}
+
+// "EPS", "earnings per share", "per diluted share", "dividends per share", ...
+// in the clause before an amount
+static PER_SHARE_CUE_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\bEPS\b|(?i:\bper\s+(?:(?:basic|diluted|common)\s+)?share\b)").unwrap()
+});
+
+// "per share", "per diluted share" or "a share" right after an amount
+static PER_SHARE_SUFFIX_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^\s*(?i:(?:per|a)\s+(?:(?:basic|diluted|common)\s+)?share)\b").unwrap()
+});
+
+// A number before "shares", with any qualifiers in between ("2.1 million
+// shares", "105 million diluted shares"), or after "shares ... of/were"
+// ("weighted average diluted shares were 105.3 million")
+static SHARE_COUNT_RE: LazyLock<Regex> = LazyLock::new(|| {
+    let number = r"\d[\d,]*(?:\.\d+)?";
+    let scale = r"(?i:million|billion|thousand|mn|bn|m)\b";
+    let qualifiers = r"(?i:(?:(?:weighted[- ]average|basic|diluted|common|ordinary|treasury|class\s+[a-c])\s+)*)";
+    Regex::new(&format!(
+        r"\b(?P<count>{number})(?:\s*(?P<scale>{scale}))?\s+{qualifiers}(?i:shares?)\b|{qualifiers}(?i:shares\s+(?:outstanding\s+)?(?:of|were|was|totaled|totalled|totaling))\s+(?P<count_after>{number})(?:\s*(?P<scale_after>{scale}))?"
+    ))
+    .unwrap()
+});
+
+/// Bytes before an amount searched for a per-share cue.
+const CUE_WINDOW: usize = 48;
+
+/// "basic" or "diluted", whichever of the two comes last in `context`.
+fn basis_in(context: &str) -> Option<&'static str> {
+    let lower = context.to_lowercase();
+    match (lower.rfind("basic"), lower.rfind("diluted")) {
+        (Some(basic), Some(diluted)) if basic > diluted => Some("basic"),
+        (_, Some(_)) => Some("diluted"),
+        (Some(_), None) => Some("basic"),
+        (None, None) => None,
+    }
+}
+
+/// The text before an amount at `start` in which a cue may govern it: at most
+/// `CUE_WINDOW` bytes, not reaching back past the previous amount (ending at
+/// `floor`) or the end of the previous sentence or clause.
+fn clause_before(text: &str, floor: usize, start: usize) -> &str {
+    let mut from = start.saturating_sub(CUE_WINDOW).max(floor);
+    while !text.is_char_boundary(from) {
+        from += 1;
+    }
+    let window = &text[from..start];
+    match window.rfind(['.', ',', ';', '!', '?']) {
+        Some(end) => &window[end + 1..],
+        None => window,
+    }
+}
+
+/// Monetary amounts in `text` stated per share, in document order.
+pub(crate) fn extract_per_share_amounts(text: &str) -> Vec<PerShareAmount> {
+    let mut amounts = Vec::new();
+    let mut previous_end = 0;
+    for m in MONEY_RE.find_iter(text) {
+        let before = clause_before(text, previous_end, m.start());
+        previous_end = m.end();
+        let suffix = PER_SHARE_SUFFIX_RE.find(&text[m.end()..]);
+        let context = match suffix {
+            Some(suffix) => suffix.as_str(),
+            None if PER_SHARE_CUE_RE.is_match(before) => before,
+            None => continue,
+        };
+        amounts.push(PerShareAmount {
+            raw: m.as_str().to_string(),
+            value: parse_money(m.as_str()),
+            currency: split_currency(m.as_str()).map(|(currency, _)| currency.to_string()),
+            basis: basis_in(context).or_else(|| basis_in(before)),
+        });
+    }
+    amounts
+}
+
+/// Whether an unscaled share count is a year instead ("In 2024 shares rose").
+fn is_year(count: &str) -> bool {
+    count.len() == 4 && (count.starts_with("19") || count.starts_with("20"))
+}
+
+/// Numbers of shares in `text`, in document order.
+pub(crate) fn extract_share_counts(text: &str) -> Vec<ShareCount> {
+    SHARE_COUNT_RE
+        .captures_iter(text)
+        .filter_map(|caps| {
+            let count = caps.name("count").or(caps.name("count_after"))?;
+            let scale = caps.name("scale").or(caps.name("scale_after"));
+            if scale.is_none() && is_year(count.as_str()) {
+                return None;
+            }
+            // "$5 million shares" is an amount, not a count
+            if text[..count.start()].ends_with(['$', '€', '£', '¥', '₹']) {
+                return None;
+            }
+            let multiplier = match scale {
+                Some(scale) => scale_multiplier(scale.as_str()),
+                None => Some(1.0),
+            };
+            Some(ShareCount {
+                raw: caps[0].to_string(),
+                value: parse_number(count.as_str())
+                    .zip(multiplier)
+                    .map(|(n, m)| n * m),
+                basis: basis_in(&caps[0]),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RELEASE: &str = "Diluted EPS was $1.23, up from $1.10, and basic earnings per share \
+                           of $1.25 on revenue of $4.2 billion. The company repurchased \
+                           3,400,000 shares for $150 million and had 2.1 million shares \
+                           outstanding; weighted average diluted shares were 105.3 million. \
+                           It declared a dividend of $0.45 per share.";
+
+    #[test]
+    fn test_per_share_amounts() {
+        let amounts: Vec<(String, Option<f64>, Option<&str>)> = extract_per_share_amounts(RELEASE)
+            .into_iter()
+            .map(|a| (a.raw, a.value, a.basis))
+            .collect();
+        assert_eq!(
+            amounts,
+            vec![
+                ("$1.23".to_string(), Some(1.23), Some("diluted")),
+                ("$1.25".to_string(), Some(1.25), Some("basic")),
+                ("$0.45".to_string(), Some(0.45), None),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_per_share_suffix_forms() {
+        let text = "Net income was $2 billion, or $1.50 per diluted share, and the stock \
+                    paid $0.25 a share.";
+        let amounts = extract_per_share_amounts(text);
+        let raw: Vec<(&str, Option<&str>)> =
+            amounts.iter().map(|a| (a.raw.as_str(), a.basis)).collect();
+        assert_eq!(raw, vec![("$1.50", Some("diluted")), ("$0.25", None)]);
+        assert_eq!(amounts[0].currency.as_deref(), Some("USD"));
+    }
+
+    #[test]
+    fn test_share_counts() {
+        let counts: Vec<(String, Option<f64>, Option<&str>)> = extract_share_counts(RELEASE)
+            .into_iter()
+            .map(|c| (c.raw, c.value, c.basis))
+            .collect();
+        assert_eq!(
+            counts,
+            vec![
+                ("3,400,000 shares".to_string(), Some(3_400_000.0), None),
+                ("2.1 million shares".to_string(), Some(2_100_000.0), None),
+                (
+                    "weighted average diluted shares were 105.3 million".to_string(),
+                    Some(105_300_000.0),
+                    Some("diluted")
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_share_counts_skip_years_and_amounts() {
+        assert_eq!(
+            extract_share_counts("In 2024 shares rose, and $5 million shares were sold."),
+            vec![]
+        );
+    }
+}
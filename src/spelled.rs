@@ -0,0 +1,216 @@
+//! Monetary amounts written in words ("five million dollars", "twenty-five
+//! thousand euros"), reported as `monetary_amounts_spelled`.
+//!
+//! The grammar is deliberately small: cardinal number words from "one" to
+//! "ninety-nine", "hundred" and the scales "thousand" to "trillion", joined by
+//! spaces or hyphens with an optional "and" ("two hundred and fifty"), then a
+//! spelled currency name as accepted after digits ("dollars", "euros",
+//! "pounds sterling", ...). Amounts with digits ("5 million dollars") are
+//! `MONEY_RE`'s; fractions ("half a million"), "a million" and amounts without
+//! a currency word ("two billion in revenue") are not recognized.
+
+use crate::{normalize, ParsedMoney};
+use regex::Regex;
+use std::sync::LazyLock;
+
+/// Number words up to ninety with their values, longest first within each
+/// prefix so "seventeen" is not read as "seven".
+const NUMBER_WORDS: &[(&str, u64)] = &[
+    ("one", 1),
+    ("two", 2),
+    ("three", 3),
+    ("four", 4),
+    ("five", 5),
+    ("six", 6),
+    ("seven", 7),
+    ("eight", 8),
+    ("nine", 9),
+    ("ten", 10),
+    ("eleven", 11),
+    ("twelve", 12),
+    ("thirteen", 13),
+    ("fourteen", 14),
+    ("fifteen", 15),
+    ("sixteen", 16),
+    ("seventeen", 17),
+    ("eighteen", 18),
+    ("nineteen", 19),
+    ("twenty", 20),
+    ("thirty", 30),
+    ("forty", 40),
+    ("fifty", 50),
+    ("sixty", 60),
+    ("seventy", 70),
+    ("eighty", 80),
+    ("ninety", 90),
+];
+
+/// Scale words with their multipliers, smallest first.
+const SCALE_WORDS: &[(&str, f64)] = &[
+    ("thousand", 1e3),
+    ("million", 1e6),
+    ("billion", 1e9),
+    ("trillion", 1e12),
+];
+
+// A number word sequence starting below a hundred, then a currency name
+static SPELLED_MONEY_RE: LazyLock<Regex> = LazyLock::new(|| {
+    let numbers: Vec<&str> = NUMBER_WORDS.iter().map(|(word, _)| *word).collect();
+    let number = format!(r"(?:{})\b", numbers.join("|"));
+    let word = format!(r"(?:{number}|hundred\b|thousand\b|million\b|billion\b|trillion\b)");
+    let currency = r"(?:pounds[ \t]+sterling|dollars?|euros?|pounds?|rupees?|francs?|yen)\b";
+    Regex::new(&format!(
+        r"(?i)\b{number}(?:(?:[ \t]+and)?[ \t-]+{word})*[ \t]+{currency}"
+    ))
+    .unwrap()
+});
+
+/// Value of a spelled-out number and the name of its largest scale word, or
+/// None if the words do not form a number ("five five", "thousand million").
+fn parse_number_words(words: &str) -> Option<(f64, Option<&'static str>)> {
+    let mut total = 0.0;
+    // The part before the next scale word
+    let mut current = 0u64;
+    let mut largest_scale: Option<(&'static str, f64)> = None;
+    let mut previous_scale = f64::INFINITY;
+    for word in words
+        .split(|c: char| c.is_whitespace() || c == '-')
+        .filter(|w| !w.is_empty() && !w.eq_ignore_ascii_case("and"))
+    {
+        let word = word.to_lowercase();
+        if let Some(&(_, value)) = NUMBER_WORDS.iter().find(|(w, _)| *w == word) {
+            // Only a single digit may follow a multiple of ten ("twenty-five")
+            let below_hundred = current % 100;
+            let fits = below_hundred == 0
+                || (below_hundred.is_multiple_of(10) && below_hundred >= 20 && value < 10);
+            if !fits {
+                return None;
+            }
+            current += value;
+        } else if word == "hundred" {
+            if current == 0 || current >= 100 {
+                return None;
+            }
+            current *= 100;
+        } else {
+            let &(name, multiplier) = SCALE_WORDS.iter().find(|(w, _)| *w == word)?;
+            if current == 0 || multiplier >= previous_scale {
+                return None;
+            }
+            total += current as f64 * multiplier;
+            current = 0;
+            previous_scale = multiplier;
+            largest_scale = largest_scale.or(Some((name, multiplier)));
+        }
+    }
+    Some((total + current as f64, largest_scale.map(|(name, _)| name)))
+}
+
+/// Spelled-out monetary amounts in document order, each with its value in
+/// base units (None when the number words are not well formed), currency and
+/// largest scale word.
+pub(crate) fn extract_spelled_money(text: &str) -> Vec<ParsedMoney> {
+    SPELLED_MONEY_RE
+        .find_iter(text)
+        .map(|m| {
+            let raw = m.as_str();
+            let (currency, words) = normalize::split_currency(raw)
+                .map_or((None, raw), |(currency, words)| (Some(currency), words));
+            let parsed = parse_number_words(words);
+            ParsedMoney {
+                raw: raw.to_string(),
+                value: parsed.map(|(value, _)| value),
+                currency: currency.map(str::to_string),
+                scale: parsed.and_then(|(_, scale)| scale),
+                sign_inferred: false,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values(text: &str) -> Vec<(String, Option<f64>, Option<String>)> {
+        extract_spelled_money(text)
+            .into_iter()
+            .map(|m| (m.raw, m.value, m.currency))
+            .collect()
+    }
+
+    #[test]
+    fn test_five_million_dollars() {
+        let found = extract_spelled_money("It cost approximately five million dollars.");
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].raw, "five million dollars");
+        assert_eq!(found[0].value, Some(5_000_000.0));
+        assert_eq!(found[0].currency.as_deref(), Some("USD"));
+        assert_eq!(found[0].scale, Some("million"));
+    }
+
+    #[test]
+    fn test_compound_amounts_and_currencies() {
+        assert_eq!(
+            values(
+                "Fees of twenty-five thousand euros, Two Hundred and Fifty Million Pounds \
+                 Sterling and one billion three hundred million dollars."
+            ),
+            vec![
+                (
+                    "twenty-five thousand euros".to_string(),
+                    Some(25e3),
+                    Some("EUR".to_string())
+                ),
+                (
+                    "Two Hundred and Fifty Million Pounds Sterling".to_string(),
+                    Some(250e6),
+                    Some("GBP".to_string())
+                ),
+                (
+                    "one billion three hundred million dollars".to_string(),
+                    Some(1.3e9),
+                    Some("USD".to_string())
+                ),
+            ]
+        );
+        assert_eq!(
+            values("one dollar and fifteen hundred yen"),
+            vec![
+                ("one dollar".to_string(), Some(1.0), Some("USD".to_string())),
+                (
+                    "fifteen hundred yen".to_string(),
+                    Some(1500.0),
+                    Some("JPY".to_string())
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_malformed_numbers_have_no_value() {
+        assert_eq!(
+            values("five five dollars, two thousand million euros"),
+            vec![
+                (
+                    "five five dollars".to_string(),
+                    None,
+                    Some("USD".to_string())
+                ),
+                (
+                    "two thousand million euros".to_string(),
+                    None,
+                    Some("EUR".to_string())
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_ignores_digits_and_missing_currency() {
+        assert!(extract_spelled_money(
+            "two billion in revenue, $2 billion, 5 million dollars, someone dollars"
+        )
+        .is_empty());
+    }
+}
@@ -0,0 +1,83 @@
+//! Size and shape of a document, backing `text_stats`.
+//!
+//! Everything is measured on the cleaned text, since that is what chunking
+//! sees: a profile taken here translates directly into `chunk_size` and
+//! `chunk_overlap` choices.
+
+use crate::{
+    char_len, clean_text_with_options, split_sentences_preserve_punct, word_count, CleanOptions,
+};
+
+/// Result of `text_stats_impl`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextStats {
+    pub char_count: usize,
+    pub byte_count: usize,
+    /// Whitespace-delimited tokens.
+    pub word_count: usize,
+    pub sentence_count: usize,
+    /// Mean words per sentence; 0.0 without sentences.
+    pub avg_sentence_length: f64,
+    /// Non-empty blocks separated by a blank line.
+    pub paragraph_count: usize,
+}
+
+/// Clean `text` with `options` and measure the result.
+pub fn text_stats_impl(text: &str, options: &CleanOptions) -> TextStats {
+    let cleaned = clean_text_with_options(text, options);
+    let word_count = word_count(&cleaned);
+    let sentence_count = split_sentences_preserve_punct(&cleaned).len();
+    let avg_sentence_length = if sentence_count == 0 {
+        0.0
+    } else {
+        word_count as f64 / sentence_count as f64
+    };
+
+    TextStats {
+        char_count: char_len(&cleaned),
+        byte_count: cleaned.len(),
+        word_count,
+        sentence_count,
+        avg_sentence_length,
+        paragraph_count: cleaned
+            .split("\n\n")
+            .filter(|p| !p.trim().is_empty())
+            .count(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_text_stats_known_paragraph() {
+        let text = "The quarter was  strong. Revenue grew 12% to €5M!\n\n\n\
+                    Margins held steady. Costs fell?";
+        assert_eq!(
+            text_stats_impl(text, &CleanOptions::default()),
+            TextStats {
+                char_count: 82,
+                byte_count: 84,
+                word_count: 14,
+                sentence_count: 4,
+                avg_sentence_length: 3.5,
+                paragraph_count: 2,
+            }
+        );
+    }
+
+    #[test]
+    fn test_text_stats_empty_input() {
+        let zero = TextStats {
+            char_count: 0,
+            byte_count: 0,
+            word_count: 0,
+            sentence_count: 0,
+            avg_sentence_length: 0.0,
+            paragraph_count: 0,
+        };
+        assert_eq!(text_stats_impl("", &CleanOptions::default()), zero);
+        assert_eq!(text_stats_impl(" \n\n\t ", &CleanOptions::default()), zero);
+    }
+}
@@ -0,0 +1,466 @@
+//! Lazy chunking for very large documents, backing `ChunkIterator`.
+//!
+//! `chunk_text` splits the whole text into sentences and packs every chunk
+//! before returning any of them. `ChunkStream` finds the next sentence only
+//! when the chunk being packed needs one, so besides the text it holds just
+//! that chunk, the sentences kept for overlap and, with `min_chunk_size`, the
+//! chunk after it. The chunks are the same as `chunk_text`'s, one at a time.
+//!
+//! The text can also be pushed in pieces, so it never has to be held whole:
+//! the stream keeps the tail not yet packed, and asks for the next piece with
+//! `Incomplete` when a sentence might continue past it. Text already packed
+//! is dropped from the front of the buffer once it makes up half of it.
+
+use crate::{
+    split_oversized_sentence, ChunkPacker, Incomplete, Overlap, PackedChunk, SentenceCursor,
+    SizeUnit, Unit, UnitOptions,
+};
+use regex::Regex;
+use std::collections::VecDeque;
+
+/// The chunks of an owned text, packed as they are requested.
+#[derive(Debug, Clone)]
+pub(crate) struct ChunkStream {
+    /// The text from the start of the sentence being looked at.
+    text: String,
+    /// Whether `text` ends where the whole text does.
+    input_ended: bool,
+    target_size: usize,
+    size_unit: SizeUnit,
+    min_chunk_size: usize,
+    merge_all_small: bool,
+    boundary: Option<Regex>,
+    ellipsis_terminal: bool,
+    newline_is_boundary: bool,
+    sentences: SentenceCursor,
+    /// Windows of an oversized sentence not packed yet.
+    windows: VecDeque<Unit>,
+    /// Whether the text had any sentence; without one it is returned or
+    /// split by size as a whole, as in `chunk_text`.
+    found_sentence: bool,
+    packer: ChunkPacker,
+    /// Packed chunks looked at to decide whether they are merged, and chunks
+    /// of a text without sentences.
+    packed: VecDeque<PackedChunk<'static>>,
+    exhausted: bool,
+}
+
+impl ChunkStream {
+    /// Chunk `text` as `chunk_text_with_min_size` does without
+    /// `preserve_code_blocks`. Returns None if `target_size` is 0.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        text: String,
+        target_size: usize,
+        overlap: Overlap,
+        size_unit: SizeUnit,
+        min_chunk_size: usize,
+        merge_all_small: bool,
+        boundary: Option<Regex>,
+        ellipsis_terminal: bool,
+        newline_is_boundary: bool,
+    ) -> Option<Self> {
+        if target_size == 0 {
+            return None;
+        }
+        Some(ChunkStream {
+            text,
+            input_ended: true,
+            target_size,
+            size_unit,
+            min_chunk_size,
+            merge_all_small,
+            boundary,
+            ellipsis_terminal,
+            newline_is_boundary,
+            sentences: SentenceCursor::default(),
+            windows: VecDeque::new(),
+            found_sentence: false,
+            packer: ChunkPacker::new(target_size, overlap, false, size_unit),
+            packed: VecDeque::new(),
+            exhausted: false,
+        })
+    }
+
+    /// Expect the text to continue with pieces passed to `push_text`, until
+    /// `end_input` is called.
+    pub fn awaiting_input(mut self) -> Self {
+        self.input_ended = false;
+        self
+    }
+
+    /// Append the next piece of the text.
+    pub fn push_text(&mut self, piece: &str) {
+        debug_assert!(!self.input_ended, "text pushed after end_input");
+        self.text.push_str(piece);
+    }
+
+    /// Mark the text pushed so far as the whole text.
+    pub fn end_input(&mut self) {
+        self.input_ended = true;
+    }
+
+    /// The next sentence, dropping the text before it once that is worth it.
+    fn next_sentence(&mut self) -> Result<Option<Unit>, Incomplete> {
+        let units = UnitOptions {
+            boundary: self.boundary.as_ref(),
+            ellipsis_terminal: self.ellipsis_terminal,
+            newline_is_boundary: self.newline_is_boundary,
+            ..UnitOptions::default()
+        };
+        let sentence = self
+            .sentences
+            .next_unit_of(&self.text, &units, self.input_ended)?;
+        // Dropping only half the buffer or more at a time keeps the copying linear
+        let consumed = self.sentences.consumed(&self.text);
+        if consumed > 0 && consumed * 2 >= self.text.len() {
+            self.text.drain(..consumed);
+            self.sentences.shift(consumed);
+        }
+        Ok(sentence)
+    }
+
+    /// Pack units until the next chunk is complete.
+    fn pack_next(&mut self) -> Result<Option<PackedChunk<'static>>, Incomplete> {
+        while !self.exhausted {
+            let unit = match self.windows.pop_front() {
+                Some(window) => Some(window),
+                None => self.next_sentence()?.map(|sentence| {
+                    self.windows =
+                        split_oversized_sentence(sentence, self.target_size, self.size_unit).into();
+                    self.windows.pop_front().unwrap()
+                }),
+            };
+            match unit {
+                Some(unit) => {
+                    self.found_sentence = true;
+                    if let Some(chunk) = self.packer.push(unit) {
+                        return Ok(Some(chunk));
+                    }
+                }
+                None => {
+                    self.exhausted = true;
+                    if !self.found_sentence && !self.text.is_empty() {
+                        self.packed.extend(self.unsplit_chunks());
+                    }
+                    return Ok(self.packer.finish());
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    /// Chunks of a text without sentences, as `pack_chunks` returns them.
+    fn unsplit_chunks(&self) -> Vec<PackedChunk<'static>> {
+        let pieces = if self.size_unit.measure(&self.text) <= self.target_size {
+            vec![self.text.clone()]
+        } else {
+            self.size_unit.split(&self.text, self.target_size)
+        };
+        pieces
+            .into_iter()
+            .map(|text| PackedChunk {
+                text,
+                new_start: 0,
+                joiner: " ",
+            })
+            .collect()
+    }
+
+    /// The packed chunk `ahead` places after the next one, packing as needed.
+    fn peek(&mut self, ahead: usize) -> Result<Option<&PackedChunk<'static>>, Incomplete> {
+        while self.packed.len() <= ahead && !self.exhausted {
+            if let Some(chunk) = self.pack_next()? {
+                self.packed.push_back(chunk);
+            }
+        }
+        Ok(self.packed.get(ahead))
+    }
+
+    /// The next chunk, with the small chunks after it merged in as
+    /// `merge_small_chunks` does, or `Incomplete` if more text must be pushed
+    /// first. Nothing is taken from the stream until the chunk is complete.
+    pub fn poll_next(&mut self) -> Result<Option<String>, Incomplete> {
+        if self.peek(0)?.is_none() {
+            return Ok(None);
+        }
+        // How many packed chunks make up the next one
+        let mut count = 1;
+        while self.min_chunk_size > 0 {
+            let (size_unit, min_chunk_size) = (self.size_unit, self.min_chunk_size);
+            let Some(small) = self
+                .peek(count)?
+                .map(|chunk| size_unit.measure(&chunk.text) < min_chunk_size)
+            else {
+                break;
+            };
+            if !small || !(self.merge_all_small || self.peek(count + 1)?.is_none()) {
+                break;
+            }
+            count += 1;
+        }
+        let mut chunks = self.packed.drain(..count);
+        let mut merged = chunks.next().unwrap().text;
+        for chunk in chunks {
+            merged.push_str(chunk.joiner);
+            merged.push_str(&chunk.text[chunk.new_start..]);
+        }
+        Ok(Some(merged))
+    }
+}
+
+impl Iterator for ChunkStream {
+    type Item = String;
+
+    /// The next chunk of a stream whose input has ended.
+    fn next(&mut self) -> Option<String> {
+        self.poll_next()
+            .expect("a stream is only iterated once its input has ended")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{chunk_text_with_min_size, compile_boundary_pattern};
+
+    fn document() -> String {
+        (0..400)
+            .map(|i| match i % 7 {
+                0 => format!("Section {i} opens here.\n"),
+                3 => format!(
+                    "A much longer sentence {i} on revenue and {} without a break. ",
+                    "costs ".repeat(i % 40)
+                ),
+                _ => format!("Sentence {i} is short! "),
+            })
+            .collect()
+    }
+
+    fn assert_matches_chunk_text(
+        text: &str,
+        target_size: usize,
+        overlap: Overlap,
+        size_unit: SizeUnit,
+        min_chunk_size: usize,
+        merge_all_small: bool,
+        boundary: Option<&str>,
+    ) {
+        let boundary = boundary.map(|p| compile_boundary_pattern(p).unwrap());
+        let expected = chunk_text_with_min_size(
+            text,
+            target_size,
+            overlap,
+            &UnitOptions {
+                boundary: boundary.as_ref(),
+                ..UnitOptions::default()
+            },
+            size_unit,
+            min_chunk_size,
+            merge_all_small,
+        )
+        .unwrap();
+        let streamed: Vec<String> = ChunkStream::new(
+            text.to_string(),
+            target_size,
+            overlap,
+            size_unit,
+            min_chunk_size,
+            merge_all_small,
+            boundary,
+            false,
+            false,
+        )
+        .unwrap()
+        .collect();
+        assert_eq!(streamed, expected);
+    }
+
+    #[test]
+    fn test_stream_matches_chunk_text() {
+        let text = document();
+        for target_size in [40, 200, 1500] {
+            for overlap in [Overlap::Size(0), Overlap::Size(60), Overlap::Sentences(2)] {
+                assert_matches_chunk_text(
+                    &text,
+                    target_size,
+                    overlap,
+                    SizeUnit::Chars,
+                    0,
+                    false,
+                    None,
+                );
+            }
+        }
+        assert_matches_chunk_text(&text, 30, Overlap::Size(5), SizeUnit::Words, 0, false, None);
+        assert_matches_chunk_text(
+            &text,
+            100,
+            Overlap::Size(20),
+            SizeUnit::Chars,
+            0,
+            false,
+            Some(r",\s*"),
+        );
+    }
+
+    #[test]
+    fn test_stream_merges_small_chunks() {
+        let text = document();
+        for merge_all_small in [false, true] {
+            assert_matches_chunk_text(
+                &text,
+                120,
+                Overlap::Size(30),
+                SizeUnit::Chars,
+                60,
+                merge_all_small,
+                None,
+            );
+        }
+        assert_matches_chunk_text(
+            "One two three. Four.",
+            15,
+            Overlap::Sentences(0),
+            SizeUnit::Chars,
+            10,
+            false,
+            None,
+        );
+    }
+
+    /// Chunks of `text` pushed in pieces of about `piece_len` bytes.
+    fn chunks_from_pieces(text: &str, piece_len: usize, stream: ChunkStream) -> Vec<String> {
+        let mut stream = stream.awaiting_input();
+        let mut rest = text;
+        let mut chunks = Vec::new();
+        loop {
+            match stream.poll_next() {
+                Ok(Some(chunk)) => chunks.push(chunk),
+                Ok(None) => return chunks,
+                Err(Incomplete) if rest.is_empty() => stream.end_input(),
+                Err(Incomplete) => {
+                    let mut end = piece_len.min(rest.len());
+                    while !rest.is_char_boundary(end) {
+                        end += 1;
+                    }
+                    stream.push_text(&rest[..end]);
+                    rest = &rest[end..];
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_stream_from_pieces_matches_chunk_text() {
+        let text = format!(
+            "{}Dr. Lee met the U.S. team... then left. 1. Intro\n2. Plan! \"Why?\" he asked\u{2026} \
+             Fine. 今日は晴れです。明日は雨？はい。",
+            document()
+        );
+        let new_stream = |min_chunk_size, boundary: Option<&str>| {
+            ChunkStream::new(
+                String::new(),
+                120,
+                Overlap::Size(30),
+                SizeUnit::Chars,
+                min_chunk_size,
+                false,
+                boundary.map(|p| compile_boundary_pattern(p).unwrap()),
+                false,
+                false,
+            )
+            .unwrap()
+        };
+        let expected = |min_chunk_size, boundary: Option<&str>| {
+            ChunkStream::new(
+                text.clone(),
+                120,
+                Overlap::Size(30),
+                SizeUnit::Chars,
+                min_chunk_size,
+                false,
+                boundary.map(|p| compile_boundary_pattern(p).unwrap()),
+                false,
+                false,
+            )
+            .unwrap()
+            .collect::<Vec<_>>()
+        };
+        for piece_len in [1, 7, 64, 4096] {
+            for min_chunk_size in [0, 60] {
+                assert_eq!(
+                    chunks_from_pieces(&text, piece_len, new_stream(min_chunk_size, None)),
+                    expected(min_chunk_size, None),
+                    "piece_len {piece_len}, min_chunk_size {min_chunk_size}"
+                );
+            }
+            assert_eq!(
+                chunks_from_pieces(&text, piece_len, new_stream(0, Some(r",\s*"))),
+                expected(0, Some(r",\s*"))
+            );
+        }
+        assert!(chunks_from_pieces("", 4, new_stream(0, None)).is_empty());
+        assert_eq!(
+            chunks_from_pieces("   ", 1, new_stream(0, None)),
+            vec!["   "]
+        );
+    }
+
+    #[test]
+    fn test_stream_from_pieces_keeps_buffer_small() {
+        let text = document().repeat(120);
+        let mut stream = ChunkStream::new(
+            String::new(),
+            1500,
+            Overlap::Size(200),
+            SizeUnit::Chars,
+            0,
+            false,
+            None,
+            false,
+            false,
+        )
+        .unwrap()
+        .awaiting_input();
+        let mut pieces = text.as_bytes().chunks(4096);
+        let (mut chunks, mut largest_buffer) = (0, 0);
+        loop {
+            match stream.poll_next() {
+                Ok(Some(_)) => chunks += 1,
+                Ok(None) => break,
+                Err(Incomplete) => match pieces.next() {
+                    Some(piece) => stream.push_text(std::str::from_utf8(piece).unwrap()),
+                    None => stream.end_input(),
+                },
+            }
+            largest_buffer = largest_buffer.max(stream.text.len());
+        }
+        assert!(text.len() > 2_000_000 && chunks > 1000);
+        assert!(largest_buffer < 32 * 1024, "{largest_buffer}");
+    }
+
+    #[test]
+    fn test_stream_edge_cases() {
+        for text in [
+            "",
+            "   ",
+            "no punctuation at all in this fairly long line",
+            "Short.",
+        ] {
+            assert_matches_chunk_text(text, 10, Overlap::Size(3), SizeUnit::Chars, 0, false, None);
+        }
+        assert!(ChunkStream::new(
+            "text".to_string(),
+            0,
+            Overlap::Size(0),
+            SizeUnit::Chars,
+            0,
+            false,
+            None,
+            false,
+            false
+        )
+        .is_none());
+    }
+}
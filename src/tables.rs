@@ -0,0 +1,269 @@
+//! Plain-text tables: financial statements pasted as whitespace-aligned
+//! columns, found for `extract_tables`, kept out of whitespace collapsing by
+//! `CleanOptions(preserve_tables=True)` and chunked whole by
+//! `process_document`.
+//!
+//! A row is a line whose cells are separated by a tab or by two or more
+//! spaces. A table is a run of at least `MIN_TABLE_ROWS` such lines, each
+//! sharing a column edge (the start or end of a cell after the first, with
+//! tabs expanded to stops of 8) with the row before it, so right-aligned
+//! figures and left-aligned columns both line up. Lines of one cell between
+//! rows ("Current assets:") belong to the table. Prose with two spaces after
+//! a period rarely lines up that way on three lines running.
+
+use regex::Regex;
+use std::collections::HashSet;
+use std::sync::LazyLock;
+
+/// Rows with at least two cells a table needs, so that two lines of prose
+/// that happen to line up are not one.
+const MIN_TABLE_ROWS: usize = 3;
+
+/// Label lines in a row a table may hold between two of its rows.
+const MAX_LABEL_LINES: usize = 2;
+
+/// Column of a tab stop multiple, for lines aligned with tabs.
+const TAB_WIDTH: usize = 8;
+
+// A tab, or two or more spaces, between cells
+static CELL_GAP_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"[ ]*\t[ \t]*|[ ]{2,}").unwrap());
+
+/// A cell of a line with the columns it starts and ends at.
+struct Cell<'a> {
+    text: &'a str,
+    start: usize,
+    end: usize,
+}
+
+/// Cells of `line`, with columns counted in characters after expanding tabs.
+fn cells(line: &str) -> Vec<Cell<'_>> {
+    let mut column = 0;
+    let mut position = 0;
+    let mut advance = |to: usize| {
+        for c in line[position..to].chars() {
+            column = if c == '\t' {
+                (column / TAB_WIDTH + 1) * TAB_WIDTH
+            } else {
+                column + 1
+            };
+        }
+        position = to;
+        column
+    };
+    let mut cells = Vec::new();
+    let mut cell_start = 0;
+    let content_end = line.trim_end().len();
+    let gaps = CELL_GAP_RE
+        .find_iter(&line[..content_end])
+        .map(|gap| (gap.start(), gap.end()))
+        .chain([(content_end, content_end)]);
+    for (gap_start, gap_end) in gaps {
+        let text = &line[cell_start..gap_start];
+        let leading = text.len() - text.trim_start().len();
+        if !text.trim().is_empty() {
+            let start = advance(cell_start + leading);
+            let end = advance(gap_start);
+            cells.push(Cell {
+                text: text.trim(),
+                start,
+                end,
+            });
+        }
+        cell_start = gap_end;
+    }
+    cells
+}
+
+/// Column edges of the cells of a row after the first.
+fn edges(cells: &[Cell]) -> HashSet<usize> {
+    cells
+        .iter()
+        .skip(1)
+        .flat_map(|cell| [cell.start, cell.end])
+        .collect()
+}
+
+/// A table found in text.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Table {
+    /// Byte offset of the first line.
+    pub start: usize,
+    /// Byte offset after the last line, before its line break.
+    pub end: usize,
+    /// Cells of each line, in order; a label line is a row of one cell.
+    pub rows: Vec<Vec<String>>,
+}
+
+/// A line of text with its byte range, line break excluded.
+struct Line<'a> {
+    text: &'a str,
+    start: usize,
+    end: usize,
+}
+
+/// Tables in `text`, in document order.
+pub(crate) fn find_tables(text: &str) -> Vec<Table> {
+    let mut lines = Vec::new();
+    let mut offset = 0;
+    for line in text.split_inclusive('\n') {
+        let content = line.trim_end_matches(['\n', '\r']);
+        lines.push(Line {
+            text: content,
+            start: offset,
+            end: offset + content.len(),
+        });
+        offset += line.len();
+    }
+
+    let mut tables = Vec::new();
+    // Lines of the table being built: index of its first line, and the
+    // index and edges of its last row of two or more cells
+    let mut first: Option<usize> = None;
+    let mut last_row: Option<(usize, HashSet<usize>)> = None;
+    let mut row_count = 0;
+    let mut labels = 0;
+    let mut finish = |first: usize, last: usize, row_count: usize| {
+        if row_count >= MIN_TABLE_ROWS {
+            tables.push(Table {
+                start: lines[first].start,
+                end: lines[last].end,
+                rows: lines[first..=last]
+                    .iter()
+                    .map(|line| {
+                        cells(line.text)
+                            .iter()
+                            .map(|c| c.text.to_string())
+                            .collect()
+                    })
+                    .collect(),
+            });
+        }
+    };
+    for (i, line) in lines.iter().enumerate() {
+        let line_cells = cells(line.text);
+        let aligned = match &last_row {
+            Some((_, previous)) if line_cells.len() >= 2 => {
+                !previous.is_disjoint(&edges(&line_cells))
+            }
+            _ => false,
+        };
+        if aligned {
+            row_count += 1;
+            labels = 0;
+            last_row = Some((i, edges(&line_cells)));
+        } else if line_cells.len() == 1 && first.is_some() && labels < MAX_LABEL_LINES {
+            // A label line, part of the table if more rows follow
+            labels += 1;
+        } else {
+            labels = 0;
+            if let (Some(start), Some((end, _))) = (first, &last_row) {
+                finish(start, *end, row_count);
+            }
+            (first, last_row, row_count) = if line_cells.len() >= 2 {
+                (Some(i), Some((i, edges(&line_cells))), 1)
+            } else {
+                (None, None, 0)
+            };
+        }
+    }
+    if let (Some(start), Some((end, _))) = (first, &last_row) {
+        finish(start, *end, row_count);
+    }
+    tables
+}
+
+/// A piece of text cut by `split_tables`.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Block<'a> {
+    Prose(&'a str),
+    Table(&'a str),
+}
+
+/// Cut `text` into tables and the prose between them, in order; the blocks
+/// concatenated give back `text`.
+pub(crate) fn split_tables(text: &str) -> Vec<Block<'_>> {
+    let mut blocks = Vec::new();
+    let mut prose_start = 0;
+    for table in find_tables(text) {
+        if prose_start < table.start {
+            blocks.push(Block::Prose(&text[prose_start..table.start]));
+        }
+        blocks.push(Block::Table(&text[table.start..table.end]));
+        prose_start = table.end;
+    }
+    if prose_start < text.len() {
+        blocks.push(Block::Prose(&text[prose_start..]));
+    }
+    blocks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STATEMENT: &str = "The balance sheet is summarized below.\n\
+                             \n\
+                             (in millions)             2024        2023\n\
+                             Cash and equivalents   $ 1,234     $ 1,100\n\
+                             Current assets:\n\
+                             Receivables                512         480\n\
+                             Total assets           $ 4,310     $ 3,950\n\
+                             \n\
+                             Total assets grew 9%.  Liquidity remains strong.\n";
+
+    #[test]
+    fn test_find_tables() {
+        let tables = find_tables(STATEMENT);
+        assert_eq!(tables.len(), 1);
+        assert_eq!(
+            tables[0].rows,
+            vec![
+                vec!["(in millions)", "2024", "2023"],
+                vec!["Cash and equivalents", "$ 1,234", "$ 1,100"],
+                vec!["Current assets:"],
+                vec!["Receivables", "512", "480"],
+                vec!["Total assets", "$ 4,310", "$ 3,950"],
+            ]
+        );
+        let table = &STATEMENT[tables[0].start..tables[0].end];
+        assert!(table.starts_with("(in millions)") && table.ends_with("$ 3,950"));
+    }
+
+    #[test]
+    fn test_tab_separated_table() {
+        let text = "Segment\tRevenue\tMargin\nCloud\t5,200\t31%\nDevices\t2,100\t12%\n";
+        assert_eq!(
+            find_tables(text)[0].rows[2],
+            vec![
+                "Devices".to_string(),
+                "2,100".to_string(),
+                "12%".to_string()
+            ]
+        );
+    }
+
+    #[test]
+    fn test_prose_is_not_a_table() {
+        // Two aligned rows are not enough, and double-spaced prose does not align
+        let text = "Revenue      2024\nCosts        2023\n\n\
+                    We grew.  Costs fell sharply this year.\n\
+                    Margins rose.  Cash was strong in every quarter.\n\
+                    Outlook is good.  We expect more.\n";
+        assert_eq!(find_tables(text), vec![]);
+    }
+
+    #[test]
+    fn test_split_tables_round_trips() {
+        let blocks = split_tables(STATEMENT);
+        assert_eq!(blocks.len(), 3);
+        assert!(matches!(blocks[1], Block::Table(_)));
+        let joined: String = blocks
+            .iter()
+            .map(|block| match block {
+                Block::Prose(text) | Block::Table(text) => *text,
+            })
+            .collect();
+        assert_eq!(joined, STATEMENT);
+    }
+}
@@ -0,0 +1,221 @@
+//! Explicit ticker mentions: cashtags ("$AAPL") and exchange-qualified symbols
+//! ("NASDAQ: AAPL", "NYSE:BRK.B").
+//!
+//! Unlike the bare uppercase words collected as `potential_tickers` in `lib.rs`,
+//! these forms only ever name a security, so they are reported as-is without
+//! stopword filtering. `TickerOptions` holds the user's configuration for the
+//! heuristic list.
+
+use crate::COMMON_TICKER_STOPWORDS;
+use regex::Regex;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::ops::Range;
+use std::sync::LazyLock;
+
+// A symbol of 1-5 capitals with an optional share-class suffix ("BRK.B")
+const SYMBOL: &str = r"[A-Z]{1,5}(?:\.[A-Z]{1,2})?";
+// An exchange name, a colon and a symbol ("NASDAQ: ACME", "NYSE:BRK.B")
+const EXCHANGE_QUALIFIED: &str = r"\b(?P<exchange>NASDAQ|Nasdaq|NYSE(?:\s+(?:American|Arca))?|AMEX|TSXV?|LSE|OTC(?:QX|QB)?|CBOE|Cboe)\s*:\s*(?P<symbol>";
+static EXCHANGE_TICKER_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(&format!(r"{EXCHANGE_QUALIFIED}{SYMBOL})\b")).unwrap());
+// A cashtag not glued to a preceding word ("$AAPL", not "US$AAPL"), or an
+// exchange-qualified symbol
+static EXPLICIT_TICKER_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(&format!(
+        r"\B\$(?P<cashtag>{SYMBOL})\b|{EXCHANGE_QUALIFIED}{SYMBOL})\b"
+    ))
+    .unwrap()
+});
+
+/// Order of `potential_tickers` and its parallel lists.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) enum TickerOrder {
+    /// Sorted by symbol, so output does not depend on where symbols appear.
+    #[default]
+    Alphabetical,
+    /// By first appearance in the text, for `ticker_order="document"`.
+    #[cfg_attr(not(feature = "python"), allow(dead_code))]
+    Document,
+}
+
+impl TickerOrder {
+    #[cfg(feature = "python")]
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "alphabetical" => Some(TickerOrder::Alphabetical),
+            "document" => Some(TickerOrder::Document),
+            _ => None,
+        }
+    }
+}
+
+/// User configuration for `potential_tickers`, converted from Python once.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct TickerOptions {
+    /// Words never reported, on top of the built-in stopwords.
+    pub extra_stopwords: HashSet<String>,
+    /// When set, only these symbols are reported, stopwords or not.
+    pub allowlist: Option<HashSet<String>>,
+    pub order: TickerOrder,
+}
+
+impl TickerOptions {
+    /// Keep only symbols in a dictionary of known tickers; an empty dictionary
+    /// keeps the heuristic instead of rejecting everything.
+    #[cfg(any(feature = "python", test))]
+    pub fn known_tickers(known: HashSet<String>) -> Self {
+        TickerOptions {
+            allowlist: (!known.is_empty()).then_some(known),
+            ..TickerOptions::default()
+        }
+    }
+
+    /// Whether a bare uppercase word is kept as a potential ticker.
+    pub fn accepts(&self, symbol: &str) -> bool {
+        match &self.allowlist {
+            Some(allowlist) => allowlist.contains(symbol),
+            None => {
+                !COMMON_TICKER_STOPWORDS.contains(symbol) && !self.extra_stopwords.contains(symbol)
+            }
+        }
+    }
+
+    /// Whether an exchange-qualified symbol is kept; being explicit, it only
+    /// has to pass the allowlist.
+    pub fn accepts_qualified(&self, symbol: &str) -> bool {
+        self.allowlist
+            .as_ref()
+            .is_none_or(|allowlist| allowlist.contains(symbol))
+    }
+}
+
+/// A ticker written in an unambiguous form.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+pub struct ExplicitTicker {
+    pub symbol: String,
+    /// Exchange the symbol was qualified with; None for cashtags.
+    pub exchange: Option<String>,
+}
+
+/// Spell the exchange the same way however it was cased or spaced
+/// ("Nasdaq" -> "NASDAQ", "NYSE  Arca" -> "NYSE Arca").
+fn canonical_exchange(name: &str) -> String {
+    let name = name.split_whitespace().collect::<Vec<_>>().join(" ");
+    if name.eq_ignore_ascii_case("nasdaq") || name.eq_ignore_ascii_case("cboe") {
+        name.to_uppercase()
+    } else {
+        name
+    }
+}
+
+/// Find exchange-qualified tickers with the byte range of the whole mention,
+/// exchange name included, in document order.
+pub(crate) fn find_exchange_qualified(text: &str) -> Vec<(Range<usize>, ExplicitTicker)> {
+    EXCHANGE_TICKER_RE
+        .captures_iter(text)
+        .map(|caps| {
+            let ticker = ExplicitTicker {
+                symbol: caps["symbol"].to_string(),
+                exchange: Some(canonical_exchange(&caps["exchange"])),
+            };
+            (caps.get(0).unwrap().range(), ticker)
+        })
+        .collect()
+}
+
+/// Extract cashtags and exchange-qualified tickers, deduplicated by symbol and
+/// exchange in first-occurrence order.
+pub(crate) fn extract_explicit_tickers(text: &str) -> Vec<ExplicitTicker> {
+    let mut seen = HashSet::new();
+    EXPLICIT_TICKER_RE
+        .captures_iter(text)
+        .map(|caps| match caps.name("cashtag") {
+            Some(cashtag) => ExplicitTicker {
+                symbol: cashtag.as_str().to_string(),
+                exchange: None,
+            },
+            None => ExplicitTicker {
+                symbol: caps["symbol"].to_string(),
+                exchange: Some(canonical_exchange(&caps["exchange"])),
+            },
+        })
+        .filter(|ticker| seen.insert(ticker.clone()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ticker(symbol: &str, exchange: Option<&str>) -> ExplicitTicker {
+        ExplicitTicker {
+            symbol: symbol.to_string(),
+            exchange: exchange.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_press_release_opening_line() {
+        assert_eq!(
+            extract_explicit_tickers("Acme Corp. (NASDAQ: ACME) today announced results."),
+            vec![ticker("ACME", Some("NASDAQ"))]
+        );
+    }
+
+    #[test]
+    fn test_exchange_forms_and_cashtags() {
+        let text = "Berkshire (NYSE:BRK.B) and Microsoft (Nasdaq: MSFT) beat; \
+                    $AAPL rallied, $AAPL again, NYSE  Arca: SPY too.";
+        assert_eq!(
+            extract_explicit_tickers(text),
+            vec![
+                ticker("BRK.B", Some("NYSE")),
+                ticker("MSFT", Some("NASDAQ")),
+                ticker("AAPL", None),
+                ticker("SPY", Some("NYSE Arca")),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_find_exchange_qualified_ranges() {
+        let text = "Shares (NASDAQ:AAPL) and NYSE: MSFT.";
+        let found = find_exchange_qualified(text);
+        let mentions: Vec<&str> = found.iter().map(|(r, _)| &text[r.clone()]).collect();
+        assert_eq!(mentions, vec!["NASDAQ:AAPL", "NYSE: MSFT"]);
+        assert_eq!(found[1].1, ticker("MSFT", Some("NYSE")));
+    }
+
+    #[test]
+    fn test_ticker_options() {
+        let default = TickerOptions::default();
+        assert!(default.accepts("AAPL") && !default.accepts("THE"));
+
+        let extra = TickerOptions {
+            extra_stopwords: ["GAAP".to_string()].into(),
+            ..TickerOptions::default()
+        };
+        assert!(!extra.accepts("GAAP") && !extra.accepts("THE") && extra.accepts("AAPL"));
+        assert!(extra.accepts_qualified("GAAP"));
+
+        let allow = TickerOptions {
+            allowlist: Some(["AAPL".to_string(), "ONE".to_string()].into()),
+            ..TickerOptions::default()
+        };
+        assert!(allow.accepts("ONE") && !allow.accepts("MSFT"));
+        assert!(!allow.accepts_qualified("MSFT"));
+
+        assert_eq!(TickerOptions::known_tickers(HashSet::new()), default);
+        assert_eq!(
+            TickerOptions::known_tickers(["AAPL".to_string()].into()).allowlist,
+            Some(["AAPL".to_string()].into())
+        );
+    }
+
+    #[test]
+    fn test_ignores_amounts_and_plain_words() {
+        let text = "Revenue was $5M, up from US$4M; THE CEO spoke at 10:30.";
+        assert!(extract_explicit_tickers(text).is_empty());
+    }
+}
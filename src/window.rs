@@ -0,0 +1,146 @@
+//! Fixed-size sliding windows, backing `chunk_text(strategy="window")`.
+//!
+//! Windows ignore sentences entirely: each is `target_size` units of the text
+//! as written, and the next starts `target_size - overlap` units later, so
+//! chunk boundaries depend only on length. Units are grapheme clusters or
+//! whitespace-delimited words, as for sentence packing.
+
+use crate::{graphemes, SizeUnit};
+
+/// Byte ranges of the units of `text`, in order.
+fn unit_spans(text: &str, size_unit: SizeUnit) -> Vec<(usize, usize)> {
+    match size_unit {
+        SizeUnit::Chars => {
+            let mut start = 0;
+            graphemes::clusters(text)
+                .map(|cluster| {
+                    let span = (start, start + cluster.len());
+                    start = span.1;
+                    span
+                })
+                .collect()
+        }
+        SizeUnit::Words => text
+            .split_whitespace()
+            .map(|word| {
+                let start = word.as_ptr() as usize - text.as_ptr() as usize;
+                (start, start + word.len())
+            })
+            .collect(),
+    }
+}
+
+/// Slice `text` into windows of `target_size` units starting every
+/// `target_size - overlap` units. The last window may be shorter, and ends
+/// the text: no window lies wholly inside the previous one. A window of words
+/// keeps the whitespace between them.
+///
+/// Returns an error message if `target_size` is 0 or `overlap` is not smaller
+/// than it.
+pub fn window_chunks(
+    text: &str,
+    target_size: usize,
+    overlap: usize,
+    size_unit: SizeUnit,
+) -> Result<Vec<String>, String> {
+    if target_size == 0 {
+        return Err("target_size must be greater than 0".to_string());
+    }
+    if overlap >= target_size {
+        return Err(format!(
+            "overlap ({overlap}) must be smaller than target_size ({target_size}) \
+             with strategy=\"window\""
+        ));
+    }
+    let spans = unit_spans(text, size_unit);
+    let step = target_size - overlap;
+    let mut windows = Vec::new();
+    let mut first = 0;
+    while first < spans.len() {
+        let last = (first + target_size).min(spans.len()) - 1;
+        windows.push(text[spans[first].0..spans[last].1].to_string());
+        if last == spans.len() - 1 {
+            break;
+        }
+        first += step;
+    }
+    Ok(windows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn windows(text: &str, target_size: usize, overlap: usize) -> Vec<String> {
+        window_chunks(text, target_size, overlap, SizeUnit::Chars).unwrap()
+    }
+
+    #[test]
+    fn test_window_boundaries() {
+        // 26 letters, windows of 10 stepping by 8: starts 0, 8 and 16, the
+        // last one partial
+        let alphabet = "abcdefghijklmnopqrstuvwxyz";
+        assert_eq!(
+            windows(alphabet, 10, 2),
+            vec!["abcdefghij", "ijklmnopqr", "qrstuvwxyz"]
+        );
+        assert_eq!(
+            windows(alphabet, 10, 0),
+            vec!["abcdefghij", "klmnopqrst", "uvwxyz"]
+        );
+        // Starts 0, 6, 12 and 18; the window at 18 reaches the end
+        assert_eq!(
+            windows(alphabet, 8, 2),
+            vec!["abcdefgh", "ghijklmn", "mnopqrst", "stuvwxyz"]
+        );
+    }
+
+    #[test]
+    fn test_window_overlap_counts() {
+        let text = "The quarter was strong. Revenue grew 12% while costs fell.";
+        let chunks = windows(text, 20, 5);
+        assert_eq!(chunks.len(), 4);
+        for pair in chunks.windows(2) {
+            let tail: String = pair[0].chars().skip(15).collect();
+            assert!(pair[1].starts_with(&tail), "{pair:?}");
+            assert_eq!(tail.chars().count(), 5);
+        }
+        assert!(chunks[..3].iter().all(|c| c.chars().count() == 20));
+        assert_eq!(chunks[3], "e costs fell.");
+    }
+
+    #[test]
+    fn test_window_exact_fit_and_short_text() {
+        // 20 chars with windows of 10 stepping by 10: no empty third window
+        assert_eq!(
+            windows("abcdefghijklmnopqrst", 10, 0),
+            vec!["abcdefghij", "klmnopqrst"]
+        );
+        assert_eq!(windows("short", 10, 3), vec!["short"]);
+        assert!(windows("", 10, 3).is_empty());
+    }
+
+    #[test]
+    fn test_window_counts_grapheme_clusters() {
+        // "é" as e + combining acute is one unit
+        let text = "cafe\u{301} au lait";
+        assert_eq!(windows(text, 4, 0), vec!["cafe\u{301}", " au ", "lait"]);
+    }
+
+    #[test]
+    fn test_window_words() {
+        let text = "one two  three four\nfive six seven";
+        assert_eq!(
+            window_chunks(text, 3, 1, SizeUnit::Words).unwrap(),
+            vec!["one two  three", "three four\nfive", "five six seven"]
+        );
+    }
+
+    #[test]
+    fn test_window_rejects_overlap_not_below_target() {
+        assert!(window_chunks("text", 10, 10, SizeUnit::Chars).is_err());
+        assert!(window_chunks("text", 10, 11, SizeUnit::Chars).is_err());
+        assert!(window_chunks("text", 0, 0, SizeUnit::Chars).is_err());
+        assert!(window_chunks("text", 10, 9, SizeUnit::Chars).is_ok());
+    }
+}
@@ -0,0 +1,52 @@
+"""Benchmark: one large document, sequential vs intra-document parallel processing.
+
+Run after building the module (`make build-release` for meaningful numbers):
+
+    uv run python tests/bench/bench_large_document.py
+    uv run pytest tests/bench -s
+"""
+
+import os
+import time
+from pathlib import Path
+
+import rag_rust_core
+
+DATA_DIR = Path(__file__).resolve().parents[2] / "data" / "sample_transcripts"
+TARGET_BYTES = 30 * 1024 * 1024
+
+
+def load_large_document(target_bytes: int = TARGET_BYTES) -> str:
+    """Concatenate the sample transcripts until the document reaches target_bytes."""
+    samples = [p.read_text(encoding="utf-8") for p in sorted(DATA_DIR.glob("*.txt"))]
+    parts, size = [], 0
+    while size < target_bytes:
+        sample = samples[len(parts) % len(samples)]
+        parts.append(sample)
+        size += len(sample.encode("utf-8"))
+    return "\n\n".join(parts)
+
+
+def test_parallel_matches_sequential_and_is_faster():
+    text = load_large_document()
+
+    start = time.perf_counter()
+    sequential = rag_rust_core.process_document(text, parallel=False)
+    sequential_secs = time.perf_counter() - start
+
+    start = time.perf_counter()
+    parallel = rag_rust_core.process_document(text, parallel=True)
+    parallel_secs = time.perf_counter() - start
+
+    print(
+        f"\n{len(text) / 1e6:.0f} MB document on {os.cpu_count()} cores: "
+        f"sequential {sequential_secs:.2f}s, parallel {parallel_secs:.2f}s "
+        f"({sequential_secs / parallel_secs:.1f}x)"
+    )
+    assert parallel == sequential
+    if (os.cpu_count() or 1) > 1:
+        assert parallel_secs < sequential_secs
+
+
+if __name__ == "__main__":
+    test_parallel_matches_sequential_and_is_faster()
@@ -0,0 +1,63 @@
+"""Benchmark: serial process_document loop vs parallel process_documents.
+
+Run after building the module (`make build-release` for meaningful numbers):
+
+    uv run python tests/bench/bench_process_documents.py
+    uv run pytest tests/bench -s
+"""
+
+import os
+import time
+from pathlib import Path
+
+import rag_rust_core
+
+DATA_DIR = Path(__file__).resolve().parents[2] / "data" / "sample_transcripts"
+N_DOCUMENTS = 10_000
+
+
+def load_corpus(n_documents: int = N_DOCUMENTS) -> list[str]:
+    """Repeat the sample transcripts until the corpus has n_documents entries."""
+    samples = [p.read_text(encoding="utf-8") for p in sorted(DATA_DIR.glob("*.txt"))]
+    return [samples[i % len(samples)] for i in range(n_documents)]
+
+
+def run_serial(texts: list[str]) -> list[list[dict]]:
+    return [rag_rust_core.process_document(t) for t in texts]
+
+
+def run_parallel(texts: list[str]) -> list[list[dict]]:
+    return rag_rust_core.process_documents(texts)
+
+
+def test_batch_matches_serial_and_is_faster():
+    texts = load_corpus()
+
+    start = time.perf_counter()
+    serial = run_serial(texts)
+    serial_secs = time.perf_counter() - start
+
+    start = time.perf_counter()
+    parallel = run_parallel(texts)
+    parallel_secs = time.perf_counter() - start
+
+    print(
+        f"\n{len(texts)} documents on {os.cpu_count()} cores: "
+        f"serial {serial_secs:.2f}s, parallel {parallel_secs:.2f}s "
+        f"({serial_secs / parallel_secs:.1f}x)"
+    )
+    assert parallel == serial
+    if (os.cpu_count() or 1) > 1:
+        assert parallel_secs < serial_secs
+
+
+def test_bad_document_does_not_abort_batch():
+    results = rag_rust_core.process_documents(["Good text.", None, 42, "More text."])
+    assert results[1] is None and results[2] is None
+    assert results[0][0]["text"] == "Good text."
+    assert results[3][0]["text"] == "More text."
+
+
+if __name__ == "__main__":
+    test_batch_matches_serial_and_is_faster()
+    test_bad_document_does_not_abort_batch()
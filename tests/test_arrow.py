@@ -0,0 +1,150 @@
+"""Arrow record batch output with `process_document_arrow` (cargo feature `arrow`).
+
+Requires the module built with the feature (`uv run maturin develop --features arrow`),
+then: `uv run pytest tests/test_arrow.py`.
+"""
+
+import ctypes
+
+import pytest
+
+import rag_rust_core
+
+pytestmark = pytest.mark.skipif(
+    not hasattr(rag_rust_core, "process_document_arrow"),
+    reason="built without the arrow feature",
+)
+
+TEXT = (
+    "Acme (NASDAQ: ACME) reported Q3 2024 revenue of $5.2 million, up 12%. "
+    "Costs fell 40 bps in March 2024. AAPL and MSFT were cited. "
+) * 8
+METADATA_COLUMNS = ["monetary_amounts", "percentages", "dates", "potential_tickers"]
+
+
+class ArrowSchema(ctypes.Structure):
+    pass
+
+
+ArrowSchema._fields_ = [
+    ("format", ctypes.c_char_p),
+    ("name", ctypes.c_char_p),
+    ("metadata", ctypes.c_char_p),
+    ("flags", ctypes.c_int64),
+    ("n_children", ctypes.c_int64),
+    ("children", ctypes.POINTER(ctypes.POINTER(ArrowSchema))),
+    ("dictionary", ctypes.POINTER(ArrowSchema)),
+    ("release", ctypes.c_void_p),
+    ("private_data", ctypes.c_void_p),
+]
+
+
+class ArrowArray(ctypes.Structure):
+    pass
+
+
+ArrowArray._fields_ = [
+    ("length", ctypes.c_int64),
+    ("null_count", ctypes.c_int64),
+    ("offset", ctypes.c_int64),
+    ("n_buffers", ctypes.c_int64),
+    ("n_children", ctypes.c_int64),
+    ("buffers", ctypes.POINTER(ctypes.c_void_p)),
+    ("children", ctypes.POINTER(ctypes.POINTER(ArrowArray))),
+    ("dictionary", ctypes.POINTER(ArrowArray)),
+    ("release", ctypes.c_void_p),
+    ("private_data", ctypes.c_void_p),
+]
+
+get_pointer = ctypes.pythonapi.PyCapsule_GetPointer
+get_pointer.restype = ctypes.c_void_p
+get_pointer.argtypes = [ctypes.py_object, ctypes.c_char_p]
+
+
+def int32s(array):
+    values = ctypes.cast(array.buffers[1], ctypes.POINTER(ctypes.c_int32))
+    return [values[i] for i in range(array.length)]
+
+
+def strings(array):
+    offsets = ctypes.cast(array.buffers[1], ctypes.POINTER(ctypes.c_int32))
+    data = array.buffers[2]
+    return [
+        ctypes.string_at(data + offsets[i], offsets[i + 1] - offsets[i]).decode("utf-8")
+        for i in range(array.length)
+    ]
+
+
+def lists(array):
+    offsets = ctypes.cast(array.buffers[1], ctypes.POINTER(ctypes.c_int32))
+    items = strings(array.children[0].contents)
+    return [items[offsets[i] : offsets[i + 1]] for i in range(array.length)]
+
+
+def read_batch(batch):
+    """Decode an exported record batch with the C data interface, as an Arrow
+    library would: {column name: list of values}."""
+    schema_capsule, array_capsule = batch.__arrow_c_array__()
+    schema = ArrowSchema.from_address(get_pointer(schema_capsule, b"arrow_schema"))
+    array = ArrowArray.from_address(get_pointer(array_capsule, b"arrow_array"))
+    assert schema.format == b"+s"
+    assert schema.n_children == array.n_children
+    columns = {}
+    for i in range(schema.n_children):
+        field = schema.children[i].contents
+        child = array.children[i].contents
+        assert child.length == array.length and child.null_count == 0
+        read = {b"i": int32s, b"u": strings, b"+l": lists}[field.format]
+        columns[field.name.decode()] = read(child)
+    return columns
+
+
+def test_columns_match_process_document():
+    kwargs = {"chunk_size": 200, "chunk_overlap": 20}
+    batch = rag_rust_core.process_document_arrow(TEXT, **kwargs)
+    chunks = rag_rust_core.process_document(TEXT, **kwargs)
+    assert len(batch) == batch.num_rows == len(chunks) > 1
+    assert batch.column_names == ["chunk_index", "text", "char_count", *METADATA_COLUMNS]
+    columns = read_batch(batch)
+    assert columns["chunk_index"] == [c["chunk_index"] for c in chunks]
+    assert columns["text"] == [c["text"] for c in chunks]
+    assert columns["char_count"] == [c["char_count"] for c in chunks]
+    for name in METADATA_COLUMNS:
+        assert columns[name] == [c["metadata"][name] for c in chunks]
+
+
+def test_batch_has_document_ids():
+    texts = ["Revenue rose 5%.", 42, TEXT]
+    batch = rag_rust_core.process_documents_arrow(texts, chunk_size=300, extract_meta=False)
+    assert batch.column_names == ["document_id", "chunk_index", "text", "char_count"]
+    expected = rag_rust_core.process_documents(texts, chunk_size=300, extract_meta=False)
+    columns = read_batch(batch)
+    assert columns["document_id"] == [i for i, doc in enumerate(expected) if doc for _ in doc]
+    assert columns["text"] == [c["text"] for doc in expected if doc for c in doc]
+
+
+def test_exports_repeatedly_and_empty():
+    batch = rag_rust_core.process_document_arrow("Margins held. Costs fell.")
+    assert read_batch(batch) == read_batch(batch)
+    empty = rag_rust_core.process_document_arrow("   ")
+    assert empty.num_rows == 0
+    assert read_batch(empty)["text"] == []
+    assert repr(empty).startswith("ArrowChunks(num_rows=0")
+
+
+def test_errors():
+    with pytest.raises(rag_rust_core.InvalidChunkParams):
+        rag_rust_core.process_document_arrow(TEXT, chunk_size=0)
+    with pytest.raises(rag_rust_core.EmptyDocument):
+        rag_rust_core.process_document_arrow("  ", on_empty="error")
+
+
+def test_pyarrow_record_batch():
+    pa = pytest.importorskip("pyarrow")
+    batch = rag_rust_core.process_document_arrow(TEXT, chunk_size=200)
+    record_batch = pa.record_batch(batch)
+    assert record_batch.schema.field("chunk_index").type == pa.int32()
+    assert record_batch.schema.field("text").type == pa.utf8()
+    assert record_batch.schema.field("dates").type == pa.list_(pa.utf8())
+    assert record_batch.to_pylist() == batch.to_pyarrow().to_pylist()
+    assert record_batch.column("text").to_pylist() == read_batch(batch)["text"]
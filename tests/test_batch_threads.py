@@ -0,0 +1,29 @@
+"""process_documents with a dedicated thread pool.
+
+Requires the built module (`make build`), then: `uv run pytest tests/test_batch_threads.py`.
+"""
+
+import pytest
+
+import rag_rust_core
+
+TEXTS = [f"Document {i} reports ${i} million in Q3 2024. " * (i % 5 + 1) for i in range(100)]
+
+
+@pytest.mark.parametrize("num_threads", [1, 2, 8])
+def test_num_threads_preserves_order(num_threads):
+    expected = [rag_rust_core.process_document(t) for t in TEXTS]
+    assert rag_rust_core.process_documents(TEXTS, num_threads=num_threads) == expected
+
+
+def test_num_threads_zero_rejected():
+    with pytest.raises(ValueError, match="num_threads"):
+        rag_rust_core.process_documents(TEXTS, num_threads=0)
+
+
+def test_n_threads_is_a_deprecated_alias():
+    with pytest.warns(DeprecationWarning, match="num_threads"):
+        result = rag_rust_core.process_documents(TEXTS[:4], n_threads=2)
+    assert result == rag_rust_core.process_documents(TEXTS[:4], num_threads=2)
+    with pytest.raises(rag_rust_core.InvalidOption):
+        rag_rust_core.process_documents(TEXTS[:4], num_threads=2, n_threads=2)
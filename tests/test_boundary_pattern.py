@@ -0,0 +1,37 @@
+"""Custom sentence boundaries for `chunk_text`.
+
+Requires the built module (`make build`), then: `uv run pytest tests/test_boundary_pattern.py`.
+"""
+
+import pytest
+
+import rag_rust_core
+
+CONTRACT = (
+    "Seller delivers the goods; Buyer pays within 30 days; "
+    "either party may terminate. Notices go in writing"
+)
+
+
+def test_semicolon_boundaries():
+    chunks = rag_rust_core.chunk_text(CONTRACT, target_size=60, overlap=0, boundary_pattern=r";\s*")
+    assert chunks == [
+        "Seller delivers the goods; Buyer pays within 30 days;",
+        "either party may terminate. Notices go in writing",
+    ]
+    # The default rules see one overlong sentence and cut it mid-clause
+    assert rag_rust_core.chunk_text(CONTRACT, target_size=60, overlap=0)[0].endswith("either")
+
+
+def test_enumerated_clauses_start_sentences():
+    text = "The Licensee shall (a) keep records, (b) allow audits and (c) pay fees."
+    chunks = rag_rust_core.chunk_text(
+        text, target_size=25, overlap_sentences=0, boundary_pattern=r"\s+\([a-z]\)"
+    )
+    assert chunks == ["The Licensee shall", "(a) keep records,", "(b) allow audits and", "(c) pay fees."]
+
+
+@pytest.mark.parametrize("pattern", ["(", ";?"])
+def test_invalid_patterns_raise(pattern):
+    with pytest.raises(rag_rust_core.InvalidOption, match="boundary_pattern"):
+        rag_rust_core.chunk_text(CONTRACT, boundary_pattern=pattern)
@@ -0,0 +1,39 @@
+"""Word-sized chunking with `chunk_text_by_words`.
+
+Requires the built module (`make build`), then: `uv run pytest tests/test_chunk_by_words.py`.
+"""
+
+import pytest
+
+import rag_rust_core
+
+# Sentences of 6, 2, 8 and 5 words
+PARAGRAPH = (
+    "Revenue grew 12% in the quarter. Costs fell. "
+    "Margins expanded to a record level this year. The board approved a dividend."
+)
+
+
+def test_known_paragraph():
+    assert rag_rust_core.chunk_text_by_words(PARAGRAPH, max_words=10, overlap_words=3) == [
+        "Revenue grew 12% in the quarter. Costs fell.",
+        "Costs fell. Margins expanded to a record level this year.",
+        "The board approved a dividend.",
+    ]
+
+
+def test_defaults_fit_short_text():
+    assert rag_rust_core.chunk_text_by_words(PARAGRAPH) == [PARAGRAPH]
+
+
+def test_long_sentence_emitted_whole():
+    long = "This single sentence has far more than five words in it."
+    chunks = rag_rust_core.chunk_text_by_words(
+        f"Short one. {long} Another short one.", max_words=5, overlap_words=0
+    )
+    assert chunks == ["Short one.", long, "Another short one."]
+
+
+def test_zero_max_words_rejected():
+    with pytest.raises(rag_rust_core.InvalidChunkParams):
+        rag_rust_core.chunk_text_by_words(PARAGRAPH, max_words=0)
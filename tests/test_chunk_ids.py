@@ -0,0 +1,29 @@
+"""Content-derived chunk IDs and `hash_text`.
+
+Requires the built module (`make build`), then: `uv run pytest tests/test_chunk_ids.py`.
+"""
+
+import rag_rust_core
+
+TEXT = "Revenue rose 5% to $10 million. Margins improved in Q3 2024."
+
+
+def test_hash_text_digests_are_pinned():
+    assert rag_rust_core.hash_text("") == "ef46db3751d8e999"
+    assert rag_rust_core.hash_text("abc") == "44bc2cf5ad770999"
+
+
+def test_chunk_id_is_hash_of_index_and_text():
+    chunks = rag_rust_core.process_document(TEXT)
+    assert chunks[0]["chunk_id"] == "e3534b23b44f24e5"
+    for chunk in chunks:
+        expected = rag_rust_core.hash_text(f"{chunk['chunk_index']}:{chunk['text']}")
+        assert chunk["chunk_id"] == expected
+    assert rag_rust_core.process_document(TEXT) == chunks
+
+
+def test_same_text_at_another_index_gets_another_id():
+    text = "Same sentence here. Same sentence here."
+    chunks = rag_rust_core.process_document(text, chunk_size=20, chunk_overlap=0)
+    assert chunks[0]["text"] == chunks[1]["text"]
+    assert chunks[0]["chunk_id"] != chunks[1]["chunk_id"]
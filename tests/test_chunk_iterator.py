@@ -0,0 +1,61 @@
+"""Lazy chunking with `ChunkIterator`.
+
+Requires the built module (`make build`), then: `uv run pytest tests/test_chunk_iterator.py`.
+"""
+
+import pytest
+
+import rag_rust_core
+
+TEXT = "".join(
+    f"Sentence {i} covers revenue and margins{', costs' * (i % 30)}."
+    + ("\n" if i % 9 == 0 else " ")
+    for i in range(2000)
+)
+
+
+@pytest.mark.parametrize(
+    "kwargs",
+    [
+        {},
+        {"target_size": 200, "overlap": 0},
+        {"target_size": 300, "overlap": 0.2},
+        {"target_size": 500, "overlap_sentences": 2},
+        {"target_size": 40, "size_unit": "words", "overlap": 5},
+        {"target_size": 250, "min_chunk_size": 120, "merge_all_small": True},
+        {"target_size": 150, "boundary_pattern": r",\s*"},
+    ],
+)
+def test_matches_chunk_text(kwargs):
+    assert list(rag_rust_core.ChunkIterator(TEXT, **kwargs)) == rag_rust_core.chunk_text(
+        TEXT, **kwargs
+    )
+
+
+def test_yields_lazily():
+    chunks = rag_rust_core.ChunkIterator(TEXT * 50, target_size=1000)
+    assert iter(chunks) is chunks
+    first = next(chunks)
+    assert first == rag_rust_core.chunk_text(TEXT, target_size=1000)[0]
+    assert len(first) <= 1000
+
+
+def test_exhausted_iterator_stops():
+    chunks = rag_rust_core.ChunkIterator("One sentence. Another one.", target_size=15, overlap=0)
+    assert list(chunks) == ["One sentence.", "Another one."]
+    assert list(chunks) == []
+    assert list(rag_rust_core.ChunkIterator("")) == []
+
+
+@pytest.mark.parametrize(
+    "kwargs",
+    [{"target_size": 0}, {"overlap": 1.5}, {"overlap": 10, "overlap_sentences": 1}],
+)
+def test_invalid_params(kwargs):
+    with pytest.raises(rag_rust_core.InvalidChunkParams):
+        rag_rust_core.ChunkIterator(TEXT, **kwargs)
+
+
+def test_invalid_option():
+    with pytest.raises(rag_rust_core.InvalidOption):
+        rag_rust_core.ChunkIterator(TEXT, size_unit="tokens")
@@ -0,0 +1,39 @@
+"""Markdown section-aware chunking.
+
+Requires the built module (`make build`), then: `uv run pytest tests/test_chunk_markdown.py`.
+"""
+
+import pytest
+
+import rag_rust_core
+
+DOC = """# Results
+## Revenue
+Revenue grew 12%. Growth was broad based.
+
+### North America
+North America rose 15%.
+
+## Costs
+Costs fell 3%.
+"""
+
+
+def test_chunks_carry_heading_trail():
+    assert rag_rust_core.chunk_markdown(DOC) == [
+        "# Results > ## Revenue\n\nRevenue grew 12%. Growth was broad based.",
+        "# Results > ## Revenue > ### North America\n\nNorth America rose 15%.",
+        "# Results > ## Costs\n\nCosts fell 3%.",
+    ]
+
+
+def test_plain_text_matches_chunk_text():
+    text = "No headings at all. " * 50
+    assert rag_rust_core.chunk_markdown(text, target_size=200, overlap=20) == (
+        rag_rust_core.chunk_text(text, target_size=200, overlap=20)
+    )
+
+
+def test_zero_target_size_rejected():
+    with pytest.raises(ValueError):
+        rag_rust_core.chunk_markdown(DOC, target_size=0)
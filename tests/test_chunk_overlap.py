@@ -0,0 +1,111 @@
+"""`chunk_text` overlap alternatives: a float `overlap`, `overlap_ratio` and
+`overlap_sentences`.
+
+Requires the built module (`make build`), then: `uv run pytest tests/test_chunk_overlap.py`.
+"""
+
+import pytest
+
+import rag_rust_core
+
+TEXT = " ".join(f"Sentence number {i} talks about revenue." for i in range(200))
+
+
+@pytest.mark.parametrize("target_size", [200, 500, 1000])
+def test_ratio_matches_absolute_overlap(target_size):
+    by_ratio = rag_rust_core.chunk_text(TEXT, target_size=target_size, overlap_ratio=0.25)
+    by_chars = rag_rust_core.chunk_text(TEXT, target_size=target_size, overlap=target_size // 4)
+    assert by_ratio == by_chars
+
+
+@pytest.mark.parametrize("ratio", [-0.1, 1.0, 1.5])
+def test_ratio_out_of_range(ratio):
+    with pytest.raises(ValueError):
+        rag_rust_core.chunk_text(TEXT, overlap_ratio=ratio)
+
+
+def test_overlap_and_ratio_are_mutually_exclusive():
+    with pytest.raises(ValueError, match="mutually exclusive"):
+        rag_rust_core.chunk_text(TEXT, overlap=100, overlap_ratio=0.1)
+
+
+def test_default_overlap_unchanged():
+    assert rag_rust_core.chunk_text(TEXT) == rag_rust_core.chunk_text(TEXT, overlap=200)
+
+
+def test_overlap_sentences_repeats_whole_sentences():
+    chunks = rag_rust_core.chunk_text(TEXT, target_size=300, overlap_sentences=2)
+    for prev, nxt in zip(chunks, chunks[1:]):
+        carried = prev.split(". ")[-2:]
+        assert nxt.startswith(". ".join(carried))
+
+
+@pytest.mark.parametrize("kwargs", [{"overlap": 10}, {"overlap_ratio": 0.1}])
+def test_overlap_sentences_is_exclusive(kwargs):
+    with pytest.raises(ValueError, match="mutually exclusive"):
+        rag_rust_core.chunk_text(TEXT, overlap_sentences=1, **kwargs)
+
+
+def test_float_overlap_is_fraction_of_target_size():
+    by_fraction = rag_rust_core.chunk_text(TEXT, target_size=2000, overlap=0.15)
+    assert by_fraction == rag_rust_core.chunk_text(TEXT, target_size=2000, overlap=300)
+    assert by_fraction != rag_rust_core.chunk_text(TEXT, target_size=2000, overlap=0)
+
+
+def test_int_overlap_stays_absolute():
+    assert rag_rust_core.chunk_text(TEXT, target_size=500, overlap=1) == rag_rust_core.chunk_text(
+        TEXT, target_size=500, overlap_ratio=0.002
+    )
+
+
+@pytest.mark.parametrize("overlap", [1.0, 1.5, -0.1])
+def test_float_overlap_out_of_range(overlap):
+    with pytest.raises(ValueError, match=r"fraction of the target size"):
+        rag_rust_core.chunk_text(TEXT, overlap=overlap)
+    with pytest.raises(ValueError, match=r"fraction of the target size"):
+        rag_rust_core.process_document(TEXT, chunk_overlap=overlap)
+
+
+def test_process_document_float_chunk_overlap():
+    by_fraction = rag_rust_core.process_document(TEXT, chunk_size=2000, chunk_overlap=0.15)
+    by_chars = rag_rust_core.process_document(TEXT, chunk_size=2000, chunk_overlap=300)
+    assert [c["text"] for c in by_fraction] == [c["text"] for c in by_chars]
+    assert rag_rust_core.process_documents([TEXT], chunk_size=2000, chunk_overlap=0.15) == [
+        by_fraction
+    ]
+
+
+def test_respect_paragraphs_keeps_overlap_in_its_paragraph():
+    text = "Alpha one here. Alpha two here. Alpha three here.\n\nBeta one here. Beta two here."
+    default = rag_rust_core.chunk_text(text, target_size=40, overlap_sentences=2)
+    assert "Alpha three here. Beta one here." in default
+    chunks = rag_rust_core.chunk_text(
+        text, target_size=40, overlap_sentences=2, respect_paragraphs=True
+    )
+    assert chunks == [
+        "Alpha one here. Alpha two here.",
+        "Alpha two here. Alpha three here.",
+        "Beta one here. Beta two here.",
+    ]
+
+
+def test_respect_paragraphs_needs_sentence_strategy():
+    with pytest.raises(rag_rust_core.InvalidOption):
+        rag_rust_core.chunk_text(TEXT, strategy="window", overlap=0, respect_paragraphs=True)
+
+
+@pytest.mark.parametrize("overlap", [1500, 5000])
+def test_overlap_at_least_target_size_is_clamped(overlap):
+    clamped = rag_rust_core.chunk_text(TEXT, target_size=1500, overlap=overlap)
+    assert clamped == rag_rust_core.chunk_text(TEXT, target_size=1500, overlap=1499)
+
+
+@pytest.mark.parametrize("overlap", [1500, 5000])
+def test_strict_overlap_raises(overlap):
+    with pytest.raises(rag_rust_core.InvalidChunkParams, match="strict_overlap"):
+        rag_rust_core.chunk_text(TEXT, target_size=1500, overlap=overlap, strict_overlap=True)
+
+
+def test_strict_overlap_accepts_smaller_overlap():
+    strict = rag_rust_core.chunk_text(TEXT, target_size=1500, overlap=1499, strict_overlap=True)
+    assert strict == rag_rust_core.chunk_text(TEXT, target_size=1500, overlap=1499)
@@ -0,0 +1,69 @@
+"""Recursive chunking over a separator hierarchy.
+
+Requires the built module (`make build`), then: `uv run pytest tests/test_chunk_recursive.py`.
+"""
+
+import pytest
+
+import rag_rust_core
+
+LONG_LINE = "ACME-" + "0123456789" * 12
+DOCUMENT = (
+    "# Quarterly Report\n\n"
+    "Revenue grew in every region. Europe led with strong demand.\n\n"
+    "## Appendix\n\n"
+    f"{LONG_LINE}\n\n"
+    "## Outlook\n\n"
+    "Guidance is unchanged. Margins should hold."
+)
+
+
+def test_structure_versus_sentence_packing():
+    recursive = rag_rust_core.chunk_text_recursive(DOCUMENT, target_size=70, overlap=0)
+    flat = rag_rust_core.chunk_text(DOCUMENT, target_size=70, overlap=0)
+    assert all(len(chunk) <= 70 for chunk in recursive + flat)
+    # Whole paragraphs and headings survive
+    assert "Revenue grew in every region. Europe led with strong demand." in recursive
+    assert "## Outlook\n\nGuidance is unchanged. Margins should hold." in recursive
+    # The unbroken line is cut into character windows that share no chunk with
+    # its neighbours
+    line_chunks = [chunk for chunk in recursive if "0123" in chunk]
+    assert line_chunks[0].startswith("ACME-") and all("#" not in c for c in line_chunks)
+    # Flat packing runs the heading into the paragraph and the long line into
+    # the next heading
+    assert flat[0].startswith("# Quarterly Report\n\nRevenue")
+    assert any("## Outlook" in chunk and "0123" in chunk for chunk in flat)
+
+
+def test_matches_chunk_text_on_plain_sentences():
+    text = "Aa. Bb. Cc. Dd. Ee. Ff. Gg."
+    for target, overlap in [(8, 0), (12, 4), (20, 10)]:
+        assert rag_rust_core.chunk_text_recursive(
+            text, target_size=target, overlap=overlap
+        ) == rag_rust_core.chunk_text(text, target_size=target, overlap=overlap)
+
+
+def test_custom_separators():
+    text = "alpha|beta|gamma|delta"
+    assert rag_rust_core.chunk_text_recursive(
+        text, target_size=11, overlap=0, separators=["|"]
+    ) == ["alpha|beta", "beta|gamma", "gamma|delta"]
+    sentences = rag_rust_core.chunk_text_recursive(
+        "One here. Two there.", target_size=10, separators=[rag_rust_core.SENTENCE_SEPARATOR]
+    )
+    assert sentences == ["One here.", "Two there."]
+
+
+def test_zero_target_raises():
+    with pytest.raises(rag_rust_core.InvalidChunkParams):
+        rag_rust_core.chunk_text_recursive(DOCUMENT, target_size=0)
+
+
+def test_min_chunk_size_merges_mid_document():
+    text = "Revenue grew in every region this year.\n\n## Appendix\n\n" + "x" * 60 + "\n\nThanks."
+    chunks = rag_rust_core.chunk_text_recursive(text, target_size=40, overlap=0)
+    assert "## Appendix" in chunks and chunks[-1] == "Thanks."
+    merged = rag_rust_core.chunk_text_recursive(text, target_size=40, overlap=0, min_chunk_size=15)
+    assert merged[0] == "Revenue grew in every region this year.\n\n## Appendix"
+    assert merged[-1].endswith("x\n\nThanks.")
+    assert all(len(chunk) >= 15 for chunk in merged)
@@ -0,0 +1,64 @@
+"""Chunk size report from `process_document(return_report=True)`.
+
+Requires the built module (`make build`), then: `uv run pytest tests/test_chunk_report.py`.
+"""
+
+import rag_rust_core
+
+CODE_BLOCK = "```python\ndef total(rows):\n    return sum(r.amount for r in rows)\n```"
+TEXT = f"Revenue rose. Costs fell.\n{CODE_BLOCK}\nOk."
+KEEP_CODE = rag_rust_core.CleanOptions(preserve_code_blocks=True)
+
+
+def test_default_return_shape_unchanged():
+    chunks = rag_rust_core.process_document(TEXT, chunk_size=40, chunk_overlap=0)
+    assert isinstance(chunks, list)
+    assert chunks == rag_rust_core.process_document(
+        TEXT, chunk_size=40, chunk_overlap=0, return_report=False
+    )
+
+
+def test_oversized_unit_is_reported():
+    chunks, report = rag_rust_core.process_document(
+        TEXT, chunk_size=40, chunk_overlap=0, clean_options=KEEP_CODE, return_report=True
+    )
+    block = next(c["chunk_index"] for c in chunks if c["text"] == CODE_BLOCK)
+    assert report["exceeded"] == 1
+    assert report["exceeded_chunks"] == [block]
+    assert report["max_size"] == len(CODE_BLOCK)
+    assert report["chunk_count"] == len(chunks)
+    assert report["chunk_size"] == 40
+    assert report["floor"] == 4
+    # "Ok." is under a tenth of chunk_size
+    assert report["below_floor"] == 1
+
+
+def test_long_sentence_is_split_to_fit():
+    sentence = " ".join(["word"] * 40) + "."
+    _, report = rag_rust_core.process_document(
+        sentence, chunk_size=40, chunk_overlap=0, return_report=True
+    )
+    assert report["exceeded"] == 0
+    assert report["max_size"] <= 40
+
+
+def test_report_in_words_and_with_document_metadata():
+    result, report = rag_rust_core.process_document(
+        TEXT,
+        chunk_size=3,
+        chunk_overlap=0,
+        size_unit="words",
+        include_document_metadata=True,
+        return_report=True,
+    )
+    assert set(result) == {"chunks", "document_metadata"}
+    assert report["max_size"] == max(c["word_count"] for c in result["chunks"])
+
+
+def test_placeholder_is_not_counted():
+    chunks, report = rag_rust_core.process_document(
+        "   ", on_empty="placeholder", return_report=True
+    )
+    assert len(chunks) == 1
+    assert report["chunk_count"] == 0
+    assert report["max_size"] == 0
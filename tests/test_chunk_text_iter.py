@@ -0,0 +1,81 @@
+"""Streaming chunking with `chunk_text_iter`, from a str or from pieces.
+
+Requires the built module (`make build`), then: `uv run pytest tests/test_chunk_text_iter.py`.
+"""
+
+import pytest
+
+import rag_rust_core
+
+# About 4 MB of filing-like prose, with line breaks and initials mixed in
+DOCUMENT = "".join(
+    f"Item {i}. The U.S. segment reported revenue of ${i}.5 million{', costs' * (i % 25)}."
+    + ("\n" if i % 11 == 0 else " ")
+    + ("Growth slowed... then recovered. " if i % 13 == 0 else "")
+    for i in range(40_000)
+)
+
+
+def pieces(text, size):
+    return (text[i : i + size] for i in range(0, len(text), size))
+
+
+def test_document_is_multi_megabyte():
+    assert len(DOCUMENT) > 3_000_000
+
+
+def test_str_input_matches_chunk_text():
+    chunks = rag_rust_core.chunk_text_iter(DOCUMENT, target_size=1500, overlap=200)
+    assert isinstance(chunks, rag_rust_core.ChunkIterator)
+    assert list(chunks) == rag_rust_core.chunk_text(DOCUMENT, target_size=1500, overlap=200)
+
+
+@pytest.mark.parametrize("piece_size", [1 << 16, 4093])
+def test_pieces_match_chunk_text(piece_size):
+    expected = rag_rust_core.chunk_text(DOCUMENT, target_size=1500, overlap=200)
+    streamed = rag_rust_core.chunk_text_iter(
+        pieces(DOCUMENT, piece_size), target_size=1500, overlap=200
+    )
+    assert list(streamed) == expected
+
+
+def test_sentences_spanning_piece_edges():
+    text = "Dr. Lee met the U.S. team... then left. 1. Intro\n2. Plan! Fine."
+    expected = rag_rust_core.chunk_text(text, target_size=20, overlap_sentences=0)
+    for size in range(1, 8):
+        assert (
+            list(rag_rust_core.chunk_text_iter(pieces(text, size), target_size=20, overlap_sentences=0))
+            == expected
+        )
+
+
+def test_options_apply_to_pieces():
+    text = DOCUMENT[:200_000]
+    kwargs = {"target_size": 400, "min_chunk_size": 150, "merge_all_small": True}
+    assert list(rag_rust_core.chunk_text_iter(pieces(text, 1000), **kwargs)) == (
+        rag_rust_core.chunk_text(text, **kwargs)
+    )
+
+
+def test_pieces_taken_lazily():
+    taken = []
+
+    def blocks():
+        for block in pieces(DOCUMENT, 1 << 16):
+            taken.append(block)
+            yield block
+
+    chunks = rag_rust_core.ChunkIterator(blocks(), target_size=1000)
+    next(chunks)
+    assert len(taken) == 1
+
+
+def test_empty_and_invalid_input():
+    assert list(rag_rust_core.chunk_text_iter([])) == []
+    assert list(rag_rust_core.chunk_text_iter(["", "  "])) == ["  "]
+    with pytest.raises(TypeError):
+        rag_rust_core.chunk_text_iter(42)
+    with pytest.raises(TypeError):
+        list(rag_rust_core.chunk_text_iter(["Fine. ", b"bytes"]))
+    with pytest.raises(rag_rust_core.InvalidChunkParams):
+        rag_rust_core.chunk_text_iter(["text"], target_size=0)
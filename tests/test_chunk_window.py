@@ -0,0 +1,76 @@
+"""Fixed-size window chunking with `chunk_text(strategy="window")`.
+
+Requires the built module (`make build`), then: `uv run pytest tests/test_chunk_window.py`.
+"""
+
+import pytest
+
+import rag_rust_core
+
+ALPHABET = "abcdefghijklmnopqrstuvwxyz"
+
+
+def test_window_boundaries():
+    # Windows of 10 stepping by 8 start at 0, 8 and 16
+    assert rag_rust_core.chunk_text(ALPHABET, target_size=10, overlap=2, strategy="window") == [
+        "abcdefghij",
+        "ijklmnopqr",
+        "qrstuvwxyz",
+    ]
+
+
+def test_last_partial_window_included():
+    assert rag_rust_core.chunk_text(ALPHABET, target_size=10, overlap=0, strategy="window") == [
+        "abcdefghij",
+        "klmnopqrst",
+        "uvwxyz",
+    ]
+
+
+def test_overlap_counts():
+    text = "The quarter was strong. Revenue grew 12% while costs fell."
+    chunks = rag_rust_core.chunk_text(text, target_size=20, overlap=5, strategy="window")
+    assert [len(c) for c in chunks] == [20, 20, 20, 13]
+    for previous, current in zip(chunks, chunks[1:]):
+        assert current[:5] == previous[-5:]
+
+
+def test_float_overlap_and_words():
+    chunks = rag_rust_core.chunk_text(ALPHABET, target_size=10, overlap=0.5, strategy="window")
+    assert chunks[:2] == ["abcdefghij", "fghijklmno"]
+    assert rag_rust_core.chunk_text(
+        "one two three four five", target_size=2, overlap=1, size_unit="words", strategy="window"
+    ) == ["one two", "two three", "three four", "four five"]
+
+
+def test_grapheme_clusters_count_once():
+    text = "café au lait"
+    assert rag_rust_core.chunk_text(text, target_size=4, overlap=0, strategy="window") == [
+        "café",
+        " au ",
+        "lait",
+    ]
+
+
+def test_sentence_is_default():
+    text = "First sentence here. Second one."
+    assert rag_rust_core.chunk_text(text, target_size=25, overlap=0) == rag_rust_core.chunk_text(
+        text, target_size=25, overlap=0, strategy="sentence"
+    )
+
+
+@pytest.mark.parametrize("overlap", [10, 11])
+def test_overlap_not_below_target_rejected(overlap):
+    with pytest.raises(rag_rust_core.InvalidChunkParams, match="smaller than target_size"):
+        rag_rust_core.chunk_text(ALPHABET, target_size=10, overlap=overlap, strategy="window")
+
+
+def test_invalid_combinations_rejected():
+    with pytest.raises(rag_rust_core.InvalidOption, match="strategy"):
+        rag_rust_core.chunk_text(ALPHABET, strategy="paragraph")
+    with pytest.raises(rag_rust_core.InvalidOption, match="preserve_lists"):
+        rag_rust_core.chunk_text(
+            ALPHABET, target_size=10, overlap=0, preserve_lists=True, strategy="window"
+        )
+    with pytest.raises(rag_rust_core.InvalidChunkParams, match="overlap_sentences"):
+        rag_rust_core.chunk_text(ALPHABET, target_size=10, overlap_sentences=1, strategy="window")
@@ -0,0 +1,30 @@
+"""HTML input via `CleanOptions(strip_html=True)`.
+
+Requires the built module (`make build`), then: `uv run pytest tests/test_clean_html.py`.
+"""
+
+import rag_rust_core
+
+HTML = """<!DOCTYPE html>
+<html><head><script>var x = "<p>not text</p>";</script></head>
+<body><h2>Results</h2><p>Revenue rose 5% to $10&nbsp;million.</p>
+<p data-note="a > b">AT&amp;T&#8217;s margins improved.</p></body></html>"""
+
+
+def test_clean_text_strips_html():
+    options = rag_rust_core.CleanOptions(strip_html=True)
+    assert rag_rust_core.clean_text(HTML, options=options) == (
+        "Results\n\nRevenue rose 5% to $10 million.\n\nAT&T's margins improved."
+    )
+
+
+def test_process_document_takes_raw_html():
+    options = rag_rust_core.CleanOptions(strip_html=True)
+    (chunk,) = rag_rust_core.process_document(HTML, clean_options=options)
+    assert "<" not in chunk["text"]
+    assert chunk["metadata"]["monetary_amounts"] == ["$10 million"]
+
+
+def test_strip_html_off_by_default():
+    assert not rag_rust_core.CleanOptions().strip_html
+    assert "<p>" in rag_rust_core.clean_text(HTML)
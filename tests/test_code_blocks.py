@@ -0,0 +1,39 @@
+"""Fenced code blocks survive cleaning and chunking verbatim.
+
+Requires the built module (`make build`), then: `uv run pytest tests/test_code_blocks.py`.
+"""
+
+import rag_rust_core
+
+BLOCK = (
+    "```python\n"
+    "def greet(name):\n"
+    "    msg = “hello”  —  name\n"
+    "\n"
+    "\n"
+    "    return msg\n"
+    "```"
+)
+DOC = f"Call   the “greet” helper.\n\n{BLOCK}\n\nIt returns   a string. " + "Filler sentence here. " * 20
+
+
+def test_clean_text_leaves_block_untouched():
+    options = rag_rust_core.CleanOptions(preserve_code_blocks=True)
+    cleaned = rag_rust_core.clean_text(DOC, options=options)
+    assert BLOCK in cleaned
+    assert cleaned.startswith('Call the "greet" helper.\n\n```python')
+    assert BLOCK not in rag_rust_core.clean_text(DOC)
+
+
+def test_chunk_text_never_splits_block():
+    chunks = rag_rust_core.chunk_text(DOC, target_size=60, overlap=0, preserve_code_blocks=True)
+    assert sum(BLOCK in c for c in chunks) == 1
+    assert not any("```" in c and BLOCK not in c for c in chunks)
+
+
+def test_process_document_keeps_block_whole():
+    options = rag_rust_core.CleanOptions(preserve_code_blocks=True)
+    chunks = rag_rust_core.process_document(
+        DOC, chunk_size=80, chunk_overlap=0, clean_options=options
+    )
+    assert sum(BLOCK in c["text"] for c in chunks) == 1
@@ -0,0 +1,51 @@
+"""ISO 8601 normalization of extracted dates.
+
+Requires the built module (`make build`), then: `uv run pytest tests/test_dates_parsed.py`.
+"""
+
+import pytest
+
+import rag_rust_core
+
+
+def test_dates_parsed_shapes():
+    text = "Filed 2024-01-15 for Q1 2024; see January 15, 2024 and February 30, 2024."
+    meta = rag_rust_core.extract_metadata(text)
+    assert meta["dates"] == ["2024-01-15", "Q1 2024", "January 15, 2024", "February 30, 2024"]
+    assert meta["dates_parsed"] == [
+        {"raw": "2024-01-15", "kind": "day", "normalized": "2024-01-15", "valid": True},
+        {"raw": "Q1 2024", "kind": "quarter", "normalized": {"year": 2024, "quarter": 1}, "valid": True},
+        {"raw": "January 15, 2024", "kind": "day", "normalized": "2024-01-15", "valid": True},
+        {"raw": "February 30, 2024", "kind": "day", "normalized": None, "valid": False},
+    ]
+
+
+def test_fiscal_years_and_month_years():
+    meta = rag_rust_core.extract_metadata("Fiscal 2023 closed in Dec. 2023 at $2,024 (page 2024).")
+    assert meta["dates_parsed"] == [
+        {"raw": "Fiscal 2023", "kind": "year", "normalized": "2023", "valid": True},
+        {"raw": "Dec. 2023", "kind": "month", "normalized": "2023-12", "valid": True},
+    ]
+
+
+def test_dates_normalized():
+    meta = rag_rust_core.extract_metadata("Filed March 5, 2024 for Q3 2023, updated January 2024.")
+    assert meta["dates"] == ["March 5, 2024", "Q3 2023", "January 2024"]
+    assert meta["dates_normalized"] == ["2024-03-05", "2023-Q3", None]
+    assert rag_rust_core.normalize_date("Q3 2023") == "2023-Q3"
+    assert rag_rust_core.normalize_date("March 5, 2024") == "2024-03-05"
+    assert rag_rust_core.normalize_date("not a date") is None
+
+
+def test_slash_date_format_hint():
+    text = "Signed 03/04/2024, amended 13/04/2024."
+    us = rag_rust_core.extract_metadata(text)
+    eu = rag_rust_core.extract_metadata(text, date_format="eu")
+    assert us["dates"] == eu["dates"] == ["03/04/2024", "13/04/2024"]
+    assert us["dates_normalized"] == ["2024-03-04", "2024-04-13"]
+    assert eu["dates_normalized"] == ["2024-04-03", "2024-04-13"]
+    assert us["dates_format_overridden"] == ["13/04/2024"]
+    assert eu["dates_format_overridden"] == []
+    assert rag_rust_core.normalize_date("03/04/2024", date_format="eu") == "2024-04-03"
+    with pytest.raises(ValueError, match="date_format"):
+        rag_rust_core.extract_metadata(text, date_format="uk")
@@ -0,0 +1,56 @@
+"""Duplicate chunk removal with `dedupe_chunks`.
+
+Requires the built module (`make build`), then: `uv run pytest tests/test_dedupe_chunks.py`.
+"""
+
+import pytest
+
+import rag_rust_core
+
+DISCLAIMER = "Past performance is no guarantee of future results."
+
+
+def test_exact_dedup_preserves_order():
+    chunks = ["b", DISCLAIMER, "a", DISCLAIMER, "b", "c", DISCLAIMER]
+    assert rag_rust_core.dedupe_chunks(chunks) == ["b", DISCLAIMER, "a", "c"]
+    assert rag_rust_core.dedupe_chunks(chunks, return_dropped=True) == (
+        ["b", DISCLAIMER, "a", "c"],
+        {3: 1, 4: 0, 6: 1},
+    )
+
+
+def test_exact_keeps_case_differences():
+    chunks = [DISCLAIMER, DISCLAIMER.upper()]
+    assert rag_rust_core.dedupe_chunks(chunks) == chunks
+
+
+def test_normalized_dedup_collapses_case_and_spacing():
+    chunks = [
+        DISCLAIMER,
+        "Revenue rose 12%.",
+        DISCLAIMER.upper(),
+        "  past performance is no\nguarantee of   future results. ",
+        "revenue ROSE 12%.",
+    ]
+    deduped, dropped = rag_rust_core.dedupe_chunks(chunks, exact=False, return_dropped=True)
+    assert deduped == [DISCLAIMER, "Revenue rose 12%."]
+    assert dropped == {2: 0, 3: 0, 4: 1}
+
+
+def test_deterministic_across_processed_batches():
+    docs = [f"Filing {i}. {DISCLAIMER}" for i in range(3)]
+    chunks = [
+        c["text"]
+        for doc in docs
+        for c in rag_rust_core.process_document(doc, chunk_size=60, chunk_overlap=0)
+    ]
+    first = rag_rust_core.dedupe_chunks(chunks, exact=False)
+    assert first == rag_rust_core.dedupe_chunks(chunks, exact=False)
+    assert first.count(DISCLAIMER) == 1
+    assert len(first) == 4
+
+
+def test_empty_and_keyword_only():
+    assert rag_rust_core.dedupe_chunks([]) == []
+    with pytest.raises(TypeError):
+        rag_rust_core.dedupe_chunks(["a"], False)
@@ -0,0 +1,23 @@
+"""Rejoining words hyphenated across line breaks.
+
+Requires the built module (`make build`), then: `uv run pytest tests/test_dehyphenate.py`.
+"""
+
+import rag_rust_core
+
+
+def test_dehyphenate_joins_lowercase_fragments():
+    assert rag_rust_core.dehyphenate("inter-\nnational sales") == "international sales"
+
+
+def test_dehyphenate_keeps_compounds():
+    text = "a state-\nof-the-art plant, Coca-\nCola"
+    assert rag_rust_core.dehyphenate(text) == text
+
+
+def test_clean_text_dehyphenate_option():
+    text = "Our inter-\nnational sales\ngrew."
+    options = rag_rust_core.CleanOptions(dehyphenate=True)
+    assert options.dehyphenate
+    assert rag_rust_core.clean_text(text, options=options) == "Our international sales grew."
+    assert rag_rust_core.clean_text(text) == "Our inter- national sales grew."
@@ -0,0 +1,26 @@
+"""Requires the built module (`make build`), then: `uv run pytest tests/test_detect_language.py`."""
+
+import rag_rust_core
+
+
+def test_english():
+    text = (
+        "The company reported that revenue for the third quarter was up, "
+        "and it expects the same from the next one."
+    )
+    assert rag_rust_core.detect_language(text) == "en"
+
+
+def test_japanese():
+    text = "当社の売上高は前年同期比で五パーセント増加しました。"
+    assert rag_rust_core.detect_language(text) == "ja"
+
+
+def test_russian():
+    text = "Выручка компании выросла на пять процентов по сравнению с прошлым годом."
+    assert rag_rust_core.detect_language(text) == "ru"
+
+
+def test_undetermined():
+    assert rag_rust_core.detect_language("") == "und"
+    assert rag_rust_core.detect_language("12,345.67 -- 2024") == "und"
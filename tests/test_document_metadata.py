@@ -0,0 +1,29 @@
+"""Document-level metadata from process_document.
+
+Requires the built module (`make build`), then: `uv run pytest tests/test_document_metadata.py`.
+"""
+
+import rag_rust_core
+
+TEXT = "Revenue reached $1.5 billion in total. ACME and MSFT beat; ACME rose 5%."
+
+
+def test_entity_split_by_chunking_is_found_at_document_level():
+    result = rag_rust_core.process_document(
+        TEXT, chunk_size=20, chunk_overlap=0, include_document_metadata=True
+    )
+    chunks = result["chunks"]
+    assert chunks == rag_rust_core.process_document(TEXT, chunk_size=20, chunk_overlap=0)
+    assert all("$1.5 billion" not in c["metadata"]["monetary_amounts"] for c in chunks)
+
+    document = result["document_metadata"]
+    assert document["monetary_amounts"] == ["$1.5 billion"]
+    assert document["potential_tickers"] == ["ACME", "MSFT"]
+    assert document["ticker_counts"] == {"ACME": 2, "MSFT": 1}
+    assert document["counts"] == {
+        "monetary_amounts": 1,
+        "percentages": 1,
+        "dates": 0,
+        "fiscal_periods": 0,
+        "potential_tickers": 3,
+    }
@@ -0,0 +1,39 @@
+"""Ellipses as pauses rather than sentence ends in `chunk_text`.
+
+Requires the built module (`make build`), then: `uv run pytest tests/test_ellipsis.py`.
+"""
+
+import rag_rust_core
+
+
+def test_ellipsis_before_lowercase_is_a_pause():
+    assert rag_rust_core.chunk_text(
+        "He paused... then continued.", target_size=20, overlap_sentences=0
+    ) == ["He paused... then", "continued."]
+
+
+def test_ellipsis_before_capital_ends_sentence():
+    assert rag_rust_core.chunk_text(
+        "It ended… The next day began.", target_size=20, overlap_sentences=0
+    ) == ["It ended…", "The next day began."]
+
+
+def test_punctuation_clusters_stay_attached():
+    assert rag_rust_core.chunk_text(
+        "Really?! Yes!?... it did.", target_size=12, overlap_sentences=0
+    ) == ["Really?!", "Yes!?...", "it did."]
+
+
+def test_ellipsis_terminal_restores_old_behavior():
+    chunks = rag_rust_core.chunk_text(
+        "He paused... then continued.", target_size=20, overlap_sentences=0, ellipsis_terminal=True
+    )
+    assert chunks == ["He paused...", "then continued."]
+    assert list(
+        rag_rust_core.ChunkIterator(
+            "He paused... then continued.",
+            target_size=20,
+            overlap_sentences=0,
+            ellipsis_terminal=True,
+        )
+    ) == chunks
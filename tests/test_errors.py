@@ -0,0 +1,46 @@
+"""Exception hierarchy rooted at RagCoreError.
+
+Requires the built module (`make build`), then: `uv run pytest tests/test_errors.py`.
+"""
+
+import pytest
+
+import rag_rust_core
+
+
+def test_hierarchy():
+    assert issubclass(rag_rust_core.RagCoreError, ValueError)
+    for exc in (rag_rust_core.InvalidChunkParams, rag_rust_core.InvalidOption, rag_rust_core.EmptyDocument):
+        assert issubclass(exc, rag_rust_core.RagCoreError)
+    assert "RagCoreError" in rag_rust_core.__doc__
+
+
+def test_invalid_chunk_params_catchable_as_both():
+    with pytest.raises(rag_rust_core.InvalidChunkParams, match="target_size"):
+        rag_rust_core.chunk_text("Some text.", target_size=0)
+    with pytest.raises(rag_rust_core.RagCoreError):
+        rag_rust_core.process_document("Some text.", chunk_size=0)
+    with pytest.raises(ValueError):
+        rag_rust_core.chunk_markdown("# Title\n\nBody.", target_size=0)
+    with pytest.raises(rag_rust_core.InvalidChunkParams, match="mutually exclusive"):
+        rag_rust_core.chunk_text("Some text.", overlap=10, overlap_ratio=0.1)
+
+
+@pytest.mark.parametrize(
+    "process",
+    [
+        lambda: rag_rust_core.process_document("Some text.", chunk_size=0),
+        lambda: rag_rust_core.process_document_json("Some text.", chunk_size=0),
+        lambda: rag_rust_core.process_documents(["Some text."], chunk_size=0),
+    ],
+)
+def test_zero_chunk_size_names_chunk_size(process):
+    with pytest.raises(rag_rust_core.InvalidChunkParams, match="^chunk_size must be"):
+        process()
+
+
+def test_invalid_option():
+    with pytest.raises(rag_rust_core.InvalidOption, match="on_empty"):
+        rag_rust_core.process_document("", on_empty="ignore")
+    with pytest.raises(rag_rust_core.InvalidOption, match="num_threads"):
+        rag_rust_core.process_documents(["a."], num_threads=0)
@@ -0,0 +1,25 @@
+"""Skipping metadata extraction in `process_document` with `extract_meta=False`.
+
+Requires the built module (`make build`), then: `uv run pytest tests/test_extract_meta.py`.
+"""
+
+import rag_rust_core
+
+TEXT = "Revenue hit $5 million. Margins rose 3%."
+
+
+def test_metadata_key_absent_when_disabled():
+    chunks = rag_rust_core.process_document(TEXT, extract_meta=False)
+    assert all("metadata" not in chunk for chunk in chunks)
+    assert [c["text"] for c in chunks] == [c["text"] for c in rag_rust_core.process_document(TEXT)]
+
+
+def test_metadata_key_present_by_default():
+    chunks = rag_rust_core.process_document(TEXT)
+    assert all("metadata" in chunk for chunk in chunks)
+    assert chunks[0]["metadata"] == rag_rust_core.extract_metadata(chunks[0]["text"])
+
+
+def test_process_documents_forwards_extract_meta():
+    [chunks] = rag_rust_core.process_documents([TEXT], extract_meta=False)
+    assert "metadata" not in chunks[0]
@@ -0,0 +1,39 @@
+"""Fiscal-year and period-over-period references.
+
+Requires the built module (`make build`), then: `uv run pytest tests/test_fiscal_periods.py`.
+"""
+
+import rag_rust_core
+
+
+def test_fiscal_periods_kept_out_of_dates():
+    meta = rag_rust_core.extract_metadata("In FY2024 revenue rose YoY; FY23 was flat YoY.")
+    assert meta["fiscal_periods"] == ["FY2024", "YoY", "FY23"]
+    assert "FY23" not in meta["dates"]
+    assert "YoY" not in meta["dates"]
+
+
+def test_10q_periods_parsed():
+    text = (
+        "Net sales for the three months ended June 30, 2024 were $85.8 billion, "
+        "and we expect the second quarter of fiscal 2025 to improve on H1 2024."
+    )
+    meta = rag_rust_core.extract_metadata(text)
+    assert meta["fiscal_periods"] == [
+        "three months ended June 30, 2024",
+        "second quarter of fiscal 2025",
+        "H1 2024",
+    ]
+    assert meta["fiscal_periods_parsed"] == [
+        {"raw": "three months ended June 30, 2024", "kind": "Q", "number": 2, "year": 2024},
+        {"raw": "second quarter of fiscal 2025", "kind": "Q", "number": 2, "year": 2025},
+        {"raw": "H1 2024", "kind": "H", "number": 1, "year": 2024},
+    ]
+
+
+def test_fiscal_years_and_tokens_parsed():
+    meta = rag_rust_core.extract_metadata("FY23 revenue grew YoY.")
+    assert meta["fiscal_periods_parsed"] == [
+        {"raw": "FY23", "kind": "FY", "number": None, "year": 2023},
+        {"raw": "YoY", "kind": "YoY", "number": None, "year": None},
+    ]
@@ -0,0 +1,22 @@
+"""Accent folding with `CleanOptions(fold_to_ascii=True)`.
+
+Requires the built module (`make build`), then: `uv run pytest tests/test_fold_to_ascii.py`.
+"""
+
+import rag_rust_core
+
+FOLD = rag_rust_core.CleanOptions(fold_to_ascii=True)
+
+
+def test_accented_latin():
+    text = "The café in São Paulo hired a naïve Großhändler from Århus."
+    assert rag_rust_core.clean_text(text, options=FOLD) == (
+        "The cafe in Sao Paulo hired a naive Grosshandler from Arhus."
+    )
+
+
+def test_opt_in_and_non_latin_kept():
+    assert rag_rust_core.clean_text("café") == "café"
+    assert not rag_rust_core.CleanOptions().fold_to_ascii
+    text = "€5 Москва 東京"
+    assert rag_rust_core.clean_text(text, options=FOLD) == text
@@ -0,0 +1,46 @@
+"""Entity frequencies from `extract_metadata(frequencies=True)`.
+
+Requires the built module (`make build`), then: `uv run pytest tests/test_frequencies.py`.
+"""
+
+import rag_rust_core
+
+TEXT = (
+    "AAPL rose 5% to $3 billion in Q3 2024. MSFT fell 2%. "
+    "AAPL guided to $3 billion again, and AAPL kept its Q3 2024 dividend, up 5% YoY."
+)
+COUNT_KEYS = {
+    "monetary_amounts_counts",
+    "percentages_counts",
+    "dates_counts",
+    "fiscal_periods_counts",
+}
+
+
+def test_counts_are_off_by_default():
+    metadata = rag_rust_core.extract_metadata(TEXT)
+    assert not COUNT_KEYS & set(metadata)
+    assert rag_rust_core.extract_metadata(TEXT, frequencies=False) == metadata
+
+
+def test_ticker_appearing_three_times_counts_three():
+    metadata = rag_rust_core.extract_metadata(TEXT, frequencies=True)
+    assert metadata["ticker_counts"] == {"AAPL": 3, "MSFT": 1}
+
+
+def test_entity_counts():
+    metadata = rag_rust_core.extract_metadata(TEXT, frequencies=True)
+    assert metadata["monetary_amounts_counts"] == {"$3 billion": 2}
+    assert metadata["percentages_counts"] == {"2%": 1, "5%": 2}
+    assert metadata["dates_counts"] == {"Q3 2024": 2}
+    assert metadata["fiscal_periods_counts"] == {"YoY": 1}
+    # The lists themselves are unchanged
+    plain = rag_rust_core.extract_metadata(TEXT)
+    assert {k: v for k, v in metadata.items() if k not in COUNT_KEYS} == plain
+
+
+def test_count_keys_are_sorted():
+    text = "Growth was 9%, then 1%, then 5%, then 1% again."
+    counts = rag_rust_core.extract_metadata(text, frequencies=True)["percentages_counts"]
+    assert list(counts) == sorted(counts)
+    assert counts == {"1%": 2, "5%": 1, "9%": 1}
@@ -0,0 +1,111 @@
+"""The heavy pyfunctions release the GIL while they work.
+
+Requires the built module (`make build-release`), then:
+`uv run pytest tests/test_gil_release.py -s`.
+"""
+
+import os
+import threading
+import time
+from concurrent.futures import ThreadPoolExecutor
+
+import rag_rust_core
+
+PARAGRAPH = (
+    "Revenue grew 12% to $1,250 million in Q3 2024.   ACME   raised guidance.\n"
+    "Margins   expanded   on lower costs.\n\n"
+)
+BIG_TEXT = PARAGRAPH * (50 * 1024 * 1024 // len(PARAGRAPH))  # ~50 MB
+
+
+def test_clean_text_from_four_threads():
+    n_threads = 4
+
+    start = time.perf_counter()
+    expected = rag_rust_core.clean_text(BIG_TEXT)
+    single_secs = time.perf_counter() - start
+
+    start = time.perf_counter()
+    with ThreadPoolExecutor(max_workers=n_threads) as pool:
+        results = list(pool.map(rag_rust_core.clean_text, [BIG_TEXT] * n_threads))
+    threaded_secs = time.perf_counter() - start
+
+    print(
+        f"\nclean_text on {len(BIG_TEXT) / 1e6:.0f} MB: 1 call {single_secs:.2f}s, "
+        f"{n_threads} threaded calls {threaded_secs:.2f}s on {os.cpu_count()} cores"
+    )
+    assert all(r == expected for r in results)
+    if (os.cpu_count() or 1) >= n_threads:
+        # Serialized calls would take ~n_threads * single_secs
+        assert threaded_secs < single_secs * n_threads * 0.75
+
+
+def test_other_threads_run_while_processing():
+    ticks = 0
+    stop = threading.Event()
+
+    def ticker():
+        nonlocal ticks
+        while not stop.is_set():
+            ticks += 1
+            time.sleep(0.001)
+
+    thread = threading.Thread(target=ticker)
+    thread.start()
+    try:
+        rag_rust_core.clean_text(BIG_TEXT)
+        rag_rust_core.process_document(BIG_TEXT[: 5 * 1024 * 1024])
+    finally:
+        stop.set()
+        thread.join()
+
+    # With the GIL held for the whole call the ticker would be starved
+    assert ticks > 10
+
+
+def test_chunk_and_extract_from_threads():
+    text = rag_rust_core.clean_text(PARAGRAPH * 1000)
+    expected_chunks = rag_rust_core.chunk_text(text)
+    expected_meta = rag_rust_core.extract_metadata(text)
+
+    with ThreadPoolExecutor(max_workers=4) as pool:
+        chunks = list(pool.map(rag_rust_core.chunk_text, [text] * 8))
+        metas = list(pool.map(rag_rust_core.extract_metadata, [text] * 8))
+
+    assert all(c == expected_chunks for c in chunks)
+    assert all(m == expected_meta for m in metas)
+
+
+def test_process_documents_batch_releases_gil():
+    texts = [f"Document {i}. " + PARAGRAPH * 20 for i in range(1000)]
+    ticks = 0
+    stop = threading.Event()
+
+    def ticker():
+        nonlocal ticks
+        while not stop.is_set():
+            ticks += 1
+            time.sleep(0.001)
+
+    start = time.perf_counter()
+    serial = [rag_rust_core.process_document(t) for t in texts]
+    serial_secs = time.perf_counter() - start
+
+    thread = threading.Thread(target=ticker)
+    thread.start()
+    try:
+        start = time.perf_counter()
+        batch = rag_rust_core.process_documents(texts)
+        batch_secs = time.perf_counter() - start
+    finally:
+        stop.set()
+        thread.join()
+
+    print(
+        f"\n1000-document batch on {os.cpu_count()} cores: process_document loop "
+        f"{serial_secs:.2f}s, process_documents {batch_secs:.2f}s "
+        f"({serial_secs / batch_secs:.1f}x), ticker ran {ticks} times"
+    )
+    assert batch == serial
+    # The ticker keeps running while the batch is processed without the GIL
+    assert ticks > 10
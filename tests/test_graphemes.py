@@ -0,0 +1,31 @@
+"""Sizes and splits counted in grapheme clusters.
+
+Requires the built module (`make build`), then: `uv run pytest tests/test_graphemes.py`.
+"""
+
+import rag_rust_core
+
+FLAG = "\U0001F1E9\U0001F1EA"
+FAMILY = "\U0001F468\u200d\U0001F469\u200d\U0001F467\u200d\U0001F466"
+DECOMPOSED = "cafe\u0301"
+
+# Keep ZWJ sequences and decomposed accents as written
+RAW = rag_rust_core.CleanOptions(keep_joiners=True, unicode_normalization="none")
+
+
+def test_char_count_counts_clusters():
+    text = f"The {FAMILY} met in the {DECOMPOSED} under a {FLAG}."
+    chunks = rag_rust_core.process_document(text, clean_options=RAW)
+    assert chunks[0]["text"] == text
+    assert chunks[0]["char_count"] == 32
+    assert rag_rust_core.text_stats(text, clean_options=RAW)["char_count"] == 32
+
+
+def test_chunks_never_cut_inside_a_cluster():
+    for cluster in (FLAG, FAMILY, "e\u0301"):
+        text = cluster * 9 + "."
+        chunks = rag_rust_core.chunk_text(text, target_size=4, overlap=0)
+        assert "".join(chunks) == text
+        assert [chunk.count(cluster) for chunk in chunks] == [4, 4, 1]
+        for chunk in chunks:
+            assert not chunk.startswith(("\u200d", "\u0301", "\U0001F1EA"))
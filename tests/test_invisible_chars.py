@@ -0,0 +1,34 @@
+"""Zero-width characters, BOMs, soft hyphens and bidi controls removed by `clean_text`.
+
+Requires the built module (`make build`), then: `uv run pytest tests/test_invisible_chars.py`.
+"""
+
+import rag_rust_core
+
+
+def test_soft_hyphenated_word_rejoins():
+    assert rag_rust_core.clean_text("fi\u00adnan\u00adcial re\u200bsults") == "financial results"
+
+
+def test_bom_prefixed_document():
+    chunks = rag_rust_core.process_document("\ufeffRevenue rose 5%.")
+    assert chunks[0]["text"] == "Revenue rose 5%."
+
+
+def test_options():
+    text = "\u200dab\u200c\u200b"
+    keep = rag_rust_core.CleanOptions(keep_joiners=True)
+    assert rag_rust_core.clean_text(text, options=keep) == "\u200dab\u200c"
+    off = rag_rust_core.CleanOptions(strip_invisible=False)
+    assert rag_rust_core.clean_text(text, options=off) == text
+    assert rag_rust_core.CleanOptions().strip_invisible
+
+
+def test_bidi_controls_removed():
+    text = "\ufeffPay to\u200b: invoice\u202efdp.exe \u2067\u200fok\u2069"
+    assert rag_rust_core.clean_text(text) == "Pay to: invoicefdp.exe ok"
+
+
+def test_combining_marks_kept():
+    text = "\u0928\u092e\u0938\u094d\u0924\u0947"
+    assert rag_rust_core.clean_text("\u200b" + text + "\u202c") == text
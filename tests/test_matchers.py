@@ -0,0 +1,41 @@
+"""Running single metadata categories with `Matchers`.
+
+Requires the built module (`make build`), then: `uv run pytest tests/test_matchers.py`.
+"""
+
+import rag_rust_core
+
+TEXT = (
+    "Apple (NASDAQ:AAPL) reported revenue of $1.2 billion on March 5, 2024, "
+    "up 12.5% from Q3 2023; IBM and THE board noted margins of 40 bps."
+)
+
+
+def test_find_money():
+    matchers = rag_rust_core.Matchers()
+    assert matchers.find_money(TEXT) == ["$1.2 billion"]
+    assert matchers.find_money("No amounts here.") == []
+
+
+def test_find_percentages():
+    matchers = rag_rust_core.Matchers()
+    assert matchers.find_percentages(TEXT) == ["12.5%", "40 bps"]
+
+
+def test_find_dates():
+    matchers = rag_rust_core.Matchers()
+    assert matchers.find_dates(TEXT) == ["March 5, 2024", "Q3 2023"]
+
+
+def test_find_tickers():
+    matchers = rag_rust_core.Matchers()
+    assert matchers.find_tickers(TEXT) == ["AAPL", "IBM"]
+
+
+def test_matches_extract_metadata():
+    matchers = rag_rust_core.Matchers()
+    meta = rag_rust_core.extract_metadata(TEXT)
+    assert matchers.find_money(TEXT) == meta["monetary_amounts"]
+    assert matchers.find_percentages(TEXT) == meta["percentages"]
+    assert matchers.find_dates(TEXT) == meta["dates"]
+    assert matchers.find_tickers(TEXT) == meta["potential_tickers"]
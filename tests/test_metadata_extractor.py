@@ -0,0 +1,47 @@
+"""Custom patterns on `MetadataExtractor` and the `extractor` argument.
+
+Requires the built module (`make build`), then: `uv run pytest tests/test_metadata_extractor.py`.
+"""
+
+import pytest
+
+import rag_rust_core
+
+TEXT = "Contract CT-123456 adds $5 million; CT-654321 renews it. AAPL holds it."
+
+
+def test_custom_pattern_alongside_built_in_keys():
+    extractor = rag_rust_core.MetadataExtractor()
+    extractor.add_pattern("contract_id", r"CT-\d{6}")
+    meta = extractor.extract(TEXT)
+    expected = rag_rust_core.extract_metadata(TEXT)
+    assert {k: v for k, v in meta.items() if k != "contract_id"} == expected
+    assert meta["contract_id"] == ["CT-123456", "CT-654321"]
+    assert extractor.pattern_names == ["contract_id"]
+
+
+def test_add_pattern_errors():
+    extractor = rag_rust_core.MetadataExtractor()
+    with pytest.raises(ValueError, match="invalid pattern"):
+        extractor.add_pattern("contract_id", "CT-(")
+    for key in rag_rust_core.extract_metadata(""):
+        with pytest.raises(ValueError, match="built-in"):
+            extractor.add_pattern(key, "x")
+    assert extractor.pattern_names == []
+
+
+def test_process_document_with_extractor():
+    extractor = rag_rust_core.MetadataExtractor(allowlist={"AAPL"})
+    extractor.add_pattern("contract_id", r"CT-\d{6}")
+    chunks = rag_rust_core.process_document(TEXT, extractor=extractor)
+    assert chunks[0]["metadata"]["contract_id"] == ["CT-123456", "CT-654321"]
+    assert chunks[0]["metadata"]["potential_tickers"] == ["AAPL"]
+    assert rag_rust_core.process_documents([TEXT], extractor=extractor) == [chunks]
+
+
+def test_extractor_excludes_ticker_arguments():
+    extractor = rag_rust_core.MetadataExtractor()
+    with pytest.raises(ValueError, match="extractor"):
+        rag_rust_core.process_document(TEXT, extractor=extractor, allowlist={"AAPL"})
+    with pytest.raises(ValueError, match="extractor"):
+        rag_rust_core.process_documents([TEXT], extractor=extractor, ticker_order="document")
@@ -0,0 +1,34 @@
+"""`chunk_text` merging of undersized chunks via `min_chunk_size`.
+
+Requires the built module (`make build`), then: `uv run pytest tests/test_min_chunk_size.py`.
+"""
+
+import rag_rust_core
+
+TEXT = "Revenue grew strongly this quarter. Margins improved across every region. Thanks."
+
+
+def test_tiny_tail_is_merged():
+    chunks = rag_rust_core.chunk_text(TEXT, target_size=80, overlap_sentences=0)
+    assert chunks[-1] == "Thanks."
+    merged = rag_rust_core.chunk_text(TEXT, target_size=80, overlap_sentences=0, min_chunk_size=20)
+    assert merged == [TEXT]
+    assert all(len(chunk) >= 20 for chunk in merged)
+
+
+def test_merged_tail_does_not_repeat_overlap():
+    chunks = rag_rust_core.chunk_text(TEXT, target_size=80, overlap=40)
+    assert chunks[-1] == "Margins improved across every region. Thanks."
+    assert rag_rust_core.chunk_text(TEXT, target_size=80, overlap=40, min_chunk_size=50) == [TEXT]
+
+
+def test_merge_all_small():
+    text = "Alpha beta gamma delta eps. Hi there. Zeta eta theta iota kappa."
+    kwargs = {"target_size": 30, "overlap_sentences": 0, "min_chunk_size": 10}
+    assert rag_rust_core.chunk_text(text, **kwargs) == rag_rust_core.chunk_text(
+        text, target_size=30, overlap_sentences=0
+    )
+    assert rag_rust_core.chunk_text(text, merge_all_small=True, **kwargs) == [
+        "Alpha beta gamma delta eps. Hi there.",
+        "Zeta eta theta iota kappa.",
+    ]
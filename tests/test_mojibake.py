@@ -0,0 +1,47 @@
+"""Repairing UTF-8 text that was decoded as Windows-1252.
+
+Requires the built module (`make build`), then: `uv run pytest tests/test_mojibake.py`.
+"""
+
+import rag_rust_core
+
+
+def cp1252_char(byte):
+    try:
+        return bytes([byte]).decode("cp1252")
+    except UnicodeDecodeError:
+        # Undefined in Windows-1252; decoders fall back to Latin-1
+        return chr(byte)
+
+
+def garble(text, times=1):
+    for _ in range(times):
+        text = "".join(cp1252_char(byte) for byte in text.encode("utf-8"))
+    return text
+
+
+def test_known_mojibake():
+    assert rag_rust_core.repair_mojibake("â€œHelloâ€\u009d") == "“Hello”"
+    assert rag_rust_core.repair_mojibake("CafÃ© â€“ 5â‚¬") == "Café – 5€"
+
+
+def test_round_trips():
+    for text in (
+        "The CFO’s “update” — revenue up 12%…",
+        "Zürich, Málaga and Kraków: 25°C, ½, ™",
+    ):
+        assert rag_rust_core.repair_mojibake(garble(text)) == text
+        assert rag_rust_core.repair_mojibake(garble(text, times=2)) == text
+
+
+def test_correct_text_is_unchanged():
+    for text in ("Plain text.", "Ça va, à bientôt, Ångström", "Ã alone, â la carte"):
+        assert rag_rust_core.repair_mojibake(text) == text
+
+
+def test_clean_text_option():
+    text = "â€œCafÃ©â€\u009d"
+    fixed = rag_rust_core.clean_text(text, options=rag_rust_core.CleanOptions(fix_mojibake=True))
+    assert fixed == '"Café"'
+    assert rag_rust_core.clean_text(text) != fixed
+    assert "fix_mojibake=False" in repr(rag_rust_core.CleanOptions())
@@ -0,0 +1,50 @@
+"""Requires the built module (`make build`), then: `uv run pytest tests/test_negative_amounts.py`."""
+
+import rag_rust_core
+
+INCOME_STATEMENT = (
+    "Operating income was $4.2 million, up from $(1.3) million a year earlier. "
+    "Other expense, net was ($0.8 million) and FX losses of -$150k offset a "
+    "$2.1M gain on the sale of assets. Gross margin improved 2.5% while "
+    "operating margin was (1.2)% and EPS growth was (4%)."
+)
+
+
+def test_negative_amounts_keep_surface_form():
+    meta = rag_rust_core.extract_metadata(INCOME_STATEMENT)
+    assert meta["monetary_amounts"] == [
+        "$4.2 million",
+        "$(1.3) million",
+        "($0.8 million)",
+        "-$150k",
+        "$2.1M",
+    ]
+    assert meta["monetary_values"] == [4.2e6, -1.3e6, -0.8e6, -150_000.0, 2.1e6]
+
+
+def test_parsed_money_is_negative_with_currency():
+    parsed = rag_rust_core.extract_metadata(INCOME_STATEMENT)["monetary_amounts_parsed"]
+    assert [(p["raw"], p["value"], p["currency"], p["scale"]) for p in parsed[1:4]] == [
+        ("$(1.3) million", -1.3e6, "USD", "million"),
+        ("($0.8 million)", -0.8e6, "USD", "million"),
+        ("-$150k", -150_000.0, "USD", "thousand"),
+    ]
+
+
+def test_negative_percentages():
+    meta = rag_rust_core.extract_metadata(INCOME_STATEMENT)
+    assert meta["percentages"] == ["2.5%", "(1.2)%", "(4%)"]
+    assert meta["percentage_values"] == [2.5, -1.2, -4.0]
+
+
+def test_written_negatives_are_not_sign_inferred():
+    meta = rag_rust_core.extract_metadata("Net loss of $(3) million.", infer_sign=True)
+    [value] = meta["monetary_amounts_normalized"]
+    assert value == {"raw": "$(3) million", "value": -3e6, "sign_inferred": False}
+
+
+def test_normalize_money_negatives():
+    assert rag_rust_core.normalize_money("$(1.2) million") == -1.2e6
+    assert rag_rust_core.normalize_money("($1.2 million)") == -1.2e6
+    assert rag_rust_core.normalize_money("-$5M") == -5e6
+    assert rag_rust_core.normalize_money("$5M") == 5e6
@@ -0,0 +1,47 @@
+"""Line breaks as sentence boundaries with `chunk_text(newline_is_boundary=True)`.
+
+Requires the built module (`make build`), then: `uv run pytest tests/test_newline_boundary.py`.
+"""
+
+import rag_rust_core
+
+SLIDE = "Q3 Highlights\n- Revenue up 12%\n- Margin expansion\n- New markets opened"
+
+
+def test_each_bullet_is_a_sentence():
+    # With no overlap and a target that fits one line, each sentence is a chunk
+    assert rag_rust_core.chunk_text(
+        SLIDE, target_size=20, overlap_sentences=0, newline_is_boundary=True
+    ) == ["Q3 Highlights", "- Revenue up 12%", "- Margin expansion", "- New markets opened"]
+
+
+def test_without_it_lines_fuse_and_split_by_size():
+    chunks = rag_rust_core.chunk_text(SLIDE, target_size=20, overlap_sentences=0)
+    assert chunks != ["Q3 Highlights", "- Revenue up 12%", "- Margin expansion", "- New markets opened"]
+
+
+def test_packed_lines_keep_their_line_breaks():
+    assert rag_rust_core.chunk_text(
+        SLIDE, target_size=40, overlap_sentences=0, newline_is_boundary=True
+    ) == ["Q3 Highlights\n- Revenue up 12%", "- Margin expansion\n- New markets opened"]
+
+
+def test_punctuated_lines_unchanged():
+    text = "Revenue rose. Costs fell.\nMargins held."
+    assert rag_rust_core.chunk_text(
+        text, target_size=20, overlap_sentences=0, newline_is_boundary=True
+    ) == rag_rust_core.chunk_text(text, target_size=20, overlap_sentences=0)
+
+
+def test_chunk_iterator_matches():
+    kwargs = dict(target_size=40, overlap_sentences=1, newline_is_boundary=True)
+    assert list(rag_rust_core.ChunkIterator(SLIDE, **kwargs)) == rag_rust_core.chunk_text(
+        SLIDE, **kwargs
+    )
+
+
+def test_composes_with_preserve_lists():
+    text = "Agenda\nBudget\n- Review the plan. Approve it\n- Hiring"
+    assert rag_rust_core.chunk_text(
+        text, target_size=30, overlap_sentences=0, preserve_lists=True, newline_is_boundary=True
+    ) == ["Agenda\nBudget", "- Review the plan. Approve it", "- Hiring"]
@@ -0,0 +1,47 @@
+"""`on_empty` contracts for documents that clean down to nothing.
+
+Requires the built module (`make build`), then: `uv run pytest tests/test_on_empty.py`.
+"""
+
+import pytest
+
+import rag_rust_core
+
+JUNK = "\x00\x01\x07\x1b\x7f"
+TEXT = "Revenue rose 5% to $10 million."
+
+
+def test_empty_list_is_default():
+    assert rag_rust_core.process_document(JUNK) == []
+    assert rag_rust_core.process_document(JUNK, on_empty="empty_list") == []
+
+
+def test_placeholder_chunk():
+    (chunk,) = rag_rust_core.process_document(JUNK, on_empty="placeholder")
+    assert chunk["text"] == ""
+    assert chunk["char_count"] == 0
+    assert chunk["reason"] == "cleaned_to_empty"
+
+
+def test_error_raises_empty_document():
+    with pytest.raises(rag_rust_core.EmptyDocument):
+        rag_rust_core.process_document(JUNK, on_empty="error")
+    assert issubclass(rag_rust_core.EmptyDocument, ValueError)
+
+
+def test_batch_error_mode_yields_none():
+    results = rag_rust_core.process_documents([TEXT, JUNK], on_empty="error")
+    assert results[0] is not None
+    assert results[1] is None
+
+
+@pytest.mark.parametrize("on_empty", ["empty_list", "placeholder", "error"])
+def test_normal_input_unaffected(on_empty):
+    chunks = rag_rust_core.process_document(TEXT, on_empty=on_empty)
+    assert chunks == rag_rust_core.process_document(TEXT)
+    assert all("reason" not in c for c in chunks)
+
+
+def test_unknown_mode_rejected():
+    with pytest.raises(ValueError):
+        rag_rust_core.process_document(TEXT, on_empty="raise")
@@ -0,0 +1,43 @@
+"""Paragraph separator detection with `CleanOptions(paragraph_sep=...)`.
+
+Requires the built module (`make build`), then: `uv run pytest tests/test_paragraph_sep.py`.
+"""
+
+import pytest
+
+import rag_rust_core
+
+PDF = "First paragraph of the letter.\nSecond paragraph.\nThird paragraph."
+
+
+def test_default_is_double():
+    options = rag_rust_core.CleanOptions()
+    assert options.paragraph_sep == "double"
+    assert rag_rust_core.clean_text(PDF, options=options) == (
+        "First paragraph of the letter. Second paragraph. Third paragraph."
+    )
+
+
+def test_single_keeps_line_paragraphs_separate():
+    options = rag_rust_core.CleanOptions(paragraph_sep="single")
+    assert rag_rust_core.clean_text(PDF, options=options) == (
+        "First paragraph of the letter.\n\nSecond paragraph.\n\nThird paragraph."
+    )
+
+
+def test_blank_line_joins_soft_wraps():
+    text = "Revenue rose 12% in the\nquarter.\n  \n\n\nCosts fell\nsharply."
+    options = rag_rust_core.CleanOptions(paragraph_sep="blank-line")
+    assert rag_rust_core.clean_text(text, options=options) == (
+        "Revenue rose 12% in the quarter.\n\nCosts fell sharply."
+    )
+
+
+def test_setter_and_repr():
+    options = rag_rust_core.CleanOptions()
+    options.paragraph_sep = "blank-line"
+    assert "paragraph_sep='blank-line'" in repr(options)
+    with pytest.raises(rag_rust_core.InvalidOption):
+        options.paragraph_sep = "triple"
+    with pytest.raises(rag_rust_core.InvalidOption):
+        rag_rust_core.CleanOptions(paragraph_sep="lines")
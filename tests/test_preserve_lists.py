@@ -0,0 +1,64 @@
+"""List items kept one per line by cleaning and whole by chunking.
+
+Requires the built module (`make build`), then: `uv run pytest tests/test_preserve_lists.py`.
+"""
+
+import rag_rust_core
+
+REPORT = (
+    "Highlights for the quarter:\n"
+    "- Revenue rose 12%\n"
+    "- Operating costs   fell\n"
+    "• Margins held at 31%\n"
+    "1. Buy back shares\n"
+    "2) Raise the dividend\n"
+    "Overall a strong quarter."
+)
+
+
+def test_bulleted_list_survives_cleaning():
+    options = rag_rust_core.CleanOptions(preserve_lists=True)
+    assert rag_rust_core.clean_text(REPORT, options=options).split("\n") == [
+        "Highlights for the quarter:",
+        "- Revenue rose 12%",
+        "- Operating costs fell",
+        "• Margins held at 31%",
+        "1. Buy back shares",
+        "2) Raise the dividend",
+        "Overall a strong quarter.",
+    ]
+
+
+def test_default_cleaning_unchanged():
+    assert "\n" not in rag_rust_core.clean_text(REPORT)
+    assert not rag_rust_core.CleanOptions().preserve_lists
+    assert "preserve_lists=False" in repr(rag_rust_core.CleanOptions())
+
+
+def test_wrapped_item_is_joined():
+    text = "- Revenue rose because of strong\n  demand in Asia\n- Costs fell"
+    options = rag_rust_core.CleanOptions(preserve_lists=True)
+    assert rag_rust_core.clean_text(text, options=options) == (
+        "- Revenue rose because of strong demand in Asia\n- Costs fell"
+    )
+
+
+def test_items_are_chunk_units():
+    text = "Results were mixed. Highlights:\n- Revenue rose. Demand was strong\n- Costs fell"
+    chunks = rag_rust_core.chunk_text(
+        text, target_size=50, overlap_sentences=0, preserve_lists=True
+    )
+    assert chunks == [
+        "Results were mixed. Highlights:",
+        "- Revenue rose. Demand was strong\n- Costs fell",
+    ]
+
+
+def test_process_document_keeps_items_whole():
+    options = rag_rust_core.CleanOptions(preserve_lists=True)
+    chunks = rag_rust_core.process_document(
+        REPORT, chunk_size=60, chunk_overlap=0, clean_options=options, extract_meta=False
+    )
+    lines = [line for chunk in chunks for line in chunk["text"].split("\n")]
+    assert "- Operating costs fell" in lines
+    assert "1. Buy back shares" in lines
@@ -0,0 +1,41 @@
+"""Line-oriented layouts kept by `CleanOptions(preserve_single_newlines=True)`.
+
+Requires the built module (`make build`), then: `uv run pytest tests/test_preserve_newlines.py`.
+"""
+
+import pytest
+
+import rag_rust_core
+
+ADDRESS = "Acme Corp\n1 Main St\nSpringfield, IL 62704"
+KEEP = rag_rust_core.CleanOptions(preserve_single_newlines=True)
+
+
+def test_three_line_address_survives_cleaning():
+    assert rag_rust_core.clean_text(ADDRESS, options=KEEP) == ADDRESS
+    messy = "  Acme   Corp \n 1 Main St\t\nSpringfield, IL 62704\n\n\n\nNext."
+    assert rag_rust_core.clean_text(messy, options=KEEP) == ADDRESS + "\n\nNext."
+    assert rag_rust_core.clean_text(ADDRESS) == "Acme Corp 1 Main St Springfield, IL 62704"
+
+
+def test_chunks_keep_lines():
+    text = rag_rust_core.clean_text("Ship to:\n" + ADDRESS + "\n\nThanks. Call us.\nBye.", options=KEEP)
+    chunks = rag_rust_core.chunk_text(text, target_size=1000, overlap=0)
+    assert chunks == ["Ship to:\n" + ADDRESS + "\n\nThanks. Call us.\nBye."]
+
+
+def test_keep_single_newlines_is_preserve_single_newlines():
+    keep = rag_rust_core.CleanOptions(keep_single_newlines=True)
+    assert repr(keep) == repr(KEEP)
+    poem = "Roses are red,\nviolets are blue.\n\nSecond   verse\there."
+    assert rag_rust_core.clean_text(poem, clean_options=keep) == (
+        "Roses are red,\nviolets are blue.\n\nSecond verse here."
+    )
+    assert rag_rust_core.clean_text(poem) == "Roses are red, violets are blue.\n\nSecond verse here."
+    off = rag_rust_core.CleanOptions(keep_single_newlines=False)
+    assert rag_rust_core.clean_text(poem, clean_options=off) == rag_rust_core.clean_text(poem)
+
+
+def test_keep_single_newlines_and_its_alias_conflict():
+    with pytest.raises(rag_rust_core.InvalidOption):
+        rag_rust_core.CleanOptions(keep_single_newlines=True, preserve_single_newlines=True)
@@ -0,0 +1,71 @@
+"""JSON string output with `process_document_json`.
+
+Requires the built module (`make build`), then: `uv run pytest tests/test_process_document_json.py`.
+"""
+
+import json
+from pathlib import Path
+
+import pytest
+
+import rag_rust_core
+
+TEXT = (
+    "Acme (NASDAQ: ACME) reported Q3 2024 revenue of $5.2 million, up 12% year-over-year. "
+    "Contract CT-123456 was signed on March 5, 2024 for five million dollars. "
+    "Costs fell 40 bps; FY2024 guidance is $(1.2) million lower. AAPL and MSFT were cited. "
+) * 6
+TRANSCRIPTS = sorted(Path(__file__).parent.parent.glob("data/sample_transcripts/*.txt"))
+
+
+@pytest.mark.parametrize(
+    "kwargs",
+    [
+        {},
+        {"chunk_size": 300, "chunk_overlap": 50},
+        {"chunk_size": 40, "chunk_overlap": 0.25, "size_unit": "words", "infer_sign": True},
+        {"include_spans": True, "ticker_order": "document"},
+        {"extract_meta": False},
+        {"include_document_metadata": True, "chunk_size": 400},
+        {"clean_options": rag_rust_core.CleanOptions(fold_to_ascii=True)},
+    ],
+)
+def test_matches_dict_output(kwargs):
+    output = rag_rust_core.process_document_json(TEXT, **kwargs)
+    assert isinstance(output, str)
+    assert json.loads(output) == rag_rust_core.process_document(TEXT, **kwargs)
+
+
+def test_matches_dict_output_on_transcripts():
+    assert TRANSCRIPTS
+    for path in TRANSCRIPTS:
+        text = path.read_text(encoding="utf-8")
+        assert json.loads(rag_rust_core.process_document_json(text)) == (
+            rag_rust_core.process_document(text)
+        )
+
+
+def test_custom_patterns():
+    extractor = rag_rust_core.MetadataExtractor()
+    extractor.add_pattern("contract_id", r"CT-\d{6}")
+    chunks = json.loads(rag_rust_core.process_document_json(TEXT, extractor=extractor))
+    assert chunks[0]["metadata"]["contract_id"][0] == "CT-123456"
+    assert chunks == rag_rust_core.process_document(TEXT, extractor=extractor)
+
+
+def test_empty_documents():
+    assert rag_rust_core.process_document_json("  ") == "[]"
+    placeholder = json.loads(rag_rust_core.process_document_json("  ", on_empty="placeholder"))
+    assert placeholder == rag_rust_core.process_document("  ", on_empty="placeholder")
+    assert placeholder[0]["reason"] == "cleaned_to_empty"
+    with pytest.raises(rag_rust_core.EmptyDocument):
+        rag_rust_core.process_document_json("  ", on_empty="error")
+
+
+def test_errors_match_process_document():
+    with pytest.raises(rag_rust_core.InvalidChunkParams):
+        rag_rust_core.process_document_json(TEXT, chunk_size=0)
+    with pytest.raises(rag_rust_core.InvalidOption):
+        rag_rust_core.process_document_json(TEXT, size_unit="tokens")
+    with pytest.raises(TypeError):
+        rag_rust_core.process_document_json(TEXT, 1500)
@@ -0,0 +1,86 @@
+"""Reading documents from disk with `process_file` and `process_files`.
+
+Requires the built module (`make build`), then: `uv run pytest tests/test_process_file.py`.
+"""
+
+import pytest
+
+import rag_rust_core
+
+TEXT = "Revenue rose 5% to $10 million in Q3 2024. Margins improved. " * 40
+
+
+def write(path, data):
+    path.write_bytes(data if isinstance(data, bytes) else data.encode("utf-8"))
+    return path
+
+
+def test_matches_process_document(tmp_path):
+    path = write(tmp_path / "report.txt", TEXT)
+    expected = rag_rust_core.process_document(TEXT, chunk_size=500, chunk_overlap=50)
+    assert len(expected) > 1
+    assert rag_rust_core.process_file(path, chunk_size=500, chunk_overlap=50) == expected
+    assert rag_rust_core.process_file(str(path), chunk_size=500, chunk_overlap=50) == expected
+
+
+def test_options_are_forwarded(tmp_path):
+    path = write(tmp_path / "report.txt", TEXT)
+    chunks = rag_rust_core.process_file(path, extract_meta=False, size_unit="words", chunk_size=50)
+    assert chunks == rag_rust_core.process_document(
+        TEXT, extract_meta=False, size_unit="words", chunk_size=50
+    )
+    assert "metadata" not in chunks[0]
+
+
+def test_missing_file_raises_file_not_found(tmp_path):
+    path = tmp_path / "missing.txt"
+    with pytest.raises(FileNotFoundError) as info:
+        rag_rust_core.process_file(path)
+    assert info.value.filename == str(path)
+
+
+def test_directory_raises_os_error(tmp_path):
+    with pytest.raises(OSError):
+        rag_rust_core.process_file(tmp_path)
+
+
+def test_encodings(tmp_path):
+    path = write(tmp_path / "legacy.txt", b"Caf\xe9 revenue rose 5%.")
+    with pytest.raises(UnicodeDecodeError) as info:
+        rag_rust_core.process_file(path)
+    assert (info.value.start, info.value.end) == (3, 4)
+
+    def text(encoding):
+        return rag_rust_core.process_file(path, encoding=encoding)[0]["text"]
+
+    assert text("utf-8-lossy") == "Caf� revenue rose 5%."
+    assert text("latin-1") == "Café revenue rose 5%."
+    assert text("utf-8-or-latin-1") == "Café revenue rose 5%."
+
+    with pytest.raises(rag_rust_core.InvalidOption):
+        rag_rust_core.process_file(path, encoding="cp1252")
+
+
+def test_utf8_byte_order_mark_is_dropped(tmp_path):
+    path = write(tmp_path / "bom.txt", b"\xef\xbb\xbfRevenue rose 5%.")
+    assert rag_rust_core.process_file(path)[0]["text"] == "Revenue rose 5%."
+
+
+def test_process_files_in_order_with_failures(tmp_path):
+    paths = [write(tmp_path / f"doc{i}.txt", f"Document {i}. " + TEXT) for i in range(8)]
+    bad = write(tmp_path / "bad.txt", b"\xff\xfe")
+    batch = rag_rust_core.process_files(
+        [*paths, tmp_path / "missing.txt", bad, 42], chunk_size=500, num_threads=2
+    )
+    assert batch[:8] == [rag_rust_core.process_file(p, chunk_size=500) for p in paths]
+    assert batch[8:] == [None, None, None]
+
+    lossy = rag_rust_core.process_files([bad], encoding="utf-8-lossy")
+    assert lossy[0][0]["text"] == "��"
+
+
+def test_keyword_only():
+    with pytest.raises(TypeError):
+        rag_rust_core.process_file("report.txt", 500)
+    with pytest.raises(TypeError):
+        rag_rust_core.process_files(["report.txt"], 500)
@@ -0,0 +1,104 @@
+"""Streaming JSONL corpora through `process_jsonl`.
+
+Requires the built module (`make build`), then: `uv run pytest tests/test_process_jsonl.py`.
+"""
+
+import json
+import threading
+
+import pytest
+
+import rag_rust_core
+
+DOCS = [
+    {"id": "acme-q3", "text": "Revenue rose 12% to $5 million in Q3 2024. " * 20},
+    {"id": 42, "text": "Margins held steady. NASDAQ: ACME closed higher."},
+    {"id": None, "text": "   "},
+]
+
+
+def write_jsonl(path, lines):
+    path.write_text("\n".join(lines) + "\n", encoding="utf-8")
+    return path
+
+
+def read_jsonl(path):
+    return [json.loads(line) for line in path.read_text(encoding="utf-8").splitlines()]
+
+
+def test_chunks_match_process_document(tmp_path):
+    source = write_jsonl(tmp_path / "in.jsonl", [json.dumps(d) for d in DOCS])
+    output = tmp_path / "out.jsonl"
+    summary = rag_rust_core.process_jsonl(source, output, chunk_size=300, chunk_overlap=50)
+
+    expected = []
+    for doc in DOCS:
+        for chunk in rag_rust_core.process_document(doc["text"], chunk_size=300, chunk_overlap=50):
+            expected.append({**chunk, "id": doc["id"]})
+    assert read_jsonl(output) == expected
+    assert summary == {"records": 3, "chunks": len(expected), "skipped": 0, "skipped_lines": []}
+
+
+def test_malformed_lines_are_skipped(tmp_path):
+    lines = [
+        json.dumps({"id": 1, "text": "Costs fell."}),
+        "{not json",
+        "",
+        json.dumps({"id": 2, "body": "wrong field"}),
+        json.dumps({"id": 3, "text": ["not", "a", "string"]}),
+        json.dumps(["a list"]),
+        json.dumps({"text": "No id here."}),
+    ]
+    output = tmp_path / "out.jsonl"
+    summary = rag_rust_core.process_jsonl(write_jsonl(tmp_path / "in.jsonl", lines), output)
+    assert summary == {"records": 2, "chunks": 2, "skipped": 4, "skipped_lines": [2, 4, 5, 6]}
+    assert [(c["id"], c["text"]) for c in read_jsonl(output)] == [
+        (1, "Costs fell."),
+        (None, "No id here."),
+    ]
+
+
+def test_custom_fields_and_options(tmp_path):
+    source = write_jsonl(
+        tmp_path / "in.jsonl", [json.dumps({"doc_id": "x", "content": "Revenue rose 5%."})]
+    )
+    output = tmp_path / "out.jsonl"
+    rag_rust_core.process_jsonl(
+        str(source), str(output), text_field="content", id_field="doc_id", extract_meta=False
+    )
+    assert read_jsonl(output) == [
+        {**rag_rust_core.process_document("Revenue rose 5%.", extract_meta=False)[0], "id": "x"}
+    ]
+
+
+def test_concurrent_files_from_threads(tmp_path):
+    jobs = []
+    for i in range(4):
+        docs = [json.dumps({"id": f"{i}-{n}", "text": DOCS[0]["text"]}) for n in range(30)]
+        jobs.append((write_jsonl(tmp_path / f"in{i}.jsonl", docs), tmp_path / f"out{i}.jsonl"))
+    results = {}
+
+    def run(source, output):
+        results[output] = rag_rust_core.process_jsonl(source, output, chunk_size=200)
+
+    threads = [threading.Thread(target=run, args=job) for job in jobs]
+    for thread in threads:
+        thread.start()
+    for thread in threads:
+        thread.join()
+    per_doc = len(rag_rust_core.process_document(DOCS[0]["text"], chunk_size=200))
+    for _, output in jobs:
+        assert results[output]["chunks"] == 30 * per_doc
+        assert len(read_jsonl(output)) == 30 * per_doc
+
+
+def test_missing_input_raises_file_not_found(tmp_path):
+    with pytest.raises(FileNotFoundError):
+        rag_rust_core.process_jsonl(tmp_path / "missing.jsonl", tmp_path / "out.jsonl")
+    assert not (tmp_path / "out.jsonl").exists()
+
+
+def test_invalid_chunk_size(tmp_path):
+    source = write_jsonl(tmp_path / "in.jsonl", [])
+    with pytest.raises(rag_rust_core.InvalidChunkParams):
+        rag_rust_core.process_jsonl(source, tmp_path / "out.jsonl", chunk_size=0)
@@ -0,0 +1,36 @@
+"""`profile_run` per-stage timings.
+
+Requires the built module (`make build`), then: `uv run pytest tests/test_profile_run.py`.
+"""
+
+from pathlib import Path
+
+import pytest
+
+import rag_rust_core
+
+FIXTURE = Path(__file__).parent.parent / "benches" / "fixtures" / "news_brief.txt"
+STAGES = {"clean", "split_sentences", "chunk", "extract_metadata", "total"}
+
+
+def test_profile_run_from_path():
+    report = rag_rust_core.profile_run(str(FIXTURE), iterations=3)
+    assert report["iterations"] == 3
+    assert report["input_chars"] == len(FIXTURE.read_text(encoding="utf-8"))
+    assert set(report["stages"]) == STAGES
+    for timings in report["stages"].values():
+        assert 0 <= timings["min_s"] <= timings["median_s"]
+        assert timings["mean_s"] >= 0
+
+
+def test_profile_run_from_text():
+    text = FIXTURE.read_text(encoding="utf-8")
+    report = rag_rust_core.profile_run(text, iterations=2, chunk_size=200, chunk_overlap=50)
+    assert report["input_chars"] == len(text)
+    assert report["chunk_count"] > 1
+
+
+@pytest.mark.parametrize("kwargs", [{"iterations": 0}, {"chunk_size": 0}])
+def test_profile_run_rejects_zero(kwargs):
+    with pytest.raises(ValueError):
+        rag_rust_core.profile_run("Some text.", **kwargs)
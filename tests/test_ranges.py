@@ -0,0 +1,74 @@
+"""Requires the built module (`make build`), then: `uv run pytest tests/test_ranges.py`."""
+
+import pytest
+
+import rag_rust_core
+
+
+def ranges(text):
+    return rag_rust_core.extract_metadata(text)["ranges"]
+
+
+@pytest.mark.parametrize(
+    "text, raw, low, high",
+    [
+        ("Revenue of $5.0 to $5.2 billion.", "$5.0 to $5.2 billion", 5.0e9, 5.2e9),
+        ("Capex of $5-7 million.", "$5-7 million", 5e6, 7e6),
+        ("Capex of $5–7 million.", "$5–7 million", 5e6, 7e6),
+        ("Costs between $300 million and $350 million.", "between $300 million and $350 million", 300e6, 350e6),
+    ],
+)
+def test_money_range_connectors(text, raw, low, high):
+    assert ranges(text) == [
+        {"raw": raw, "kind": "money", "low": low, "high": high, "currency": "USD", "unit": None}
+    ]
+
+
+@pytest.mark.parametrize(
+    "text, raw, low, high, unit",
+    [
+        ("Growth of 3 to 4%.", "3 to 4%", 3.0, 4.0, "percent"),
+        ("Growth of 3-4%.", "3-4%", 3.0, 4.0, "percent"),
+        ("Spreads 40–60 bps wider.", "40–60 bps", 0.4, 0.6, "basis_points"),
+        ("Margins between 3% and 4%.", "between 3% and 4%", 3.0, 4.0, "percent"),
+    ],
+)
+def test_percentage_range_connectors(text, raw, low, high, unit):
+    assert ranges(text) == [
+        {"raw": raw, "kind": "percentage", "low": low, "high": high, "currency": None, "unit": unit}
+    ]
+
+
+def test_en_dash_range_after_cleaning():
+    cleaned = rag_rust_core.clean_text("Capex of $5–7 million.")
+    assert [(r["low"], r["high"]) for r in ranges(cleaned)] == [(5e6, 7e6)]
+
+
+def test_unrelated_amounts_are_not_ranges():
+    assert ranges("Revenue was $5 and costs $3 million; fees were 3% to 40 bps.") == []
+
+
+@pytest.mark.parametrize(
+    "text, raw, low, high",
+    [
+        ("The 2020–2024 strategic plan.", "2020–2024", 2020.0, 2024.0),
+        ("Targets for 2019-23 were met.", "2019-23", 2019.0, 2023.0),
+        ("Results from 2021 to 2022.", "2021 to 2022", 2021.0, 2022.0),
+    ],
+)
+def test_year_ranges(text, raw, low, high):
+    assert ranges(text) == [
+        {"raw": raw, "kind": "year", "low": low, "high": high, "currency": None, "unit": None}
+    ]
+
+
+def test_number_ranges_next_to_dollar_range():
+    found = ranges("Shipments of 10 to 12 million units and capex of $5–10 million.")
+    assert [(r["raw"], r["kind"], r["low"], r["high"]) for r in found] == [
+        ("10 to 12 million", "number", 10e6, 12e6),
+        ("$5–10 million", "money", 5e6, 10e6),
+    ]
+
+
+def test_dates_and_phone_numbers_are_not_ranges():
+    assert ranges("Filed 2010-12-31; call 212-555-0123. The score was 5-3.") == []
@@ -0,0 +1,46 @@
+"""Requires the built module (`make build`), then: `uv run pytest tests/test_redact_pii.py`."""
+
+import pytest
+
+import rag_rust_core
+
+TEXT = "Email ir@acme.com or call (212) 555-0123. SSN 123-45-6789 is on file."
+
+
+def test_defaults_mask_emails_and_phones_only():
+    assert rag_rust_core.redact_pii(TEXT) == (
+        "Email [REDACTED] or call [REDACTED]. SSN 123-45-6789 is on file."
+    )
+
+
+def test_each_category_toggles_independently():
+    assert rag_rust_core.redact_pii(TEXT, phones=False) == (
+        "Email [REDACTED] or call (212) 555-0123. SSN 123-45-6789 is on file."
+    )
+    assert rag_rust_core.redact_pii(TEXT, emails=False) == (
+        "Email ir@acme.com or call [REDACTED]. SSN 123-45-6789 is on file."
+    )
+    assert rag_rust_core.redact_pii(TEXT, emails=False, phones=False, ssns=True) == (
+        "Email ir@acme.com or call (212) 555-0123. SSN [REDACTED] is on file."
+    )
+    assert rag_rust_core.redact_pii(TEXT, emails=False, phones=False) == TEXT
+
+
+def test_single_character_mask_keeps_offsets():
+    redacted = rag_rust_core.redact_pii(TEXT, "*", ssns=True)
+    assert len(redacted) == len(TEXT)
+    start = TEXT.index("ir@acme.com")
+    assert redacted[start : start + len("ir@acme.com")] == "*" * len("ir@acme.com")
+
+
+def test_chunking_sees_the_same_sentences():
+    redacted = rag_rust_core.redact_pii(TEXT, ssns=True)
+    chunks = rag_rust_core.chunk_text(redacted, target_size=40, overlap=0)
+    assert [c.endswith(".") for c in chunks] == [True] * len(chunks)
+    assert len(chunks) == len(rag_rust_core.chunk_text(TEXT, target_size=40, overlap=0))
+
+
+def test_mask_breaking_sentences_rejected():
+    for mask in ["", "[PII REMOVED]", "N/A."]:
+        with pytest.raises(rag_rust_core.InvalidOption):
+            rag_rust_core.redact_pii(TEXT, mask)
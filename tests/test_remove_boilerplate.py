@@ -0,0 +1,89 @@
+"""Header, footer and page number removal with `remove_boilerplate`.
+
+Requires the built module (`make build`), then: `uv run pytest tests/test_remove_boilerplate.py`.
+"""
+
+import pytest
+
+import rag_rust_core
+
+SEGMENTS = ["cloud", "devices", "services", "licensing", "consumer"]
+
+
+def five_pages():
+    return "".join(
+        f"ACME Corp Annual Report 2024\n"
+        f"The {segment} segment grew revenue to ${page}0 million.\n"
+        f"Margins in {segment} improved by {page}%.\n"
+        f"Outlook for {segment} remains stable.\n"
+        f"\n"
+        f"Confidential — Page {page}\n"
+        f"{page}\n"
+        for page, segment in enumerate(SEGMENTS, start=1)
+    )
+
+
+def test_repeated_footer_stripped_body_kept():
+    cleaned = rag_rust_core.remove_boilerplate(five_pages(), min_lines=10)
+    assert "Confidential" not in cleaned
+    assert "ACME Corp Annual Report" not in cleaned
+    assert not any(line.strip().isdigit() for line in cleaned.splitlines())
+    for page, segment in enumerate(SEGMENTS, start=1):
+        assert f"The {segment} segment grew revenue to ${page}0 million.\n" in cleaned
+        assert f"Margins in {segment} improved by {page}%.\n" in cleaned
+
+
+def test_thresholds():
+    text = five_pages()
+    # 30 non-blank lines: enough for the default min_lines of 20
+    assert "Confidential" not in rag_rust_core.remove_boilerplate(text)
+    assert rag_rust_core.remove_boilerplate(text, min_lines=31) == text
+    kept = rag_rust_core.remove_boilerplate(text, min_occurrences=6)
+    assert kept.count("Confidential") == 5
+
+
+def test_cleaned_text_chunks_without_noise():
+    cleaned = rag_rust_core.clean_text(rag_rust_core.remove_boilerplate(five_pages()))
+    assert all("Page" not in chunk for chunk in rag_rust_core.chunk_text(cleaned, target_size=200))
+
+
+def test_invalid_min_occurrences():
+    with pytest.raises(rag_rust_core.InvalidOption):
+        rag_rust_core.remove_boilerplate(five_pages(), min_occurrences=1)
+    with pytest.raises(TypeError):
+        rag_rust_core.remove_boilerplate(five_pages(), 3)
+
+
+def ten_page_deck():
+    """Ten pages, each with a header, a title line and a "Page N of 10" footer."""
+    return "".join(
+        f"Acme Corp. Confidential\n"
+        f"Q3 2024 Earnings Presentation\n"
+        f"Slide {page}: revenue in region {page} rose {page + 2}% year over year.\n"
+        f"Operating costs in region {page} were ${page}.{page} million.\n"
+        f"\n"
+        f"Page {page} of 10\n"
+        for page in range(1, 11)
+    )
+
+
+def test_ten_page_headers_and_footers_removed():
+    text = ten_page_deck()
+    cleaned = rag_rust_core.remove_boilerplate(text)
+    assert "Acme Corp. Confidential" not in cleaned
+    assert "Q3 2024 Earnings Presentation" not in cleaned
+    assert "of 10" not in cleaned
+    body = [line for line in text.splitlines() if line.startswith(("Slide", "Operating"))]
+    assert cleaned.splitlines() == [
+        line for pair in zip(body[::2], body[1::2], [""] * 10) for line in pair
+    ]
+
+
+def test_long_repeated_lines_kept():
+    notice = (
+        "All forward-looking statements are subject to the risks described "
+        "in our most recent annual report on Form 10-K."
+    )
+    text = ten_page_deck().replace("Page ", f"{notice}\nPage ")
+    assert rag_rust_core.remove_boilerplate(text).count(notice) == 10
+    assert notice not in rag_rust_core.remove_boilerplate(text, max_line_length=200)
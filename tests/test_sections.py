@@ -0,0 +1,62 @@
+"""Requires the built module (`make build`), then: `uv run pytest tests/test_sections.py`."""
+
+import json
+
+import rag_rust_core
+
+FILING = (
+    "Cover page of the annual report.\n\n"
+    "PART I\n\n"
+    "ITEM 1A. RISK FACTORS\n"
+    "Our business is subject to many risks. See Item 7. Management's Discussion "
+    "and Analysis for more.\n\n"
+    "Item 7. Management's Discussion and Analysis of\n"
+    "Financial Condition and Results of Operations\n"
+    "Revenue rose 12% to $5 million.\n\n"
+    "Controls and Procedures\n\n"
+    "We maintain disclosure controls."
+)
+
+
+def test_detect_sections():
+    sections = rag_rust_core.detect_sections(FILING)
+    assert [title for title, _ in sections] == [
+        "Part I",
+        "Item 1A. Risk Factors",
+        "Item 7. Management's Discussion and Analysis of Financial Condition "
+        "and Results of Operations",
+        "Controls and Procedures",
+    ]
+    for title, start in sections:
+        assert FILING[start:].lower().startswith(title.split(".")[0].lower())
+
+
+def test_references_are_not_headings():
+    text = "As described in Item 1A. Risk Factors, our risk factors are many."
+    assert rag_rust_core.detect_sections(text) == []
+
+
+def test_detect_sections_char_offsets():
+    text = "Résumé — intro.\nItem 2. Properties\nWe own offices."
+    assert rag_rust_core.detect_sections(text) == [("Item 2. Properties", 16)]
+
+
+def test_process_document_sections():
+    chunks = rag_rust_core.process_document(
+        FILING, chunk_size=80, chunk_overlap=0, include_sections=True
+    )
+    assert chunks[0]["section"] is None
+    assert chunks[-1]["section"] == "Controls and Procedures"
+    [revenue] = [c for c in chunks if "Revenue rose" in c["text"]]
+    assert revenue["section"].startswith("Item 7. Management's Discussion")
+
+
+def test_sections_off_by_default():
+    chunks = rag_rust_core.process_document(FILING, chunk_size=80, chunk_overlap=0)
+    assert all("section" not in c for c in chunks)
+
+
+def test_process_document_json_sections():
+    kwargs = dict(chunk_size=80, chunk_overlap=0, include_sections=True)
+    as_json = json.loads(rag_rust_core.process_document_json(FILING, **kwargs))
+    assert as_json == rag_rust_core.process_document(FILING, **kwargs)
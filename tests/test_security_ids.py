@@ -0,0 +1,37 @@
+"""Requires the built module (`make build`), then: `uv run pytest tests/test_security_ids.py`."""
+
+import rag_rust_core
+
+TEXT = (
+    "Apple Inc. (ISIN US0378331005, CUSIP 037833100) and BAE Systems "
+    "(SEDOL 0263494, ISIN GB0002634946). Typos: US0378331006, 037833101, B0YBKJ8."
+)
+
+
+def test_valid_identifiers():
+    metadata = rag_rust_core.extract_metadata(TEXT)
+    assert metadata["isins"] == ["US0378331005", "GB0002634946"]
+    assert metadata["cusips"] == ["037833100"]
+    assert metadata["sedols"] == ["0263494"]
+
+
+def test_invalid_identifiers_dropped_by_default():
+    metadata = rag_rust_core.extract_metadata(TEXT)
+    assert "security_ids_unvalidated" not in metadata
+    assert "US0378331006" not in metadata["isins"]
+    assert "037833101" not in metadata["cusips"]
+
+
+def test_include_unvalidated():
+    metadata = rag_rust_core.extract_metadata(TEXT, include_unvalidated=True)
+    assert metadata["security_ids_unvalidated"] == [
+        {"raw": "US0378331006", "kind": "isin"},
+        {"raw": "037833101", "kind": "cusip"},
+        {"raw": "B0YBKJ8", "kind": "sedol"},
+    ]
+
+
+def test_unlabeled_digits_are_not_sedols():
+    metadata = rag_rust_core.extract_metadata("Order 0263494 shipped.", include_unvalidated=True)
+    assert metadata["sedols"] == []
+    assert metadata["security_ids_unvalidated"] == []
@@ -0,0 +1,39 @@
+"""Requires the built module (`make build`), then: `uv run pytest tests/test_shares.py`."""
+
+import rag_rust_core
+
+RELEASE = (
+    "Diluted EPS was $1.23 and basic earnings per share of $1.25 on revenue of "
+    "$4.2 billion. The company repurchased 3,400,000 shares for $150 million and "
+    "had 2.1 million shares outstanding. It declared a dividend of $0.45 per share."
+)
+
+
+def test_per_share_amounts():
+    assert rag_rust_core.extract_metadata(RELEASE)["per_share_amounts"] == [
+        {"raw": "$1.23", "value": 1.23, "currency": "USD", "basis": "diluted"},
+        {"raw": "$1.25", "value": 1.25, "currency": "USD", "basis": "basic"},
+        {"raw": "$0.45", "value": 0.45, "currency": "USD", "basis": None},
+    ]
+
+
+def test_share_counts():
+    assert rag_rust_core.extract_metadata(RELEASE)["share_counts"] == [
+        {"raw": "3,400,000 shares", "value": 3_400_000.0, "basis": None},
+        {"raw": "2.1 million shares", "value": 2_100_000.0, "basis": None},
+    ]
+
+
+def test_aggregate_amounts_are_not_per_share():
+    meta = rag_rust_core.extract_metadata(RELEASE)
+    per_share = {a["raw"] for a in meta["per_share_amounts"]}
+    assert "$4.2 billion" not in per_share and "$150 million" not in per_share
+    # Every amount, per share or not, stays in monetary_amounts
+    assert meta["monetary_amounts"] == ["$1.23", "$1.25", "$4.2 billion", "$150 million", "$0.45"]
+
+
+def test_diluted_share_count():
+    text = "Weighted average diluted shares were 105.3 million."
+    [count] = rag_rust_core.extract_metadata(text)["share_counts"]
+    assert count["value"] == 105_300_000.0
+    assert count["basis"] == "diluted"
@@ -0,0 +1,53 @@
+"""Keyword-only enforcement for the Python-facing API.
+
+Requires the built module (`make build`), then: `uv run pytest tests/test_signatures.py`.
+"""
+
+import pytest
+
+import rag_rust_core
+
+TEXT = "Revenue rose 5% to $10 million. Margins improved in Q3 2024."
+
+
+@pytest.mark.parametrize(
+    "call",
+    [
+        lambda: rag_rust_core.chunk_text(TEXT, 100),
+        lambda: rag_rust_core.chunk_text(TEXT, 100, 10),
+        lambda: rag_rust_core.chunk_markdown(TEXT, 100),
+        lambda: rag_rust_core.extract_metadata(TEXT, True),
+        lambda: rag_rust_core.process_document(TEXT, 100, 10),
+        lambda: rag_rust_core.process_documents([TEXT], 100, 10),
+        lambda: rag_rust_core.clean_text(TEXT, True),
+    ],
+)
+def test_options_are_keyword_only(call):
+    with pytest.raises(TypeError):
+        call()
+
+
+def test_documented_call_patterns_still_work():
+    assert rag_rust_core.clean_text("a  b") == "a b"
+    assert rag_rust_core.chunk_text(TEXT) == rag_rust_core.chunk_text(
+        TEXT, target_size=1500, overlap=200
+    )
+    assert "percentages" in rag_rust_core.extract_metadata(TEXT)
+    assert rag_rust_core.extract_metadata(TEXT, infer_sign=True)["percentages"] == ["5%"]
+    chunks = rag_rust_core.process_document(TEXT, chunk_size=1500, chunk_overlap=200)
+    assert chunks[0]["text"] == TEXT
+    assert rag_rust_core.process_documents([TEXT], chunk_size=1500) == [chunks]
+
+
+def test_keyword_arguments_are_not_swapped():
+    chunks = rag_rust_core.chunk_text(TEXT, overlap=0, target_size=40)
+    assert len(chunks) == 2
+
+
+def test_clean_text_takes_clean_options_like_process_document():
+    options = rag_rust_core.CleanOptions(standardize_quotes=False)
+    text = "“quoted”"
+    assert rag_rust_core.clean_text(text, clean_options=options) == text
+    assert rag_rust_core.clean_text(text, options=options) == text
+    with pytest.raises(rag_rust_core.InvalidOption):
+        rag_rust_core.clean_text(text, clean_options=options, options=options)
@@ -0,0 +1,32 @@
+"""Entity spans with kinds, for extract_metadata_spans and process_document.
+
+Requires the built module (`make build`), then: `uv run pytest tests/test_spans.py`.
+"""
+
+import rag_rust_core
+
+
+def test_spans_have_kinds_and_slice_to_text():
+    text = "Café ACME earned €5 million (up 3%) in FY 2024."
+    result = rag_rust_core.extract_metadata_spans(text)
+    kinds = [(s["kind"], s["text"]) for s in result["spans"]]
+    assert kinds == [
+        ("ticker", "ACME"),
+        ("money", "€5 million"),
+        ("percentage", "3%"),
+        ("ticker", "FY"),
+        ("date", "FY 2024"),
+    ]
+    for span in result["spans"]:
+        assert text[span["start"] : span["end"]] == span["text"]
+    assert result["dates"] == [s for s in result["spans"] if s["kind"] == "date"]
+
+
+def test_process_document_spans_refer_to_chunk_text():
+    text = "Sales rose 5% in Q1 2024. ACME paid $3 million to MSFT."
+    chunks = rag_rust_core.process_document(text, chunk_size=40, chunk_overlap=0, include_spans=True)
+    assert len(chunks) == 2
+    for chunk in chunks:
+        for span in chunk["spans"]:
+            assert chunk["text"][span["start"] : span["end"]] == span["text"]
+    assert "spans" not in rag_rust_core.process_document(text)[0]
@@ -0,0 +1,38 @@
+"""Spelled-out monetary amounts under `monetary_amounts_spelled`.
+
+Requires the built module (`make build`), then: `uv run pytest tests/test_spelled_money.py`.
+"""
+
+import rag_rust_core
+
+
+def test_five_million_dollars():
+    metadata = rag_rust_core.extract_metadata("The deal was worth approximately five million dollars.")
+    assert metadata["monetary_amounts_spelled"] == [
+        {"raw": "five million dollars", "value": 5000000.0, "currency": "USD", "scale": "million"}
+    ]
+    assert metadata["monetary_amounts"] == []
+
+
+def test_kept_apart_from_symbol_amounts():
+    metadata = rag_rust_core.extract_metadata(
+        "Revenue hit $2 billion, versus two billion euros a year ago and two billion in costs."
+    )
+    assert metadata["monetary_amounts"] == ["$2 billion"]
+    assert [m["raw"] for m in metadata["monetary_amounts_spelled"]] == ["two billion euros"]
+    assert metadata["monetary_amounts_spelled"][0]["value"] == 2e9
+
+
+def test_compound_and_malformed():
+    spelled = rag_rust_core.extract_metadata(
+        "Twenty-Five Thousand Pounds Sterling in fees and five five dollars in typos."
+    )["monetary_amounts_spelled"]
+    assert [(m["raw"], m["value"], m["currency"]) for m in spelled] == [
+        ("Twenty-Five Thousand Pounds Sterling", 25000.0, "GBP"),
+        ("five five dollars", None, "USD"),
+    ]
+
+
+def test_in_process_document():
+    chunks = rag_rust_core.process_document("They paid one hundred million dollars.")
+    assert chunks[0]["metadata"]["monetary_amounts_spelled"][0]["value"] == 1e8
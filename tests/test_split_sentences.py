@@ -0,0 +1,36 @@
+"""Requires the built module (`make build`), then: `uv run pytest tests/test_split_sentences.py`."""
+
+import rag_rust_core
+
+
+def test_split_sentences_preserve_punct():
+    assert rag_rust_core.split_sentences("Hello! How are you? I am fine.") == [
+        "Hello!",
+        "How are you?",
+        "I am fine.",
+    ]
+
+
+def test_decimals_initials_and_abbreviations_do_not_split():
+    text = "The U.S. Treasury sold approx. 4 million bonds at $4.5 billion. J. Smith agreed."
+    assert rag_rust_core.split_sentences(text) == [
+        "The U.S. Treasury sold approx. 4 million bonds at $4.5 billion.",
+        "J. Smith agreed.",
+    ]
+
+
+def test_cjk_sentences():
+    assert rag_rust_core.split_sentences("売上高は増加した。利益も増えた。") == [
+        "売上高は増加した。",
+        "利益も増えた。",
+    ]
+
+
+def test_matches_chunk_boundaries():
+    text = "First sentence here. Second one follows. Third closes it."
+    chunks = rag_rust_core.chunk_text(text, target_size=25, overlap=0)
+    assert chunks == rag_rust_core.split_sentences(text)
+
+
+def test_empty_text():
+    assert rag_rust_core.split_sentences("") == []
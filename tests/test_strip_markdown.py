@@ -0,0 +1,51 @@
+"""Markdown syntax removal with `CleanOptions(strip_markdown=True)`.
+
+Requires the built module (`make build`), then: `uv run pytest tests/test_strip_markdown.py`.
+"""
+
+import rag_rust_core
+
+DOC = """# Quarterly Update
+
+Revenue grew **12%** to _$1.2 billion_, see [the report](https://example.com/q3).
+![Revenue chart](chart.png)
+
+## Highlights
+- Margins improved.
+* New `fast_path` API shipped.
+1. Guidance raised.
+"""
+
+
+def test_exact_plain_text():
+    lines = rag_rust_core.CleanOptions(strip_markdown=True, preserve_single_newlines=True)
+    assert rag_rust_core.clean_text(DOC, options=lines) == (
+        "Quarterly Update\n\n"
+        "Revenue grew 12% to $1.2 billion, see the report.\n"
+        "Revenue chart\n\n"
+        "Highlights\n\n"
+        "- Margins improved.\n"
+        "- New fast_path API shipped.\n"
+        "- Guidance raised."
+    )
+    flowing = rag_rust_core.CleanOptions(strip_markdown=True)
+    assert rag_rust_core.clean_text(DOC, options=flowing) == (
+        "Quarterly Update\n\n"
+        "Revenue grew 12% to $1.2 billion, see the report. Revenue chart\n\n"
+        "Highlights\n\n"
+        "- Margins improved. - New fast_path API shipped. - Guidance raised."
+    )
+
+
+def test_literal_asterisks_and_code_blocks_kept():
+    options = rag_rust_core.CleanOptions(strip_markdown=True, preserve_code_blocks=True)
+    text = "Computed as 5 * 3 * 2, not a*b*c.\n\n```py\nf(**kwargs)\n```"
+    assert rag_rust_core.clean_text(text, options=options) == text
+    assert not rag_rust_core.CleanOptions().strip_markdown
+
+
+def test_flows_into_chunk_text():
+    text = rag_rust_core.clean_text(DOC, options=rag_rust_core.CleanOptions(strip_markdown=True))
+    chunks = rag_rust_core.chunk_text(text, target_size=60, overlap=0)
+    assert all("**" not in c and "](" not in c and "#" not in c for c in chunks)
+    assert chunks[0].startswith("Quarterly Update")
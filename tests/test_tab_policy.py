@@ -0,0 +1,42 @@
+"""Tab handling when collapsing whitespace, with `CleanOptions(tab_policy=...)`.
+
+Requires the built module (`make build`), then: `uv run pytest tests/test_tab_policy.py`.
+"""
+
+import pytest
+
+import rag_rust_core
+
+TABLE = "Segment\tQ1\tQ2\n\tRetail  \t 10\t12"
+
+
+def clean(tab_policy):
+    options = rag_rust_core.CleanOptions(tab_policy=tab_policy, preserve_single_newlines=True)
+    return rag_rust_core.clean_text(TABLE, options=options)
+
+
+def test_default_collapses_tabs():
+    assert rag_rust_core.CleanOptions().tab_policy == "collapse"
+    assert clean("collapse") == "Segment Q1 Q2\nRetail 10 12"
+
+
+def test_preserve_keeps_tabs():
+    assert clean("preserve") == "Segment\tQ1\tQ2\n\tRetail \t 10\t12"
+
+
+def test_to_spaces_expands_tabs():
+    assert clean("to_spaces(4)") == "Segment    Q1    Q2\n    Retail      10    12"
+    assert clean("to_spaces(2)") == "Segment  Q1  Q2\n  Retail    10  12"
+
+
+def test_setter_and_repr():
+    options = rag_rust_core.CleanOptions()
+    options.tab_policy = "to_spaces(8)"
+    assert options.tab_policy == "to_spaces(8)"
+    assert "tab_policy='to_spaces(8)'" in repr(options)
+
+
+@pytest.mark.parametrize("tab_policy", ["keep", "to_spaces(0)", "to_spaces(x)"])
+def test_invalid_policy(tab_policy):
+    with pytest.raises(rag_rust_core.InvalidOption):
+        rag_rust_core.CleanOptions(tab_policy=tab_policy)
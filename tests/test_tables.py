@@ -0,0 +1,78 @@
+"""Requires the built module (`make build`), then: `uv run pytest tests/test_tables.py`."""
+
+import rag_rust_core
+
+TABLE = (
+    "(in millions)             2024        2023\n"
+    "Cash and equivalents   $ 1,234     $ 1,100\n"
+    "Current assets:\n"
+    "Receivables                512         480\n"
+    "Total assets           $ 4,310     $ 3,950"
+)
+
+FILING = (
+    "The balance sheet is summarized below. Liquidity remains strong.\n\n"
+    + TABLE
+    + "\n\nTotal assets grew 9% on higher cash balances. We expect further growth."
+)
+
+
+def test_extract_tables_rows():
+    assert rag_rust_core.extract_tables(FILING) == [
+        [
+            ["(in millions)", "2024", "2023"],
+            ["Cash and equivalents", "$ 1,234", "$ 1,100"],
+            ["Current assets:"],
+            ["Receivables", "512", "480"],
+            ["Total assets", "$ 4,310", "$ 3,950"],
+        ]
+    ]
+
+
+def test_tab_separated_table():
+    text = "Segment\tRevenue\tMargin\nCloud\t5,200\t31%\nDevices\t2,100\t12%\n"
+    assert rag_rust_core.extract_tables(text) == [
+        [["Segment", "Revenue", "Margin"], ["Cloud", "5,200", "31%"], ["Devices", "2,100", "12%"]]
+    ]
+
+
+def test_prose_is_not_a_table():
+    prose = (
+        "We grew.  Costs fell sharply this year.\n"
+        "Margins rose.  Cash was strong in every quarter.\n"
+        "Outlook is good.  We expect more.\n"
+    )
+    assert rag_rust_core.extract_tables(prose) == []
+    assert rag_rust_core.extract_tables("Revenue      2024\nCosts        2023\n") == []
+
+
+def test_clean_text_preserves_tables():
+    options = rag_rust_core.CleanOptions(preserve_tables=True)
+    assert TABLE in rag_rust_core.clean_text(FILING, options=options)
+    assert TABLE not in rag_rust_core.clean_text(FILING)
+
+
+def test_process_document_table_chunk():
+    chunks = rag_rust_core.process_document(
+        FILING,
+        chunk_size=60,
+        chunk_overlap=20,
+        clean_options=rag_rust_core.CleanOptions(preserve_tables=True),
+    )
+    tables = [c for c in chunks if c["is_table"]]
+    assert [c["text"] for c in tables] == [TABLE]
+    assert not any("Receivables" in c["text"] for c in chunks if not c["is_table"])
+    assert "is_table" not in rag_rust_core.process_document(FILING)[0]
+
+
+def test_report_counts_oversized_table():
+    chunks, report = rag_rust_core.process_document(
+        FILING,
+        chunk_size=60,
+        chunk_overlap=0,
+        clean_options=rag_rust_core.CleanOptions(preserve_tables=True),
+        return_report=True,
+    )
+    [table] = [c["chunk_index"] for c in chunks if c["is_table"]]
+    assert report["exceeded_chunks"] == [table]
+    assert report["max_size"] == len(TABLE)
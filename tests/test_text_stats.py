@@ -0,0 +1,30 @@
+"""`text_stats` document profile.
+
+Requires the built module (`make build`), then: `uv run pytest tests/test_text_stats.py`.
+"""
+
+import rag_rust_core
+
+
+def test_text_stats_known_paragraph():
+    text = "The quarter was  strong. Revenue grew 12% to €5M!\n\n\nMargins held steady. Costs fell?"
+    assert rag_rust_core.text_stats(text) == {
+        "char_count": 82,
+        "byte_count": 84,
+        "word_count": 14,
+        "sentence_count": 4,
+        "avg_sentence_length": 3.5,
+        "paragraph_count": 2,
+    }
+
+
+def test_text_stats_empty_input():
+    stats = rag_rust_core.text_stats("")
+    assert set(stats.values()) == {0}
+    assert stats["avg_sentence_length"] == 0.0
+
+
+def test_text_stats_clean_options():
+    options = rag_rust_core.CleanOptions(collapse_whitespace=False)
+    assert rag_rust_core.text_stats("a  b.", clean_options=options)["char_count"] == 5
+    assert rag_rust_core.text_stats("a  b.")["char_count"] == 4
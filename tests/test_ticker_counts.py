@@ -0,0 +1,29 @@
+"""Ticker occurrence counts and document-order output.
+
+Requires the built module (`make build`), then: `uv run pytest tests/test_ticker_counts.py`.
+"""
+
+import pytest
+
+import rag_rust_core
+
+TEXT = "MSFT rose. AAPL fell, AAPL recovered; MSFT and AAPL closed higher."
+
+
+def test_ticker_counts_default_order():
+    meta = rag_rust_core.extract_metadata(TEXT)
+    assert meta["potential_tickers"] == ["AAPL", "MSFT"]
+    assert meta["ticker_counts"] == {"AAPL": 3, "MSFT": 2}
+
+
+def test_document_order():
+    meta = rag_rust_core.extract_metadata(TEXT, ticker_order="document")
+    assert meta["potential_tickers"] == ["MSFT", "AAPL"]
+    assert list(meta["ticker_counts"]) == ["MSFT", "AAPL"]
+    extractor = rag_rust_core.MetadataExtractor(ticker_order="document")
+    assert extractor.extract(TEXT) == meta
+
+
+def test_unknown_order_is_rejected():
+    with pytest.raises(ValueError):
+        rag_rust_core.extract_metadata(TEXT, ticker_order="frequency")
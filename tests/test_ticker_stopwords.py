@@ -0,0 +1,63 @@
+"""Ticker stopwords, allowlists and exchange-qualified potential tickers.
+
+Requires the built module (`make build`), then: `uv run pytest tests/test_ticker_stopwords.py`.
+"""
+
+import pytest
+
+import rag_rust_core
+
+
+def test_extra_stopwords():
+    text = "EBITDA grew while AAPL rose; GAAP results held."
+    assert rag_rust_core.extract_metadata(text)["potential_tickers"] == ["AAPL", "GAAP"]
+    meta = rag_rust_core.extract_metadata(text, extra_stopwords={"EBITDA", "GAAP"})
+    assert meta["potential_tickers"] == ["AAPL"]
+
+
+def test_exchange_qualified_potential_tickers():
+    meta = rag_rust_core.extract_metadata("NASDAQ:AAPL and NYSE: MSFT beat IBM.")
+    assert meta["potential_tickers"] == ["AAPL", "IBM", "MSFT"]
+    assert meta["potential_tickers_detailed"] == [
+        {"symbol": "AAPL", "exchange": "NASDAQ"},
+        {"symbol": "IBM", "exchange": None},
+        {"symbol": "MSFT", "exchange": "NYSE"},
+    ]
+
+
+def test_extra_stopwords_accept_lists():
+    meta = rag_rust_core.extract_metadata("GAAP EPS for AAPL.", extra_stopwords=["GAAP", "EPS"])
+    assert meta["potential_tickers"] == ["AAPL"]
+
+
+def test_allowlist_suppresses_everything_else():
+    text = "NOTE: AAPL and MSFT rose while IBM and GAAP ITEM totals fell."
+    meta = rag_rust_core.extract_metadata(text, allowlist={"AAPL", "MSFT"})
+    assert meta["potential_tickers"] == ["AAPL", "MSFT"]
+
+
+def test_metadata_extractor_reuses_configuration():
+    extractor = rag_rust_core.MetadataExtractor(extra_stopwords={"GAAP"}, allowlist=None)
+    text = "GAAP results for AAPL."
+    assert extractor.extract(text) == rag_rust_core.extract_metadata(text, extra_stopwords={"GAAP"})
+    assert extractor.extract(text)["potential_tickers"] == ["AAPL"]
+
+
+def test_process_document_forwards_ticker_configuration():
+    chunks = rag_rust_core.process_document("GAAP results for AAPL and IBM.", allowlist={"IBM"})
+    assert chunks[0]["metadata"]["potential_tickers"] == ["IBM"]
+    batch = rag_rust_core.process_documents(["GAAP results for AAPL."], extra_stopwords={"GAAP"})
+    assert batch[0][0]["metadata"]["potential_tickers"] == ["AAPL"]
+
+
+def test_bare_string_is_rejected():
+    with pytest.raises(TypeError):
+        rag_rust_core.extract_metadata("AAPL", allowlist="AAPL")
+
+
+def test_extract_metadata_with_known_tickers():
+    text = "AAPL and MSFT rose, IBM fell; GAAP comments followed."
+    meta = rag_rust_core.extract_metadata_with_tickers(text, {"AAPL", "MSFT", "TSLA"})
+    assert meta["potential_tickers"] == ["AAPL", "MSFT"]
+    assert rag_rust_core.extract_metadata_with_tickers(text, set()) == rag_rust_core.extract_metadata(text)
+    assert rag_rust_core.extract_metadata_with_tickers(text) == rag_rust_core.extract_metadata(text)
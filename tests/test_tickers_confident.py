@@ -0,0 +1,19 @@
+"""Cashtags and exchange-qualified tickers.
+
+Requires the built module (`make build`), then: `uv run pytest tests/test_tickers_confident.py`.
+"""
+
+import rag_rust_core
+
+
+def test_press_release_opening_line():
+    meta = rag_rust_core.extract_metadata("Acme Corp. (NASDAQ: ACME) today announced record revenue.")
+    assert meta["tickers_confident"] == [{"symbol": "ACME", "exchange": "NASDAQ"}]
+
+
+def test_cashtags_and_share_classes():
+    meta = rag_rust_core.extract_metadata("$AAPL and NYSE:BRK.B both rose.")
+    assert meta["tickers_confident"] == [
+        {"symbol": "AAPL", "exchange": None},
+        {"symbol": "BRK.B", "exchange": "NYSE"},
+    ]
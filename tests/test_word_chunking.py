@@ -0,0 +1,34 @@
+"""Chunk sizes counted in words with `size_unit="words"`.
+
+Requires the built module (`make build`), then: `uv run pytest tests/test_word_chunking.py`.
+"""
+
+import pytest
+
+import rag_rust_core
+
+TEXT = "Alpha beta gamma. Delta epsilon zeta eta. Theta iota."
+
+
+def test_chunk_text_in_words():
+    chunks = rag_rust_core.chunk_text(TEXT, target_size=7, overlap_sentences=0, size_unit="words")
+    assert chunks == ["Alpha beta gamma. Delta epsilon zeta eta.", "Theta iota."]
+    long = "One two three four five six seven eight nine ten eleven twelve."
+    assert rag_rust_core.chunk_text(long, target_size=5, overlap=0, size_unit="words") == [
+        "One two three four five",
+        "six seven eight nine ten",
+        "eleven twelve.",
+    ]
+
+
+def test_word_count_in_process_document():
+    chunks = rag_rust_core.process_document("Year-over-year revenue rose 12.5% to $1,234.5 million.")
+    assert chunks[0]["word_count"] == 7
+    assert chunks[0]["char_count"] == 54
+    by_words = rag_rust_core.process_document(TEXT, chunk_size=7, chunk_overlap=0, size_unit="words")
+    assert [c["word_count"] for c in by_words] == [7, 6]
+
+
+def test_unknown_size_unit():
+    with pytest.raises(rag_rust_core.InvalidOption, match="size_unit"):
+        rag_rust_core.chunk_text(TEXT, size_unit="tokens")